@@ -1,7 +1,12 @@
 extern crate cbindgen;
 extern crate cc;
 
-use std::{env, fs, fs::DirEntry, path::Path};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    fs::DirEntry,
+    path::Path,
+};
 
 // include src/header directories that don't start with '_'
 fn include_dir(d: &DirEntry) -> bool {
@@ -33,6 +38,107 @@ fn generate_bindings(cbindgen_config_path: &Path) {
         .write_to_file(header_path);
 }
 
+// name of a function defined by a line like `pub unsafe extern "C" fn name(`
+fn rust_fn_name(line: &str) -> Option<&str> {
+    let rest = line
+        .strip_prefix("pub unsafe extern \"C\" fn ")
+        .or_else(|| line.strip_prefix("pub extern \"C\" fn "))?;
+    let end = rest.find(|c: char| !c.is_alphanumeric() && c != '_')?;
+    Some(&rest[..end])
+}
+
+// name of a function defined by a line like `int name(...) {`
+fn c_fn_name(line: &str) -> Option<&str> {
+    if line.starts_with(|c: char| !c.is_alphabetic())
+        || line.starts_with("static ")
+        || !line.trim_end().ends_with('{')
+    {
+        return None;
+    }
+    let head = line[..line.find('(')?].trim_end();
+    let start = head
+        .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+        .map_or(0, |i| i + 1);
+    Some(&head[start..]).filter(|name| !name.is_empty())
+}
+
+// Generate the entries of relibc's export table (see src/ld_so/exports.rs)
+// - the #[no_mangle] functions of the modules declared in src/header/mod.rs
+// - the non-static functions of src/c/*.c, other than dlmalloc's, which are
+//   exported through the Rust allocator functions
+fn generate_exports(out_path: &Path) {
+    // name => address expression
+    let mut exports = BTreeMap::new();
+    let mut c_fns = BTreeSet::new();
+
+    println!("cargo:rerun-if-changed=src/header/mod.rs");
+    let header_mod = fs::read_to_string("src/header/mod.rs").unwrap();
+    let mut mod_path = None;
+    for line in header_mod.lines() {
+        if let Some(path) = line.strip_prefix("#[path = \"") {
+            mod_path = path.strip_suffix("\"]").map(|path| path.to_string());
+            continue;
+        }
+        let module = match line.strip_prefix("pub mod ") {
+            Some(module) => module.trim_end_matches(';'),
+            None => continue,
+        };
+        let path = Path::new("src/header").join(
+            mod_path
+                .take()
+                .unwrap_or_else(|| format!("{}/mod.rs", module)),
+        );
+        println!("cargo:rerun-if-changed={:?}", path);
+        let source = fs::read_to_string(&path).unwrap();
+        let mut no_mangle = false;
+        for line in source.lines() {
+            if line == "#[no_mangle]" {
+                no_mangle = true;
+            } else if !line.starts_with("#[") && !line.starts_with("//") {
+                if let Some(name) = rust_fn_name(line).filter(|_| no_mangle) {
+                    exports.insert(
+                        name.to_string(),
+                        format!("crate::header::{}::{}", module, name),
+                    );
+                }
+                no_mangle = false;
+            }
+        }
+    }
+
+    println!("cargo:rerun-if-changed=src/c");
+    for entry in fs::read_dir("src/c").unwrap().filter_map(Result::ok) {
+        if entry.file_name() == "dlmalloc.c" {
+            continue;
+        }
+        let source = fs::read_to_string(entry.path()).unwrap();
+        for name in source.lines().filter_map(c_fn_name) {
+            if !exports.contains_key(name) {
+                c_fns.insert(name.to_string());
+                exports.insert(name.to_string(), name.to_string());
+            }
+        }
+    }
+
+    let mut out = String::from("extern \"C\" {\n");
+    for name in c_fns.iter() {
+        out.push_str(&format!("    fn {}();\n", name));
+    }
+    out.push_str("}\n\n");
+    out.push_str(&format!(
+        "static ENTRIES: [Export; {}] = [\n",
+        exports.len()
+    ));
+    for (name, addr) in exports.iter() {
+        out.push_str(&format!(
+            "    Export {{ name: \"{}\\0\".as_ptr() as *const c_char, addr: {} as *const c_void }},\n",
+            name, addr
+        ));
+    }
+    out.push_str("];\n");
+    fs::write(out_path, out).unwrap();
+}
+
 fn main() {
     let crate_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR not set");
 
@@ -53,7 +159,16 @@ fn main() {
             generate_bindings(&p);
         });
 
-    cc::Build::new()
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    generate_exports(&Path::new(&out_dir).join("exports.rs"));
+
+    let mut build = cc::Build::new();
+    if env::var("CARGO_FEATURE_CHECK_BOUNDS").is_ok() {
+        // Needed for mspace_inspect_all, used to look up heap allocations
+        build.define("MALLOC_INSPECT_ALL", "1");
+    }
+
+    build
         .flag("-nostdinc")
         .flag("-nostdlib")
         .include(&format!("{}/include", crate_dir))
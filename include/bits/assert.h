@@ -1,11 +1,15 @@
-#ifndef _BITS_ASSERT_H
-#define _BITS_ASSERT_H
+// Deliberately not guarded, so that each inclusion of <assert.h> redefines
+// assert according to the current NDEBUG, as C requires
+#undef assert
 
 #ifdef NDEBUG
-# define assert(cond) (void) 0
+# define assert(cond) ((void) 0)
 #else
+# if defined(__cplusplus) || defined(__GNUC__)
+#  define __ASSERT_FUNCTION __extension__ __PRETTY_FUNCTION__
+# else
+#  define __ASSERT_FUNCTION __func__
+# endif
 # define assert(cond) \
-  ((void)((cond) || (__assert_fail(__func__, __FILE__, __LINE__, #cond), 0)))
-#endif
-
+  ((void)((cond) || (__assert_fail(#cond, __FILE__, __LINE__, __ASSERT_FUNCTION), 0)))
 #endif
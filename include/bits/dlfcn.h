@@ -0,0 +1,17 @@
+#ifndef _BITS_DLFCN_H
+#define _BITS_DLFCN_H
+
+#define RTLD_DEFAULT ((void *) 0)
+#define RTLD_NEXT ((void *) -1)
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+void *dlsym(void *handle, const char *symbol);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _BITS_DLFCN_H */
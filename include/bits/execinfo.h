@@ -0,0 +1,14 @@
+#ifndef _BITS_EXECINFO_H
+#define _BITS_EXECINFO_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int backtrace(void **buffer, int size);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _BITS_EXECINFO_H */
@@ -6,6 +6,7 @@ extern "C" {
 #endif
 
 int open(const char* filename, int flags, ...);
+int openat(int dirfd, const char* filename, int flags, ...);
 int fcntl(int fildes, int cmd, ...);
 
 #ifdef __cplusplus
@@ -0,0 +1,15 @@
+#ifndef _BITS_LINK_H
+#define _BITS_LINK_H
+
+// The ELF class of the target, which the Elf types of ElfW are named after
+#if __SIZEOF_POINTER__ == 8
+# define __ELF_NATIVE_CLASS 64
+#else
+# define __ELF_NATIVE_CLASS 32
+#endif
+
+#define ElfW(type) _ElfW(Elf, __ELF_NATIVE_CLASS, type)
+#define _ElfW(e, w, t) _ElfW_1(e, w, _##t)
+#define _ElfW_1(e, w, t) e##w##t
+
+#endif /* _BITS_LINK_H */
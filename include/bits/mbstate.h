@@ -0,0 +1,10 @@
+#ifndef _BITS_MBSTATE_H
+#define _BITS_MBSTATE_H
+
+// Shared by stdio.h for fpos_t and wchar.h, matching mbstate_t in
+// src/header/wchar/mod.rs
+typedef struct {
+    unsigned int __state;
+} mbstate_t;
+
+#endif /* _BITS_MBSTATE_H */
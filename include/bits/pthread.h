@@ -0,0 +1,29 @@
+#ifndef _BITS_PTHREAD_H
+#define _BITS_PTHREAD_H
+
+// POSIX pthread functions implemented by relibc rather than pthreads-emb,
+// appended to its pthread.h on install
+
+#include <stddef.h>
+#include <sys/types.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int pthread_attr_getguardsize(const pthread_attr_t *attr, size_t *guardsize);
+int pthread_attr_getstack(const pthread_attr_t *attr, void **stackaddr, size_t *stacksize);
+int pthread_attr_setguardsize(pthread_attr_t *attr, size_t guardsize);
+int pthread_attr_setstack(pthread_attr_t *attr, void *stackaddr, size_t stacksize);
+int pthread_getcpuclockid(pthread_t thread, clockid_t *clock_id);
+
+#ifdef _GNU_SOURCE
+int pthread_getname_np(pthread_t thread, char *name, size_t len);
+int pthread_setname_np(pthread_t thread, const char *name);
+#endif
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _BITS_PTHREAD_H */
@@ -0,0 +1,16 @@
+#ifndef _BITS_SEARCH_H
+#define _BITS_SEARCH_H
+
+typedef enum {
+    FIND,
+    ENTER
+} ACTION;
+
+typedef enum {
+    preorder,
+    postorder,
+    endorder,
+    leaf
+} VISIT;
+
+#endif /* _BITS_SEARCH_H */
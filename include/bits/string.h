@@ -0,0 +1,9 @@
+#ifndef _BITS_STRING_H
+#define _BITS_STRING_H
+
+// With _GNU_SOURCE, strerror_r is the GNU version returning a string
+#ifdef _GNU_SOURCE
+#define strerror_r __gnu_strerror_r
+#endif
+
+#endif /* _BITS_STRING_H */
@@ -3,4 +3,14 @@
 
 #define MAP_FAILED ((void *) -1)
 
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+void *mremap(void *old_address, size_t old_size, size_t new_size, int flags, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
 #endif
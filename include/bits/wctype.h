@@ -4,4 +4,6 @@
 
 #define __need_wint_t
 
+typedef int64_t wctype_t;
+
 #endif /* _BITS_WCTYPE_H */
@@ -0,0 +1,16 @@
+#ifndef _BITS_WPRINTF_H
+#define _BITS_WPRINTF_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int fwprintf(FILE * stream, const wchar_t * fmt, ...);
+int swprintf(wchar_t * s, size_t n, const wchar_t * fmt, ...);
+int wprintf(const wchar_t * fmt, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _BITS_WPRINTF_H */
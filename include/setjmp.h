@@ -3,6 +3,7 @@
 
 #ifdef __aarch64__
 typedef unsigned long jmp_buf[22];
+typedef unsigned long sigjmp_buf[22 + 3];
 #endif
 
 #ifdef __arm__
@@ -59,6 +60,7 @@ typedef unsigned long long jmp_buf[8];
 
 #ifdef __x86_64__
 typedef unsigned long jmp_buf[8];
+typedef unsigned long sigjmp_buf[8 + 3];
 #endif
 
 #ifdef __cplusplus
@@ -68,6 +70,11 @@ extern "C" {
 int setjmp(jmp_buf buf);
 void longjmp(jmp_buf buf, int value);
 
+#if defined(__aarch64__) || defined(__x86_64__)
+int sigsetjmp(sigjmp_buf buf, int savemask);
+void siglongjmp(sigjmp_buf buf, int value);
+#endif
+
 #ifdef __cplusplus
 } // extern "C"
 #endif
@@ -0,0 +1,116 @@
+#ifndef _SYS_UCONTEXT_H
+#define _SYS_UCONTEXT_H
+
+#include <signal.h>
+
+#ifdef __x86_64__
+
+typedef long long greg_t;
+
+// The general registers, in the order the kernel saves them for signal handlers
+#define NGREG 23
+typedef greg_t gregset_t[NGREG];
+
+#ifdef _GNU_SOURCE
+enum {
+    REG_R8 = 0,
+    REG_R9,
+    REG_R10,
+    REG_R11,
+    REG_R12,
+    REG_R13,
+    REG_R14,
+    REG_R15,
+    REG_RDI,
+    REG_RSI,
+    REG_RBP,
+    REG_RBX,
+    REG_RDX,
+    REG_RAX,
+    REG_RCX,
+    REG_RSP,
+    REG_RIP,
+    REG_EFL,
+    REG_CSGSFS,
+    REG_ERR,
+    REG_TRAPNO,
+    REG_OLDMASK,
+    REG_CR2,
+};
+#endif
+
+struct _libc_fpxreg {
+    unsigned short significand[4];
+    unsigned short exponent;
+    unsigned short __reserved[3];
+};
+
+struct _libc_xmmreg {
+    unsigned int element[4];
+};
+
+// The layout used by fxsave
+struct _libc_fpstate {
+    unsigned short cwd;
+    unsigned short swd;
+    unsigned short ftw;
+    unsigned short fop;
+    unsigned long long rip;
+    unsigned long long rdp;
+    unsigned int mxcsr;
+    unsigned int mxcr_mask;
+    struct _libc_fpxreg _st[8];
+    struct _libc_xmmreg _xmm[16];
+    unsigned int __reserved[24];
+};
+
+typedef struct _libc_fpstate *fpregset_t;
+
+typedef struct {
+    gregset_t gregs;
+    fpregset_t fpregs;
+    unsigned long long __reserved[8];
+} mcontext_t;
+
+typedef struct ucontext_t {
+    unsigned long uc_flags;
+    struct ucontext_t *uc_link;
+    stack_t uc_stack;
+    mcontext_t uc_mcontext;
+    sigset_t uc_sigmask;
+    struct _libc_fpstate __fpregs_mem;
+} ucontext_t;
+
+#elif defined(__aarch64__)
+
+typedef unsigned long greg_t;
+
+#define NGREG 34
+typedef greg_t gregset_t[NGREG];
+
+// The layout of the kernel's struct sigcontext. getcontext stores the
+// floating point registers in __reserved as the kernel does, in a struct
+// fpsimd_context followed by an empty record
+typedef struct {
+    unsigned long long fault_address;
+    unsigned long long regs[31];
+    unsigned long long sp;
+    unsigned long long pc;
+    unsigned long long pstate;
+    unsigned char __reserved[4096] __attribute__((aligned(16)));
+} mcontext_t;
+
+typedef struct ucontext_t {
+    unsigned long uc_flags;
+    struct ucontext_t *uc_link;
+    stack_t uc_stack;
+    sigset_t uc_sigmask;
+    unsigned char __unused[128 - sizeof(sigset_t)];
+    mcontext_t uc_mcontext;
+} ucontext_t;
+
+#else
+#error "ucontext is not implemented for this architecture"
+#endif
+
+#endif /* _SYS_UCONTEXT_H */
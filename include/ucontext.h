@@ -0,0 +1,19 @@
+#ifndef _UCONTEXT_H
+#define _UCONTEXT_H
+
+#include <sys/ucontext.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int getcontext(ucontext_t *ucp);
+int setcontext(const ucontext_t *ucp);
+void makecontext(ucontext_t *ucp, void (*func)(void), int argc, ...);
+int swapcontext(ucontext_t *oucp, const ucontext_t *ucp);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _UCONTEXT_H */
@@ -0,0 +1,8 @@
+// TODO: Can be implemented in rust when the return address is available there
+
+void *sys_dlsym(void *handle, const char *symbol, const void *caller);
+
+void *dlsym(void *handle, const char *symbol) {
+    // The caller is needed to find the objects after it for RTLD_NEXT
+    return sys_dlsym(handle, symbol, __builtin_return_address(0));
+}
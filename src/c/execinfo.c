@@ -0,0 +1,25 @@
+// TODO: Can be implemented in rust when the frame address is available there
+
+#include <stddef.h>
+#include <stdint.h>
+
+int backtrace(void ** buffer, int size) {
+    // Each frame starts with the caller's frame pointer, followed by the
+    // return address into the caller
+    void ** frame = __builtin_frame_address(0);
+    int depth = 0;
+    while (depth < size && frame != NULL && frame[1] != NULL) {
+        buffer[depth++] = frame[1];
+
+        // Code built without frame pointers breaks the chain, so stop at
+        // anything that doesn't look like an outer frame of the same stack
+        void ** next = frame[0];
+        if (next <= frame
+            || (uintptr_t) next % sizeof(void *) != 0
+            || (uintptr_t) next - (uintptr_t) frame > 0x100000) {
+            break;
+        }
+        frame = next;
+    }
+    return depth;
+}
@@ -14,13 +14,25 @@ int open(const char* filename, int flags, ...) {
     return sys_open(filename, flags, mode);
 }
 
-int sys_fcntl(int fildes, int cmd, int args);
+int sys_openat(int dirfd, const char* filename, int flags, mode_t mode);
+
+int openat(int dirfd, const char* filename, int flags, ...) {
+    mode_t mode = 0;
+    va_list ap;
+    va_start(ap, flags);
+    mode = va_arg(ap, mode_t);
+    va_end(ap);
+    return sys_openat(dirfd, filename, flags, mode);
+}
+
+int sys_fcntl(int fildes, int cmd, unsigned long args);
 
 int fcntl(int fildes, int cmd, ...) {
-    int args = 0;
+    // Read as unsigned long so pointer arguments (struct flock *) survive
+    unsigned long args = 0;
     va_list ap;
     va_start(ap, cmd);
-    args = va_arg(ap, int);
+    args = va_arg(ap, unsigned long);
     va_end(ap);
     return sys_fcntl(fildes, cmd, args);
 }
@@ -0,0 +1,15 @@
+#include <stdarg.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+int sys_mq_open(const char *name, int oflag, va_list ap);
+
+int mq_open(const char *name, int oflag, ...) {
+    // The mode and attributes are only passed with O_CREAT, so they are read
+    // by sys_mq_open, which knows its value
+    va_list ap;
+    va_start(ap, oflag);
+    int ret = sys_mq_open(name, oflag, ap);
+    va_end(ap);
+    return ret;
+}
@@ -0,0 +1,15 @@
+#include <stdarg.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+void *sys_sem_open(const char *name, int oflag, va_list ap);
+
+void *sem_open(const char *name, int oflag, ...) {
+    // The mode and value are only passed with O_CREAT, so they are read by
+    // sys_sem_open, which knows its value
+    va_list ap;
+    va_start(ap, oflag);
+    void *ret = sys_sem_open(name, oflag, ap);
+    va_end(ap);
+    return ret;
+}
@@ -1,10 +1,16 @@
+#include <stddef.h>
 #include <stdint.h>
 
 void abort();
+long write(int fd, const void * buf, size_t count);
 
+// Replaced with a random value by relibc_start, before main
 uintptr_t __stack_chk_guard = 0xd048c37519fcadfe;
 
 __attribute__((noreturn))
 void __stack_chk_fail(void) {
+	// Avoid stdio, as its state may be part of what was overwritten
+	static const char message[] = "*** stack smashing detected ***: terminated\n";
+	write(2, message, sizeof(message) - 1);
 	abort();
 }
@@ -0,0 +1,16 @@
+#include <stdarg.h>
+#include <stddef.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+void *sys_mremap(void *old_address, size_t old_size, size_t new_size, int flags, va_list ap);
+
+void *mremap(void *old_address, size_t old_size, size_t new_size, int flags, ...) {
+    // The new address is only passed with MREMAP_FIXED, so it is read by
+    // sys_mremap, which knows its value
+    va_list ap;
+    va_start(ap, flags);
+    void *ret = sys_mremap(old_address, old_size, new_size, flags, ap);
+    va_end(ap);
+    return ret;
+}
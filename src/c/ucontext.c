@@ -0,0 +1,14 @@
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+#include <stdarg.h>
+
+#if defined(__aarch64__) || defined(__x86_64__)
+void sys_makecontext(void *ucp, void (*func)(void), int argc, va_list ap);
+
+void makecontext(void *ucp, void (*func)(void), int argc, ...) {
+    va_list ap;
+    va_start(ap, argc);
+    sys_makecontext(ucp, func, argc, ap);
+    va_end(ap);
+}
+#endif
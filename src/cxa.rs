@@ -1,28 +1,65 @@
-use crate::platform::types::*;
+use alloc::vec::Vec;
 
-// TODO: Implement cxa_finalize and uncomment this
+use crate::{platform::types::*, sync::Mutex};
 
-#[derive(Clone, Copy)]
 struct CxaAtExitFunc {
-    //func: extern "C" fn(*mut c_void),
-//arg: *mut c_void,
-//dso: *mut c_void,
+    func: extern "C" fn(*mut c_void),
+    arg: *mut c_void,
+    dso: *mut c_void,
 }
+// The pointers are only handed back to the registered function
+unsafe impl Send for CxaAtExitFunc {}
 
-static mut CXA_ATEXIT_FUNCS: [Option<CxaAtExitFunc>; 32] = [None; 32];
+/// Handlers registered by `__cxa_atexit`, oldest first
+static CXA_ATEXIT_FUNCS: Mutex<Vec<CxaAtExitFunc>> = Mutex::new(Vec::new());
 
+/// Register `func` to be called with `arg` at exit, or when the object
+/// identified by `dso` is unloaded
 #[no_mangle]
 pub unsafe extern "C" fn __cxa_atexit(
     func_opt: Option<extern "C" fn(*mut c_void)>,
     arg: *mut c_void,
     dso: *mut c_void,
 ) -> c_int {
-    for item in &mut CXA_ATEXIT_FUNCS {
-        if item.is_none() {
-            *item = func_opt.map(|func| CxaAtExitFunc {} /*{ func, arg, dso }*/);
-            return 0;
-        }
+    if let Some(func) = func_opt {
+        CXA_ATEXIT_FUNCS
+            .lock()
+            .push(CxaAtExitFunc { func, arg, dso });
     }
+    0
+}
 
-    -1
+/// Call and remove the handlers registered for `dso`, or every handler if
+/// `dso` is null, newest first. Shared objects call this from their own
+/// finalizers when they are unloaded.
+#[no_mangle]
+pub unsafe extern "C" fn __cxa_finalize(dso: *mut c_void) {
+    finalize_matching(|handler| dso.is_null() || handler.dso == dso);
+}
+
+/// Call and remove the handlers belonging to an object mapped at
+/// `start..end`, newest first, when it is unloaded. These are the handlers
+/// whose object, function or argument lies within it, so that handlers
+/// registered with atexit or without a `__dso_handle` are included.
+pub(crate) unsafe fn finalize_range(start: usize, end: usize) {
+    let contains = |ptr: usize| start <= ptr && ptr < end;
+    finalize_matching(|handler| {
+        contains(handler.dso as usize)
+            || contains(handler.func as usize)
+            || contains(handler.arg as usize)
+    });
+}
+
+fn finalize_matching<F: Fn(&CxaAtExitFunc) -> bool>(matches: F) {
+    // The lock is released while a handler runs, so it can register more
+    loop {
+        let handler = {
+            let mut funcs = CXA_ATEXIT_FUNCS.lock();
+            match funcs.iter().rposition(&matches) {
+                Some(i) => funcs.remove(i),
+                None => break,
+            }
+        };
+        (handler.func)(handler.arg);
+    }
 }
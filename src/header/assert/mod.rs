@@ -7,25 +7,30 @@ use crate::{
 };
 use core::fmt::Write;
 
+/// Report a failed assertion in the same format as glibc, then abort
 #[no_mangle]
 pub unsafe extern "C" fn __assert_fail(
-    func: *const c_char,
+    assertion: *const c_char,
     file: *const c_char,
-    line: c_int,
-    cond: *const c_char,
-) {
-    let func = CStr::from_ptr(func).to_str().unwrap();
-    let file = CStr::from_ptr(file).to_str().unwrap();
-    let cond = CStr::from_ptr(cond).to_str().unwrap();
+    line: c_uint,
+    function: *const c_char,
+) -> ! {
+    let assertion = CStr::from_ptr(assertion).to_string_lossy();
+    let file = CStr::from_ptr(file).to_string_lossy();
+
+    let w = &mut *stdio::stderr;
+    let _ = if function.is_null() {
+        writeln!(w, "{}:{}: Assertion `{}' failed.", file, line, assertion)
+    } else {
+        let function = CStr::from_ptr(function).to_string_lossy();
+        writeln!(
+            w,
+            "{}:{}: {}: Assertion `{}' failed.",
+            file, line, function, assertion
+        )
+    };
+    stdio::fflush(w);
 
-    writeln!(
-        *stdio::stderr,
-        "{}: {}:{}: Assertion `{}` failed.",
-        func,
-        file,
-        line,
-        cond
-    )
-    .unwrap();
     stdlib::abort();
+    unreachable!();
 }
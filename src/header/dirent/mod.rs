@@ -1,13 +1,17 @@
 //! dirent implementation following http://pubs.opengroup.org/onlinepubs/009695399/basedefs/dirent.h.html
 
 use alloc::boxed::Box;
-use core::{mem, ptr};
+use core::{mem, ptr, slice};
 
 use crate::{
     c_str::CStr,
     c_vec::CVec,
     fs::File,
-    header::{errno, fcntl, stdlib, string},
+    header::{
+        errno, fcntl, stdlib, string,
+        sys_stat::{stat, S_IFDIR, S_IFMT},
+        unistd::SEEK_SET,
+    },
     io::{Seek, SeekFrom},
     platform::{self, types::*, Pal, Sys},
 };
@@ -47,6 +51,30 @@ pub unsafe extern "C" fn opendir(path: *const c_char) -> *mut DIR {
         Err(_) => return ptr::null_mut(),
     };
 
+    new_dir(file)
+}
+
+/// Wrap an open directory descriptor into a directory stream, which takes
+/// ownership of it. Reading starts from the first entry, wherever the
+/// descriptor was positioned.
+#[no_mangle]
+pub unsafe extern "C" fn fdopendir(fd: c_int) -> *mut DIR {
+    let mut st: stat = mem::zeroed();
+    if Sys::fstat(fd, &mut st) < 0 {
+        return ptr::null_mut();
+    }
+    if st.st_mode as c_int & S_IFMT != S_IFDIR {
+        platform::errno = errno::ENOTDIR;
+        return ptr::null_mut();
+    }
+    if Sys::lseek(fd, 0, SEEK_SET) < 0 {
+        return ptr::null_mut();
+    }
+
+    new_dir(File::new(fd))
+}
+
+fn new_dir(file: File) -> *mut DIR {
     Box::into_raw(Box::new(DIR {
         file,
         buf: [0; DIR_BUF_SIZE],
@@ -56,6 +84,11 @@ pub unsafe extern "C" fn opendir(path: *const c_char) -> *mut DIR {
     }))
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn dirfd(dir: *mut DIR) -> c_int {
+    *(*dir).file
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn closedir(dir: *mut DIR) -> c_int {
     let mut dir = Box::from_raw(dir);
@@ -68,30 +101,67 @@ pub unsafe extern "C" fn closedir(dir: *mut DIR) -> c_int {
     ret
 }
 
+/// Size of the fixed part of a record returned by getdents, before d_name
+const DIRENT_HEADER_SIZE: usize = offset_of!(dirent, d_name);
+
+/// Locate the record at `index` in `buf`, which holds data returned by
+/// getdents, and return it along with the index of the following record.
+///
+/// Returns `None` if the record is truncated or has an invalid `d_reclen`,
+/// which could otherwise lead to reading past the end of the buffer.
+unsafe fn next_entry(buf: &[u8], index: usize) -> Option<(*mut dirent, usize)> {
+    let remaining = buf.len().checked_sub(index)?;
+    if remaining < DIRENT_HEADER_SIZE {
+        return None;
+    }
+
+    let entry = buf.as_ptr().add(index);
+    let reclen = ptr::read_unaligned(entry.add(offset_of!(dirent, d_reclen)) as *const c_ushort);
+    let reclen = reclen as usize;
+    if reclen <= DIRENT_HEADER_SIZE || reclen > remaining {
+        return None;
+    }
+
+    // d_name must be terminated within the record
+    let name = &buf[index + DIRENT_HEADER_SIZE..index + reclen];
+    if !name.contains(&0) {
+        return None;
+    }
+
+    Some((entry as *mut dirent, index + reclen))
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn readdir(dir: *mut DIR) -> *mut dirent {
-    if (*dir).index >= (*dir).len {
-        let read = Sys::getdents(
-            *(*dir).file,
-            (*dir).buf.as_mut_ptr() as *mut dirent,
-            (*dir).buf.len(),
-        );
-        if read <= 0 {
-            if read != 0 && read != -errno::ENOENT {
-                platform::errno = -read;
+    loop {
+        if (*dir).index >= (*dir).len {
+            let read = Sys::getdents(
+                *(*dir).file,
+                (*dir).buf.as_mut_ptr() as *mut dirent,
+                (*dir).buf.len(),
+            );
+            if read <= 0 {
+                if read != 0 && read != -errno::ENOENT {
+                    platform::errno = -read;
+                }
+                return ptr::null_mut();
             }
-            return ptr::null_mut();
+
+            (*dir).index = 0;
+            (*dir).len = (read as usize).min((*dir).buf.len());
         }
 
-        (*dir).index = 0;
-        (*dir).len = read as usize;
+        let buf = slice::from_raw_parts((*dir).buf.as_ptr() as *const u8, (*dir).len);
+        match next_entry(buf, (*dir).index) {
+            Some((ptr, next)) => {
+                (*dir).offset = (*ptr).d_off as usize;
+                (*dir).index = next;
+                return ptr;
+            }
+            // Treat a corrupt record as the end of the buffer, and refill it
+            None => (*dir).index = (*dir).len,
+        }
     }
-
-    let ptr = (*dir).buf.as_mut_ptr().add((*dir).index) as *mut dirent;
-
-    (*dir).offset = (*ptr).d_off as usize;
-    (*dir).index += (*ptr).d_reclen as usize;
-    ptr
 }
 // #[no_mangle]
 pub extern "C" fn readdir_r(
@@ -191,3 +261,111 @@ pub unsafe extern "C" fn scandir(
         len as c_int
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{dirent, next_entry, DIRENT_HEADER_SIZE};
+    use crate::{
+        header::sys_mman::{MAP_ANONYMOUS, MAP_PRIVATE, PROT_NONE, PROT_READ, PROT_WRITE},
+        platform::{Pal, Sys},
+    };
+    use core::{ptr, slice};
+
+    const PAGE_SIZE: usize = 4096;
+
+    /// Run `f` with a buffer of `len` bytes placed right before an
+    /// inaccessible guard page, so that any over-read faults.
+    fn with_guarded_buf<F: FnOnce(&mut [u8])>(len: usize, f: F) {
+        unsafe {
+            let map = Sys::mmap(
+                ptr::null_mut(),
+                2 * PAGE_SIZE,
+                PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS,
+                -1,
+                0,
+            ) as *mut u8;
+            assert_ne!(map as usize, !0);
+            assert_eq!(
+                Sys::mprotect(map.add(PAGE_SIZE) as *mut _, PAGE_SIZE, PROT_NONE),
+                0
+            );
+
+            f(slice::from_raw_parts_mut(map.add(PAGE_SIZE - len), len));
+
+            assert_eq!(Sys::munmap(map as *mut _, 2 * PAGE_SIZE), 0);
+        }
+    }
+
+    fn set_reclen(buf: &mut [u8], reclen: u16) {
+        let offset = offset_of!(dirent, d_reclen);
+        buf[offset..offset + 2].copy_from_slice(&reclen.to_ne_bytes());
+    }
+
+    #[test]
+    fn valid_record() {
+        with_guarded_buf(24, |buf| {
+            set_reclen(buf, 24);
+            buf[DIRENT_HEADER_SIZE] = b'a';
+            let (entry, next) = unsafe { next_entry(buf, 0) }.unwrap();
+            assert_eq!(entry as *const u8, buf.as_ptr());
+            assert_eq!(next, 24);
+            assert!(unsafe { next_entry(buf, next) }.is_none());
+        });
+    }
+
+    #[test]
+    fn truncated_header() {
+        for len in 0..DIRENT_HEADER_SIZE {
+            with_guarded_buf(len, |buf| {
+                assert!(unsafe { next_entry(buf, 0) }.is_none());
+            });
+        }
+    }
+
+    #[test]
+    fn invalid_reclen() {
+        with_guarded_buf(24, |buf| {
+            for &reclen in &[0, DIRENT_HEADER_SIZE as u16, 32, u16::max_value()] {
+                set_reclen(buf, reclen);
+                assert!(unsafe { next_entry(buf, 0) }.is_none());
+            }
+        });
+    }
+
+    #[test]
+    fn unterminated_name() {
+        with_guarded_buf(24, |buf| {
+            set_reclen(buf, 24);
+            for b in &mut buf[DIRENT_HEADER_SIZE..] {
+                *b = b'a';
+            }
+            assert!(unsafe { next_entry(buf, 0) }.is_none());
+        });
+    }
+
+    #[test]
+    fn fuzzed_reclen() {
+        // Every d_reclen against every buffer length up to a few records, with
+        // the name terminated only in the last byte. Any record accepted must
+        // lie within the buffer, and none may read past it into the guard page.
+        for len in DIRENT_HEADER_SIZE..4 * DIRENT_HEADER_SIZE {
+            with_guarded_buf(len, |buf| {
+                for b in &mut buf[DIRENT_HEADER_SIZE..] {
+                    *b = b'a';
+                }
+                buf[len - 1] = 0;
+                for reclen in 0..=u16::max_value() {
+                    set_reclen(buf, reclen);
+                    match unsafe { next_entry(buf, 0) } {
+                        Some((_, next)) => {
+                            assert_eq!(next, reclen as usize);
+                            assert_eq!(next, len);
+                        }
+                        None => assert!(reclen as usize != len || len == DIRENT_HEADER_SIZE),
+                    }
+                }
+            });
+        }
+    }
+}
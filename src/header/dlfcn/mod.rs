@@ -5,13 +5,16 @@ use core::{
     sync::atomic::{AtomicUsize, Ordering},
 };
 
-use crate::{c_str::CStr, ld_so::tcb::Tcb, platform::types::*};
+use crate::{c_str::CStr, cxa, ld_so::tcb::Tcb, platform::types::*};
 
 pub const RTLD_LAZY: c_int = 0x0001;
 pub const RTLD_NOW: c_int = 0x0002;
 pub const RTLD_GLOBAL: c_int = 0x0100;
 pub const RTLD_LOCAL: c_int = 0x0000;
 
+// The RTLD_NEXT handle, ((void *) -1)
+const RTLD_NEXT: usize = !0;
+
 static ERROR_NOT_SUPPORTED: &'static CStr = c_str!("dlfcn not supported");
 
 #[thread_local]
@@ -19,20 +22,45 @@ static ERROR: AtomicUsize = AtomicUsize::new(0);
 
 #[repr(C)]
 pub struct Dl_info {
-    dli_fname: *const c_char,
-    dli_fbase: *mut c_void,
-    dli_sname: *const c_char,
-    dli_saddr: *mut c_void,
+    pub dli_fname: *const c_char,
+    pub dli_fbase: *mut c_void,
+    pub dli_sname: *const c_char,
+    pub dli_saddr: *mut c_void,
 }
 
+/// Find the loaded object containing `addr`, and the symbol whose definition
+/// contains it. Returns 0 if no object contains `addr`. Without a symbol,
+/// `dli_sname` and `dli_saddr` are null.
 #[no_mangle]
 pub unsafe extern "C" fn dladdr(addr: *mut c_void, info: *mut Dl_info) -> c_int {
-    //TODO
     (*info).dli_fname = ptr::null();
     (*info).dli_fbase = ptr::null_mut();
     (*info).dli_sname = ptr::null();
     (*info).dli_saddr = ptr::null_mut();
-    0
+
+    let tcb = match Tcb::current() {
+        Some(tcb) => tcb,
+        None => return 0,
+    };
+    if tcb.linker_ptr.is_null() {
+        return 0;
+    }
+    let linker = (&*tcb.linker_ptr).lock();
+    let cbs_c = linker.cbs.clone();
+    let cbs = cbs_c.borrow();
+
+    // The strings belong to the linker, and live until the object is unloaded
+    let addr_info = match (cbs.addr_info)(&linker, addr as usize) {
+        Some(addr_info) => addr_info,
+        None => return 0,
+    };
+    (*info).dli_fname = addr_info.path.as_ptr();
+    (*info).dli_fbase = addr_info.base as *mut c_void;
+    if let Some(def) = addr_info.symbol {
+        (*info).dli_sname = def.name.as_ptr();
+        (*info).dli_saddr = def.addr as *mut c_void;
+    }
+    1
 }
 
 #[no_mangle]
@@ -92,8 +120,13 @@ pub unsafe extern "C" fn dlopen(cfilename: *const c_char, flags: c_int) -> *mut
     id as *mut c_void
 }
 
+// Called by dlsym in C, which passes its return address as `caller`
 #[no_mangle]
-pub unsafe extern "C" fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void {
+pub unsafe extern "C" fn sys_dlsym(
+    handle: *mut c_void,
+    symbol: *const c_char,
+    caller: *const c_void,
+) -> *mut c_void {
     if symbol.is_null() {
         ERROR.store(ERROR_NOT_SUPPORTED.as_ptr() as usize, Ordering::SeqCst);
         return ptr::null_mut();
@@ -119,7 +152,14 @@ pub unsafe extern "C" fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *m
     let linker = (&*tcb.linker_ptr).lock();
     let cbs_c = linker.cbs.clone();
     let cbs = cbs_c.borrow();
-    if let Some(global) = (cbs.get_sym)(&linker, symbol_str, Some(handle as usize)) {
+    let global = match handle as usize {
+        // RTLD_DEFAULT searches every loaded object
+        0 => (cbs.get_sym)(&linker, symbol_str, None),
+        // RTLD_NEXT searches the objects loaded after the caller
+        RTLD_NEXT => (cbs.get_next_sym)(&linker, symbol_str, caller as usize),
+        id => (cbs.get_sym)(&linker, symbol_str, Some(id)),
+    };
+    if let Some(global) = global {
         global.as_ptr()
     } else {
         eprintln!("dlsym: symbol not found");
@@ -144,6 +184,17 @@ pub unsafe extern "C" fn dlclose(handle: *mut c_void) -> c_int {
         ERROR.store(ERROR_NOT_SUPPORTED.as_ptr() as usize, Ordering::SeqCst);
         return -1;
     };
+    let mappings = {
+        let linker = (&*tcb.linker_ptr).lock();
+        let cbs_c = linker.cbs.clone();
+        let cbs = cbs_c.borrow();
+        (cbs.mappings)(&linker, handle as usize)
+    };
+    // The handlers run without the linker locked, as they may use dlfcn
+    for (start, end) in mappings {
+        cxa::finalize_range(start, end);
+    }
+
     let mut linker = (&*tcb.linker_ptr).lock();
     let cbs_c = linker.cbs.clone();
     let cbs = cbs_c.borrow();
@@ -0,0 +1,63 @@
+//! endian.h implementation for Redox, following https://man7.org/linux/man-pages/man3/endian.3.html
+
+use crate::platform::types::*;
+
+#[no_mangle]
+pub extern "C" fn htobe16(host_16bits: uint16_t) -> uint16_t {
+    host_16bits.to_be()
+}
+
+#[no_mangle]
+pub extern "C" fn htole16(host_16bits: uint16_t) -> uint16_t {
+    host_16bits.to_le()
+}
+
+#[no_mangle]
+pub extern "C" fn be16toh(big_endian_16bits: uint16_t) -> uint16_t {
+    u16::from_be(big_endian_16bits)
+}
+
+#[no_mangle]
+pub extern "C" fn le16toh(little_endian_16bits: uint16_t) -> uint16_t {
+    u16::from_le(little_endian_16bits)
+}
+
+#[no_mangle]
+pub extern "C" fn htobe32(host_32bits: uint32_t) -> uint32_t {
+    host_32bits.to_be()
+}
+
+#[no_mangle]
+pub extern "C" fn htole32(host_32bits: uint32_t) -> uint32_t {
+    host_32bits.to_le()
+}
+
+#[no_mangle]
+pub extern "C" fn be32toh(big_endian_32bits: uint32_t) -> uint32_t {
+    u32::from_be(big_endian_32bits)
+}
+
+#[no_mangle]
+pub extern "C" fn le32toh(little_endian_32bits: uint32_t) -> uint32_t {
+    u32::from_le(little_endian_32bits)
+}
+
+#[no_mangle]
+pub extern "C" fn htobe64(host_64bits: uint64_t) -> uint64_t {
+    host_64bits.to_be()
+}
+
+#[no_mangle]
+pub extern "C" fn htole64(host_64bits: uint64_t) -> uint64_t {
+    host_64bits.to_le()
+}
+
+#[no_mangle]
+pub extern "C" fn be64toh(big_endian_64bits: uint64_t) -> uint64_t {
+    u64::from_be(big_endian_64bits)
+}
+
+#[no_mangle]
+pub extern "C" fn le64toh(little_endian_64bits: uint64_t) -> uint64_t {
+    u64::from_le(little_endian_64bits)
+}
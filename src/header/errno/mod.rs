@@ -149,8 +149,10 @@ pub const EKEYREVOKED: c_int = 128; /* Key has been revoked */
 pub const EKEYREJECTED: c_int = 129; /* Key was rejected by service */
 pub const EOWNERDEAD: c_int = 130; /* Owner died */
 pub const ENOTRECOVERABLE: c_int = 131; /* State not recoverable */
+pub const ERFKILL: c_int = 132; /* Operation not possible due to RF-kill */
+pub const EHWPOISON: c_int = 133; /* Memory page has hardware error */
 
-pub static STR_ERROR: [&'static str; 132] = [
+pub static STR_ERROR: [&'static str; 134] = [
     "Success",
     "Operation not permitted",
     "No such file or directory",
@@ -283,4 +285,6 @@ pub static STR_ERROR: [&'static str; 132] = [
     "Key was rejected by service",
     "Owner died",
     "State not recoverable",
+    "Operation not possible due to RF-kill",
+    "Memory page has hardware error",
 ];
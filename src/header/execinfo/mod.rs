@@ -0,0 +1,83 @@
+//! execinfo.h implementation, following the glibc interface
+
+use alloc::{string::String, vec::Vec};
+use core::{mem, ptr, slice};
+
+use crate::{
+    c_str::CStr,
+    header::{
+        dlfcn::{dladdr, Dl_info},
+        errno,
+    },
+    platform::{self, types::*, Pal, Sys},
+};
+
+/// Describe a return address like glibc, as "object(symbol+0x1a) [0x...]"
+unsafe fn describe(addr: *mut c_void) -> String {
+    let mut info = Dl_info {
+        dli_fname: ptr::null(),
+        dli_fbase: ptr::null_mut(),
+        dli_sname: ptr::null(),
+        dli_saddr: ptr::null_mut(),
+    };
+    if dladdr(addr, &mut info) == 0 {
+        return format!("[{:p}]", addr);
+    }
+
+    let object = CStr::from_ptr(info.dli_fname).to_string_lossy();
+    if info.dli_sname.is_null() {
+        format!(
+            "{}(+{:#x}) [{:p}]",
+            object,
+            addr as usize - info.dli_fbase as usize,
+            addr
+        )
+    } else {
+        format!(
+            "{}({}+{:#x}) [{:p}]",
+            object,
+            CStr::from_ptr(info.dli_sname).to_string_lossy(),
+            addr as usize - info.dli_saddr as usize,
+            addr
+        )
+    }
+}
+
+/// Describe each address of `buffer`. The strings are allocated in the same
+/// block as the array pointing to them, so a single `free` releases both.
+#[no_mangle]
+pub unsafe extern "C" fn backtrace_symbols(
+    buffer: *const *mut c_void,
+    size: c_int,
+) -> *mut *mut c_char {
+    let addrs = slice::from_raw_parts(buffer, size.max(0) as usize);
+    let lines: Vec<String> = addrs.iter().map(|&addr| describe(addr)).collect();
+
+    let array_len = lines.len() * mem::size_of::<*mut c_char>();
+    let strings_len: usize = lines.iter().map(|line| line.len() + 1).sum();
+    let array = platform::alloc(array_len + strings_len) as *mut *mut c_char;
+    if array.is_null() {
+        platform::errno = errno::ENOMEM;
+        return ptr::null_mut();
+    }
+
+    let mut string = (array as *mut c_char).add(array_len);
+    for (i, line) in lines.iter().enumerate() {
+        *array.add(i) = string;
+        ptr::copy_nonoverlapping(line.as_ptr() as *const c_char, string, line.len());
+        *string.add(line.len()) = 0;
+        string = string.add(line.len() + 1);
+    }
+    array
+}
+
+/// Write the description of each address of `buffer` to `fd`, one per line,
+/// leaving nothing for the caller to free
+#[no_mangle]
+pub unsafe extern "C" fn backtrace_symbols_fd(buffer: *const *mut c_void, size: c_int, fd: c_int) {
+    for &addr in slice::from_raw_parts(buffer, size.max(0) as usize) {
+        let mut line = describe(addr);
+        line.push('\n');
+        Sys::write(fd, line.as_bytes());
+    }
+}
@@ -1,8 +1,11 @@
 //! fcntl implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/fcntl.h.html
 
+use core::mem;
+
 use crate::{
     c_str::CStr,
-    platform::{types::*, Pal, Sys},
+    header::{errno, sys_stat::stat, unistd},
+    platform::{self, types::*, Pal, Sys},
 };
 
 pub use self::sys::*;
@@ -24,10 +27,23 @@ pub const F_GETLK: c_int = 5;
 pub const F_SETLK: c_int = 6;
 pub const F_SETLKW: c_int = 7;
 
+pub const AT_FDCWD: c_int = -100;
+pub const AT_SYMLINK_NOFOLLOW: c_int = 0x100;
+pub const AT_REMOVEDIR: c_int = 0x200;
+pub const AT_SYMLINK_FOLLOW: c_int = 0x400;
+pub const AT_EMPTY_PATH: c_int = 0x1000;
+
 pub const F_RDLCK: c_int = 0;
 pub const F_WRLCK: c_int = 1;
 pub const F_UNLCK: c_int = 2;
 
+pub const POSIX_FADV_NORMAL: c_int = 0;
+pub const POSIX_FADV_RANDOM: c_int = 1;
+pub const POSIX_FADV_SEQUENTIAL: c_int = 2;
+pub const POSIX_FADV_WILLNEED: c_int = 3;
+pub const POSIX_FADV_DONTNEED: c_int = 4;
+pub const POSIX_FADV_NOREUSE: c_int = 5;
+
 #[no_mangle]
 pub unsafe extern "C" fn creat(path: *const c_char, mode: mode_t) -> c_int {
     sys_open(path, O_WRONLY | O_CREAT | O_TRUNC, mode)
@@ -41,7 +57,75 @@ pub struct flock {
     pub l_pid: pid_t,
 }
 #[no_mangle]
-pub extern "C" fn sys_fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
+pub extern "C" fn posix_fadvise(fd: c_int, offset: off_t, len: off_t, advice: c_int) -> c_int {
+    let errno_backup = unsafe { platform::errno };
+    let ret = if Sys::fadvise(fd, offset, len, advice) < 0 {
+        unsafe { platform::errno }
+    } else {
+        0
+    };
+    unsafe { platform::errno = errno_backup };
+    ret
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_fallocate(fd: c_int, offset: off_t, len: off_t) -> c_int {
+    if offset < 0 || len <= 0 {
+        return errno::EINVAL;
+    }
+    let end = match offset.checked_add(len) {
+        Some(end) => end,
+        None => return errno::EFBIG,
+    };
+
+    let errno_backup = platform::errno;
+    let ret = if Sys::fallocate(fd, 0, offset, len) == 0 {
+        0
+    } else if platform::errno == errno::EOPNOTSUPP || platform::errno == errno::ENOSYS {
+        fallocate_by_writing(fd, offset, end)
+    } else {
+        platform::errno
+    };
+    platform::errno = errno_backup;
+    ret
+}
+
+/// Allocate `offset..end` on file systems without fallocate by writing a zero
+/// byte into every block of the range that is a hole or past the end of the
+/// file. Existing data is left untouched.
+unsafe fn fallocate_by_writing(fd: c_int, offset: off_t, end: off_t) -> c_int {
+    let mut st: stat = mem::zeroed();
+    if Sys::fstat(fd, &mut st) < 0 {
+        return platform::errno;
+    }
+    let block = if st.st_blksize > 0 {
+        st.st_blksize as off_t
+    } else {
+        4096
+    };
+
+    let mut pos = offset;
+    loop {
+        let mut byte = 0u8;
+        if pos < st.st_size {
+            if unistd::pread(fd, &mut byte as *mut u8 as *mut c_void, 1, pos) < 0 {
+                return platform::errno;
+            }
+        }
+        if byte == 0 && unistd::pwrite(fd, &byte as *const u8 as *const c_void, 1, pos) != 1 {
+            return platform::errno;
+        }
+
+        if pos == end - 1 {
+            return 0;
+        }
+        // Next block boundary, finishing with the last byte of the range
+        pos = ((pos / block + 1) * block).min(end - 1);
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn sys_fcntl(fildes: c_int, cmd: c_int, arg: c_ulong) -> c_int {
     Sys::fcntl(fildes, cmd, arg)
 }
 
@@ -51,5 +135,16 @@ pub unsafe extern "C" fn sys_open(path: *const c_char, oflag: c_int, mode: mode_
     Sys::open(path, oflag, mode)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn sys_openat(
+    dirfd: c_int,
+    path: *const c_char,
+    oflag: c_int,
+    mode: mode_t,
+) -> c_int {
+    let path = CStr::from_ptr(path);
+    Sys::openat(dirfd, path, oflag, mode)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn cbindgen_stupid_struct_user_for_fcntl(a: flock) {}
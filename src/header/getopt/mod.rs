@@ -2,14 +2,28 @@
 
 use crate::{
     header::{
-        stdio, string,
+        stdio, stdlib, string,
         unistd::{optarg, opterr, optind, optopt},
     },
     platform::types::*,
 };
-use core::ptr;
+use core::{ptr, slice};
 
 static mut CURRENT_OPT: *mut c_char = ptr::null_mut();
+// The non-options skipped while permuting are argv[FIRST_NONOPT..LAST_NONOPT]
+static mut FIRST_NONOPT: c_int = 1;
+static mut LAST_NONOPT: c_int = 1;
+
+/// How non-option arguments are handled
+#[derive(Clone, Copy, PartialEq)]
+enum Ordering {
+    /// Skip them, moving them after the options
+    Permute,
+    /// Stop at the first one, as POSIX requires
+    RequireOrder,
+    /// Return each as the argument of option 1
+    ReturnInOrder,
+}
 
 pub const no_argument: c_int = 0;
 pub const required_argument: c_int = 1;
@@ -39,91 +53,159 @@ pub unsafe extern "C" fn getopt_long(
     if optind == 0 {
         optind = 1;
         CURRENT_OPT = ptr::null_mut();
+        FIRST_NONOPT = 1;
+        LAST_NONOPT = 1;
     }
 
+    // A leading '+' or '-' (before any ':') stops at the first non-option, or
+    // returns each as the argument of option 1
+    let (ordering, optstring) = match *optstring as u8 {
+        b'+' => (Ordering::RequireOrder, optstring.offset(1)),
+        b'-' => (Ordering::ReturnInOrder, optstring.offset(1)),
+        _ if !stdlib::getenv(c_str!("POSIXLY_CORRECT").as_ptr()).is_null() => {
+            (Ordering::RequireOrder, optstring)
+        }
+        _ => (Ordering::Permute, optstring),
+    };
+
     if CURRENT_OPT.is_null() || *CURRENT_OPT == 0 {
-        if optind >= argc {
-            -1
+        if let Some(ret) = next_arg(argc, argv as *mut *mut c_char, ordering) {
+            ret
         } else {
-            let current_arg = *argv.offset(optind as isize);
-            if current_arg.is_null()
-                || *current_arg != b'-' as c_char
-                || *current_arg.offset(1) == 0
-            {
-                -1
-            } else if string::strcmp(current_arg, c_str!("--").as_ptr()) == 0 {
-                optind += 1;
-                -1
-            } else {
-                // remove the '-'
+            // remove the '-'
+            let current_arg = (*argv.offset(optind as isize)).offset(1);
+
+            if *current_arg == b'-' as c_char && !longopts.is_null() {
                 let current_arg = current_arg.offset(1);
+                // is a long option
+                for i in 0.. {
+                    let opt = &*longopts.offset(i);
+                    if opt.name.is_null() {
+                        break;
+                    }
 
-                if *current_arg == b'-' as c_char && !longopts.is_null() {
-                    let current_arg = current_arg.offset(1);
-                    // is a long option
-                    for i in 0.. {
-                        let opt = &*longopts.offset(i);
-                        if opt.name.is_null() {
-                            break;
-                        }
+                    let mut end = 0;
+                    while {
+                        let c = *current_arg.offset(end);
+                        c != 0 && c != b'=' as c_char
+                    } {
+                        end += 1;
+                    }
 
-                        let mut end = 0;
-                        while {
-                            let c = *current_arg.offset(end);
-                            c != 0 && c != b'=' as c_char
-                        } {
-                            end += 1;
-                        }
+                    if string::strncmp(current_arg, opt.name, end as size_t) == 0 {
+                        optind += 1;
+                        *longindex = i as c_int;
 
-                        if string::strncmp(current_arg, opt.name, end as size_t) == 0 {
-                            optind += 1;
-                            *longindex = i as c_int;
-
-                            if opt.has_arg == optional_argument {
-                                if *current_arg.offset(end) == b'=' as c_char {
-                                    optarg = current_arg.offset(end + 1);
-                                }
-                            } else if opt.has_arg == required_argument {
-                                if *current_arg.offset(end) == b'=' as c_char {
-                                    optarg = current_arg.offset(end + 1);
-                                } else if optind < argc {
-                                    optarg = *argv.offset(optind as isize);
-                                    optind += 1;
-                                } else if *optstring == b':' as c_char {
-                                    return b':' as c_int;
-                                } else {
-                                    stdio::fputs(*argv as _, &mut *stdio::stderr);
-                                    stdio::fputs(
-                                        ": option '--\0".as_ptr() as _,
-                                        &mut *stdio::stderr,
-                                    );
-                                    stdio::fputs(current_arg, &mut *stdio::stderr);
-                                    stdio::fputs(
-                                        "' requires an argument\n\0".as_ptr() as _,
-                                        &mut *stdio::stderr,
-                                    );
-                                    return b'?' as c_int;
-                                }
+                        if opt.has_arg == optional_argument {
+                            if *current_arg.offset(end) == b'=' as c_char {
+                                optarg = current_arg.offset(end + 1);
                             }
-
-                            if opt.flag.is_null() {
-                                return opt.val;
+                        } else if opt.has_arg == required_argument {
+                            if *current_arg.offset(end) == b'=' as c_char {
+                                optarg = current_arg.offset(end + 1);
+                            } else if optind < argc {
+                                optarg = *argv.offset(optind as isize);
+                                optind += 1;
+                            } else if *optstring == b':' as c_char {
+                                return b':' as c_int;
                             } else {
-                                *opt.flag = opt.val;
-                                return 0;
+                                stdio::fputs(*argv as _, &mut *stdio::stderr);
+                                stdio::fputs(": option '--\0".as_ptr() as _, &mut *stdio::stderr);
+                                stdio::fputs(current_arg, &mut *stdio::stderr);
+                                stdio::fputs(
+                                    "' requires an argument\n\0".as_ptr() as _,
+                                    &mut *stdio::stderr,
+                                );
+                                return b'?' as c_int;
                             }
                         }
+
+                        if opt.flag.is_null() {
+                            return opt.val;
+                        } else {
+                            *opt.flag = opt.val;
+                            return 0;
+                        }
                     }
                 }
-
-                parse_arg(argc, argv, current_arg, optstring)
             }
+
+            parse_arg(argc, argv, current_arg, optstring)
         }
     } else {
         parse_arg(argc, argv, CURRENT_OPT, optstring)
     }
 }
 
+/// Move `optind` to the next option, returning what `getopt` should return
+/// instead if there is none. When permuting, non-options are skipped, and
+/// moved after the options once those are parsed, so that `optind` ends up
+/// pointing at the first non-option.
+unsafe fn next_arg(argc: c_int, argv: *mut *mut c_char, ordering: Ordering) -> Option<c_int> {
+    let is_nonoption = |arg: *mut c_char| *arg != b'-' as c_char || *arg.offset(1) == 0;
+
+    // The caller may have moved optind back to restart
+    if LAST_NONOPT > optind {
+        LAST_NONOPT = optind;
+    }
+    if FIRST_NONOPT > optind {
+        FIRST_NONOPT = optind;
+    }
+
+    if ordering == Ordering::Permute {
+        if FIRST_NONOPT != LAST_NONOPT && LAST_NONOPT != optind {
+            exchange(argv);
+        } else if LAST_NONOPT != optind {
+            FIRST_NONOPT = optind;
+        }
+        while optind < argc && is_nonoption(*argv.offset(optind as isize)) {
+            optind += 1;
+        }
+        LAST_NONOPT = optind;
+    }
+
+    // "--" ends the options, the arguments after it are non-options too
+    if optind < argc && string::strcmp(*argv.offset(optind as isize), c_str!("--").as_ptr()) == 0 {
+        optind += 1;
+        if FIRST_NONOPT != LAST_NONOPT && LAST_NONOPT != optind {
+            exchange(argv);
+        } else if FIRST_NONOPT == LAST_NONOPT {
+            FIRST_NONOPT = optind;
+        }
+        LAST_NONOPT = argc;
+        optind = argc;
+    }
+
+    if optind >= argc {
+        if FIRST_NONOPT != LAST_NONOPT {
+            optind = FIRST_NONOPT;
+        }
+        return Some(-1);
+    }
+
+    let arg = *argv.offset(optind as isize);
+    if is_nonoption(arg) {
+        if ordering == Ordering::RequireOrder {
+            return Some(-1);
+        }
+        optarg = arg;
+        optind += 1;
+        return Some(1);
+    }
+    None
+}
+
+/// Move the non-options skipped so far after the options parsed since
+unsafe fn exchange(argv: *mut *mut c_char) {
+    let args = slice::from_raw_parts_mut(
+        argv.offset(FIRST_NONOPT as isize),
+        (optind - FIRST_NONOPT) as usize,
+    );
+    args.rotate_left((LAST_NONOPT - FIRST_NONOPT) as usize);
+    FIRST_NONOPT += optind - LAST_NONOPT;
+    LAST_NONOPT = optind;
+}
+
 unsafe fn parse_arg(
     argc: c_int,
     argv: *const *mut c_char,
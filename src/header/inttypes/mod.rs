@@ -31,7 +31,7 @@ pub unsafe extern "C" fn strtoimax(
 ) -> intmax_t {
     strto_impl!(
         intmax_t,
-        false,
+        true,
         intmax_t::max_value(),
         intmax_t::min_value(),
         s,
@@ -1,3 +1,6 @@
 //! limits.h implementation for relibc
 
 pub const PATH_MAX: usize = 4096;
+
+/// Smallest stack accepted by pthread_attr_setstacksize and pthread_attr_setstack
+pub const PTHREAD_STACK_MIN: usize = 16384;
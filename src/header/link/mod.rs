@@ -0,0 +1,89 @@
+//! link.h implementation, following the glibc interface
+
+use alloc::vec::Vec;
+use core::{mem, slice};
+
+use crate::{
+    header::{
+        elf::{Elf64_Addr, Elf64_Half, Elf64_Phdr, PT_PHDR},
+        sys_auxv::{find_auxv, AT_PHDR, AT_PHNUM},
+    },
+    ld_so::tcb::Tcb,
+    platform::types::*,
+};
+
+#[repr(C)]
+pub struct dl_phdr_info {
+    pub dlpi_addr: Elf64_Addr,
+    pub dlpi_name: *const c_char,
+    pub dlpi_phdr: *const Elf64_Phdr,
+    pub dlpi_phnum: Elf64_Half,
+}
+
+/// Call `callback` with the program headers of each loaded object, the
+/// program first, until it returns nonzero. Returns the last value returned
+/// by `callback`, or 0 if there are no objects.
+#[no_mangle]
+pub unsafe extern "C" fn dl_iterate_phdr(
+    callback: Option<unsafe extern "C" fn(*mut dl_phdr_info, size_t, *mut c_void) -> c_int>,
+    data: *mut c_void,
+) -> c_int {
+    let callback = match callback {
+        Some(callback) => callback,
+        None => return 0,
+    };
+    let tcb = match Tcb::current() {
+        Some(tcb) if !tcb.linker_ptr.is_null() => tcb,
+        // Without the dynamic linker, the program is the only object
+        _ => {
+            return match program_info() {
+                Some(mut info) => callback(&mut info, mem::size_of::<dl_phdr_info>(), data),
+                None => 0,
+            }
+        }
+    };
+
+    // Collect the objects first, so the callback can use the linker itself
+    let mut infos: Vec<dl_phdr_info> = {
+        let linker = (&*tcb.linker_ptr).lock();
+        let cbs_c = linker.cbs.clone();
+        let cbs = cbs_c.borrow();
+        (cbs.objects_info)(&linker)
+            .into_iter()
+            .map(|object| dl_phdr_info {
+                dlpi_addr: object.base as Elf64_Addr,
+                dlpi_name: object.path.as_ptr(),
+                dlpi_phdr: object.phdr as *const Elf64_Phdr,
+                dlpi_phnum: object.phnum as Elf64_Half,
+            })
+            .collect()
+    };
+
+    let mut ret = 0;
+    for info in infos.iter_mut() {
+        ret = callback(info, mem::size_of::<dl_phdr_info>(), data);
+        if ret != 0 {
+            break;
+        }
+    }
+    ret
+}
+
+/// The program headers of a program not loaded by the dynamic linker, from
+/// the auxiliary vector
+unsafe fn program_info() -> Option<dl_phdr_info> {
+    let phdr = find_auxv(AT_PHDR)? as *const Elf64_Phdr;
+    let phnum = find_auxv(AT_PHNUM)?;
+    // A static PIE is loaded at the difference between where its program
+    // headers are and where it says they are
+    let addr = slice::from_raw_parts(phdr, phnum)
+        .iter()
+        .find(|header| header.p_type as usize == PT_PHDR)
+        .map_or(0, |header| phdr as Elf64_Addr - header.p_vaddr);
+    Some(dl_phdr_info {
+        dlpi_addr: addr,
+        dlpi_name: c_str!("").as_ptr(),
+        dlpi_phdr: phdr,
+        dlpi_phnum: phnum as Elf64_Half,
+    })
+}
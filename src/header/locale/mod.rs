@@ -32,6 +32,10 @@ pub struct lconv {
 }
 unsafe impl Sync for lconv {}
 
+/// Only the C locale is supported, so locale objects don't carry anything
+#[allow(non_camel_case_types)]
+pub type locale_t = *mut c_void;
+
 static CURRENT_LOCALE: lconv = lconv {
     currency_symbol: EMPTY_PTR,
     decimal_point: ".\0" as *const _ as *const c_char,
@@ -8,7 +8,9 @@ pub mod dirent;
 pub mod dl_tls;
 pub mod dlfcn;
 pub mod elf;
+pub mod endian;
 pub mod errno;
+pub mod execinfo;
 pub mod fcntl;
 pub mod float;
 pub mod fnmatch;
@@ -17,37 +19,46 @@ pub mod grp;
 pub mod inttypes;
 pub mod libgen;
 pub mod limits;
+pub mod link;
 pub mod locale;
+pub mod mqueue;
 pub mod netdb;
 pub mod netinet_in;
 pub mod netinet_ip;
 pub mod netinet_tcp;
 pub mod poll;
+pub mod pthread;
 pub mod pwd;
 pub mod regex;
+pub mod search;
 pub mod semaphore;
 pub mod setjmp;
 pub mod sgtty;
 pub mod signal;
+pub mod spawn;
 pub mod stdio;
 pub mod stdlib;
 pub mod string;
 pub mod strings;
 pub mod sys_auxv;
 pub mod sys_epoll;
+pub mod sys_eventfd;
 pub mod sys_file;
+pub mod sys_inotify;
 pub mod sys_ioctl;
 pub mod sys_mman;
 pub mod sys_ptrace;
 pub mod sys_resource;
 pub mod sys_select;
+pub mod sys_sendfile;
+pub mod sys_signalfd;
 pub mod sys_socket;
 pub mod sys_stat;
 pub mod sys_statvfs;
 pub mod sys_time;
 pub mod sys_timeb;
+pub mod sys_timerfd;
 //pub mod sys_times;
-pub mod _wctype;
 pub mod arch_aarch64_user;
 pub mod arch_x64_user;
 pub mod sys_procfs;
@@ -59,6 +70,8 @@ pub mod sys_utsname;
 pub mod sys_wait;
 pub mod termios;
 pub mod time;
+#[cfg(any(target_arch = "aarch64", target_arch = "x86_64"))]
+pub mod ucontext;
 pub mod unistd;
 pub mod utime;
 pub mod wchar;
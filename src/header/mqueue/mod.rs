@@ -0,0 +1,251 @@
+//! mqueue implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/mqueue.h.html
+//!
+//! Message queues are provided by the kernel on Linux. Elsewhere every
+//! function fails with ENOSYS. Notifications with `SIGEV_THREAD` get a helper
+//! thread, which the kernel sends a message on a netlink socket to and which
+//! then calls the notify function.
+
+use core::{ffi::VaList, mem, ptr, slice};
+
+use crate::{
+    c_str::CStr,
+    header::{
+        errno::{EAGAIN, EINVAL},
+        fcntl::O_CREAT,
+        pthread::{pthread_attr_t, pthread_create, pthread_detach, pthread_join, pthread_t},
+        signal::{sigevent, sigset_t, sigval, SIGEV_THREAD, SIG_BLOCK, SIG_SETMASK},
+        sys_socket::{
+            self,
+            constants::{AF_NETLINK, MSG_NOSIGNAL, MSG_WAITALL, SOCK_CLOEXEC, SOCK_RAW},
+        },
+        time::timespec,
+    },
+    platform::{self, types::*, Pal, PalSignal, Sys},
+    sync::Semaphore,
+};
+
+/// Length of the cookie the kernel sends for a `SIGEV_THREAD` notification
+const NOTIFY_COOKIE_LEN: usize = 32;
+
+/// The last byte of the cookie when a message arrived, rather than the
+/// registration being removed
+const NOTIFY_WOKENUP: u8 = 1;
+
+pub type mqd_t = c_int;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct mq_attr {
+    pub mq_flags: c_long,
+    pub mq_maxmsg: c_long,
+    pub mq_msgsize: c_long,
+    pub mq_curmsgs: c_long,
+    __reserved: [c_long; 4],
+}
+
+/// Queue names start with a slash, which the kernel expects to be left out
+unsafe fn queue_name<'a>(name: *const c_char) -> Option<&'a CStr> {
+    if *name != b'/' as c_char {
+        platform::errno = EINVAL;
+        return None;
+    }
+    Some(CStr::from_ptr(name.add(1)))
+}
+
+/// `mq_open`, given its variadic arguments by the wrapper in C
+#[no_mangle]
+pub unsafe extern "C" fn sys_mq_open(name: *const c_char, oflag: c_int, mut ap: VaList) -> mqd_t {
+    // The mode and attributes are only passed with O_CREAT
+    let (mode, attr) = if oflag & O_CREAT == O_CREAT {
+        (ap.arg::<mode_t>(), ap.arg::<*const mq_attr>())
+    } else {
+        (0, ptr::null())
+    };
+    match queue_name(name) {
+        Some(name) => Sys::mq_open(name, oflag, mode, attr),
+        None => -1,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn mq_close(mqdes: mqd_t) -> c_int {
+    Sys::close(mqdes)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_unlink(name: *const c_char) -> c_int {
+    match queue_name(name) {
+        Some(name) => Sys::mq_unlink(name),
+        None => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_getattr(mqdes: mqd_t, mqstat: *mut mq_attr) -> c_int {
+    Sys::mq_getsetattr(mqdes, ptr::null(), mqstat)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_setattr(
+    mqdes: mqd_t,
+    mqstat: *const mq_attr,
+    omqstat: *mut mq_attr,
+) -> c_int {
+    Sys::mq_getsetattr(mqdes, mqstat, omqstat)
+}
+
+/// Passed by `mq_notify` to the helper thread of a `SIGEV_THREAD`
+/// notification
+struct Notifier {
+    mqdes: mqd_t,
+    socket: c_int,
+    notify: extern "C" fn(sigval),
+    value: sigval,
+    /// Posted once the helper registered for the notification, after which it
+    /// no longer uses the `Notifier`
+    registered: Semaphore,
+    /// The error from registering, or 0
+    error: c_int,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_notify(mqdes: mqd_t, notification: *const sigevent) -> c_int {
+    let event = match notification.as_ref() {
+        Some(event) if event.sigev_notify == SIGEV_THREAD => event,
+        _ => return Sys::mq_notify(mqdes, notification),
+    };
+    let notify = match event.sigev_notify_function {
+        Some(notify) => notify,
+        None => {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    };
+
+    // The kernel notifies other threads with a message on a netlink socket,
+    // which a helper thread waits for
+    let socket = sys_socket::socket(AF_NETLINK, SOCK_RAW | SOCK_CLOEXEC, 0);
+    if socket < 0 {
+        return -1;
+    }
+    let mut notifier = Notifier {
+        mqdes,
+        socket,
+        notify,
+        value: event.sigev_value,
+        registered: Semaphore::new(0),
+        error: 0,
+    };
+
+    // The helper inherits the mask, so it takes no signals meant for others
+    let all: sigset_t = !0;
+    let mut old: sigset_t = 0;
+    Sys::sigprocmask(SIG_BLOCK, &all, &mut old);
+    let mut thread: pthread_t = mem::zeroed();
+    let status = pthread_create(
+        &mut thread,
+        event.sigev_notify_attributes as *const pthread_attr_t,
+        notifier_thread,
+        &mut notifier as *mut Notifier as *mut c_void,
+    );
+    Sys::sigprocmask(SIG_SETMASK, &old, ptr::null_mut());
+    if status != 0 {
+        Sys::close(socket);
+        platform::errno = EAGAIN;
+        return -1;
+    }
+
+    notifier.registered.wait(None);
+    if notifier.error != 0 {
+        Sys::close(socket);
+        pthread_join(thread, ptr::null_mut());
+        platform::errno = notifier.error;
+        return -1;
+    }
+    pthread_detach(thread);
+    0
+}
+
+/// The helper thread of a `SIGEV_THREAD` notification, which registers for it
+/// and calls the notify function once a message arrives
+extern "C" fn notifier_thread(arg: *mut c_void) -> *mut c_void {
+    static COOKIE: [u8; NOTIFY_COOKIE_LEN] = [0; NOTIFY_COOKIE_LEN];
+
+    let notifier = arg as *mut Notifier;
+    unsafe {
+        let (socket, notify, value) = ((*notifier).socket, (*notifier).notify, (*notifier).value);
+
+        // The kernel takes the socket as the signal, and the value as the
+        // cookie to send
+        let mut event: sigevent = mem::zeroed();
+        event.sigev_notify = SIGEV_THREAD;
+        event.sigev_signo = socket;
+        event.sigev_value.sival_ptr = COOKIE.as_ptr() as *mut c_void;
+        let registered = Sys::mq_notify((*notifier).mqdes, &event) == 0;
+        if !registered {
+            (*notifier).error = platform::errno;
+        }
+        (*notifier).registered.post(1);
+        if !registered {
+            return ptr::null_mut();
+        }
+
+        let mut cookie = [0u8; NOTIFY_COOKIE_LEN];
+        let count = sys_socket::recv(
+            socket,
+            cookie.as_mut_ptr() as *mut c_void,
+            NOTIFY_COOKIE_LEN,
+            MSG_NOSIGNAL | MSG_WAITALL,
+        );
+        Sys::close(socket);
+        if count == NOTIFY_COOKIE_LEN as ssize_t && cookie[NOTIFY_COOKIE_LEN - 1] == NOTIFY_WOKENUP
+        {
+            notify(value);
+        }
+    }
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_send(
+    mqdes: mqd_t,
+    msg_ptr: *const c_char,
+    msg_len: size_t,
+    msg_prio: c_uint,
+) -> c_int {
+    mq_timedsend(mqdes, msg_ptr, msg_len, msg_prio, ptr::null())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_timedsend(
+    mqdes: mqd_t,
+    msg_ptr: *const c_char,
+    msg_len: size_t,
+    msg_prio: c_uint,
+    abstime: *const timespec,
+) -> c_int {
+    let msg = slice::from_raw_parts(msg_ptr as *const u8, msg_len);
+    platform::cancellation_point(|| Sys::mq_timedsend(mqdes, msg, msg_prio, abstime))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_receive(
+    mqdes: mqd_t,
+    msg_ptr: *mut c_char,
+    msg_len: size_t,
+    msg_prio: *mut c_uint,
+) -> ssize_t {
+    mq_timedreceive(mqdes, msg_ptr, msg_len, msg_prio, ptr::null())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_timedreceive(
+    mqdes: mqd_t,
+    msg_ptr: *mut c_char,
+    msg_len: size_t,
+    msg_prio: *mut c_uint,
+    abstime: *const timespec,
+) -> ssize_t {
+    let msg = slice::from_raw_parts_mut(msg_ptr as *mut u8, msg_len);
+    platform::cancellation_point(|| Sys::mq_timedreceive(mqdes, msg, msg_prio, abstime))
+}
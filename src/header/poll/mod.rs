@@ -1,14 +1,19 @@
 //! poll implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/poll.h.html
 
-use core::{mem, slice};
+use core::{mem, ptr, slice};
 
 use crate::{
     fs::File,
-    header::sys_epoll::{
-        epoll_create1, epoll_ctl, epoll_data, epoll_event, epoll_wait, EPOLLERR, EPOLLHUP, EPOLLIN,
-        EPOLLNVAL, EPOLLOUT, EPOLLPRI, EPOLL_CLOEXEC, EPOLL_CTL_ADD,
+    header::{
+        errno,
+        signal::sigset_t,
+        sys_epoll::{
+            epoll_create1, epoll_ctl, epoll_data, epoll_event, epoll_pwait, EPOLLERR, EPOLLHUP,
+            EPOLLIN, EPOLLNVAL, EPOLLOUT, EPOLLPRI, EPOLL_CLOEXEC, EPOLL_CTL_ADD,
+        },
+        time::timespec,
     },
-    platform::types::*,
+    platform::{self, types::*},
 };
 
 pub const POLLIN: c_short = 0x001;
@@ -27,7 +32,24 @@ pub struct pollfd {
     pub revents: c_short,
 }
 
-pub fn poll_epoll(fds: &mut [pollfd], timeout: c_int) -> c_int {
+/// Convert a `timespec` timeout to epoll milliseconds, rounding up so the
+/// wait is never cut short. A null timeout waits forever. Returns `None` if
+/// the timeout is invalid.
+pub(crate) unsafe fn timespec_to_ms(timeout: *const timespec) -> Option<c_int> {
+    if timeout.is_null() {
+        return Some(-1);
+    }
+    let timeout = &*timeout;
+    if timeout.tv_sec < 0 || timeout.tv_nsec < 0 || timeout.tv_nsec >= 1_000_000_000 {
+        return None;
+    }
+    let ms = (timeout.tv_sec as u64)
+        .saturating_mul(1000)
+        .saturating_add((timeout.tv_nsec as u64 + 999_999) / 1_000_000);
+    Some(ms.min(c_int::max_value() as u64) as c_int)
+}
+
+pub fn poll_epoll(fds: &mut [pollfd], timeout: c_int, sigmask: *const sigset_t) -> c_int {
     let event_map = [
         (POLLIN, EPOLLIN),
         (POLLPRI, EPOLLPRI),
@@ -68,7 +90,14 @@ pub fn poll_epoll(fds: &mut [pollfd], timeout: c_int) -> c_int {
     }
 
     let mut events: [epoll_event; 32] = unsafe { mem::zeroed() };
-    let res = epoll_wait(*ep, events.as_mut_ptr(), events.len() as c_int, timeout);
+    // The kernel installs sigmask only for the duration of the wait
+    let res = epoll_pwait(
+        *ep,
+        events.as_mut_ptr(),
+        events.len() as c_int,
+        timeout,
+        sigmask,
+    );
     if res < 0 {
         return -1;
     }
@@ -97,10 +126,42 @@ pub fn poll_epoll(fds: &mut [pollfd], timeout: c_int) -> c_int {
 #[no_mangle]
 pub unsafe extern "C" fn poll(fds: *mut pollfd, nfds: nfds_t, timeout: c_int) -> c_int {
     trace_expr!(
-        poll_epoll(slice::from_raw_parts_mut(fds, nfds as usize), timeout),
+        poll_epoll(
+            slice::from_raw_parts_mut(fds, nfds as usize),
+            timeout,
+            ptr::null()
+        ),
         "poll({:p}, {}, {})",
         fds,
         nfds,
         timeout
     )
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn ppoll(
+    fds: *mut pollfd,
+    nfds: nfds_t,
+    timeout: *const timespec,
+    sigmask: *const sigset_t,
+) -> c_int {
+    let timeout_ms = match timespec_to_ms(timeout) {
+        Some(ms) => ms,
+        None => {
+            platform::errno = errno::EINVAL;
+            return -1;
+        }
+    };
+    trace_expr!(
+        poll_epoll(
+            slice::from_raw_parts_mut(fds, nfds as usize),
+            timeout_ms,
+            sigmask
+        ),
+        "ppoll({:p}, {}, {:p}, {:p})",
+        fds,
+        nfds,
+        timeout,
+        sigmask
+    )
+}
@@ -0,0 +1,242 @@
+//! Non-portable pthread extensions, and wrappers adding stack attributes to
+//! pthreads-emb. The rest of pthread.h is provided by pthreads-emb
+
+use alloc::collections::BTreeMap;
+
+use crate::{
+    c_str::CStr,
+    header::{
+        errno::{EINVAL, ENOSYS, ERANGE},
+        limits::PTHREAD_STACK_MIN,
+        time::cpu_clock,
+    },
+    ld_so::PAGE_SIZE,
+    platform::{self, types::*, Pal, Sys},
+    sync::Mutex,
+};
+
+/// Longest thread name, including the NUL terminator
+const NAME_LEN: usize = 16;
+
+/// Stack size of threads created without one being set
+pub const DEFAULT_STACK_SIZE: size_t = 1024 * 1024;
+
+/// The handle used by pthreads-emb
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct pthread_t {
+    p: *mut c_void,
+    x: c_uint,
+}
+
+/// pthreads-emb's attributes, a pointer to a structure it allocates
+pub type pthread_attr_t = *mut c_void;
+
+/// The stack attributes of a `pthread_attr_t`, which pthreads-emb does not
+/// support and so are kept here
+#[derive(Clone, Copy)]
+pub struct StackAttr {
+    /// Lowest address of a stack given with `pthread_attr_setstack`, or 0
+    pub addr: usize,
+    pub size: size_t,
+    pub guard: size_t,
+}
+
+impl Default for StackAttr {
+    fn default() -> Self {
+        Self {
+            addr: 0,
+            size: DEFAULT_STACK_SIZE,
+            guard: PAGE_SIZE,
+        }
+    }
+}
+
+/// By the pointer in the `pthread_attr_t`
+static STACK_ATTRS: Mutex<Option<BTreeMap<usize, StackAttr>>> = Mutex::new(None);
+
+// Provided by pthreads-emb. The functions wrapped here are renamed when it is
+// built, see the Makefile
+extern "C" {
+    fn __pte_pthread_attr_destroy(attr: *mut pthread_attr_t) -> c_int;
+    fn __pte_pthread_attr_init(attr: *mut pthread_attr_t) -> c_int;
+    fn __pte_pthread_create(
+        thread: *mut pthread_t,
+        attr: *const pthread_attr_t,
+        start: extern "C" fn(*mut c_void) -> *mut c_void,
+        arg: *mut c_void,
+    ) -> c_int;
+    pub(crate) fn pthread_detach(thread: pthread_t) -> c_int;
+    pub(crate) fn pthread_join(thread: pthread_t, value_ptr: *mut *mut c_void) -> c_int;
+}
+
+/// Run `f` on the stack attributes of `attr`, or fail with EINVAL if it was
+/// not initialized
+unsafe fn with_stack_attr<F: FnOnce(&mut StackAttr)>(attr: *const pthread_attr_t, f: F) -> c_int {
+    let mut attrs = STACK_ATTRS.lock();
+    match attrs
+        .as_mut()
+        .and_then(|attrs| attrs.get_mut(&(*attr as usize)))
+    {
+        Some(stack) => {
+            f(stack);
+            0
+        }
+        None => EINVAL,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_init(attr: *mut pthread_attr_t) -> c_int {
+    let ret = __pte_pthread_attr_init(attr);
+    if ret == 0 {
+        STACK_ATTRS
+            .lock()
+            .get_or_insert_with(BTreeMap::new)
+            .insert(*attr as usize, StackAttr::default());
+    }
+    ret
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_destroy(attr: *mut pthread_attr_t) -> c_int {
+    if let Some(attrs) = STACK_ATTRS.lock().as_mut() {
+        attrs.remove(&(*attr as usize));
+    }
+    __pte_pthread_attr_destroy(attr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getguardsize(
+    attr: *const pthread_attr_t,
+    guardsize: *mut size_t,
+) -> c_int {
+    with_stack_attr(attr, |stack| *guardsize = stack.guard)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setguardsize(
+    attr: *mut pthread_attr_t,
+    guardsize: size_t,
+) -> c_int {
+    with_stack_attr(attr, |stack| stack.guard = guardsize)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getstack(
+    attr: *const pthread_attr_t,
+    stackaddr: *mut *mut c_void,
+    stacksize: *mut size_t,
+) -> c_int {
+    with_stack_attr(attr, |stack| {
+        *stackaddr = stack.addr as *mut c_void;
+        *stacksize = stack.size;
+    })
+}
+
+/// The stack is used as is, without a guard page
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setstack(
+    attr: *mut pthread_attr_t,
+    stackaddr: *mut c_void,
+    stacksize: size_t,
+) -> c_int {
+    if stacksize < PTHREAD_STACK_MIN {
+        return EINVAL;
+    }
+    with_stack_attr(attr, |stack| {
+        stack.addr = stackaddr as usize;
+        stack.size = stacksize;
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getstacksize(
+    attr: *const pthread_attr_t,
+    stacksize: *mut size_t,
+) -> c_int {
+    with_stack_attr(attr, |stack| *stacksize = stack.size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setstacksize(
+    attr: *mut pthread_attr_t,
+    stacksize: size_t,
+) -> c_int {
+    if stacksize < PTHREAD_STACK_MIN {
+        return EINVAL;
+    }
+    with_stack_attr(attr, |stack| stack.size = stacksize)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_create(
+    thread: *mut pthread_t,
+    attr: *const pthread_attr_t,
+    start: extern "C" fn(*mut c_void) -> *mut c_void,
+    arg: *mut c_void,
+) -> c_int {
+    let mut stack = StackAttr::default();
+    if !attr.is_null() && with_stack_attr(attr, |attr| stack = *attr) != 0 {
+        return EINVAL;
+    }
+    // pthreads-emb only passes the stack size on to pte_osThreadCreate
+    platform::with_thread_stack(stack, || __pte_pthread_create(thread, attr, start, arg))
+}
+
+/// The OS thread id behind a pthread_t
+pub(crate) unsafe fn thread_tid(thread: pthread_t) -> pid_t {
+    // pthreads-emb's thread structure starts with the handle from pte_osThreadCreate
+    *(thread.p as *const pid_t)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getname_np(
+    thread: pthread_t,
+    name: *mut c_char,
+    len: size_t,
+) -> c_int {
+    let mut buf = [0; NAME_LEN];
+    let count = Sys::pthread_getname(thread_tid(thread), &mut buf);
+    if count < 0 {
+        return platform::errno;
+    }
+    let count = count as usize;
+    if len <= count {
+        return ERANGE;
+    }
+
+    let name = name as *mut u8;
+    name.copy_from_nonoverlapping(buf.as_ptr(), count);
+    *name.add(count) = 0;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_setname_np(thread: pthread_t, name: *const c_char) -> c_int {
+    // Longer names are truncated, as the kernel would do
+    let name = CStr::from_ptr(name).to_bytes();
+    let mut buf = [0; NAME_LEN];
+    let len = name.len().min(NAME_LEN - 1);
+    buf[..len].copy_from_slice(&name[..len]);
+
+    let name = CStr::from_bytes_with_nul_unchecked(&buf[..=len]);
+    if Sys::pthread_setname(thread_tid(thread), name) < 0 {
+        return platform::errno;
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_getcpuclockid(
+    thread: pthread_t,
+    clock_id: *mut clockid_t,
+) -> c_int {
+    match cpu_clock(thread_tid(thread), true) {
+        Some(clock) => {
+            *clock_id = clock;
+            0
+        }
+        None => ENOSYS,
+    }
+}
@@ -0,0 +1,81 @@
+//! Hash table for `hsearch` and friends. Entries are allocated one by one, so
+//! they stay where they are when the table grows.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{iter, mem};
+
+use super::ENTRY;
+use crate::{c_str::CStr, platform::types::*};
+
+pub struct Table {
+    slots: Vec<Option<Box<ENTRY>>>,
+    filled: usize,
+}
+
+unsafe fn key_bytes<'a>(key: *const c_char) -> &'a [u8] {
+    CStr::from_ptr(key).to_bytes()
+}
+
+/// FNV-1a
+fn hash(key: &[u8]) -> usize {
+    key.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100_0000_01b3)
+    }) as usize
+}
+
+impl Table {
+    /// A table for `nel` entries before it has to grow
+    pub fn new(nel: usize) -> Self {
+        // Keep it at most three quarters full
+        let size = (nel.saturating_add(nel / 3) + 1).max(8).next_power_of_two();
+        Self {
+            slots: iter::repeat_with(|| None).take(size).collect(),
+            filled: 0,
+        }
+    }
+
+    /// The slot of `key`, or the empty one where it would go
+    unsafe fn slot(&self, key: *const c_char) -> usize {
+        let key = key_bytes(key);
+        let mask = self.slots.len() - 1;
+        let mut i = hash(key) & mask;
+        while let Some(entry) = &self.slots[i] {
+            if key_bytes(entry.key) == key {
+                break;
+            }
+            i = (i + 1) & mask;
+        }
+        i
+    }
+
+    pub unsafe fn find(&mut self, key: *const c_char) -> Option<&mut ENTRY> {
+        let i = self.slot(key);
+        self.slots[i].as_deref_mut()
+    }
+
+    /// Find the entry with the key of `item`, inserting `item` if there is none
+    pub unsafe fn enter(&mut self, item: ENTRY) -> &mut ENTRY {
+        let mut i = self.slot(item.key);
+        if self.slots[i].is_none() {
+            if (self.filled + 1) * 4 > self.slots.len() * 3 {
+                self.grow();
+                i = self.slot(item.key);
+            }
+            self.slots[i] = Some(Box::new(item));
+            self.filled += 1;
+        }
+        self.slots[i].as_deref_mut().unwrap()
+    }
+
+    unsafe fn grow(&mut self) {
+        let size = self.slots.len() * 2;
+        let old = mem::replace(
+            &mut self.slots,
+            iter::repeat_with(|| None).take(size).collect(),
+        );
+        for entry in old.into_iter().flatten() {
+            let i = self.slot(entry.key);
+            self.slots[i] = Some(entry);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! search.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/search.h.html
+
+use alloc::boxed::Box;
+use core::ptr;
+
+use crate::{
+    header::errno::{EINVAL, ENOENT},
+    platform::{self, types::*},
+};
+
+mod hash;
+mod tree;
+
+use self::{hash::Table, tree::Node};
+
+#[repr(C)]
+pub struct entry {
+    pub key: *mut c_char,
+    pub data: *mut c_void,
+}
+pub type ENTRY = entry;
+
+pub type ACTION = c_int;
+pub const FIND: ACTION = 0;
+pub const ENTER: ACTION = 1;
+
+#[repr(C)]
+pub struct hsearch_data {
+    __table: *mut c_void,
+    __unused1: c_uint,
+    __unused2: c_uint,
+}
+
+/// The links at the start of each element of a list for `insque`
+#[repr(C)]
+struct Links {
+    next: *mut Links,
+    prev: *mut Links,
+}
+
+static mut HSEARCH_DATA: hsearch_data = hsearch_data {
+    __table: ptr::null_mut(),
+    __unused1: 0,
+    __unused2: 0,
+};
+
+pub type VISIT = c_int;
+pub const preorder: VISIT = 0;
+pub const postorder: VISIT = 1;
+pub const endorder: VISIT = 2;
+pub const leaf: VISIT = 3;
+
+/// Find `key` in the tree at `*rootp`, inserting it if it isn't there.
+/// Returns the node of the key, which starts with a pointer to it.
+#[no_mangle]
+pub unsafe extern "C" fn tsearch(
+    key: *const c_void,
+    rootp: *mut *mut c_void,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> *mut c_void {
+    match compar {
+        Some(compar) if !rootp.is_null() => {
+            tree::insert(&mut *(rootp as *mut *mut Node), key, compar) as *mut c_void
+        }
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Find the node of `key` in the tree at `*rootp`, or return null
+#[no_mangle]
+pub unsafe extern "C" fn tfind(
+    key: *const c_void,
+    rootp: *const *mut c_void,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> *mut c_void {
+    match compar {
+        Some(compar) if !rootp.is_null() => {
+            tree::find(*rootp as *mut Node, key, compar) as *mut c_void
+        }
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Remove `key` from the tree at `*rootp`. Returns the parent of its node,
+/// `rootp` itself if that was the root, or null if `key` isn't there.
+#[no_mangle]
+pub unsafe extern "C" fn tdelete(
+    key: *const c_void,
+    rootp: *mut *mut c_void,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> *mut c_void {
+    let compar = match compar {
+        Some(compar) if !rootp.is_null() => compar,
+        _ => return ptr::null_mut(),
+    };
+    let root = &mut *(rootp as *mut *mut Node);
+    match tree::delete(root, key, compar, ptr::null_mut()) {
+        Some(parent) if parent.is_null() => rootp as *mut c_void,
+        Some(parent) => parent as *mut c_void,
+        None => ptr::null_mut(),
+    }
+}
+
+unsafe fn walk(
+    node: *const Node,
+    action: extern "C" fn(*const c_void, VISIT, c_int),
+    depth: c_int,
+) {
+    if (*node).left.is_null() && (*node).right.is_null() {
+        action(node as *const c_void, leaf, depth);
+        return;
+    }
+    action(node as *const c_void, preorder, depth);
+    if !(*node).left.is_null() {
+        walk((*node).left, action, depth + 1);
+    }
+    action(node as *const c_void, postorder, depth);
+    if !(*node).right.is_null() {
+        walk((*node).right, action, depth + 1);
+    }
+    action(node as *const c_void, endorder, depth);
+}
+
+/// Call `action` on each node of the tree at `root`: leaves once, and other
+/// nodes before, between and after visiting their children. The `postorder`
+/// visits and the leaves are in the order of the keys.
+#[no_mangle]
+pub unsafe extern "C" fn twalk(
+    root: *const c_void,
+    action: Option<extern "C" fn(*const c_void, VISIT, c_int)>,
+) {
+    if let Some(action) = action {
+        if !root.is_null() {
+            walk(root as *const Node, action, 0);
+        }
+    }
+}
+
+/// Create the table of `htab`, with room for about `nel` entries before it
+/// grows. Returns 0 if it already has a table.
+#[no_mangle]
+pub unsafe extern "C" fn hcreate_r(nel: size_t, htab: *mut hsearch_data) -> c_int {
+    if htab.is_null() {
+        platform::errno = EINVAL;
+        return 0;
+    }
+    if !(*htab).__table.is_null() {
+        return 0;
+    }
+    (*htab).__table = Box::into_raw(Box::new(Table::new(nel))) as *mut c_void;
+    1
+}
+
+/// Free the table of `htab`, but not the keys and data of its entries
+#[no_mangle]
+pub unsafe extern "C" fn hdestroy_r(htab: *mut hsearch_data) {
+    if htab.is_null() {
+        platform::errno = EINVAL;
+        return;
+    }
+    if !(*htab).__table.is_null() {
+        drop(Box::from_raw((*htab).__table as *mut Table));
+        (*htab).__table = ptr::null_mut();
+    }
+}
+
+/// Find the entry with the key of `item` in the table of `htab`, inserting
+/// `item` if there is none and `action` is `ENTER`. Sets `*retval` to the
+/// entry and returns nonzero, or sets it to null and returns 0 if there is no
+/// entry to return.
+#[no_mangle]
+pub unsafe extern "C" fn hsearch_r(
+    item: ENTRY,
+    action: ACTION,
+    retval: *mut *mut ENTRY,
+    htab: *mut hsearch_data,
+) -> c_int {
+    let table = if htab.is_null() || (*htab).__table.is_null() || item.key.is_null() {
+        None
+    } else {
+        Some(&mut *((*htab).__table as *mut Table))
+    };
+    let found = match (table, action) {
+        (Some(table), ENTER) => Some(table.enter(item) as *mut ENTRY),
+        (Some(table), FIND) => table.find(item.key).map(|entry| entry as *mut ENTRY),
+        _ => {
+            *retval = ptr::null_mut();
+            platform::errno = EINVAL;
+            return 0;
+        }
+    };
+    match found {
+        Some(entry) => {
+            *retval = entry;
+            1
+        }
+        None => {
+            *retval = ptr::null_mut();
+            platform::errno = ENOENT;
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hcreate(nel: size_t) -> c_int {
+    hcreate_r(nel, &mut HSEARCH_DATA)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hdestroy() {
+    hdestroy_r(&mut HSEARCH_DATA);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hsearch(item: ENTRY, action: ACTION) -> *mut ENTRY {
+    let mut entry = ptr::null_mut();
+    hsearch_r(item, action, &mut entry, &mut HSEARCH_DATA);
+    entry
+}
+
+/// Insert `element` after `pred` in a doubly linked list, or make it a list
+/// of its own if `pred` is null
+#[no_mangle]
+pub unsafe extern "C" fn insque(element: *mut c_void, pred: *mut c_void) {
+    let element = element as *mut Links;
+    let pred = pred as *mut Links;
+    if pred.is_null() {
+        (*element).next = ptr::null_mut();
+        (*element).prev = ptr::null_mut();
+        return;
+    }
+
+    (*element).next = (*pred).next;
+    (*element).prev = pred;
+    if !(*pred).next.is_null() {
+        (*(*pred).next).prev = element;
+    }
+    (*pred).next = element;
+}
+
+/// Remove `element` from its doubly linked list
+#[no_mangle]
+pub unsafe extern "C" fn remque(element: *mut c_void) {
+    let element = element as *mut Links;
+    if !(*element).next.is_null() {
+        (*(*element).next).prev = (*element).prev;
+    }
+    if !(*element).prev.is_null() {
+        (*(*element).prev).next = (*element).next;
+    }
+}
+
+/// Find the first element of the `*nelp` at `base` that compares equal to
+/// `key`, or return null
+#[no_mangle]
+pub unsafe extern "C" fn lfind(
+    key: *const c_void,
+    base: *const c_void,
+    nelp: *mut size_t,
+    width: size_t,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> *mut c_void {
+    let compar = match compar {
+        Some(compar) => compar,
+        None => return ptr::null_mut(),
+    };
+    for i in 0..*nelp {
+        let element = (base as *const c_char).add(i * width) as *const c_void;
+        if compar(key, element) == 0 {
+            return element as *mut c_void;
+        }
+    }
+    ptr::null_mut()
+}
+
+/// Like `lfind`, but copy `key` to the end of the array if it isn't there,
+/// which needs room for one more element
+#[no_mangle]
+pub unsafe extern "C" fn lsearch(
+    key: *const c_void,
+    base: *mut c_void,
+    nelp: *mut size_t,
+    width: size_t,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> *mut c_void {
+    if compar.is_none() {
+        return ptr::null_mut();
+    }
+    let found = lfind(key, base, nelp, width, compar);
+    if !found.is_null() {
+        return found;
+    }
+
+    let end = (base as *mut c_char).add(*nelp * width);
+    ptr::copy_nonoverlapping(key as *const c_char, end, width);
+    *nelp += 1;
+    end as *mut c_void
+}
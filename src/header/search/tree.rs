@@ -0,0 +1,158 @@
+//! AVL tree for `tsearch` and friends. Keys are never moved between nodes, so
+//! a node found once stays valid until its own key is deleted.
+
+use alloc::boxed::Box;
+use core::{cmp::Ordering, ptr};
+
+use crate::platform::types::*;
+
+pub type Compare = extern "C" fn(*const c_void, *const c_void) -> c_int;
+
+/// A node of the tree. C code reads the key through the pointers it's given,
+/// so it has to be the first field.
+#[repr(C)]
+pub struct Node {
+    pub key: *const c_void,
+    pub left: *mut Node,
+    pub right: *mut Node,
+    height: c_int,
+}
+
+fn height(node: *mut Node) -> c_int {
+    if node.is_null() {
+        0
+    } else {
+        unsafe { (*node).height }
+    }
+}
+
+unsafe fn update_height(node: *mut Node) {
+    (*node).height = 1 + height((*node).left).max(height((*node).right));
+}
+
+unsafe fn rotate_left(node: &mut *mut Node) {
+    let root = *node;
+    let right = (*root).right;
+    (*root).right = (*right).left;
+    (*right).left = root;
+    update_height(root);
+    update_height(right);
+    *node = right;
+}
+
+unsafe fn rotate_right(node: &mut *mut Node) {
+    let root = *node;
+    let left = (*root).left;
+    (*root).left = (*left).right;
+    (*left).right = root;
+    update_height(root);
+    update_height(left);
+    *node = left;
+}
+
+/// Restore the balance of `node` after one of its subtrees changed height by
+/// one
+unsafe fn rebalance(node: &mut *mut Node) {
+    let root = *node;
+    let balance = height((*root).left) - height((*root).right);
+    if balance > 1 {
+        let left = &mut (*root).left;
+        if height((**left).left) < height((**left).right) {
+            rotate_left(left);
+        }
+        rotate_right(node);
+    } else if balance < -1 {
+        let right = &mut (*root).right;
+        if height((**right).right) < height((**right).left) {
+            rotate_right(right);
+        }
+        rotate_left(node);
+    } else {
+        update_height(root);
+    }
+}
+
+/// Find the node for `key`
+pub unsafe fn find(mut node: *mut Node, key: *const c_void, compar: Compare) -> *mut Node {
+    while !node.is_null() {
+        node = match compar(key, (*node).key).cmp(&0) {
+            Ordering::Less => (*node).left,
+            Ordering::Greater => (*node).right,
+            Ordering::Equal => return node,
+        };
+    }
+    ptr::null_mut()
+}
+
+/// Find the node for `key`, inserting one if there is none
+pub unsafe fn insert(node: &mut *mut Node, key: *const c_void, compar: Compare) -> *mut Node {
+    let root = *node;
+    if root.is_null() {
+        *node = Box::into_raw(Box::new(Node {
+            key,
+            left: ptr::null_mut(),
+            right: ptr::null_mut(),
+            height: 1,
+        }));
+        return *node;
+    }
+
+    let found = match compar(key, (*root).key).cmp(&0) {
+        Ordering::Less => insert(&mut (*root).left, key, compar),
+        Ordering::Greater => insert(&mut (*root).right, key, compar),
+        Ordering::Equal => return root,
+    };
+    rebalance(node);
+    found
+}
+
+/// Remove and free the node for `key`. Returns its parent, which is `parent`
+/// if it's the root of this subtree, or `None` if there is no such node.
+pub unsafe fn delete(
+    node: &mut *mut Node,
+    key: *const c_void,
+    compar: Compare,
+    parent: *mut Node,
+) -> Option<*mut Node> {
+    let root = *node;
+    if root.is_null() {
+        return None;
+    }
+
+    let removed_parent = match compar(key, (*root).key).cmp(&0) {
+        Ordering::Less => delete(&mut (*root).left, key, compar, root)?,
+        Ordering::Greater => delete(&mut (*root).right, key, compar, root)?,
+        Ordering::Equal => {
+            *node = if (*root).left.is_null() {
+                (*root).right
+            } else if (*root).right.is_null() {
+                (*root).left
+            } else {
+                // Relink the next node in its place, rather than moving keys
+                let next = take_first(&mut (*root).right);
+                (*next).left = (*root).left;
+                (*next).right = (*root).right;
+                next
+            };
+            drop(Box::from_raw(root));
+            parent
+        }
+    };
+    if !node.is_null() {
+        rebalance(node);
+    }
+    Some(removed_parent)
+}
+
+/// Unlink the first node of the subtree at `node`
+unsafe fn take_first(node: &mut *mut Node) -> *mut Node {
+    let root = *node;
+    if (*root).left.is_null() {
+        *node = (*root).right;
+        root
+    } else {
+        let first = take_first(&mut (*root).left);
+        rebalance(node);
+        first
+    }
+}
@@ -1,5 +1,26 @@
-use crate::platform::types::*;
+//! semaphore implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/semaphore.h.html
+//!
+//! These replace the semaphores of pthreads-emb, which also uses them for its
+//! barriers and condition variables.
 
+use alloc::collections::BTreeMap;
+use core::{ffi::VaList, mem, ptr, slice};
+
+use crate::{
+    header::{
+        errno::{EAGAIN, EEXIST, EINVAL, ENOENT, EOVERFLOW, ETIMEDOUT},
+        fcntl::{O_CLOEXEC, O_CREAT, O_EXCL, O_RDWR},
+        sys_mman::{shm_path, MAP_SHARED, PROT_READ, PROT_WRITE},
+        sys_stat::stat,
+        time::timespec,
+    },
+    platform::{self, types::*, Pal, Sys},
+    sync::{Mutex, Semaphore},
+};
+
+pub const SEM_VALUE_MAX: c_int = 0x7FFF_FFFF;
+
+/// Holds a `Semaphore`. Named semaphores are a `sem_t` mapped from a file
 #[repr(C)]
 #[derive(Copy)]
 pub union sem_t {
@@ -12,50 +33,242 @@ impl Clone for sem_t {
         *self
     }
 }
-// #[no_mangle]
-pub extern "C" fn sem_init(sem: *mut sem_t, pshared: c_int, value: c_uint) -> c_int {
-    unimplemented!();
+
+/// Prefix of the shared memory files backing named semaphores
+const SEM_PREFIX: &'static [u8] = b"sem.";
+
+/// How many milliseconds `sem_open` waits for another process to finish
+/// creating a semaphore
+const SEM_OPEN_TIMEOUT: usize = 1000;
+
+/// Each named semaphore mapped, by the device and inode of its file, with how
+/// many times it is open, so that opening it again gives the same address
+static NAMED: Mutex<Option<BTreeMap<(dev_t, ino_t), (usize, usize)>>> = Mutex::new(None);
+
+unsafe fn semaphore<'a>(sem: *mut sem_t) -> &'a Semaphore {
+    &*(sem as *const Semaphore)
 }
 
-// #[no_mangle]
-pub extern "C" fn sem_destroy(sem: *mut sem_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn sem_init(sem: *mut sem_t, _pshared: c_int, value: c_uint) -> c_int {
+    if value > SEM_VALUE_MAX as c_uint {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    // The futex is never private to the process, so pshared needs no handling
+    ptr::write(sem as *mut Semaphore, Semaphore::new(value as c_int));
+    0
 }
 
-/*
- *#[no_mangle]
- *pub extern "C" fn sem_open(name: *const c_char,
- *                    oflag: c_int, ...) -> *mut sem_t {
- *    unimplemented!();
- *}
- */
+#[no_mangle]
+pub unsafe extern "C" fn sem_destroy(_sem: *mut sem_t) -> c_int {
+    0
+}
 
-// #[no_mangle]
-pub extern "C" fn sem_close(sem: *mut sem_t) -> c_int {
-    unimplemented!();
+/// `sem_open`, given its variadic arguments by the wrapper in C
+#[no_mangle]
+pub unsafe extern "C" fn sys_sem_open(
+    name: *const c_char,
+    oflag: c_int,
+    mut ap: VaList,
+) -> *mut sem_t {
+    // The mode and value are only passed with O_CREAT
+    let (mode, value) = if oflag & O_CREAT == O_CREAT {
+        (ap.arg::<mode_t>(), ap.arg::<c_uint>())
+    } else {
+        (0, 0)
+    };
+    let path = match shm_path(name, SEM_PREFIX) {
+        Ok(path) => path,
+        Err(err) => {
+            platform::errno = err;
+            return ptr::null_mut();
+        }
+    };
+    if oflag & O_CREAT == O_CREAT && value > SEM_VALUE_MAX as c_uint {
+        platform::errno = EINVAL;
+        return ptr::null_mut();
+    }
+
+    let fd = loop {
+        if oflag & (O_CREAT | O_EXCL) != O_CREAT | O_EXCL {
+            let fd = Sys::open(&path, O_RDWR | O_CLOEXEC, 0);
+            if fd >= 0 || platform::errno != ENOENT || oflag & O_CREAT != O_CREAT {
+                break fd;
+            }
+        }
+
+        let fd = Sys::open(&path, O_RDWR | O_CLOEXEC | O_CREAT | O_EXCL, mode);
+        if fd >= 0 {
+            // Written in one go, so that nobody maps a partially created semaphore
+            let mut init: sem_t = mem::zeroed();
+            sem_init(&mut init, 1, value);
+            let bytes =
+                slice::from_raw_parts(&init as *const sem_t as *const u8, mem::size_of::<sem_t>());
+            if Sys::write(fd, bytes) != bytes.len() as ssize_t {
+                Sys::close(fd);
+                Sys::unlink(&path);
+                return ptr::null_mut();
+            }
+            break fd;
+        }
+        if platform::errno != EEXIST || oflag & O_EXCL == O_EXCL {
+            break fd;
+        }
+        // Created by someone else between the two calls, so open it
+    };
+    if fd < 0 {
+        return ptr::null_mut();
+    }
+
+    // Another process may still be writing the semaphore it created. If it
+    // died before finishing, the file stays too short, so give up eventually
+    let mut st: stat = mem::zeroed();
+    let mut waited = 0;
+    loop {
+        if Sys::fstat(fd, &mut st) < 0 {
+            Sys::close(fd);
+            return ptr::null_mut();
+        }
+        if st.st_size as usize >= mem::size_of::<sem_t>() {
+            break;
+        }
+        if waited == SEM_OPEN_TIMEOUT {
+            Sys::close(fd);
+            platform::errno = EAGAIN;
+            return ptr::null_mut();
+        }
+        let delay = timespec {
+            tv_sec: 0,
+            tv_nsec: 1_000_000,
+        };
+        Sys::nanosleep(&delay, ptr::null_mut());
+        waited += 1;
+    }
+
+    let mut named = NAMED.lock();
+    let named = named.get_or_insert_with(BTreeMap::new);
+    if let Some((sem, opens)) = named.get_mut(&(st.st_dev, st.st_ino)) {
+        Sys::close(fd);
+        *opens += 1;
+        return *sem as *mut sem_t;
+    }
+    let sem = Sys::mmap(
+        ptr::null_mut(),
+        mem::size_of::<sem_t>(),
+        PROT_READ | PROT_WRITE,
+        MAP_SHARED,
+        fd,
+        0,
+    );
+    Sys::close(fd);
+    if sem as isize == -1 {
+        return ptr::null_mut();
+    }
+    named.insert((st.st_dev, st.st_ino), (sem as usize, 1));
+    sem as *mut sem_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sem_close(sem: *mut sem_t) -> c_int {
+    let mut named = NAMED.lock();
+    let named = named.get_or_insert_with(BTreeMap::new);
+    let key = match named
+        .iter()
+        .find(|(_, &(addr, _))| addr == sem as usize)
+        .map(|(&key, _)| key)
+    {
+        Some(key) => key,
+        None => {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    };
+    let opens = &mut named.get_mut(&key).unwrap().1;
+    *opens -= 1;
+    if *opens > 0 {
+        return 0;
+    }
+    named.remove(&key);
+    Sys::munmap(sem as *mut c_void, mem::size_of::<sem_t>())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sem_unlink(name: *const c_char) -> c_int {
+    match shm_path(name, SEM_PREFIX) {
+        Ok(path) => Sys::unlink(&path),
+        Err(err) => {
+            platform::errno = err;
+            -1
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sem_wait(sem: *mut sem_t) -> c_int {
+    platform::cancellable_wait(semaphore(sem), None);
+    0
 }
 
-// #[no_mangle]
-pub extern "C" fn sem_unlink(name: *const c_char) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn sem_trywait(sem: *mut sem_t) -> c_int {
+    if semaphore(sem).try_wait() {
+        0
+    } else {
+        platform::errno = EAGAIN;
+        -1
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn sem_wait(sem: *mut sem_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn sem_timedwait(sem: *mut sem_t, abstime: *const timespec) -> c_int {
+    let sem = semaphore(sem);
+    if sem.try_wait() {
+        return 0;
+    }
+    // pthreads-emb waits without a timeout by passing NULL
+    let abstime = abstime.as_ref();
+    if let Some(abstime) = abstime {
+        if abstime.tv_nsec < 0 || abstime.tv_nsec >= 1_000_000_000 {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    }
+    if platform::cancellable_wait(sem, abstime) {
+        0
+    } else {
+        platform::errno = ETIMEDOUT;
+        -1
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn sem_trywait(sem: *mut sem_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn sem_post(sem: *mut sem_t) -> c_int {
+    if semaphore(sem).try_post(1, SEM_VALUE_MAX) {
+        0
+    } else {
+        platform::errno = EOVERFLOW;
+        -1
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn sem_post(sem: *mut sem_t) -> c_int {
-    unimplemented!();
+/// Not in POSIX, but used by pthreads-emb
+#[no_mangle]
+pub unsafe extern "C" fn sem_post_multiple(sem: *mut sem_t, count: c_int) -> c_int {
+    if count <= 0 {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    if semaphore(sem).try_post(count, SEM_VALUE_MAX) {
+        0
+    } else {
+        platform::errno = EOVERFLOW;
+        -1
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn sem_getvalue(sem: *mut sem_t, sval: *mut c_int) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn sem_getvalue(sem: *mut sem_t, sval: *mut c_int) -> c_int {
+    *sval = semaphore(sem).value();
+    0
 }
@@ -0,0 +1,24 @@
+.global sigsetjmp
+.global __sigsetjmp
+.type sigsetjmp,@function
+.type __sigsetjmp,@function
+.hidden __sigsetjmp_tail
+sigsetjmp:
+__sigsetjmp:
+	test %esi,%esi
+	jz 1f
+
+	popq 64(%rdi)           /* keep our return addr, setjmp returns here instead */
+	mov %rbx,80(%rdi)       /* rbx is callee saved, so it holds the buffer across setjmp */
+	mov %rdi,%rbx
+
+	call setjmp@PLT
+
+	pushq 64(%rbx)
+	mov %rbx,%rdi
+	mov %eax,%esi
+	mov 80(%rbx),%rbx
+
+	jmp __sigsetjmp_tail /* save or restore the mask, depending on the return value */
+
+1:	jmp setjmp@PLT
@@ -1,5 +1,12 @@
 //! setjmp implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/setjmp.h.html
 
+use core::ptr;
+
+use crate::{
+    header::signal::{sigset_t, SIG_SETMASK},
+    platform::{types::*, Pal, Sys},
+};
+
 macro_rules! platform_specific {
     ($($arch:expr,$ext:expr;)+) => {
         $(
@@ -28,3 +35,43 @@ platform_specific! {
     "x32","s";
     "x86_64","s";
 }
+
+// sigsetjmp keeps its return address, the signal mask and a scratch register
+// after what setjmp saves. It is only implemented for these architectures.
+macro_rules! sigsetjmp_specific {
+    ($($arch:expr,$ext:expr,$jmp_buf_len:expr;)+) => {
+        $(
+            #[cfg(target_arch = $arch)]
+            global_asm!(include_str!(concat!("impl/", $arch, "/sigsetjmp.", $ext)));
+            #[cfg(target_arch = $arch)]
+            const JMP_BUF_LEN: usize = $jmp_buf_len;
+        )+
+    }
+}
+
+sigsetjmp_specific! {
+    "aarch64","s",22;
+    "x86_64","s",8;
+}
+
+/// Called by sigsetjmp once setjmp returns, either directly or through siglongjmp, to save or
+/// restore the signal mask. The assembly marks it hidden, so it is not exported from libc.
+#[no_mangle]
+unsafe extern "C" fn __sigsetjmp_tail(buf: *mut c_ulong, ret: c_int) -> c_int {
+    let mask = buf.add(JMP_BUF_LEN + 1) as *mut sigset_t;
+    if ret == 0 {
+        Sys::sigprocmask(SIG_SETMASK, ptr::null(), mask);
+    } else {
+        Sys::sigprocmask(SIG_SETMASK, mask, ptr::null_mut());
+    }
+    ret
+}
+
+/// longjmp returns into sigsetjmp, which restores the signal mask if it was saved
+#[no_mangle]
+pub unsafe extern "C" fn siglongjmp(buf: *mut c_ulong, value: c_int) {
+    extern "C" {
+        fn longjmp(buf: *mut c_ulong, value: c_int);
+    }
+    longjmp(buf, value);
+}
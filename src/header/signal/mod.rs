@@ -1,6 +1,6 @@
 //! signal implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/signal.h.html
 
-use core::mem;
+use core::{mem, ptr};
 
 use cbitset::BitSet;
 
@@ -25,10 +25,25 @@ pub const SIG_DFL: usize = 0;
 pub const SIG_IGN: usize = 1;
 pub const SIG_ERR: isize = -1;
 
+pub const CLD_EXITED: c_int = 1;
+pub const CLD_KILLED: c_int = 2;
+pub const CLD_DUMPED: c_int = 3;
+pub const CLD_TRAPPED: c_int = 4;
+pub const CLD_STOPPED: c_int = 5;
+pub const CLD_CONTINUED: c_int = 6;
+
 pub const SIG_BLOCK: c_int = 0;
 pub const SIG_UNBLOCK: c_int = 1;
 pub const SIG_SETMASK: c_int = 2;
 
+/// Sent by pthread_cancel to interrupt the blocking call a thread may be in
+pub(crate) const SIGCANCEL: c_int = 32;
+/// Sent to the helper thread of a `SIGEV_THREAD` timer
+pub(crate) const SIGTIMER: c_int = 33;
+
+/// The signals relibc uses itself, which programs can neither handle nor block
+pub(crate) const RESERVED: sigset_t = 1 << (SIGCANCEL - 1) | 1 << (SIGTIMER - 1);
+
 #[repr(C)]
 #[derive(Clone, Debug)]
 pub struct sigaction {
@@ -46,6 +61,46 @@ pub struct sigaltstack {
     pub ss_size: size_t,
 }
 
+/// Only the fields for SIGCHLD are named, the rest of the kernel's union is
+/// padding
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct siginfo {
+    pub si_signo: c_int,
+    pub si_errno: c_int,
+    pub si_code: c_int,
+    _pad0: c_int,
+    pub si_pid: pid_t,
+    pub si_uid: uid_t,
+    pub si_status: c_int,
+    _pad1: [c_int; 25],
+}
+
+pub type siginfo_t = siginfo;
+
+pub const SIGEV_SIGNAL: c_int = 0;
+pub const SIGEV_NONE: c_int = 1;
+pub const SIGEV_THREAD: c_int = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union sigval {
+    pub sival_int: c_int,
+    pub sival_ptr: *mut c_void,
+}
+
+/// Laid out like the kernel's, which pads it to 64 bytes
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sigevent {
+    pub sigev_value: sigval,
+    pub sigev_signo: c_int,
+    pub sigev_notify: c_int,
+    pub sigev_notify_function: Option<extern "C" fn(sigval)>,
+    pub sigev_notify_attributes: *mut c_void,
+    _pad: [c_int; 8],
+}
+
 pub type sigset_t = c_ulong;
 
 pub type stack_t = sigaltstack;
@@ -85,6 +140,19 @@ pub unsafe extern "C" fn sigaction(
     sig: c_int,
     act: *const sigaction,
     oact: *mut sigaction,
+) -> c_int {
+    if sig == SIGCANCEL || sig == SIGTIMER {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    sigaction_unchecked(sig, act, oact)
+}
+
+/// `sigaction` without the check for reserved signals, for relibc itself
+pub(crate) unsafe fn sigaction_unchecked(
+    sig: c_int,
+    act: *const sigaction,
+    oact: *mut sigaction,
 ) -> c_int {
     let act_opt = act.as_ref().map(|act| {
         let mut act_clone = act.clone();
@@ -113,11 +181,13 @@ pub extern "C" fn sigaddset(set: *mut sigset_t, signo: c_int) -> c_int {
 #[no_mangle]
 pub unsafe extern "C" fn sigaltstack(ss: *const stack_t, old_ss: *mut stack_t) -> c_int {
     if !ss.is_null() {
-        if (*ss).ss_flags != SS_DISABLE as c_int {
-            return errno::EINVAL;
+        if (*ss).ss_flags & !(SS_DISABLE as c_int) != 0 {
+            platform::errno = errno::EINVAL;
+            return -1;
         }
-        if (*ss).ss_size < MINSIGSTKSZ {
-            return errno::ENOMEM;
+        if (*ss).ss_flags & SS_DISABLE as c_int == 0 && (*ss).ss_size < MINSIGSTKSZ {
+            platform::errno = errno::ENOMEM;
+            return -1;
         }
     }
 
@@ -223,7 +293,20 @@ pub extern "C" fn sigpending(set: *mut sigset_t) -> c_int {
 
 #[no_mangle]
 pub extern "C" fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
-    Sys::sigprocmask(how, set, oset)
+    // Blocking or unblocking the reserved signals is silently ignored, so they
+    // keep their current state when the whole mask is set
+    let set = match unsafe { set.as_ref() } {
+        Some(&set) if how == SIG_SETMASK => {
+            let mut current: sigset_t = 0;
+            if Sys::sigprocmask(SIG_BLOCK, ptr::null(), &mut current) < 0 {
+                return -1;
+            }
+            Some(set & !RESERVED | current & RESERVED)
+        }
+        Some(&set) => Some(set & !RESERVED),
+        None => None,
+    };
+    Sys::sigprocmask(how, set.as_ref().map_or(ptr::null(), |set| set), oset)
 }
 
 // #[no_mangle]
@@ -0,0 +1,591 @@
+//! spawn implementation for Redox, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/spawn.h.html
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{mem, ptr};
+
+use crate::{
+    c_str::{CStr, CString},
+    header::{
+        errno, fcntl, signal,
+        signal::{sigset_t, NSIG, SIGKILL, SIGSTOP},
+        unistd,
+    },
+    platform::{self, types::*, Pal, Sys},
+};
+
+pub const POSIX_SPAWN_RESETIDS: c_short = 0x01;
+pub const POSIX_SPAWN_SETPGROUP: c_short = 0x02;
+pub const POSIX_SPAWN_SETSIGDEF: c_short = 0x04;
+pub const POSIX_SPAWN_SETSIGMASK: c_short = 0x08;
+pub const POSIX_SPAWN_SETSCHEDPARAM: c_short = 0x10;
+pub const POSIX_SPAWN_SETSCHEDULER: c_short = 0x20;
+
+const ALL_FLAGS: c_short = POSIX_SPAWN_RESETIDS
+    | POSIX_SPAWN_SETPGROUP
+    | POSIX_SPAWN_SETSIGDEF
+    | POSIX_SPAWN_SETSIGMASK
+    | POSIX_SPAWN_SETSCHEDPARAM
+    | POSIX_SPAWN_SETSCHEDULER;
+
+// sched_param is defined in the sched.h of pthreads-emb
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct sched_param {
+    pub sched_priority: c_int,
+}
+
+enum FileAction {
+    Open {
+        fd: c_int,
+        path: CString,
+        oflag: c_int,
+        mode: mode_t,
+    },
+    Close(c_int),
+    Dup2(c_int, c_int),
+}
+
+#[repr(C)]
+pub struct posix_spawn_file_actions_t {
+    // A Box<Vec<FileAction>>, null until the first action is added
+    __actions: *mut c_void,
+}
+
+#[repr(C)]
+pub struct posix_spawnattr_t {
+    __flags: c_short,
+    __pgroup: pid_t,
+    __sigdefault: sigset_t,
+    __sigmask: sigset_t,
+    __schedpolicy: c_int,
+    __schedparam: sched_param,
+}
+
+unsafe fn add_action(file_actions: *mut posix_spawn_file_actions_t, action: FileAction) -> c_int {
+    let file_actions = &mut *file_actions;
+    if file_actions.__actions.is_null() {
+        file_actions.__actions = Box::into_raw(Box::new(Vec::<FileAction>::new())) as *mut c_void;
+    }
+    (*(file_actions.__actions as *mut Vec<FileAction>)).push(action);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_init(
+    file_actions: *mut posix_spawn_file_actions_t,
+) -> c_int {
+    (*file_actions).__actions = ptr::null_mut();
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_destroy(
+    file_actions: *mut posix_spawn_file_actions_t,
+) -> c_int {
+    let actions = mem::replace(&mut (*file_actions).__actions, ptr::null_mut());
+    if !actions.is_null() {
+        drop(Box::from_raw(actions as *mut Vec<FileAction>));
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addopen(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fd: c_int,
+    path: *const c_char,
+    oflag: c_int,
+    mode: mode_t,
+) -> c_int {
+    if fd < 0 {
+        return errno::EBADF;
+    }
+    // The path is copied, so the caller may free it before spawning
+    let path = CStr::from_ptr(path).to_owned();
+    add_action(
+        file_actions,
+        FileAction::Open {
+            fd,
+            path,
+            oflag,
+            mode,
+        },
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addclose(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fd: c_int,
+) -> c_int {
+    if fd < 0 {
+        return errno::EBADF;
+    }
+    add_action(file_actions, FileAction::Close(fd))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_adddup2(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fd: c_int,
+    newfd: c_int,
+) -> c_int {
+    if fd < 0 || newfd < 0 {
+        return errno::EBADF;
+    }
+    add_action(file_actions, FileAction::Dup2(fd, newfd))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_init(attr: *mut posix_spawnattr_t) -> c_int {
+    ptr::write(
+        attr,
+        posix_spawnattr_t {
+            __flags: 0,
+            __pgroup: 0,
+            __sigdefault: 0,
+            __sigmask: 0,
+            __schedpolicy: 0,
+            __schedparam: sched_param::default(),
+        },
+    );
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn posix_spawnattr_destroy(_attr: *mut posix_spawnattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getflags(
+    attr: *const posix_spawnattr_t,
+    flags: *mut c_short,
+) -> c_int {
+    *flags = (*attr).__flags;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setflags(
+    attr: *mut posix_spawnattr_t,
+    flags: c_short,
+) -> c_int {
+    if flags & !ALL_FLAGS != 0 {
+        return errno::EINVAL;
+    }
+    (*attr).__flags = flags;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getpgroup(
+    attr: *const posix_spawnattr_t,
+    pgroup: *mut pid_t,
+) -> c_int {
+    *pgroup = (*attr).__pgroup;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setpgroup(
+    attr: *mut posix_spawnattr_t,
+    pgroup: pid_t,
+) -> c_int {
+    (*attr).__pgroup = pgroup;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getsigdefault(
+    attr: *const posix_spawnattr_t,
+    sigdefault: *mut sigset_t,
+) -> c_int {
+    *sigdefault = (*attr).__sigdefault;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setsigdefault(
+    attr: *mut posix_spawnattr_t,
+    sigdefault: *const sigset_t,
+) -> c_int {
+    (*attr).__sigdefault = *sigdefault;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getsigmask(
+    attr: *const posix_spawnattr_t,
+    sigmask: *mut sigset_t,
+) -> c_int {
+    *sigmask = (*attr).__sigmask;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setsigmask(
+    attr: *mut posix_spawnattr_t,
+    sigmask: *const sigset_t,
+) -> c_int {
+    (*attr).__sigmask = *sigmask;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getschedpolicy(
+    attr: *const posix_spawnattr_t,
+    policy: *mut c_int,
+) -> c_int {
+    *policy = (*attr).__schedpolicy;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setschedpolicy(
+    attr: *mut posix_spawnattr_t,
+    policy: c_int,
+) -> c_int {
+    (*attr).__schedpolicy = policy;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getschedparam(
+    attr: *const posix_spawnattr_t,
+    param: *mut sched_param,
+) -> c_int {
+    *param = (*attr).__schedparam;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setschedparam(
+    attr: *mut posix_spawnattr_t,
+    param: *const sched_param,
+) -> c_int {
+    (*attr).__schedparam = *param;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn(
+    pid: *mut pid_t,
+    path: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    spawn(pid, path, file_actions, attr, argv, envp, false)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnp(
+    pid: *mut pid_t,
+    file: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    spawn(pid, file, file_actions, attr, argv, envp, true)
+}
+
+/// The highest descriptor `file_actions` use, or -1
+unsafe fn max_action_fd(file_actions: *const posix_spawn_file_actions_t) -> c_int {
+    let actions = file_actions
+        .as_ref()
+        .and_then(|file_actions| (file_actions.__actions as *const Vec<FileAction>).as_ref());
+    let actions = match actions {
+        Some(actions) => actions,
+        None => return -1,
+    };
+    actions
+        .iter()
+        .map(|action| match *action {
+            FileAction::Open { fd, .. } | FileAction::Close(fd) => fd,
+            FileAction::Dup2(fd, newfd) => fd.max(newfd),
+        })
+        .max()
+        .unwrap_or(-1)
+}
+
+/// Everything the child needs, shared with it through its stack
+struct SpawnArgs<'a> {
+    programs: &'a [CString],
+    shell_argv: &'a mut [*mut c_char],
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    search: bool,
+    mask: sigset_t,
+    max_fd: c_int,
+    fds: [c_int; 2],
+}
+
+/// Size of the child's stack, which only has to last until exec
+const CHILD_STACK_SIZE: usize = 64 * 1024;
+
+/// Start a child that execs `path`, like vfork. The child reports an error in
+/// setting itself up or in exec by writing errno to a close-on-exec pipe, so
+/// the parent can tell a successful exec, which closes the pipe, from a
+/// failure.
+///
+/// The child may share the parent's memory until it execs, so anything it
+/// needs is allocated beforehand, and it must not run the parent's signal
+/// handlers.
+unsafe fn spawn(
+    pid: *mut pid_t,
+    path: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    search: bool,
+) -> c_int {
+    let errno_backup = platform::errno;
+
+    // With posix_spawnp, PATH is searched here, and the child tries each
+    // program found
+    let programs = if search {
+        unistd::search_path(CStr::from_ptr(path))
+    } else {
+        vec![CStr::from_ptr(path).to_owned()]
+    };
+    let mut shell_argv = unistd::shell_argv(argv);
+    let mut stack = vec![0usize; CHILD_STACK_SIZE / mem::size_of::<usize>()];
+
+    let mut fds = [0; 2];
+    if Sys::pipe2(&mut fds, fcntl::O_CLOEXEC) < 0 {
+        let err = platform::errno;
+        platform::errno = errno_backup;
+        return err;
+    }
+
+    // Block every signal, including those reserved by relibc, until the
+    // child has reset the handlers it inherited
+    let all: sigset_t = !0;
+    let mut old_mask: sigset_t = 0;
+    Sys::sigprocmask(signal::SIG_BLOCK, &all, &mut old_mask);
+
+    let mut args = SpawnArgs {
+        programs: &programs,
+        shell_argv: &mut shell_argv,
+        file_actions,
+        attr,
+        argv,
+        envp,
+        search,
+        mask: old_mask,
+        max_fd: max_action_fd(file_actions),
+        fds,
+    };
+
+    let mut sp = (stack.as_mut_ptr().add(stack.len()) as usize & !15) as *mut usize;
+    {
+        let mut push = |value: usize| {
+            sp = sp.offset(-1);
+            *sp = value;
+        };
+
+        // The entry point and its register arguments, which leave the stack
+        // 128-bit aligned once the child has popped them
+        for _ in 0..5 {
+            push(0);
+        }
+        push(&mut args as *mut SpawnArgs as usize);
+        push(spawn_entry as usize);
+    }
+
+    let child = Sys::spawn_clone(sp);
+    let clone_errno = platform::errno;
+    Sys::sigprocmask(signal::SIG_SETMASK, &old_mask, ptr::null_mut());
+    Sys::close(fds[1]);
+
+    let ret = if child < 0 {
+        clone_errno
+    } else {
+        let mut buf = [0; mem::size_of::<c_int>()];
+        if Sys::read(fds[0], &mut buf) == buf.len() as ssize_t {
+            // Reap the child, which exited without running the program
+            Sys::waitpid(child, ptr::null_mut(), 0);
+            c_int::from_ne_bytes(buf)
+        } else {
+            if !pid.is_null() {
+                *pid = child;
+            }
+            0
+        }
+    };
+    Sys::close(fds[0]);
+
+    platform::errno = errno_backup;
+    ret
+}
+
+/// Entry point of the child, which never returns
+extern "C" fn spawn_entry(args: *mut SpawnArgs) {
+    unsafe {
+        let args = &mut *args;
+        Sys::close(args.fds[0]);
+        // Out of the way of the file actions, which could otherwise close or
+        // replace it
+        let mut err_fd = args.fds[1];
+        if err_fd <= args.max_fd {
+            let moved = Sys::fcntl(err_fd, fcntl::F_DUPFD, (args.max_fd + 1) as c_ulong);
+            if moved < 0 || Sys::fcntl(moved, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong) < 0 {
+                Sys::write(err_fd, &platform::errno.to_ne_bytes());
+                Sys::exit(127);
+            }
+            Sys::close(err_fd);
+            err_fd = moved;
+        }
+        let err = spawn_child(
+            args.programs,
+            args.shell_argv,
+            args.file_actions,
+            args.attr,
+            args.argv,
+            args.envp,
+            args.search,
+            args.mask,
+        );
+        Sys::write(err_fd, &err.to_ne_bytes());
+        Sys::exit(127);
+    }
+}
+
+/// Set up the child as described by `file_actions` and `attr` and exec.
+/// Only returns on failure, with the error number.
+unsafe fn spawn_child(
+    programs: &[CString],
+    shell_argv: &mut [*mut c_char],
+    file_actions: *const posix_spawn_file_actions_t,
+    attr: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    search: bool,
+    mut mask: sigset_t,
+) -> c_int {
+    macro_rules! check {
+        ($expr:expr) => {
+            if $expr < 0 {
+                return platform::errno;
+            }
+        };
+    }
+
+    // A handler could run as soon as signals are unblocked, in memory shared
+    // with the parent, so every caught signal is reset to its default action,
+    // which exec would do anyway. Those in POSIX_SPAWN_SETSIGDEF are reset
+    // even if they are ignored.
+    let sigdefault = match attr.as_ref() {
+        Some(attr) if attr.__flags & POSIX_SPAWN_SETSIGDEF != 0 => attr.__sigdefault,
+        _ => 0,
+    };
+    let default = signal::sigaction {
+        sa_handler: None,
+        sa_flags: 0,
+        sa_restorer: None,
+        sa_mask: 0,
+    };
+    for sig in 1..=NSIG as c_int {
+        if sig == SIGKILL as c_int || sig == SIGSTOP as c_int {
+            continue;
+        }
+        let mut old = default.clone();
+        if Sys::sigaction(sig, None, Some(&mut old)) < 0 {
+            continue;
+        }
+        let handler = old
+            .sa_handler
+            .map_or(signal::SIG_DFL, |handler| handler as usize);
+        if handler != signal::SIG_DFL
+            && (handler != signal::SIG_IGN || sigdefault & (1 << (sig - 1)) != 0)
+        {
+            check!(Sys::sigaction(sig, Some(&default), None));
+        }
+    }
+
+    if let Some(attr) = attr.as_ref() {
+        let flags = attr.__flags;
+
+        if flags & POSIX_SPAWN_SETSIGMASK != 0 {
+            mask = attr.__sigmask;
+        }
+        if flags & POSIX_SPAWN_SETPGROUP != 0 {
+            check!(Sys::setpgid(0, attr.__pgroup));
+        }
+        if flags & POSIX_SPAWN_SETSCHEDULER != 0 {
+            check!(Sys::sched_setscheduler(
+                0,
+                attr.__schedpolicy,
+                &attr.__schedparam
+            ));
+        } else if flags & POSIX_SPAWN_SETSCHEDPARAM != 0 {
+            check!(Sys::sched_setparam(0, &attr.__schedparam));
+        }
+        if flags & POSIX_SPAWN_RESETIDS != 0 {
+            check!(Sys::setregid(!0, Sys::getgid()));
+            check!(Sys::setreuid(!0, Sys::getuid()));
+        }
+    }
+
+    if let Some(file_actions) = file_actions.as_ref() {
+        if let Some(actions) = (file_actions.__actions as *const Vec<FileAction>).as_ref() {
+            for action in actions {
+                match *action {
+                    FileAction::Open {
+                        fd,
+                        ref path,
+                        oflag,
+                        mode,
+                    } => {
+                        let opened = Sys::open(path, oflag, mode);
+                        check!(opened);
+                        if opened != fd {
+                            check!(Sys::dup2(opened, fd));
+                            Sys::close(opened);
+                        }
+                    }
+                    FileAction::Close(fd) => {
+                        // Closing a descriptor that is not open is allowed
+                        if Sys::close(fd) < 0 && platform::errno != errno::EBADF {
+                            return platform::errno;
+                        }
+                    }
+                    FileAction::Dup2(fd, newfd) => {
+                        if fd == newfd {
+                            // The descriptor is inherited, so clear close-on-exec
+                            check!(Sys::fcntl(fd, fcntl::F_SETFD, 0));
+                        } else {
+                            check!(Sys::dup2(fd, newfd));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Everything was blocked for the clone, including the reserved signals
+    // signal::sigprocmask leaves alone, which the new program may use
+    check!(Sys::sigprocmask(
+        signal::SIG_SETMASK,
+        &(mask & !signal::RESERVED),
+        ptr::null_mut()
+    ));
+
+    let envp = if envp.is_null() {
+        platform::environ as *const *mut c_char
+    } else {
+        envp
+    };
+    if search {
+        unistd::execve_first(programs, argv, envp, shell_argv);
+    } else {
+        Sys::execve(&programs[0], argv, envp);
+    }
+    platform::errno
+}
@@ -1,4 +1,4 @@
-use crate::platform::types::*;
+use crate::{header::wchar::mbstate_t, platform::types::*};
 
 pub const EOF: c_int = -1;
 pub const BUFSIZ: c_int = 1024;
@@ -33,5 +33,11 @@ pub const TMP_MAX: int32_t = 2_147_483_647;
 //      string constants in any form AFAICT
 pub const P_tmpdir: &[u8; 5] = b"/tmp\0";
 
+/// A stream position, along with the conversion state of a wide stream there
 #[allow(non_camel_case_types)]
-pub type fpos_t = off_t;
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct fpos_t {
+    pub __off: off_t,
+    pub __state: mbstate_t,
+}
@@ -1,7 +1,7 @@
 use super::{constants, Buffer, BUFSIZ, FILE};
 use core::{cell::UnsafeCell, ptr};
 
-use crate::{fs::File, io::LineWriter, platform::types::*, sync::Mutex};
+use crate::{fs::File, header::wchar::mbstate_t, io::LineWriter, platform::types::*, sync::Mutex};
 use alloc::vec::Vec;
 
 pub struct GlobalFile(UnsafeCell<FILE>);
@@ -23,6 +23,7 @@ impl GlobalFile {
             pid: None,
 
             orientation: 0,
+            mbstate: mbstate_t::new(),
         }))
     }
     pub fn get(&self) -> *mut FILE {
@@ -3,6 +3,22 @@ use crate::{
     platform::types::*,
 };
 
+/// Size of the read buffer
+#[no_mangle]
+pub extern "C" fn __fbufsize(stream: *mut FILE) -> size_t {
+    let stream = unsafe { &mut *stream }.lock();
+
+    stream.read_buf.len() as size_t
+}
+
+/// Output is always line buffered, so this is whether the stream is writable
+#[no_mangle]
+pub extern "C" fn __flbf(stream: *mut FILE) -> c_int {
+    let stream = unsafe { &mut *stream }.lock();
+
+    (stream.flags & F_NOWR == 0) as c_int
+}
+
 #[no_mangle]
 pub extern "C" fn __fpending(stream: *mut FILE) -> size_t {
     let stream = unsafe { &mut *stream }.lock();
@@ -10,6 +26,25 @@ pub extern "C" fn __fpending(stream: *mut FILE) -> size_t {
     stream.writer.inner.buf.len() as size_t
 }
 
+#[no_mangle]
+pub extern "C" fn __freadahead(stream: *mut FILE) -> size_t {
+    let stream = unsafe { &mut *stream }.lock();
+
+    stream.read_ahead() as size_t
+}
+
+/// Discard buffered data, both output that was not written yet and input that
+/// was not read yet, including pushed back characters
+#[no_mangle]
+pub extern "C" fn __fpurge(stream: *mut FILE) {
+    let mut stream = unsafe { &mut *stream }.lock();
+
+    stream.writer.inner.buf.clear();
+    stream.read_pos = 0;
+    stream.read_size = 0;
+    stream.unget.clear();
+}
+
 #[no_mangle]
 pub extern "C" fn __freadable(stream: *mut FILE) -> c_int {
     let stream = unsafe { &mut *stream }.lock();
@@ -39,3 +74,10 @@ pub extern "C" fn __fwriting(stream: *mut FILE) -> c_int {
 
     (stream.flags & F_NOWR == 0) as c_int
 }
+
+/// BSD name of `__fpurge`
+#[no_mangle]
+pub extern "C" fn fpurge(stream: *mut FILE) -> c_int {
+    __fpurge(stream);
+    0
+}
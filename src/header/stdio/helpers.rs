@@ -1,9 +1,9 @@
 use alloc::boxed::Box;
 
-use super::{constants::*, Buffer, FILE};
+use super::{constants::*, Buffer, FILE, OPEN_FILES};
 use crate::{
     fs::File,
-    header::{errno, fcntl::*, string::strchr},
+    header::{errno, fcntl::*, string::strchr, wchar::mbstate_t},
     io::LineWriter,
     platform::{self, types::*},
     sync::Mutex,
@@ -50,13 +50,13 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
     }
 
     if !strchr(mode, b'e' as i32).is_null() {
-        sys_fcntl(fd, F_SETFD, FD_CLOEXEC);
+        sys_fcntl(fd, F_SETFD, FD_CLOEXEC as c_ulong);
     }
 
     if *mode == 'a' as i8 {
         let f = sys_fcntl(fd, F_GETFL, 0);
         if (f & O_APPEND) == 0 {
-            sys_fcntl(fd, F_SETFL, f | O_APPEND);
+            sys_fcntl(fd, F_SETFL, (f | O_APPEND) as c_ulong);
         }
         flags |= F_APP;
     }
@@ -64,7 +64,7 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
     let file = File::new(fd);
     let writer = LineWriter::new(file.get_ref());
 
-    Some(Box::into_raw(Box::new(FILE {
+    let stream = Box::into_raw(Box::new(FILE {
         lock: Mutex::new(()),
 
         file,
@@ -78,5 +78,8 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
         pid: None,
 
         orientation: 0,
-    })))
+        mbstate: mbstate_t::new(),
+    }));
+    OPEN_FILES.lock().0.push(stream);
+    Some(stream)
 }
@@ -8,6 +8,10 @@ struct LookAheadBuffer {
 impl LookAheadBuffer {
     fn look_ahead(&mut self) -> Result<Option<u8>, i32> {
         let byte = unsafe { *self.buf.offset(self.look_ahead) };
+        // The end of the string is the end of input, like EOF for a file
+        if byte == 0 {
+            return Ok(None);
+        }
         self.look_ahead += 1;
         Ok(Some(byte))
     }
@@ -7,6 +7,7 @@ use alloc::{
 };
 use core::{
     cmp,
+    convert::TryFrom,
     ffi::VaList as va_list,
     fmt::{self, Write as WriteFmt},
     i32, mem,
@@ -16,13 +17,13 @@ use core::{
 
 use crate::{
     c_str::CStr,
-    c_vec::CVec,
     fs::File,
     header::{
         errno::{self, STR_ERROR},
         fcntl, stdlib,
         string::{self, strlen},
         unistd,
+        wchar::mbstate_t,
     },
     io::{self, BufRead, LineWriter, Read, Write},
     platform::{self, errno, types::*, Pal, Sys, WriteByte},
@@ -41,7 +42,7 @@ mod getdelim;
 mod ext;
 mod helpers;
 mod lookaheadreader;
-mod printf;
+pub(crate) mod printf;
 mod scanf;
 use lookaheadreader::LookAheadReader;
 static mut TMPNAM_BUF: [c_char; L_tmpnam as usize + 1] = [0; L_tmpnam as usize + 1];
@@ -69,6 +70,14 @@ impl<'a> DerefMut for Buffer<'a> {
     }
 }
 
+/// Streams opened by `fopen` and friends, so that they can all be flushed by
+/// `fflush(NULL)` and at exit. Doesn't include stdin, stdout and stderr.
+pub(crate) struct OpenFiles(pub(crate) Vec<*mut FILE>);
+// The streams themselves are only accessed through their own locks
+unsafe impl Send for OpenFiles {}
+
+pub(crate) static OPEN_FILES: Mutex<OpenFiles> = Mutex::new(OpenFiles(Vec::new()));
+
 /// This struct gets exposed to the C API.
 pub struct FILE {
     lock: Mutex<()>,
@@ -88,6 +97,7 @@ pub struct FILE {
 
     // wchar support
     pub(crate) orientation: c_int,
+    pub(crate) mbstate: mbstate_t,
 }
 
 impl Read for FILE {
@@ -173,6 +183,12 @@ impl FILE {
         LockGuard(self)
     }
 
+    /// Number of bytes which have been read from the file descriptor, but not
+    /// yet consumed by the user. This includes bytes pushed back by `ungetc`.
+    pub(crate) fn read_ahead(&self) -> usize {
+        self.read_size - self.read_pos + self.unget.len()
+    }
+
     pub fn try_set_orientation(&mut self, mode: c_int) -> c_int {
         let stream = self.lock();
         stream.0.try_set_orientation_unlocked(mode)
@@ -241,8 +257,16 @@ pub extern "C" fn cuserid(_s: *mut c_char) -> *mut c_char {
 /// prior to using this function.
 #[no_mangle]
 pub unsafe extern "C" fn fclose(stream: *mut FILE) -> c_int {
-    let stream = &mut *stream;
+    // The registry is locked before the stream, in the same order as
+    // fflush(NULL), which then can't flush the stream once it is unlocked
+    let perm = (*stream).flags & constants::F_PERM != 0;
+    let open_files = if perm { None } else { Some(OPEN_FILES.lock()) };
     flockfile(stream);
+    if let Some(mut open_files) = open_files {
+        open_files.0.retain(|&file| file != stream);
+    }
+
+    let stream = &mut *stream;
 
     let mut r = stream.flush().is_err();
     let close = Sys::close(*stream.file) < 0;
@@ -284,26 +308,26 @@ pub unsafe extern "C" fn ferror(stream: *mut FILE) -> c_int {
     stream.flags & F_ERR
 }
 
-/// Flush output to stream, or sync read position
+/// Flush output to stream, or to every open stream if `stream` is NULL
 /// Ensure the file is unlocked before calling this function, as it will attempt to lock the file
 /// itself.
 #[no_mangle]
 pub unsafe extern "C" fn fflush(stream: *mut FILE) -> c_int {
     if stream.is_null() {
-        //TODO: flush all files!
-
-        if fflush(stdout) != 0 {
-            return EOF;
+        // Try every stream, even if one of them fails
+        // Locks the registry before each stream, like fclose
+        let mut ret = 0;
+        for &file in OPEN_FILES.lock().0.iter().chain(&[stdout, stderr]) {
+            if fflush(file) != 0 {
+                ret = EOF;
+            }
         }
+        return ret;
+    }
 
-        if fflush(stderr) != 0 {
-            return EOF;
-        }
-    } else {
-        let mut stream = (*stream).lock();
-        if stream.flush().is_err() {
-            return EOF;
-        }
+    let mut stream = (*stream).lock();
+    if stream.flush().is_err() {
+        return EOF;
     }
 
     0
@@ -323,11 +347,15 @@ pub unsafe extern "C" fn fgetc(stream: *mut FILE) -> c_int {
 /// Get the position of the stream and store it in pos
 #[no_mangle]
 pub unsafe extern "C" fn fgetpos(stream: *mut FILE, pos: *mut fpos_t) -> c_int {
-    let off = ftello(stream);
+    let mut stream = (*stream).lock();
+    let off = ftell_locked(&mut *stream);
     if off < 0 {
         return -1;
     }
-    *pos = off;
+    *pos = fpos_t {
+        __off: off,
+        __state: stream.mbstate,
+    };
     0
 }
 
@@ -338,32 +366,37 @@ pub unsafe extern "C" fn fgets(
     max: c_int,
     stream: *mut FILE,
 ) -> *mut c_char {
+    if max < 1 {
+        return ptr::null_mut();
+    }
+
     let mut stream = (*stream).lock();
     if let Err(_) = (*stream).try_set_byte_orientation_unlocked() {
         return ptr::null_mut();
     }
 
     let mut out = original;
-    let max = max as usize;
-    let mut left = max.saturating_sub(1); // Make space for the terminating NUL-byte
+    let mut left = max as usize - 1; // Make space for the terminating NUL-byte
     let mut wrote = false;
+    let mut newline = false;
 
-    if left >= 1 {
-        let unget_read_size = cmp::min(left, stream.unget.len());
-        for _ in 0..unget_read_size {
-            *out = stream.unget.pop().unwrap() as i8;
-            out = out.offset(1);
-        }
-        left -= unget_read_size;
+    // Bytes pushed back by ungetc come first, and may contain the newline
+    while left > 0 && !newline {
+        let c = match stream.unget.pop() {
+            Some(c) => c,
+            None => break,
+        };
+        *out = c as c_char;
+        out = out.add(1);
+        left -= 1;
+        wrote = true;
+        newline = c == b'\n';
     }
 
-    loop {
-        if left == 0 {
-            break;
-        }
-
+    // NUL bytes are copied like any other, only a newline ends the line
+    while left > 0 && !newline {
         // TODO: When NLL is a thing, this block can be flattened out
-        let (read, exit) = {
+        let read = {
             let buf = match stream.fill_buf() {
                 Ok(buf) => buf,
                 Err(_) => return ptr::null_mut(),
@@ -374,29 +407,26 @@ pub unsafe extern "C" fn fgets(
             wrote = true;
             let len = buf.len().min(left);
 
-            let newline = buf[..len].iter().position(|&c| c == b'\n');
-            let len = newline.map(|i| i + 1).unwrap_or(len);
+            let pos = buf[..len].iter().position(|&c| c == b'\n');
+            newline = pos.is_some();
+            let len = pos.map(|i| i + 1).unwrap_or(len);
 
             ptr::copy_nonoverlapping(buf.as_ptr(), out as *mut u8, len);
 
-            (len, newline.is_some())
+            len
         };
 
         stream.consume(read);
 
         out = out.add(read);
         left -= read;
-
-        if exit {
-            break;
-        }
     }
 
-    if max >= 1 {
-        // Write the NUL byte
-        *out = 0;
-    }
-    if wrote {
+    // Write the NUL byte
+    *out = 0;
+
+    // With room for only the NUL byte, nothing has to be read
+    if wrote || max == 1 {
         original
     } else {
         ptr::null_mut()
@@ -441,7 +471,7 @@ pub unsafe extern "C" fn fopen(filename: *const c_char, mode: *const c_char) ->
     }
 
     if flags & fcntl::O_CLOEXEC > 0 {
-        fcntl::sys_fcntl(fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC);
+        fcntl::sys_fcntl(fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong);
     }
 
     if let Some(f) = helpers::_fdopen(fd, mode) {
@@ -515,43 +545,62 @@ pub unsafe extern "C" fn freopen(
     stream: &mut FILE,
 ) -> *mut FILE {
     let mut flags = helpers::parse_mode_flags(mode);
-    flockfile(stream);
-
-    let _ = stream.flush();
-    if filename.is_null() {
-        // Reopen stream in new mode
-        if flags & fcntl::O_CLOEXEC > 0 {
-            fcntl::sys_fcntl(*stream.file, fcntl::F_SETFD, fcntl::FD_CLOEXEC);
-        }
-        flags &= !(fcntl::O_CREAT | fcntl::O_EXCL | fcntl::O_CLOEXEC);
-        if fcntl::sys_fcntl(*stream.file, fcntl::F_SETFL, flags) < 0 {
-            funlockfile(stream);
-            fclose(stream);
-            return ptr::null_mut();
-        }
+    // Before opening, which may truncate the same file
+    fflush(stream);
+
+    // fopen and fclose lock the registry of open streams, which fflush(NULL)
+    // and fclose lock before any stream, so they're called with the stream
+    // unlocked
+    let new = if filename.is_null() {
+        None
     } else {
         let new = fopen(filename, mode);
         if new.is_null() {
-            funlockfile(stream);
             fclose(stream);
             return ptr::null_mut();
         }
-        let new = &mut *new; // Should be safe, new is not null
-        if *new.file == *stream.file {
-            new.file.fd = -1;
-        } else if Sys::dup2(*new.file, *stream.file) < 0
-            || fcntl::sys_fcntl(*stream.file, fcntl::F_SETFL, flags & fcntl::O_CLOEXEC) < 0
-        {
-            funlockfile(stream);
-            fclose(new);
-            fclose(stream);
-            return ptr::null_mut();
+        Some(new)
+    };
+
+    flockfile(stream);
+    match new {
+        None => {
+            // Reopen stream in new mode
+            if flags & fcntl::O_CLOEXEC > 0 {
+                fcntl::sys_fcntl(*stream.file, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong);
+            }
+            flags &= !(fcntl::O_CREAT | fcntl::O_EXCL | fcntl::O_CLOEXEC);
+            if fcntl::sys_fcntl(*stream.file, fcntl::F_SETFL, flags as c_ulong) < 0 {
+                funlockfile(stream);
+                fclose(stream);
+                return ptr::null_mut();
+            }
+        }
+        Some(new) => {
+            let new = &mut *new; // Should be safe, new is not null
+            if *new.file == *stream.file {
+                new.file.fd = -1;
+            } else if Sys::dup2(*new.file, *stream.file) < 0
+                || fcntl::sys_fcntl(
+                    *stream.file,
+                    fcntl::F_SETFL,
+                    (flags & fcntl::O_CLOEXEC) as c_ulong,
+                ) < 0
+            {
+                funlockfile(stream);
+                fclose(new);
+                fclose(stream);
+                return ptr::null_mut();
+            }
+            stream.flags = (stream.flags & constants::F_PERM) | new.flags;
         }
-        stream.flags = (stream.flags & constants::F_PERM) | new.flags;
-        fclose(new);
     }
     stream.orientation = 0;
     funlockfile(stream);
+
+    if let Some(new) = new {
+        fclose(new);
+    }
     stream
 }
 
@@ -561,7 +610,9 @@ pub unsafe extern "C" fn fseek(stream: *mut FILE, offset: c_long, whence: c_int)
     fseeko(stream, offset as off_t, whence)
 }
 
-/// Seek to an offset `offset` from `whence`
+/// Seek to an offset `offset` from `whence`. The file descriptor is seeked
+/// with the full width of `off_t`, so large files can be addressed wherever
+/// `off_t` is 64 bits.
 #[no_mangle]
 pub unsafe extern "C" fn fseeko(stream: *mut FILE, off: off_t, whence: c_int) -> c_int {
     let mut stream = (*stream).lock();
@@ -569,17 +620,18 @@ pub unsafe extern "C" fn fseeko(stream: *mut FILE, off: off_t, whence: c_int) ->
 }
 
 pub unsafe fn fseek_locked(stream: &mut FILE, mut off: off_t, whence: c_int) -> c_int {
-    if whence == SEEK_CUR {
-        // Since it's a buffered writer, our actual cursor isn't where the user
-        // thinks
-        off -= (stream.read_size - stream.read_pos) as off_t;
-    }
-
-    // Flush write buffer before seek
+    // Flush write buffer before seek, so that the file descriptor's offset
+    // includes everything written so far
     if stream.flush().is_err() {
         return -1;
     }
 
+    if whence == SEEK_CUR {
+        // Since it's a buffered reader, our actual cursor isn't where the user
+        // thinks
+        off -= stream.read_ahead() as off_t;
+    }
+
     let err = Sys::lseek(*stream.file, off, whence);
     if err < 0 {
         return err as c_int;
@@ -589,19 +641,31 @@ pub unsafe fn fseek_locked(stream: &mut FILE, mut off: off_t, whence: c_int) ->
     stream.read_pos = 0;
     stream.read_size = 0;
     stream.unget = Vec::new();
+    stream.mbstate = mbstate_t::new();
     0
 }
 
 /// Seek to a position `pos` in the file from the beginning of the file
 #[no_mangle]
 pub unsafe extern "C" fn fsetpos(stream: *mut FILE, pos: *const fpos_t) -> c_int {
-    fseek(stream, *pos, SEEK_SET)
+    let mut stream = (*stream).lock();
+    if fseek_locked(&mut *stream, (*pos).__off, SEEK_SET) < 0 {
+        return -1;
+    }
+    stream.mbstate = (*pos).__state;
+    0
 }
 
 /// Get the current position of the cursor in the file
 #[no_mangle]
 pub unsafe extern "C" fn ftell(stream: *mut FILE) -> c_long {
-    ftello(stream) as c_long
+    match c_long::try_from(ftello(stream)) {
+        Ok(off) => off,
+        Err(_) => {
+            platform::errno = errno::EOVERFLOW;
+            -1
+        }
+    }
 }
 
 /// Get the current position of the cursor in the file
@@ -616,7 +680,9 @@ pub unsafe extern "C" fn ftell_locked(stream: &mut FILE) -> off_t {
         return -1;
     }
 
-    pos - (stream.read_size - stream.read_pos) as off_t - stream.unget.len() as off_t
+    // Buffered writes have not reached the file descriptor yet, while
+    // buffered reads and pushed back bytes are ahead of the user
+    pos + stream.writer.inner.buf.len() as off_t - stream.read_ahead() as off_t
 }
 
 /// Try to lock the file. Returns 0 for success, 1 for failure
@@ -921,6 +987,18 @@ pub unsafe extern "C" fn rename(oldpath: *const c_char, newpath: *const c_char)
     Sys::rename(oldpath, newpath)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn renameat(
+    olddirfd: c_int,
+    oldpath: *const c_char,
+    newdirfd: c_int,
+    newpath: *const c_char,
+) -> c_int {
+    let oldpath = CStr::from_ptr(oldpath);
+    let newpath = CStr::from_ptr(newpath);
+    Sys::renameat(olddirfd, oldpath, newdirfd, newpath)
+}
+
 /// Rewind `stream` back to the beginning of it
 #[no_mangle]
 pub unsafe extern "C" fn rewind(stream: *mut FILE) {
@@ -1060,16 +1138,23 @@ unsafe extern "C" fn tmpnam_inner(buf: *mut c_char, offset: usize) -> *mut c_cha
     }
 }
 
-/// Push character `c` back onto `stream` so it'll be read next
+/// Push character `c` back onto `stream` so it'll be read next. Like glibc,
+/// any number of characters can be pushed back, they are read in reverse
+/// order and are dropped by a seek.
 #[no_mangle]
 pub unsafe extern "C" fn ungetc(c: c_int, stream: *mut FILE) -> c_int {
+    if c == EOF {
+        return EOF;
+    }
+
     let mut stream = (*stream).lock();
     if let Err(_) = (*stream).try_set_byte_orientation_unlocked() {
         return -1;
     }
 
     stream.unget.push(c as u8);
-    c
+    stream.flags &= !F_EOF;
+    c as c_uchar as c_int
 }
 
 #[no_mangle]
@@ -1093,12 +1178,23 @@ pub unsafe extern "C" fn vasprintf(
     format: *const c_char,
     ap: va_list,
 ) -> c_int {
-    let mut alloc_writer = CVec::new();
-    let ret = printf::printf(&mut alloc_writer, format, ap);
-    alloc_writer.push(0).unwrap();
-    alloc_writer.shrink_to_fit().unwrap();
-    *strp = alloc_writer.leak() as *mut c_char;
-    ret
+    // Measure with a copy of the arguments first, so that the string can be
+    // formatted straight into a buffer of the right size
+    let len = ap.with_copy(|ap| vsnprintf(ptr::null_mut(), 0, format, ap));
+    if len < 0 {
+        return -1;
+    }
+
+    let size = len as usize + 1;
+    let buf = platform::alloc(size) as *mut c_char;
+    if buf.is_null() {
+        platform::errno = errno::ENOMEM;
+        return -1;
+    }
+    vsnprintf(buf, size, format, ap);
+
+    *strp = buf;
+    len
 }
 
 #[no_mangle]
@@ -1140,6 +1236,8 @@ pub unsafe extern "C" fn vscanf(format: *const c_char, ap: va_list) -> c_int {
     vfscanf(&mut *stdin, format, ap)
 }
 
+/// Scan with the same scanner as `vfscanf`, reading from `s` instead of a
+/// stream. The NUL byte ends the input like EOF does for a stream.
 #[no_mangle]
 pub unsafe extern "C" fn vsscanf(s: *const c_char, format: *const c_char, ap: va_list) -> c_int {
     let reader = (s as *const u8).into();
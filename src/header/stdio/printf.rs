@@ -7,7 +7,7 @@ use alloc::{
 use core::{char, cmp, f64, ffi::VaList, fmt, num::FpCategory, ops::Range, slice};
 
 use crate::{
-    header::errno::EILSEQ,
+    header::{errno::EILSEQ, string::write_strerror},
     platform::{self, types::*},
 };
 
@@ -39,11 +39,13 @@ enum FmtKind {
     Scientific,
     Decimal,
     AnyNotation,
+    HexFloat,
 
     String,
     Char,
     Pointer,
     GetWritten,
+    Errno,
 }
 #[derive(Clone, Copy, Debug)]
 enum Number {
@@ -102,6 +104,7 @@ impl VaArg {
 
         match (fmtkind, intkind) {
             (FmtKind::Percent, _) => panic!("Can't call arg_from on %"),
+            (FmtKind::Errno, _) => panic!("Can't call arg_from on %m"),
 
             (FmtKind::Char, IntKind::Long) | (FmtKind::Char, IntKind::LongLong) => {
                 VaArg::wint_t(ap.arg::<wint_t>())
@@ -132,9 +135,10 @@ impl VaArg {
                 VaArg::ssize_t(ap.arg::<ssize_t>())
             }
 
-            (FmtKind::AnyNotation, _) | (FmtKind::Decimal, _) | (FmtKind::Scientific, _) => {
-                VaArg::c_double(ap.arg::<c_double>())
-            }
+            (FmtKind::AnyNotation, _)
+            | (FmtKind::Decimal, _)
+            | (FmtKind::Scientific, _)
+            | (FmtKind::HexFloat, _) => VaArg::c_double(ap.arg::<c_double>()),
 
             (FmtKind::GetWritten, _) | (FmtKind::Pointer, _) | (FmtKind::String, _) => {
                 VaArg::pointer(ap.arg::<*const c_void>())
@@ -178,6 +182,7 @@ impl VaArg {
         };
         match (fmtkind, intkind) {
             (FmtKind::Percent, _) => panic!("Can't call transmute on %"),
+            (FmtKind::Errno, _) => panic!("Can't call transmute on %m"),
 
             (FmtKind::Char, IntKind::Long) | (FmtKind::Char, IntKind::LongLong) => {
                 VaArg::wint_t(untyped.wint_t)
@@ -208,9 +213,10 @@ impl VaArg {
                 VaArg::ssize_t(untyped.ssize_t)
             }
 
-            (FmtKind::AnyNotation, _) | (FmtKind::Decimal, _) | (FmtKind::Scientific, _) => {
-                VaArg::c_double(untyped.c_double)
-            }
+            (FmtKind::AnyNotation, _)
+            | (FmtKind::Decimal, _)
+            | (FmtKind::Scientific, _)
+            | (FmtKind::HexFloat, _) => VaArg::c_double(untyped.c_double),
 
             (FmtKind::GetWritten, _) | (FmtKind::Pointer, _) | (FmtKind::String, _) => {
                 VaArg::pointer(untyped.pointer)
@@ -440,6 +446,100 @@ fn fmt_float_normal<W: Write>(
     Ok(string.len())
 }
 
+/// Write a finite float in the hexadecimal notation of %a. The leading digit
+/// is 1 for everything but zero, subnormals included, and rounding to the
+/// precision is to nearest even.
+fn fmt_float_hex<W: Write>(
+    w: &mut W,
+    case: FmtCase,
+    alternate: bool,
+    precision: Option<usize>,
+    float: c_double,
+    left: bool,
+    pad_space: usize,
+    pad_zero: usize,
+) -> io::Result<()> {
+    const MANTISSA_BITS: usize = 52;
+    const MANTISSA_DIGITS: usize = MANTISSA_BITS / 4;
+
+    let bits = float.to_bits();
+    let mut lead: u64 = 1;
+    let mut mantissa = bits & ((1 << MANTISSA_BITS) - 1);
+    let mut exp = ((bits >> MANTISSA_BITS) & 0x7FF) as isize;
+    if exp == 0 {
+        if mantissa == 0 {
+            lead = 0;
+        } else {
+            // Shift the first set bit of a subnormal into the leading digit
+            exp = -1022;
+            while mantissa & (1 << MANTISSA_BITS) == 0 {
+                mantissa <<= 1;
+                exp -= 1;
+            }
+            mantissa &= (1 << MANTISSA_BITS) - 1;
+        }
+    } else {
+        exp -= 1023;
+    }
+
+    let mut digits = MANTISSA_DIGITS;
+    if let Some(precision) = precision.filter(|&p| p < MANTISSA_DIGITS) {
+        let shift = 4 * (MANTISSA_DIGITS - precision);
+        let full = lead << MANTISSA_BITS | mantissa;
+        let rest = full & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        let mut full = full >> shift;
+        if rest > half || (rest == half && full & 1 == 1) {
+            full += 1;
+        }
+        if full >> (4 * precision) > 1 {
+            // Rounding carried into the leading digit, which makes it 2 and
+            // the rest zeros
+            full >>= 1;
+            exp += 1;
+        }
+        lead = full >> (4 * precision);
+        mantissa = full & ((1 << (4 * precision)) - 1);
+        digits = precision;
+    }
+
+    let mut fraction = match case {
+        FmtCase::Lower => format!("{:01$x}", mantissa, digits),
+        FmtCase::Upper => format!("{:01$X}", mantissa, digits),
+    };
+    fraction.truncate(digits);
+    match precision {
+        Some(precision) => {
+            while fraction.len() < precision {
+                fraction.push('0');
+            }
+        }
+        None => fraction.truncate(fraction.trim_end_matches('0').len()),
+    }
+
+    let (prefix, exp_char) = match case {
+        FmtCase::Lower => ("0x", 'p'),
+        FmtCase::Upper => ("0X", 'P'),
+    };
+    let dot = if alternate || !fraction.is_empty() {
+        "."
+    } else {
+        ""
+    };
+    let rest = format!("{}{}{}{}{:+}", lead, dot, fraction, exp_char, exp);
+    let sign = if float.is_sign_negative() { "-" } else { "" };
+    let len = sign.len() + prefix.len() + rest.len();
+
+    pad(w, !left, b' ', len..pad_space)?;
+    w.write_all(sign.as_bytes())?;
+    w.write_all(prefix.as_bytes())?;
+    pad(w, true, b'0', len..pad_zero)?;
+    w.write_all(rest.as_bytes())?;
+    pad(w, left, b' ', len..pad_space)?;
+
+    Ok(())
+}
+
 /// Write ±infinity or ±NaN representation for any floating-point style
 fn fmt_float_nonfinite<W: Write>(w: &mut W, float: c_double, case: FmtCase) -> io::Result<()> {
     if float.is_sign_negative() {
@@ -583,10 +683,12 @@ impl Iterator for PrintfIter {
                 b'e' | b'E' => FmtKind::Scientific,
                 b'f' | b'F' => FmtKind::Decimal,
                 b'g' | b'G' => FmtKind::AnyNotation,
+                b'a' | b'A' => FmtKind::HexFloat,
                 b's' => FmtKind::String,
                 b'c' => FmtKind::Char,
                 b'p' => FmtKind::Pointer,
                 b'n' => FmtKind::GetWritten,
+                b'm' => FmtKind::Errno,
                 _ => return Some(Err(())),
             };
             self.format = self.format.add(1);
@@ -608,8 +710,15 @@ impl Iterator for PrintfIter {
     }
 }
 
-unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) -> io::Result<c_int> {
+unsafe fn inner_printf<W: Write>(
+    w: W,
+    format: *const c_char,
+    mut ap: VaList,
+    wide: bool,
+) -> io::Result<c_int> {
     let w = &mut platform::CountingWriter::new(w);
+    // %m is the error from before the call, not one from writing
+    let errno = platform::errno;
 
     let iterator = PrintfIter {
         format: format as *const u8,
@@ -638,6 +747,9 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                 Number::Static(_) => (),
             }
         }
+        if arg.fmtkind == FmtKind::Errno {
+            continue;
+        }
         match arg.index {
             Some(i) => {
                 positional.insert(i - 1, (arg.fmtkind, arg.intkind));
@@ -685,13 +797,13 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
         let fmt = arg.fmt;
         let fmtkind = arg.fmtkind;
         let fmtcase = match fmt {
-            b'x' | b'f' | b'e' | b'g' => Some(FmtCase::Lower),
-            b'X' | b'F' | b'E' | b'G' => Some(FmtCase::Upper),
+            b'x' | b'f' | b'e' | b'g' | b'a' => Some(FmtCase::Lower),
+            b'X' | b'F' | b'E' | b'G' | b'A' => Some(FmtCase::Upper),
             _ => None,
         };
 
         let index = arg.index.map(|i| i - 1).unwrap_or_else(|| {
-            if fmtkind == FmtKind::Percent {
+            if fmtkind == FmtKind::Percent || fmtkind == FmtKind::Errno {
                 0
             } else {
                 let i = varargs.i;
@@ -702,6 +814,15 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
 
         match fmtkind {
             FmtKind::Percent => w.write_all(&[b'%'])?,
+            FmtKind::Errno => {
+                let mut string = String::new();
+                write_strerror(errno, &mut string);
+                let len = precision.map_or(string.len(), |max| max.min(string.len()));
+
+                pad(w, !left, b' ', len..pad_space)?;
+                w.write_all(&string.as_bytes()[..len])?;
+                pad(w, left, b' ', len..pad_space)?;
+            }
             FmtKind::Signed => {
                 let string = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
                     VaArg::c_char(i) => i.to_string(),
@@ -866,6 +987,26 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                     fmt_float_nonfinite(w, float, fmtcase.unwrap())?;
                 }
             }
+            FmtKind::HexFloat => {
+                let float = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
+                    VaArg::c_double(i) => i,
+                    _ => panic!("this should not be possible"),
+                };
+                if float.is_finite() {
+                    fmt_float_hex(
+                        w,
+                        fmtcase.unwrap(),
+                        alternate,
+                        precision,
+                        float,
+                        left,
+                        pad_space,
+                        pad_zero,
+                    )?;
+                } else {
+                    fmt_float_nonfinite(w, float, fmtcase.unwrap())?;
+                }
+            }
             FmtKind::String => {
                 let ptr = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
                     VaArg::pointer(p) => p,
@@ -875,12 +1016,15 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                 if ptr.is_null() {
                     w.write_all(b"(null)")?;
                 } else {
+                    // Widths and precisions count bytes, or characters when
+                    // formatting for the wide functions
                     let max = precision.unwrap_or(::core::usize::MAX);
 
                     if intkind == IntKind::Long || intkind == IntKind::LongLong {
                         // Handle wchar_t
                         let mut ptr = ptr as *const wchar_t;
                         let mut string = String::new();
+                        let mut len = 0;
 
                         while *ptr != 0 {
                             let c = match char::from_u32(*ptr as _) {
@@ -890,24 +1034,36 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                                     return Err(io::last_os_error());
                                 }
                             };
-                            if string.len() + c.len_utf8() >= max {
+                            let size = if wide { 1 } else { c.len_utf8() };
+                            if len + size > max {
                                 break;
                             }
                             string.push(c);
+                            len += size;
                             ptr = ptr.add(1);
                         }
 
-                        pad(w, !left, b' ', string.len()..pad_space)?;
+                        pad(w, !left, b' ', len..pad_space)?;
                         w.write_all(string.as_bytes())?;
-                        pad(w, left, b' ', string.len()..pad_space)?;
+                        pad(w, left, b' ', len..pad_space)?;
                     } else {
+                        // For the wide functions, the string is decoded as
+                        // UTF-8 afterwards, so continuation bytes are not
+                        // counted
+                        let mut size = 0;
                         let mut len = 0;
-                        while *ptr.add(len) != 0 && len < max {
-                            len += 1;
+                        while *ptr.add(size) != 0 {
+                            if !wide || *ptr.add(size) as u8 & 0xC0 != 0x80 {
+                                if len == max {
+                                    break;
+                                }
+                                len += 1;
+                            }
+                            size += 1;
                         }
 
                         pad(w, !left, b' ', len..pad_space)?;
-                        w.write_all(slice::from_raw_parts(ptr as *const u8, len))?;
+                        w.write_all(slice::from_raw_parts(ptr as *const u8, size))?;
                         pad(w, left, b' ', len..pad_space)?;
                     }
                 }
@@ -982,5 +1138,11 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
 }
 
 pub unsafe fn printf<W: Write>(w: W, format: *const c_char, ap: VaList) -> c_int {
-    inner_printf(w, format, ap).unwrap_or(-1)
+    inner_printf(w, format, ap, false).unwrap_or(-1)
+}
+
+/// `printf` for the wide functions, with a UTF-8 format. Field widths and
+/// precisions of strings count characters instead of bytes.
+pub unsafe fn printf_wide<W: Write>(w: W, format: *const c_char, ap: VaList) -> c_int {
+    inner_printf(w, format, ap, true).unwrap_or(-1)
 }
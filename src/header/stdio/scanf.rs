@@ -1,5 +1,5 @@
 use super::lookaheadreader::LookAheadReader;
-use crate::platform::types::*;
+use crate::{header::stdlib::strtod, platform::types::*};
 use alloc::{string::String, vec::Vec};
 use core::ffi::VaList as va_list;
 
@@ -26,6 +26,16 @@ unsafe fn next_byte(string: &mut *const c_char) -> Result<u8, c_int> {
     }
 }
 
+/// Convert text matched by %a, %e, %f or %g, which is a decimal or
+/// hexadecimal float, or an infinity or NaN. All of the text has to be part of
+/// the number.
+fn parse_float<F: strtod::Float>(n: &str) -> Option<F> {
+    match strtod::parse::<F>(n.as_bytes()) {
+        (float, len, _) if len > 0 && len == n.len() => Some(float),
+        _ => None,
+    }
+}
+
 unsafe fn inner_scanf(
     mut r: LookAheadReader,
     mut format: *const c_char,
@@ -161,8 +171,99 @@ unsafe fn inner_scanf(
                         return Ok(matched);
                     }
                 }
-                b'd' | b'i' | b'o' | b'u' | b'x' | b'X' | b'f' | b'e' | b'g' | b'E' | b'a'
-                | b'p' => {
+                b'a' | b'A' | b'e' | b'E' | b'f' | b'F' | b'g' | b'G' => {
+                    while (byte as char).is_whitespace() {
+                        if !read!() {
+                            return Ok(matched);
+                        }
+                    }
+
+                    let mut n = String::new();
+                    // Whether byte has been read but not taken yet
+                    let mut more = true;
+
+                    macro_rules! take {
+                        () => {{
+                            n.push(byte as char);
+                            r.commit();
+                            width = width.map(|w| w - 1);
+                            more = width.map(|w| w > 0).unwrap_or(true);
+                            if more && !read!() {
+                                eof = true;
+                                more = false;
+                            }
+                        }};
+                    }
+
+                    if more && (byte == b'+' || byte == b'-') {
+                        take!();
+                    }
+                    let sign_len = n.len();
+
+                    let mut hex = false;
+                    if more && byte == b'0' {
+                        take!();
+                        if more && (byte == b'x' || byte == b'X') {
+                            hex = true;
+                            take!();
+                        }
+                    }
+
+                    let radix = if hex { 16 } else { 10 };
+                    let mut dot = false;
+                    while more && ((byte as char).is_digit(radix) || (!dot && byte == b'.')) {
+                        if byte == b'.' {
+                            dot = true;
+                        }
+                        take!();
+                    }
+
+                    let exp_char = if hex { b'p' } else { b'e' };
+                    if more && n.len() > sign_len && byte | 0x20 == exp_char {
+                        take!();
+                        if more && (byte == b'+' || byte == b'-') {
+                            take!();
+                        }
+                        while more && byte.is_ascii_digit() {
+                            take!();
+                        }
+                    }
+
+                    // Infinity and NaN, as long as the letters could still
+                    // spell one of them
+                    if n.len() == sign_len {
+                        while more {
+                            let mut word = n[sign_len..].to_ascii_lowercase();
+                            word.push((byte as char).to_ascii_lowercase());
+                            if !"infinity".starts_with(&word) && !"nan".starts_with(&word) {
+                                break;
+                            }
+                            take!();
+                        }
+                    }
+
+                    if kind == IntKind::Long || kind == IntKind::LongLong {
+                        let float = match parse_float::<c_double>(&n) {
+                            Some(float) => float,
+                            None => return Ok(matched),
+                        };
+                        if !ignore {
+                            *ap.arg::<*mut c_double>() = float;
+                        }
+                    } else {
+                        let float = match parse_float::<c_float>(&n) {
+                            Some(float) => float,
+                            None => return Ok(matched),
+                        };
+                        if !ignore {
+                            *ap.arg::<*mut c_float>() = float;
+                        }
+                    }
+                    if !ignore {
+                        matched += 1;
+                    }
+                }
+                b'd' | b'i' | b'o' | b'u' | b'x' | b'X' | b'p' => {
                     while (byte as char).is_whitespace() {
                         if !read!() {
                             return Ok(matched);
@@ -172,7 +273,6 @@ unsafe fn inner_scanf(
                     let pointer = c == b'p';
                     // Pointers aren't automatic, but we do want to parse "0x"
                     let auto = c == b'i' || pointer;
-                    let float = c == b'f' || c == b'e' || c == b'g' || c == b'E' || c == b'a';
 
                     let mut radix = match c {
                         b'o' => 8,
@@ -181,12 +281,10 @@ unsafe fn inner_scanf(
                     };
 
                     let mut n = String::new();
-                    let mut dot = false;
 
                     while width.map(|w| w > 0).unwrap_or(true)
                         && ((byte >= b'0' && byte <= b'7')
                             || (radix >= 10 && (byte >= b'8' && byte <= b'9'))
-                            || (float && !dot && byte == b'.')
                             || (radix == 16
                                 && ((byte >= b'a' && byte <= b'f')
                                     || (byte >= b'A' && byte <= b'F'))))
@@ -199,9 +297,12 @@ unsafe fn inner_scanf(
                             if !pointer {
                                 radix = 8;
                             }
+                            n.push('0');
+                            r.commit();
                             width = width.map(|w| w - 1);
                             if !read!() {
-                                return Ok(matched);
+                                eof = true;
+                                break;
                             }
                             if width.map(|w| w > 0).unwrap_or(true)
                                 && (byte == b'x' || byte == b'X')
@@ -209,41 +310,29 @@ unsafe fn inner_scanf(
                                 radix = 16;
                                 width = width.map(|w| w - 1);
                                 if width.map(|w| w > 0).unwrap_or(true) && !read!() {
-                                    return Ok(matched);
+                                    eof = true;
+                                    break;
                                 }
                             }
                             continue;
                         }
-                        if byte == b'.' {
-                            // Don't allow another dot
-                            dot = true;
-                        }
                         n.push(byte as char);
                         r.commit();
                         width = width.map(|w| w - 1);
+                        // The number so far is still converted at the end of
+                        // input
                         if width.map(|w| w > 0).unwrap_or(true) && !read!() {
-                            return Ok(matched);
+                            eof = true;
+                            break;
                         }
                     }
 
+                    // Nothing that looks like a number is a matching failure
+                    if n.is_empty() {
+                        return Ok(matched);
+                    }
+
                     macro_rules! parse_type {
-                        (noformat $type:ident) => {{
-                            let n = if n.is_empty() {
-                                0 as $type
-                            } else {
-                                n.parse::<$type>().map_err(|_| 0)?
-                            };
-                            if !ignore {
-                                *ap.arg::<*mut $type>() = n;
-                                matched += 1;
-                            }
-                        }};
-                        (c_double) => {
-                            parse_type!(noformat c_double);
-                        };
-                        (c_float) => {
-                            parse_type!(noformat c_float);
-                        };
                         ($type:ident) => {
                             parse_type!($type, $type);
                         };
@@ -260,13 +349,7 @@ unsafe fn inner_scanf(
                         }};
                     }
 
-                    if float {
-                        if kind == IntKind::Long || kind == IntKind::LongLong {
-                            parse_type!(c_double);
-                        } else {
-                            parse_type!(c_float);
-                        }
-                    } else if c == b'p' {
+                    if c == b'p' {
                         parse_type!(size_t, *mut c_void);
                     } else {
                         let unsigned = c == b'o' || c == b'u' || c == b'x' || c == b'X';
@@ -409,14 +492,14 @@ unsafe fn inner_scanf(
                     let mut ptr: Option<*mut c_char> = if ignore { None } else { Some(ap.arg()) };
 
                     // While we haven't used up all the width, and it matches
-                    let mut data_stored = false;
+                    let mut data_matched = false;
                     while width.map(|w| w > 0).unwrap_or(true) && !invert == matches.contains(&byte)
                     {
                         if let Some(ref mut ptr) = ptr {
                             **ptr = byte as c_char;
                             *ptr = ptr.offset(1);
-                            data_stored = true;
                         }
+                        data_matched = true;
                         r.commit();
                         // Decrease the width, and read a new character unless the width is 0
                         width = width.map(|w| w - 1);
@@ -428,27 +511,30 @@ unsafe fn inner_scanf(
                         }
                     }
 
-                    if data_stored {
-                        *ptr.unwrap() = 0;
+                    // An empty match is a matching failure
+                    if !data_matched {
+                        return Ok(matched);
+                    }
+                    if let Some(ptr) = ptr {
+                        *ptr = 0;
                         matched += 1;
                     }
                 }
                 b'n' => {
+                    // A byte read ahead but not used yet wasn't consumed
                     if !ignore {
-                        *ap.arg::<*mut c_int>() = count as c_int;
+                        *ap.arg::<*mut c_int>() = count - skip_read as c_int;
                     }
                 }
                 _ => return Err(-1),
             }
 
-            if eof {
-                return Ok(matched);
-            }
-
-            if width != Some(0) && c != b'n' {
-                // It didn't hit the width, so an extra character was read and matched.
-                // But this character did not match so let's reuse it.
-                skip_read = true;
+            // At the end of input, carry on with the format anyway, so that a
+            // trailing %n is still stored. The next read fails again.
+            if c != b'n' {
+                // If it didn't hit the width, an extra character was read and
+                // matched. But this character did not match so let's reuse it.
+                skip_read = !eof && width != Some(0);
             }
         }
     }
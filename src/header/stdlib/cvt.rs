@@ -0,0 +1,82 @@
+//! Digit generation for `ecvt`, `fcvt` and `gcvt`. The formatting of floats
+//! in core is exact, so rounding to a number of digits is correct, including
+//! a carry into a new leading digit.
+
+use alloc::string::String;
+use core::iter;
+
+use crate::platform::types::*;
+
+/// Most digits requested from `ecvt` and `fcvt`, so that their results fit in
+/// a static buffer
+pub const NDIGIT_MAX: usize = 340;
+
+/// Split "d.ddde-x" into its digits and exponent
+fn split_exponent(s: &str) -> (String, c_int) {
+    let e = s.find('e').unwrap();
+    let digits = s[..e].chars().filter(|&c| c != '.').collect();
+    (digits, s[e + 1..].parse().unwrap())
+}
+
+/// The `ndigit` most significant digits of the finite and positive `value`,
+/// and the position of the decimal point relative to them
+pub fn significant(value: c_double, ndigit: usize) -> (String, c_int) {
+    if value == 0.0 {
+        (iter::repeat('0').take(ndigit).collect(), 1)
+    } else if ndigit == 0 {
+        let (_, exponent) = split_exponent(&format!("{:e}", value));
+        (String::new(), exponent + 1)
+    } else {
+        let (digits, exponent) = split_exponent(&format!("{:.*e}", ndigit - 1, value));
+        (digits, exponent + 1)
+    }
+}
+
+/// The digits of the finite and positive `value` rounded to `ndigit`
+/// fractional digits, without leading zeros, and the position of the decimal
+/// point relative to them. A value rounded to zero gives `ndigit + 1` zeros.
+pub fn fractional(value: c_double, ndigit: usize) -> (String, c_int) {
+    let s = format!("{:.*}", ndigit, value);
+    let point = s.find('.').unwrap_or(s.len());
+    let digits: String = s.chars().filter(|&c| c != '.').collect();
+    let zeros = digits.len() - digits.trim_start_matches('0').len();
+    if zeros == digits.len() {
+        (digits, 1)
+    } else {
+        (digits[zeros..].into(), point as c_int - zeros as c_int)
+    }
+}
+
+/// Remove the trailing zeros of a fraction, and the point if nothing is left
+fn trim_fraction(s: &str) -> &str {
+    if s.contains('.') {
+        s.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        s
+    }
+}
+
+/// Format `value` with `ndigit` significant digits, like printf's "%.*g"
+pub fn general(value: c_double, ndigit: usize) -> String {
+    if value.is_nan() {
+        return "nan".into();
+    } else if value.is_infinite() {
+        return if value < 0.0 { "-inf" } else { "inf" }.into();
+    }
+
+    let precision = ndigit.max(1);
+    let exponential = format!("{:.*e}", precision - 1, value);
+    let e = exponential.find('e').unwrap();
+    let exponent: c_int = exponential[e + 1..].parse().unwrap();
+    if exponent < -4 || exponent >= precision as c_int {
+        format!(
+            "{}e{}{:02}",
+            trim_fraction(&exponential[..e]),
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        )
+    } else {
+        let fixed = format!("{:.*}", (precision as c_int - 1 - exponent) as usize, value);
+        trim_fraction(&fixed).into()
+    }
+}
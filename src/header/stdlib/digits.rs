@@ -0,0 +1,112 @@
+//! Conversion of runs of decimal and hexadecimal digits eight at a time, by
+//! treating them as a single 64-bit integer. Used by `convert_integer` to
+//! speed up `strtol` and friends for the common bases.
+
+use core::ptr;
+
+use crate::platform::types::*;
+
+const ONES: u64 = 0x0101_0101_0101_0101;
+const HIGH_BITS: u64 = 0x8080_8080_8080_8080;
+
+/// Read eight bytes at `s`, if that stays within the page of `s`. Bytes after
+/// the end of the string may be read, but never from a page that might not
+/// be mapped.
+unsafe fn read_chunk(s: *const c_char) -> Option<[u8; 8]> {
+    if s as usize % 4096 > 4096 - 8 {
+        None
+    } else {
+        Some(ptr::read_unaligned(s as *const [u8; 8]))
+    }
+}
+
+/// Set the high bit of each byte that is at least `min`, bytes must be ASCII
+fn bytes_at_least(chunk: u64, min: u8) -> u64 {
+    chunk.wrapping_add(ONES * (0x80 - min as u64)) & HIGH_BITS
+}
+
+/// Set the high bit of each byte that is at most `max`, bytes must be ASCII
+fn bytes_at_most(chunk: u64, max: u8) -> u64 {
+    !chunk.wrapping_add(ONES * (0x7F - max as u64)) & HIGH_BITS
+}
+
+/// The value of eight decimal digits, read as a little endian integer, if they
+/// all are digits
+fn decimal_chunk(chunk: u64) -> Option<u64> {
+    let digits = (chunk & 0xF0F0_F0F0_F0F0_F0F0)
+        | ((chunk.wrapping_add(0x0606_0606_0606_0606) & 0xF0F0_F0F0_F0F0_F0F0) >> 4);
+    if digits != 0x3333_3333_3333_3333 {
+        return None;
+    }
+
+    // Combine neighbouring digits into pairs, then pairs into fours, and the
+    // two fours into the result
+    let chunk = chunk - 0x3030_3030_3030_3030;
+    let chunk = chunk.wrapping_mul(10).wrapping_add(chunk >> 8);
+    let low = (chunk & 0x0000_00FF_0000_00FF).wrapping_mul(100 + (1_000_000 << 32));
+    let high = ((chunk >> 16) & 0x0000_00FF_0000_00FF).wrapping_mul(1 + (10_000 << 32));
+    Some(low.wrapping_add(high) >> 32)
+}
+
+/// The value of eight hexadecimal digits, read as a big endian integer, if
+/// they all are digits
+fn hex_chunk(chunk: u64) -> Option<u64> {
+    if chunk & HIGH_BITS != 0 {
+        return None;
+    }
+    let lower = chunk | ONES * 0x20;
+    let digits = bytes_at_least(chunk, b'0') & bytes_at_most(chunk, b'9');
+    let letters = bytes_at_least(lower, b'a') & bytes_at_most(lower, b'f');
+    if digits | letters != HIGH_BITS {
+        return None;
+    }
+
+    // '0'..='9' have 0 and 'a'..='f' have 1 in bit 6, letters need 9 added to
+    // their low nibble
+    let nibbles = (lower & ONES * 0x0F) + ((lower >> 6) & ONES) * 9;
+    let nibbles = (nibbles | nibbles >> 4) & 0x00FF_00FF_00FF_00FF;
+    let nibbles = (nibbles | nibbles >> 8) & 0x0000_FFFF_0000_FFFF;
+    Some((nibbles | nibbles >> 16) & 0xFFFF_FFFF)
+}
+
+/// Convert the leading decimal digits of `s` in chunks of eight, stopping
+/// before a chunk that is not all digits or would overflow. Returns the value
+/// and the number of digits converted.
+pub unsafe fn decimal_prefix(s: *const c_char) -> (c_ulong, isize) {
+    let mut num: c_ulong = 0;
+    let mut idx = 0;
+    while let Some(chunk) = read_chunk(s.offset(idx)) {
+        let value = match decimal_chunk(u64::from_le_bytes(chunk)) {
+            Some(value) => value as c_ulong,
+            None => break,
+        };
+        match num
+            .checked_mul(100_000_000)
+            .and_then(|num| num.checked_add(value))
+        {
+            Some(res) => num = res,
+            None => break,
+        }
+        idx += 8;
+    }
+    (num, idx)
+}
+
+/// Convert the leading hexadecimal digits of `s` in chunks of eight, like
+/// `decimal_prefix`
+pub unsafe fn hex_prefix(s: *const c_char) -> (c_ulong, isize) {
+    let mut num: c_ulong = 0;
+    let mut idx = 0;
+    while let Some(chunk) = read_chunk(s.offset(idx)) {
+        let value = match hex_chunk(u64::from_be_bytes(chunk)) {
+            Some(value) => value as c_ulong,
+            None => break,
+        };
+        if num >> 32 != 0 {
+            break;
+        }
+        num = num << 32 | value;
+        idx += 8;
+    }
+    (num, idx)
+}
@@ -1,5 +1,6 @@
 //! stdlib implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/stdlib.h.html
 
+use alloc::{string::String, vec::Vec};
 use core::{convert::TryFrom, intrinsics, iter, mem, ptr, slice};
 use rand::{
     distributions::{Alphanumeric, Distribution, Uniform},
@@ -10,23 +11,31 @@ use rand::{
 
 use crate::{
     c_str::CStr,
+    cxa,
     fs::File,
     header::{
         ctype,
         errno::{self, *},
         fcntl::*,
         limits,
+        signal::{self, sigset_t},
+        stdio,
         string::*,
+        sys_random,
         time::constants::CLOCK_MONOTONIC,
         unistd::{self, sysconf, _SC_PAGESIZE},
         wchar::*,
     },
     platform::{self, types::*, Pal, Sys},
+    sync::Mutex,
 };
 
+mod cvt;
+mod digits;
 mod rand48;
 mod random;
 mod sort;
+pub mod strtod;
 
 pub const EXIT_FAILURE: c_int = 1;
 pub const EXIT_SUCCESS: c_int = 0;
@@ -37,7 +46,9 @@ pub const MB_CUR_MAX: c_int = 4;
 //Maximum number of bytes in a multibyte characters for any locale
 pub const MB_LEN_MAX: c_int = 4;
 
-static mut ATEXIT_FUNCS: [Option<extern "C" fn()>; 32] = [None; 32];
+/// Handlers registered by `at_quick_exit`, oldest first. Those of `atexit`
+/// are kept with the ones of `__cxa_atexit`
+static AT_QUICK_EXIT_FUNCS: Mutex<Vec<extern "C" fn()>> = Mutex::new(Vec::new());
 static mut L64A_BUFFER: [c_char; 7] = [0; 7]; // up to 6 digits plus null terminator
 static mut RNG: Option<XorShiftRng> = None;
 
@@ -89,6 +100,28 @@ pub unsafe extern "C" fn a64l(s: *const c_char) -> c_long {
 
 #[no_mangle]
 pub unsafe extern "C" fn abort() {
+    // Nothing is flushed, as the state of the streams may well be what went wrong
+    let mut set: sigset_t = 0;
+    signal::sigemptyset(&mut set);
+    signal::sigaddset(&mut set, signal::SIGABRT as c_int);
+
+    // A blocked SIGABRT would only be left pending
+    Sys::sigprocmask(signal::SIG_UNBLOCK, &set, ptr::null_mut());
+    Sys::raise(signal::SIGABRT as c_int);
+
+    // The signal was ignored or its handler returned, so restore the default action, which
+    // cannot be caught, and try again
+    let action = signal::sigaction {
+        sa_handler: None,
+        sa_flags: 0,
+        sa_restorer: None,
+        sa_mask: 0,
+    };
+    Sys::sigaction(signal::SIGABRT as c_int, Some(&action), None);
+    Sys::sigprocmask(signal::SIG_UNBLOCK, &set, ptr::null_mut());
+    Sys::raise(signal::SIGABRT as c_int);
+
+    // Should not be reachable, but abort must never return
     intrinsics::abort();
 }
 
@@ -110,15 +143,75 @@ pub unsafe extern "C" fn aligned_alloc(alignment: size_t, size: size_t) -> *mut
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn atexit(func: Option<extern "C" fn()>) -> c_int {
-    for i in 0..ATEXIT_FUNCS.len() {
-        if ATEXIT_FUNCS[i] == None {
-            ATEXIT_FUNCS[i] = func;
-            return 0;
+pub unsafe extern "C" fn arc4random() -> u32 {
+    let mut value: u32 = 0;
+    arc4random_buf(&mut value as *mut u32 as *mut c_void, mem::size_of::<u32>());
+    value
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn arc4random_buf(buf: *mut c_void, nbytes: size_t) {
+    // getrandom only comes up short when no entropy source works at all,
+    // and arc4random has no way to report that
+    if sys_random::getrandom(buf, nbytes, 0) != nbytes as ssize_t {
+        abort();
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn arc4random_uniform(upper_bound: u32) -> u32 {
+    if upper_bound < 2 {
+        return 0;
+    }
+
+    // Values below 2^32 % upper_bound would make the low results more likely
+    // than the high ones, so draw again until the value is above them
+    let min = upper_bound.wrapping_neg() % upper_bound;
+    loop {
+        let value = arc4random();
+        if value >= min {
+            return value % upper_bound;
         }
     }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn at_quick_exit(func: Option<extern "C" fn()>) -> c_int {
+    if let Some(func) = func {
+        AT_QUICK_EXIT_FUNCS.lock().push(func);
+    }
+    0
+}
+
+extern "C" fn call_atexit_handler(func: *mut c_void) {
+    let func: extern "C" fn() = unsafe { mem::transmute(func) };
+    func();
+}
+
+/// Registered like a `__cxa_atexit` handler, so that both kinds run in one
+/// reverse order of registration
+#[no_mangle]
+pub unsafe extern "C" fn atexit(func: Option<extern "C" fn()>) -> c_int {
+    match func {
+        Some(func) => cxa::__cxa_atexit(
+            Some(call_atexit_handler),
+            func as *mut c_void,
+            ptr::null_mut(),
+        ),
+        None => 0,
+    }
+}
 
-    1
+/// Call the handlers of `funcs`, newest first. The lock is released while a
+/// handler runs, so it can register more, which are called next.
+fn run_exit_handlers(funcs: &Mutex<Vec<extern "C" fn()>>) {
+    loop {
+        let func = match funcs.lock().pop() {
+            Some(func) => func,
+            None => break,
+        };
+        func();
+    }
 }
 
 #[no_mangle]
@@ -183,6 +276,8 @@ unsafe extern "C" fn void_cmp(a: *const c_void, b: *const c_void) -> c_int {
     *(a as *const i32) - *(b as *const i32) as c_int
 }
 
+/// Find an element equal to `key` in the sorted array `base`. Which one is
+/// found among several equal elements is unspecified.
 #[no_mangle]
 pub unsafe extern "C" fn bsearch(
     key: *const c_void,
@@ -194,6 +289,8 @@ pub unsafe extern "C" fn bsearch(
     let mut start = base;
     let mut len = nel;
     let cmp_fn = compar.unwrap_or(void_cmp);
+    // Only the length of the remaining range is tracked, rather than both of
+    // its ends, so finding the middle can't overflow however large it is
     while len > 0 {
         let med = (start as size_t + (len >> 1) * width) as *const c_void;
         let diff = cmp_fn(key, med);
@@ -229,6 +326,19 @@ pub unsafe extern "C" fn calloc(nelem: size_t, elsize: size_t) -> *mut c_void {
     }
 }
 
+/// Remove every variable from the environment, freeing only the strings
+/// `setenv` allocated
+#[no_mangle]
+pub unsafe extern "C" fn clearenv() -> c_int {
+    sync_environ();
+    for item in platform::inner_environ.drain(..) {
+        free_env_item(item);
+    }
+    platform::inner_environ.push(ptr::null_mut());
+    platform::environ = platform::inner_environ.as_mut_ptr();
+    0
+}
+
 #[repr(C)]
 pub struct div_t {
     quot: c_int,
@@ -249,14 +359,49 @@ pub unsafe extern "C" fn drand48() -> c_double {
     rand48::f64_from_x(new_xsubi_value)
 }
 
-// #[no_mangle]
-pub extern "C" fn ecvt(
+/// Shared by the functions of `cvt`, with room for `fcvt` of the largest
+/// double
+static mut CVT_BUFFER: [c_char; cvt::NDIGIT_MAX + 311] = [0; cvt::NDIGIT_MAX + 311];
+
+/// Store the digits of finite values given by `digits`, or "inf" or "nan", in
+/// `CVT_BUFFER`
+unsafe fn cvt_digits(
+    value: c_double,
+    decpt: *mut c_int,
+    sign: *mut c_int,
+    digits: impl FnOnce(c_double) -> (String, c_int),
+) -> *mut c_char {
+    let (digits, point) = if value.is_finite() {
+        digits(value.abs())
+    } else if value.is_nan() {
+        ("nan".into(), 0)
+    } else {
+        ("inf".into(), 0)
+    };
+    *decpt = point;
+    *sign = value.is_sign_negative() as c_int;
+
+    let len = digits.len().min(CVT_BUFFER.len() - 1);
+    ptr::copy_nonoverlapping(
+        digits.as_ptr() as *const c_char,
+        CVT_BUFFER.as_mut_ptr(),
+        len,
+    );
+    CVT_BUFFER[len] = 0;
+    CVT_BUFFER.as_mut_ptr()
+}
+
+/// Convert `value` to its `ndigit` most significant digits. The decimal point
+/// and sign are returned in `decpt` and `sign`.
+#[no_mangle]
+pub unsafe extern "C" fn ecvt(
     value: c_double,
     ndigit: c_int,
     decpt: *mut c_int,
     sign: *mut c_int,
 ) -> *mut c_char {
-    unimplemented!();
+    let ndigit = (ndigit.max(0) as usize).min(cvt::NDIGIT_MAX);
+    cvt_digits(value, decpt, sign, |value| cvt::significant(value, ndigit))
 }
 
 #[no_mangle]
@@ -275,11 +420,8 @@ pub unsafe extern "C" fn exit(status: c_int) {
         fn _fini();
     }
 
-    for i in (0..ATEXIT_FUNCS.len()).rev() {
-        if let Some(func) = ATEXIT_FUNCS[i] {
-            (func)();
-        }
-    }
+    // atexit handlers and C++ static destructors
+    cxa::__cxa_finalize(ptr::null_mut());
 
     // Look for the neighbor functions in memory until the end
     let mut f = &__fini_array_end as *const _;
@@ -291,19 +433,23 @@ pub unsafe extern "C" fn exit(status: c_int) {
 
     _fini();
 
+    stdio::fflush(ptr::null_mut());
+
     pthread_terminate();
 
     Sys::exit(status);
 }
 
-// #[no_mangle]
-pub extern "C" fn fcvt(
+/// Like `ecvt`, but with `ndigit` digits after the decimal point
+#[no_mangle]
+pub unsafe extern "C" fn fcvt(
     value: c_double,
     ndigit: c_int,
     decpt: *mut c_int,
     sign: *mut c_int,
 ) -> *mut c_char {
-    unimplemented!();
+    let ndigit = (ndigit.max(0) as usize).min(cvt::NDIGIT_MAX);
+    cvt_digits(value, decpt, sign, |value| cvt::fractional(value, ndigit))
 }
 
 #[no_mangle]
@@ -311,12 +457,77 @@ pub unsafe extern "C" fn free(ptr: *mut c_void) {
     platform::free(ptr);
 }
 
-// #[no_mangle]
-pub extern "C" fn gcvt(value: c_double, ndigit: c_int, buf: *mut c_char) -> *mut c_char {
-    unimplemented!();
+/// Format `value` into `buf` with `ndigit` significant digits, as printf's
+/// "%.*g" would
+#[no_mangle]
+pub unsafe extern "C" fn gcvt(value: c_double, ndigit: c_int, buf: *mut c_char) -> *mut c_char {
+    let s = cvt::general(value, (ndigit.max(0) as usize).min(cvt::NDIGIT_MAX));
+    ptr::copy_nonoverlapping(s.as_ptr() as *const c_char, buf, s.len());
+    *buf.add(s.len()) = 0;
+    buf
 }
 
+/// Strings allocated by `setenv` that are still in the environment, to be
+/// freed when they're replaced or removed. The others belong to someone else,
+/// like the strings given to `putenv`.
+static mut SETENV_STRINGS: Vec<*mut c_char> = Vec::new();
+
+/// Take over `environ` if the program replaced it, so that the environment is
+/// always `inner_environ`
+unsafe fn sync_environ() {
+    if platform::environ == platform::inner_environ.as_mut_ptr() {
+        return;
+    }
+    platform::inner_environ.clear();
+    if !platform::environ.is_null() {
+        let mut item = platform::environ;
+        while !(*item).is_null() {
+            platform::inner_environ.push(*item);
+            item = item.add(1);
+        }
+    }
+    platform::inner_environ.push(ptr::null_mut());
+    platform::environ = platform::inner_environ.as_mut_ptr();
+}
+
+/// Whether `name` can be the name of an environment variable, so it isn't
+/// empty and doesn't contain '='
+unsafe fn is_env_name(name: *const c_char) -> bool {
+    !name.is_null() && {
+        let name = CStr::from_ptr(name).to_bytes();
+        !name.is_empty() && !name.contains(&b'=')
+    }
+}
+
+/// Free `item` if it was allocated by `setenv`
+unsafe fn free_env_item(item: *mut c_char) {
+    if let Some(i) = SETENV_STRINGS.iter().position(|&string| string == item) {
+        SETENV_STRINGS.swap_remove(i);
+        platform::free(item as *mut c_void);
+    }
+}
+
+/// Put `item`, which is "name=value", in place of the variable of the same
+/// name, or at the end of the environment
+unsafe fn put_env_item(item: *mut c_char) {
+    match find_env(item) {
+        Some((i, _)) => {
+            free_env_item(platform::inner_environ[i]);
+            platform::inner_environ[i] = item;
+        }
+        None => {
+            let i = platform::inner_environ.len() - 1;
+            platform::inner_environ[i] = item;
+            platform::inner_environ.push(ptr::null_mut());
+            platform::environ = platform::inner_environ.as_mut_ptr();
+        }
+    }
+}
+
+/// Find the variable `search`, which ends with a nul or '='. Returns its index
+/// and value.
 unsafe fn find_env(search: *const c_char) -> Option<(usize, *mut c_char)> {
+    sync_environ();
     for (i, item) in platform::inner_environ.iter().enumerate() {
         let mut item = *item;
         if item.is_null() {
@@ -330,7 +541,10 @@ unsafe fn find_env(search: *const c_char) -> Option<(usize, *mut c_char)> {
         let mut search = search;
         loop {
             let end_of_query = *search == 0 || *search == b'=' as c_char;
-            assert_ne!(*item, 0, "environ has an item without value");
+            if *item == 0 {
+                // Items without '=' have no name to match
+                break;
+            }
             if *item == b'=' as c_char || end_of_query {
                 if *item == b'=' as c_char && end_of_query {
                     // Both keys env here
@@ -356,13 +570,55 @@ pub unsafe extern "C" fn getenv(name: *const c_char) -> *mut c_char {
     find_env(name).map(|val| val.1).unwrap_or(ptr::null_mut())
 }
 
-// #[no_mangle]
-pub extern "C" fn getsubopt(
+/// Parse the next of the comma separated suboptions of `*optionp`, and return
+/// its index in `tokens`, or -1 if it isn't found. `*valuep` is set to what
+/// follows '=' in the suboption if anything, or the whole suboption if it
+/// isn't found.
+#[no_mangle]
+pub unsafe extern "C" fn getsubopt(
     optionp: *mut *mut c_char,
     tokens: *const *mut c_char,
     valuep: *mut *mut c_char,
 ) -> c_int {
-    unimplemented!();
+    let start = *optionp;
+    if *start == 0 {
+        return -1;
+    }
+
+    let mut end = start;
+    while *end != 0 && *end != b',' as c_char {
+        end = end.add(1);
+    }
+    let mut name_end = start;
+    while name_end < end && *name_end != b'=' as c_char {
+        name_end = name_end.add(1);
+    }
+    let name_len = name_end as usize - start as usize;
+
+    // Terminate the suboption, and move past it
+    *optionp = if *end == 0 {
+        end
+    } else {
+        *end = 0;
+        end.add(1)
+    };
+
+    let mut i = 0;
+    while !(*tokens.add(i)).is_null() {
+        let token = *tokens.add(i);
+        if strncmp(token, start, name_len) == 0 && *token.add(name_len) == 0 {
+            *valuep = if name_end < end {
+                name_end.add(1)
+            } else {
+                ptr::null_mut()
+            };
+            return i as c_int;
+        }
+        i += 1;
+    }
+
+    *valuep = start;
+    -1
 }
 
 // #[no_mangle]
@@ -370,6 +626,28 @@ pub extern "C" fn grantpt(fildes: c_int) -> c_int {
     unimplemented!();
 }
 
+/// Sort `base` in place without allocating, in O(n log n) time whatever the
+/// input. Equal elements may be reordered.
+#[no_mangle]
+pub unsafe extern "C" fn heapsort(
+    base: *mut c_void,
+    nel: size_t,
+    width: size_t,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> c_int {
+    let comp = match compar {
+        Some(comp) if width > 0 => comp,
+        _ => {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    };
+    if nel > 1 {
+        sort::heapsort(base as *mut c_char, nel, width, comp);
+    }
+    0
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn initstate(seed: c_uint, state: *mut c_char, size: size_t) -> *mut c_char {
     // Ported from musl
@@ -526,7 +804,7 @@ pub unsafe extern "C" fn memalign(alignment: size_t, size: size_t) -> *mut c_voi
 #[no_mangle]
 pub unsafe extern "C" fn mblen(s: *const c_char, n: size_t) -> c_int {
     let mut wc: wchar_t = 0;
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::new();
     let result: usize = mbrtowc(&mut wc, s, n, &mut state);
 
     if result == -1isize as usize {
@@ -541,16 +819,49 @@ pub unsafe extern "C" fn mblen(s: *const c_char, n: size_t) -> c_int {
 
 #[no_mangle]
 pub unsafe extern "C" fn mbstowcs(pwcs: *mut wchar_t, mut s: *const c_char, n: size_t) -> size_t {
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::new();
     mbsrtowcs(pwcs, &mut s, n, &mut state)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mbtowc(pwc: *mut wchar_t, s: *const c_char, n: size_t) -> c_int {
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::new();
     mbrtowc(pwc, s, n, &mut state) as c_int
 }
 
+/// Sort `base` keeping equal elements in their original order. Fails with
+/// ENOMEM if the temporary buffer of half the array can't be allocated.
+#[no_mangle]
+pub unsafe extern "C" fn mergesort(
+    base: *mut c_void,
+    nel: size_t,
+    width: size_t,
+    compar: Option<extern "C" fn(*const c_void, *const c_void) -> c_int>,
+) -> c_int {
+    let comp = match compar {
+        Some(comp) if width > 0 => comp,
+        _ => {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    };
+    if nel < 2 {
+        return 0;
+    }
+
+    let buffer = match (nel / 2).checked_mul(width) {
+        Some(size) => platform::alloc(size) as *mut c_char,
+        None => ptr::null_mut(),
+    };
+    if buffer.is_null() {
+        platform::errno = ENOMEM;
+        return -1;
+    }
+    sort::mergesort(base as *mut c_char, nel, width, comp, buffer);
+    platform::free(buffer as *mut c_void);
+    0
+}
+
 fn inner_mktemp<T, F>(name: *mut c_char, suffix_len: c_int, mut attempt: F) -> Option<T>
 where
     F: FnMut() -> Option<T>,
@@ -687,24 +998,23 @@ pub extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
     unimplemented!();
 }
 
+/// Put `insert`, which is "name=value", in the environment itself, so that
+/// changing it changes the variable. Without '=', the variable `insert` is
+/// removed instead.
 #[no_mangle]
 pub unsafe extern "C" fn putenv(insert: *mut c_char) -> c_int {
-    assert_ne!(insert, ptr::null_mut(), "putenv(NULL)");
-    if let Some((i, _)) = find_env(insert) {
-        //TODO: find out why this crashes: platform::free(platform::inner_environ[i] as *mut c_void);
-        platform::inner_environ[i] = insert;
-    } else {
-        let i = platform::inner_environ.len() - 1;
-        assert_eq!(
-            platform::inner_environ[i],
-            ptr::null_mut(),
-            "environ did not end with null"
-        );
-        platform::inner_environ[i] = insert;
-        platform::inner_environ.push(ptr::null_mut());
-        platform::environ = platform::inner_environ.as_mut_ptr();
+    let bytes = CStr::from_ptr(insert).to_bytes();
+    match bytes.iter().position(|&b| b == b'=') {
+        Some(0) => {
+            platform::errno = EINVAL;
+            -1
+        }
+        Some(_) => {
+            put_env_item(insert);
+            0
+        }
+        None => unsetenv(insert),
     }
-    0
 }
 
 #[no_mangle]
@@ -724,6 +1034,14 @@ pub extern "C" fn qsort(
     }
 }
 
+/// Call the `at_quick_exit` handlers, then terminate without calling the
+/// `atexit` handlers or flushing streams
+#[no_mangle]
+pub extern "C" fn quick_exit(status: c_int) {
+    run_exit_handlers(&AT_QUICK_EXIT_FUNCS);
+    _Exit(status);
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn rand() -> c_int {
     match RNG {
@@ -828,6 +1146,17 @@ pub unsafe extern "C" fn realpath(pathname: *const c_char, resolved: *mut c_char
     ptr
 }
 
+/// Like `getenv`, but return null if the program got privileges its user
+/// doesn't have, like from setuid
+#[no_mangle]
+pub unsafe extern "C" fn secure_getenv(name: *const c_char) -> *mut c_char {
+    if platform::secure_execution {
+        ptr::null_mut()
+    } else {
+        getenv(name)
+    }
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn seed48(seed16v: *mut c_ushort) -> *mut c_ushort {
     rand48::reset_a_and_c();
@@ -843,70 +1172,37 @@ pub unsafe extern "C" fn seed48(seed16v: *mut c_ushort) -> *mut c_ushort {
     rand48::SEED48_XSUBI.as_mut_ptr()
 }
 
+/// Set the variable `key` to a copy of `value`, unless it's set and
+/// `overwrite` is 0
 #[no_mangle]
 pub unsafe extern "C" fn setenv(
-    mut key: *const c_char,
-    mut value: *const c_char,
+    key: *const c_char,
+    value: *const c_char,
     overwrite: c_int,
 ) -> c_int {
-    let mut key_len = 0;
-    while *key.offset(key_len) != 0 {
-        key_len += 1;
+    if !is_env_name(key) {
+        platform::errno = EINVAL;
+        return -1;
     }
-    let mut value_len = 0;
-    while *value.offset(value_len) != 0 {
-        value_len += 1;
+    if overwrite == 0 && find_env(key).is_some() {
+        return 0;
     }
 
-    let index = if let Some((i, existing)) = find_env(key) {
-        if overwrite == 0 {
-            return 0;
-        }
-
-        let mut existing_len = 0;
-        while *existing.offset(existing_len) != 0 {
-            existing_len += 1;
-        }
-
-        if existing_len >= value_len {
-            // Reuse existing element's allocation
-            for i in 0..=value_len {
-                *existing.offset(i) = *value.offset(i);
-            }
-            return 0;
-        } else {
-            i
-        }
-    } else {
-        let i = platform::inner_environ.len() - 1;
-        assert_eq!(
-            platform::inner_environ[i],
-            ptr::null_mut(),
-            "environ did not end with null"
-        );
-        platform::inner_environ.push(ptr::null_mut());
-        platform::environ = platform::inner_environ.as_mut_ptr();
-        i
-    };
-
-    //platform::free(platform::inner_environ[index] as *mut c_void);
-    let mut ptr = platform::alloc(key_len as usize + 1 + value_len as usize) as *mut c_char;
-    platform::inner_environ[index] = ptr;
-
-    while *key != 0 {
-        *ptr = *key;
-        ptr = ptr.offset(1);
-        key = key.offset(1);
-    }
-    *ptr = b'=' as c_char;
-    ptr = ptr.offset(1);
-    while *value != 0 {
-        *ptr = *value;
-        ptr = ptr.offset(1);
-        value = value.offset(1);
+    let key = CStr::from_ptr(key).to_bytes();
+    let value = CStr::from_ptr(value).to_bytes();
+    let item = platform::alloc(key.len() + 1 + value.len() + 1) as *mut c_char;
+    if item.is_null() {
+        platform::errno = ENOMEM;
+        return -1;
     }
-    *ptr = 0;
-
+    ptr::copy_nonoverlapping(key.as_ptr() as *const c_char, item, key.len());
+    *item.add(key.len()) = b'=' as c_char;
+    let item_value = item.add(key.len() + 1);
+    ptr::copy_nonoverlapping(value.as_ptr() as *const c_char, item_value, value.len());
+    *item_value.add(value.len()) = 0;
+
+    SETENV_STRINGS.push(item);
+    put_env_item(item);
     0
 }
 
@@ -952,13 +1248,26 @@ pub unsafe extern "C" fn srandom(seed: c_uint) {
     // TODO: unlock?
 }
 
+/// Convert the float at the start of `s`, setting `errno` to `ERANGE` if it
+/// overflows or underflows
+unsafe fn strto_float<F: strtod::Float>(s: *const c_char, endptr: *mut *mut c_char) -> F {
+    let (float, len, range) = strtod::parse::<F>(CStr::from_ptr(s).to_bytes());
+    if range {
+        platform::errno = ERANGE;
+    }
+    if !endptr.is_null() {
+        *endptr = s.add(len) as *mut c_char;
+    }
+    float
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strtod(s: *const c_char, endptr: *mut *mut c_char) -> c_double {
-    strto_float_impl!(c_double, s, endptr)
+    strto_float(s, endptr)
 }
 #[no_mangle]
 pub unsafe extern "C" fn strtof(s: *const c_char, endptr: *mut *mut c_char) -> c_float {
-    strto_float_impl!(c_float, s, endptr)
+    strto_float(s, endptr)
 }
 
 pub fn is_positive(ch: c_char) -> Option<(bool, isize)> {
@@ -1006,13 +1315,35 @@ pub unsafe fn convert_hex(s: *const c_char) -> Option<(c_ulong, isize, bool)> {
     if (*s != 0 && *s == b'0' as c_char)
         && (*s.offset(1) != 0 && (*s.offset(1) == b'x' as c_char || *s.offset(1) == b'X' as c_char))
     {
-        convert_integer(s.offset(2), 16).map(|(val, idx, overflow)| (val, idx + 2, overflow))
+        match convert_integer(s.offset(2), 16) {
+            Some((val, idx, overflow)) => Some((val, idx + 2, overflow)),
+            // without digits after it, the prefix is only a zero
+            None => Some((0, 1, false)),
+        }
     } else {
         convert_integer(s, 16).map(|(val, idx, overflow)| (val, idx, overflow))
     }
 }
 
 pub unsafe fn convert_integer(s: *const c_char, base: c_int) -> Option<(c_ulong, isize, bool)> {
+    // Take leading digits eight at a time where that is possible, the
+    // generic conversion handles the rest (and any overflow)
+    let (num, idx) = match base {
+        10 => digits::decimal_prefix(s),
+        16 => digits::hex_prefix(s),
+        _ => (0, 0),
+    };
+    convert_integer_from(s, base, num, idx)
+}
+
+/// Convert the digits starting at `s[idx]`, `num` being the value of those
+/// before it
+unsafe fn convert_integer_from(
+    s: *const c_char,
+    base: c_int,
+    mut num: c_ulong,
+    mut idx: isize,
+) -> Option<(c_ulong, isize, bool)> {
     // -1 means the character is invalid
     #[rustfmt::skip]
     const LOOKUP_TABLE: [c_long; 256] = [
@@ -1034,8 +1365,6 @@ pub unsafe fn convert_integer(s: *const c_char, base: c_int) -> Option<(c_ulong,
         -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
     ];
 
-    let mut num: c_ulong = 0;
-    let mut idx = 0;
     let mut overflowed = false;
 
     loop {
@@ -1164,12 +1493,16 @@ pub extern "C" fn unlockpt(fildes: c_int) -> c_int {
     unimplemented!();
 }
 
+/// Remove the variable `key`, and any duplicates of it
 #[no_mangle]
 pub unsafe extern "C" fn unsetenv(key: *const c_char) -> c_int {
-    if let Some((i, _)) = find_env(key) {
-        // No need to worry about updating the pointer, this does not
-        // reallocate in any way. And the final null is already shifted back.
-        platform::inner_environ.remove(i);
+    if !is_env_name(key) {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    while let Some((i, _)) = find_env(key) {
+        // This doesn't reallocate, so environ stays the same
+        free_env_item(platform::inner_environ.remove(i));
     }
     0
 }
@@ -1197,13 +1530,13 @@ pub unsafe extern "C" fn valloc(size: size_t) -> *mut c_void {
 
 #[no_mangle]
 pub extern "C" fn wcstombs(s: *mut c_char, pwcs: *mut *const wchar_t, n: size_t) -> size_t {
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::new();
     wcsrtombs(s, pwcs, n, &mut state)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn wctomb(s: *mut c_char, wc: wchar_t) -> c_int {
-    let mut state: mbstate_t = mbstate_t {};
+    let mut state: mbstate_t = mbstate_t::new();
     let result: usize = wcrtomb(s, wc, &mut state);
 
     if result == -1isize as usize {
@@ -1,5 +1,9 @@
 use crate::platform::types::*;
 
+/// Quicksort, falling back to heapsort when the partitions are bad enough that
+/// the recursion reaches `2 * log2(nel)` levels, and to insertion sort for
+/// short partitions. This keeps the worst case at O(n log n) comparisons, even
+/// with a comparison function that picks its answers to be as bad as possible.
 pub fn introsort(
     base: *mut c_char,
     nel: size_t,
@@ -81,7 +85,7 @@ fn insertion_sort(
     }
 }
 
-fn heapsort(
+pub fn heapsort(
     base: *mut c_char,
     nel: size_t,
     width: size_t,
@@ -98,6 +102,64 @@ fn heapsort(
     }
 }
 
+/// Stable sort, using `buffer` to hold up to `nel / 2` elements
+pub fn mergesort(
+    base: *mut c_char,
+    nel: size_t,
+    width: size_t,
+    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
+    buffer: *mut c_char,
+) {
+    const THRESHOLD: size_t = 8;
+
+    // insertion sort only moves an element past greater ones, so it's stable
+    if nel < THRESHOLD {
+        insertion_sort(base, nel, width, comp);
+        return;
+    }
+
+    let mid = nel / 2;
+    let right = unsafe { base.add(mid * width) };
+    mergesort(base, mid, width, comp, buffer);
+    mergesort(right, nel - mid, width, comp, buffer);
+    merge(base, mid, nel, width, comp, buffer);
+}
+
+/// Merge the sorted runs `base[..mid]` and `base[mid..nel]`, taking from the
+/// first one on ties. The first run is moved to `buffer`, and the merged
+/// elements never catch up with the unread part of the second one.
+fn merge(
+    base: *mut c_char,
+    mid: size_t,
+    nel: size_t,
+    width: size_t,
+    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
+    buffer: *mut c_char,
+) {
+    unsafe {
+        buffer.copy_from_nonoverlapping(base, mid * width);
+
+        let mut i = 0;
+        let mut j = mid;
+        let mut k = 0;
+        while i < mid && j < nel {
+            let left = buffer.add(i * width);
+            let right = base.add(j * width);
+            let src = if comp(left as *const c_void, right as *const c_void) <= 0 {
+                i += 1;
+                left
+            } else {
+                j += 1;
+                right
+            };
+            base.add(k * width).copy_from_nonoverlapping(src, width);
+            k += 1;
+        }
+        base.add(k * width)
+            .copy_from_nonoverlapping(buffer.add(i * width), (mid - i) * width);
+    }
+}
+
 fn heapify(
     base: *mut c_char,
     nel: size_t,
@@ -158,9 +220,8 @@ fn partition(
     width: size_t,
     comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
 ) -> (size_t, size_t) {
-    // calculate the median of the first, middle, and last elements and use it as the pivot
-    // to do fewer comparisons, also swap the elements into their correct positions
-    let mut pivot = median_of_three(base, nel, width, comp);
+    // the first and last elements are already on the right side of the pivot
+    let mut pivot = choose_pivot(base, nel, width, comp);
 
     let mut i = 1;
     let mut j = 1;
@@ -195,27 +256,52 @@ fn partition(
     (i, n)
 }
 
-fn median_of_three(
+/// Put the median of the first, middle and last elements in the middle, with
+/// the smaller one first and the larger one last. In long arrays, each of those
+/// is the median of three elements around it first (Tukey's ninther), which
+/// makes patterns like organ pipes much less likely to give bad pivots.
+fn choose_pivot(
     base: *mut c_char,
     nel: size_t,
     width: size_t,
     comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
 ) -> size_t {
+    const NINTHER_THRESHOLD: size_t = 64;
+
     let pivot = nel / 2;
+    let last = nel - 1;
+    if nel >= NINTHER_THRESHOLD {
+        let step = nel / 8;
+        sort_three(base, step, 0, 2 * step, width, comp);
+        sort_three(base, pivot - step, pivot, pivot + step, width, comp);
+        sort_three(base, last - 2 * step, last, last - step, width, comp);
+    }
+    sort_three(base, 0, pivot, last, width, comp);
+
+    pivot
+}
 
-    let mid = unsafe { base.add(pivot * width) };
-    let last = unsafe { base.add((nel - 1) * width) };
-    if comp(mid as *const c_void, base as *const c_void) < 0 {
-        swap(mid, base, width);
+/// Swap the elements at the indices `a`, `b` and `c` into ascending order
+fn sort_three(
+    base: *mut c_char,
+    a: size_t,
+    b: size_t,
+    c: size_t,
+    width: size_t,
+    comp: extern "C" fn(*const c_void, *const c_void) -> c_int,
+) {
+    let a = unsafe { base.add(a * width) };
+    let b = unsafe { base.add(b * width) };
+    let c = unsafe { base.add(c * width) };
+    if comp(b as *const c_void, a as *const c_void) < 0 {
+        swap(b, a, width);
     }
-    if comp(last as *const c_void, mid as *const c_void) < 0 {
-        swap(mid, last, width);
-        if comp(mid as *const c_void, base as *const c_void) < 0 {
-            swap(mid, base, width);
+    if comp(c as *const c_void, b as *const c_void) < 0 {
+        swap(b, c, width);
+        if comp(b as *const c_void, a as *const c_void) < 0 {
+            swap(b, a, width);
         }
     }
-
-    pivot
 }
 
 #[inline]
@@ -0,0 +1,451 @@
+//! Correctly rounded conversion of decimal and hexadecimal strings to floats,
+//! for `strtod` and friends. Decimal numbers that can't be converted exactly
+//! with a single floating point operation are converted with big integers.
+
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::platform::types::*;
+
+/// A binary floating point format
+pub trait Float: Copy {
+    /// Bits of precision, including the implicit leading bit
+    const MANTISSA_BITS: i64;
+    /// Exponent of the largest finite numbers
+    const MAX_EXP: i64;
+
+    fn from_bits64(bits: u64) -> Self;
+    fn nan() -> Self;
+    fn negate(self) -> Self;
+    /// Convert exactly with a single operation, if possible
+    fn fast_path(digits: u64, exp: i64) -> Option<Self>;
+}
+
+impl Float for c_double {
+    const MANTISSA_BITS: i64 = 53;
+    const MAX_EXP: i64 = 1023;
+
+    fn from_bits64(bits: u64) -> Self {
+        c_double::from_bits(bits)
+    }
+    fn nan() -> Self {
+        c_double::NAN
+    }
+    fn negate(self) -> Self {
+        -self
+    }
+    fn fast_path(digits: u64, exp: i64) -> Option<Self> {
+        // Integers below 2^53 and powers of ten up to 10^22 are exact
+        if digits >= 1 << 53 || exp.abs() > 22 {
+            return None;
+        }
+        let mut pow10 = 1.0;
+        for _ in 0..exp.abs() {
+            pow10 *= 10.0;
+        }
+        Some(if exp < 0 {
+            digits as c_double / pow10
+        } else {
+            digits as c_double * pow10
+        })
+    }
+}
+
+impl Float for c_float {
+    const MANTISSA_BITS: i64 = 24;
+    const MAX_EXP: i64 = 127;
+
+    fn from_bits64(bits: u64) -> Self {
+        c_float::from_bits(bits as u32)
+    }
+    fn nan() -> Self {
+        c_float::NAN
+    }
+    fn negate(self) -> Self {
+        -self
+    }
+    fn fast_path(digits: u64, exp: i64) -> Option<Self> {
+        if digits >= 1 << 24 || exp.abs() > 10 {
+            return None;
+        }
+        let mut pow10 = 1.0;
+        for _ in 0..exp.abs() {
+            pow10 *= 10.0;
+        }
+        Some(if exp < 0 {
+            digits as c_float / pow10
+        } else {
+            digits as c_float * pow10
+        })
+    }
+}
+
+/// Round `mantissa * 2^exp`, plus something smaller than the last bit of
+/// `mantissa` if `sticky`, to nearest even. Returns the bits of the result and
+/// whether it overflowed or underflowed.
+fn round<F: Float>(mut mantissa: u128, mut exp: i64, sticky: bool) -> (F, bool) {
+    let bits = F::MANTISSA_BITS;
+    let min_exp = 1 - F::MAX_EXP;
+    if mantissa == 0 {
+        return (F::from_bits64(0), false);
+    }
+
+    let top = 127 - mantissa.leading_zeros() as i64;
+    let lead_exp = exp + top;
+    if lead_exp > F::MAX_EXP {
+        return (F::from_bits64(infinity_bits::<F>()), true);
+    }
+    // Subnormals have fewer bits of precision, and anything below half of the
+    // smallest one rounds to zero
+    let keep = bits - (min_exp - lead_exp).max(0);
+    if keep < 0 {
+        return (F::from_bits64(0), true);
+    }
+    let shift = top + 1 - keep;
+    let mut inexact = sticky;
+    if shift > 0 {
+        let (rest, half) = if shift > 127 {
+            (mantissa, 0)
+        } else {
+            (mantissa & ((1 << shift) - 1), 1 << (shift - 1))
+        };
+        mantissa = if shift > 127 { 0 } else { mantissa >> shift };
+        if half != 0 && (rest > half || (rest == half && (sticky || mantissa & 1 == 1))) {
+            mantissa += 1;
+        }
+        inexact |= rest != 0;
+        exp += shift;
+    } else {
+        mantissa <<= -shift;
+        exp += shift;
+    }
+
+    // A carry may have added a bit
+    if mantissa >> bits != 0 {
+        mantissa >>= 1;
+        exp += 1;
+    }
+    let implicit = 1 << (bits - 1);
+    let float_bits = if mantissa >= implicit {
+        let biased = exp + bits - 1 + F::MAX_EXP;
+        if biased >= 2 * F::MAX_EXP + 1 {
+            return (F::from_bits64(infinity_bits::<F>()), true);
+        }
+        (biased as u64) << (bits - 1) | (mantissa - implicit) as u64
+    } else {
+        // Subnormal, or zero
+        mantissa as u64
+    };
+    let tiny = float_bits >> (bits - 1) == 0;
+    (F::from_bits64(float_bits), tiny && inexact)
+}
+
+fn infinity_bits<F: Float>() -> u64 {
+    ((2 * F::MAX_EXP + 1) as u64) << (F::MANTISSA_BITS - 1)
+}
+
+/// An unsigned integer of any size, least significant limb first
+#[derive(Clone)]
+struct BigInt(Vec<u32>);
+
+impl BigInt {
+    fn from_u64(n: u64) -> Self {
+        let mut big = BigInt(vec![n as u32, (n >> 32) as u32]);
+        big.trim();
+        big
+    }
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+    fn bit_len(&self) -> i64 {
+        match self.0.last() {
+            Some(last) => self.0.len() as i64 * 32 - last.leading_zeros() as i64,
+            None => 0,
+        }
+    }
+    fn mul_add_small(&mut self, mul: u32, add: u32) {
+        let mut carry = add as u64;
+        for limb in self.0.iter_mut() {
+            let n = *limb as u64 * mul as u64 + carry;
+            *limb = n as u32;
+            carry = n >> 32;
+        }
+        if carry != 0 {
+            self.0.push(carry as u32);
+        }
+        self.trim();
+    }
+    fn mul_pow5(&mut self, mut exp: i64) {
+        // 5^13 is the largest power of five that fits in a limb
+        while exp >= 13 {
+            self.mul_add_small(1_220_703_125, 0);
+            exp -= 13;
+        }
+        self.mul_add_small(5u32.pow(exp as u32), 0);
+    }
+    fn shifted_left(&self, bits: i64) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let (limbs, bits) = ((bits / 32) as usize, (bits % 32) as u32);
+        let mut shifted = vec![0; limbs];
+        let mut carry = 0;
+        for &limb in self.0.iter() {
+            shifted.push(limb << bits | carry);
+            carry = if bits == 0 { 0 } else { limb >> (32 - bits) };
+        }
+        shifted.push(carry);
+        let mut shifted = BigInt(shifted);
+        shifted.trim();
+        shifted
+    }
+    fn subtract(&mut self, other: &Self) {
+        let mut borrow = 0;
+        for i in 0..self.0.len() {
+            let n = self.0[i] as i64 - *other.0.get(i).unwrap_or(&0) as i64 - borrow;
+            self.0[i] = n as u32;
+            borrow = (n < 0) as i64;
+        }
+        self.trim();
+    }
+    fn compare(&self, other: &Self) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+}
+
+/// Convert `digits * 10^exp`, plus something smaller than the last digit if
+/// `sticky`, by dividing big integers to find enough leading bits
+fn convert_decimal<F: Float>(digits: &[u8], exp: i64, sticky: bool) -> (F, bool) {
+    let mut num = BigInt::from_u64(0);
+    for &digit in digits {
+        num.mul_add_small(10, (digit - b'0') as u32);
+    }
+    if num.is_zero() {
+        return (F::from_bits64(0), false);
+    }
+
+    // digits * 10^exp = num / den * 2^exp
+    let mut den = BigInt::from_u64(1);
+    if exp >= 0 {
+        num.mul_pow5(exp);
+    } else {
+        den.mul_pow5(-exp);
+    }
+
+    // Scale so that the quotient has a dozen bits more than needed
+    let scale = F::MANTISSA_BITS + 12 - (num.bit_len() - den.bit_len());
+    if scale >= 0 {
+        num = num.shifted_left(scale);
+    } else {
+        den = den.shifted_left(-scale);
+    }
+    let mut quotient: u128 = 0;
+    for bit in (0..F::MANTISSA_BITS + 14).rev() {
+        let shifted = den.shifted_left(bit);
+        if num.compare(&shifted) != Ordering::Less {
+            num.subtract(&shifted);
+            quotient |= 1 << bit;
+        }
+    }
+    round::<F>(quotient, exp - scale, sticky || !num.is_zero())
+}
+
+/// Significant digits kept from a decimal number. Halfway points between
+/// doubles have at most 767 significant digits, so the digits after these
+/// only matter by being nonzero.
+const MAX_DIGITS: usize = 800;
+
+fn is_space(c: u8) -> bool {
+    c == b' ' || (b'\t'..=b'\r').contains(&c)
+}
+
+/// Parse a float at the start of `s` like `strtod`, skipping leading spaces.
+/// Returns the float, the length parsed (0 if there is no number), and
+/// whether the result overflowed or underflowed.
+pub fn parse<F: Float>(s: &[u8]) -> (F, usize, bool) {
+    let at = |i: usize| s.get(i).copied().unwrap_or(0);
+    let lower_starts_with = |i: usize, word: &[u8]| {
+        s.len() >= i + word.len() && s[i..i + word.len()].eq_ignore_ascii_case(word)
+    };
+
+    let mut i = 0;
+    while is_space(at(i)) {
+        i += 1;
+    }
+    let negative = at(i) == b'-';
+    if at(i) == b'-' || at(i) == b'+' {
+        i += 1;
+    }
+    let sign = |float: F| if negative { float.negate() } else { float };
+
+    if lower_starts_with(i, b"infinity") {
+        return (sign(F::from_bits64(infinity_bits::<F>())), i + 8, false);
+    } else if lower_starts_with(i, b"inf") {
+        return (sign(F::from_bits64(infinity_bits::<F>())), i + 3, false);
+    } else if lower_starts_with(i, b"nan") {
+        i += 3;
+        // An optional "(n-char-sequence)", only parsed if it's closed
+        if at(i) == b'(' {
+            let mut end = i + 1;
+            while at(end).is_ascii_alphanumeric() || at(end) == b'_' {
+                end += 1;
+            }
+            if at(end) == b')' {
+                i = end + 1;
+            }
+        }
+        return (sign(F::nan()), i, false);
+    }
+
+    let hex = at(i) == b'0' && at(i + 1) | 0x20 == b'x' && {
+        let after = if at(i + 2) == b'.' { i + 3 } else { i + 2 };
+        at(after).is_ascii_hexdigit()
+    };
+    let (float, end, range) = if hex {
+        parse_hex::<F>(s, i + 2)
+    } else {
+        match parse_decimal::<F>(s, i) {
+            Some(parsed) => parsed,
+            None => return (F::from_bits64(0), 0, false),
+        }
+    };
+    (sign(float), end, range)
+}
+
+/// Parse the exponent at `s[i]` after its letter, if there are digits.
+/// Returns the exponent, clamped to a size that's infinite or zero anyway,
+/// and the end of it.
+fn parse_exponent(s: &[u8], mut i: usize) -> Option<(i64, usize)> {
+    let at = |i: usize| s.get(i).copied().unwrap_or(0);
+    let negative = at(i) == b'-';
+    if at(i) == b'-' || at(i) == b'+' {
+        i += 1;
+    }
+    if !at(i).is_ascii_digit() {
+        return None;
+    }
+    let mut exp: i64 = 0;
+    while at(i).is_ascii_digit() {
+        exp = (exp * 10 + (at(i) - b'0') as i64).min(1_000_000);
+        i += 1;
+    }
+    Some((if negative { -exp } else { exp }, i))
+}
+
+fn parse_decimal<F: Float>(s: &[u8], mut i: usize) -> Option<(F, usize, bool)> {
+    let at = |i: usize| s.get(i).copied().unwrap_or(0);
+
+    // The significant digits, and the exponent of the digit after them
+    let mut digits = Vec::new();
+    let mut exp: i64 = 0;
+    let mut sticky = false;
+    let mut any_digit = false;
+    let mut dot = false;
+    loop {
+        let c = at(i);
+        if c == b'.' && !dot {
+            dot = true;
+        } else if c.is_ascii_digit() {
+            any_digit = true;
+            if digits.is_empty() && c == b'0' {
+                // Leading zeros only move the point
+                if dot {
+                    exp -= 1;
+                }
+            } else if digits.len() < MAX_DIGITS {
+                digits.push(c);
+                if dot {
+                    exp -= 1;
+                }
+            } else {
+                sticky |= c != b'0';
+                if !dot {
+                    exp += 1;
+                }
+            }
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if !any_digit {
+        return None;
+    }
+    if at(i) | 0x20 == b'e' {
+        if let Some((e, end)) = parse_exponent(s, i + 1) {
+            exp += e;
+            i = end;
+        }
+    }
+
+    if digits.is_empty() {
+        return Some((F::from_bits64(0), i, false));
+    }
+    // Far outside of the range of any format, even with the most digits
+    let lead_exp = exp + digits.len() as i64;
+    if lead_exp > 5000 {
+        return Some((F::from_bits64(infinity_bits::<F>()), i, true));
+    } else if lead_exp < -5000 {
+        return Some((F::from_bits64(0), i, true));
+    }
+
+    if digits.len() <= 19 && !sticky {
+        let small = digits
+            .iter()
+            .fold(0u64, |n, &digit| n * 10 + (digit - b'0') as u64);
+        if let Some(float) = F::fast_path(small, exp) {
+            return Some((float, i, false));
+        }
+    }
+    let (float, range) = convert_decimal::<F>(&digits, exp, sticky);
+    Some((float, i, range))
+}
+
+/// Parse the hexadecimal digits at `s[i]`, after the "0x"
+fn parse_hex<F: Float>(s: &[u8], mut i: usize) -> (F, usize, bool) {
+    let at = |i: usize| s.get(i).copied().unwrap_or(0);
+
+    // Keep the first 60 bits, and whether anything after them is set
+    let mut mantissa: u128 = 0;
+    let mut exp: i64 = 0;
+    let mut sticky = false;
+    let mut dot = false;
+    loop {
+        let c = at(i);
+        if c == b'.' && !dot {
+            dot = true;
+        } else if let Some(digit) = (c as char).to_digit(16) {
+            if mantissa >> 56 == 0 {
+                mantissa = mantissa << 4 | digit as u128;
+                if dot {
+                    exp -= 4;
+                }
+            } else {
+                sticky |= digit != 0;
+                if !dot {
+                    exp += 4;
+                }
+            }
+        } else {
+            break;
+        }
+        i += 1;
+    }
+    if at(i) | 0x20 == b'p' {
+        if let Some((e, end)) = parse_exponent(s, i + 1) {
+            exp += e;
+            i = end;
+        }
+    }
+
+    let (float, range) = round::<F>(mantissa, exp, sticky);
+    (float, i, range)
+}
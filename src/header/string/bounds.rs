@@ -0,0 +1,58 @@
+//! Optional heap bounds checking for the mem/str functions.
+//!
+//! With the `check_bounds` feature, every range accessed by memcpy, memmove,
+//! memset, strcpy and strcat is looked up in the allocator, and the process
+//! aborts if the range starts inside a heap allocation but runs past its end.
+//! Pointers outside the heap (stack, statics, mmap) are not checked.
+
+use crate::platform::types::*;
+
+#[cfg(feature = "check_bounds")]
+mod imp {
+    use crate::{
+        header::stdlib,
+        platform::{self, types::*},
+    };
+
+    // Formatting the diagnostic, or the allocator itself, may call back into
+    // the checked functions
+    #[thread_local]
+    static mut CHECKING: bool = false;
+
+    pub unsafe fn check_with<F: FnOnce() -> usize>(func: &str, ptr: *const c_void, len: F) {
+        if CHECKING || ptr.is_null() {
+            return;
+        }
+        CHECKING = true;
+
+        if let Some((start, end)) = platform::allocation_containing(ptr) {
+            let len = len();
+            if (ptr as usize)
+                .checked_add(len)
+                .map_or(true, |last| last > end)
+            {
+                eprintln!(
+                    "relibc: {}: access of {} bytes at {:p} overruns heap allocation {:#x}..{:#x}",
+                    func, len, ptr, start, end
+                );
+                stdlib::abort();
+            }
+        }
+
+        CHECKING = false;
+    }
+}
+
+/// Check that `len()` bytes at `ptr` stay within the heap allocation `ptr`
+/// points into. `len` is only evaluated when checking is enabled.
+#[cfg(feature = "check_bounds")]
+pub use self::imp::check_with;
+
+#[cfg(not(feature = "check_bounds"))]
+#[inline(always)]
+pub unsafe fn check_with<F: FnOnce() -> usize>(_func: &str, _ptr: *const c_void, _len: F) {}
+
+#[inline(always)]
+pub unsafe fn check(func: &str, ptr: *const c_void, len: usize) {
+    check_with(func, ptr, || len)
+}
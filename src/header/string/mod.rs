@@ -1,14 +1,20 @@
 //! string implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/string.h.html
 
-use core::{mem, ptr, slice, usize};
+use core::{
+    fmt, mem, ptr, slice,
+    sync::atomic::{self, Ordering},
+    usize,
+};
 
 use cbitset::BitSet256;
 
 use crate::{
-    header::{errno::*, signal},
+    header::{errno::*, locale::locale_t, signal, strings},
     platform::{self, types::*},
 };
 
+mod bounds;
+
 #[no_mangle]
 pub unsafe extern "C" fn memccpy(
     dest: *mut c_void,
@@ -75,6 +81,9 @@ pub unsafe extern "C" fn memcmp(s1: *const c_void, s2: *const c_void, n: size_t)
 
 #[no_mangle]
 pub unsafe extern "C" fn memcpy(s1: *mut c_void, s2: *const c_void, n: size_t) -> *mut c_void {
+    bounds::check("memcpy", s1, n);
+    bounds::check("memcpy", s2, n);
+
     let mut i = 0;
     while i + 7 < n {
         *(s1.add(i) as *mut u64) = *(s2.add(i) as *const u64);
@@ -89,6 +98,9 @@ pub unsafe extern "C" fn memcpy(s1: *mut c_void, s2: *const c_void, n: size_t) -
 
 #[no_mangle]
 pub unsafe extern "C" fn memmove(s1: *mut c_void, s2: *const c_void, n: size_t) -> *mut c_void {
+    bounds::check("memmove", s1, n);
+    bounds::check("memmove", s2, n);
+
     if s2 < s1 as *const c_void {
         // copy from end
         let mut i = n;
@@ -107,6 +119,11 @@ pub unsafe extern "C" fn memmove(s1: *mut c_void, s2: *const c_void, n: size_t)
     s1
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn mempcpy(s1: *mut c_void, s2: *const c_void, n: size_t) -> *mut c_void {
+    (memcpy(s1, s2, n) as *mut u8).add(n) as *mut c_void
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn memrchr(
     haystack: *const c_void,
@@ -123,12 +140,59 @@ pub unsafe extern "C" fn memrchr(
 
 #[no_mangle]
 pub unsafe extern "C" fn memset(s: *mut c_void, c: c_int, n: size_t) -> *mut c_void {
+    bounds::check("memset", s, n);
+
     for i in 0..n {
         *(s as *mut u8).add(i) = c as u8;
     }
     s
 }
 
+/// Like `memset`, but the compiler may not remove the stores even if the
+/// memory is never read again. Each byte is written volatile, and the fence
+/// keeps later code from being moved before the wipe.
+unsafe fn memset_volatile(s: *mut c_void, c: c_int, n: size_t) {
+    for i in 0..n {
+        ptr::write_volatile((s as *mut u8).add(i), c as u8);
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn memset_s(s: *mut c_void, smax: size_t, c: c_int, n: size_t) -> c_int {
+    const RSIZE_MAX: size_t = usize::MAX >> 1;
+
+    if s.is_null() || smax > RSIZE_MAX {
+        return EINVAL;
+    }
+    // Still wipe as much as is allowed when n is out of range
+    if n > smax || n > RSIZE_MAX {
+        memset_volatile(s, c, smax);
+        return EINVAL;
+    }
+    memset_volatile(s, c, n);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn explicit_bzero(s: *mut c_void, n: size_t) {
+    memset_volatile(s, 0, n);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stpcpy(dst: *mut c_char, src: *const c_char) -> *mut c_char {
+    let len = strlen(src);
+    memcpy(dst as *mut c_void, src as *const c_void, len + 1);
+    dst.add(len)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stpncpy(dst: *mut c_char, src: *const c_char, n: size_t) -> *mut c_char {
+    strncpy(dst, src, n);
+    // The first NUL written, or the end if src filled all of dst
+    dst.add(strnlen(src, n))
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strchr(mut s: *const c_char, c: c_int) -> *mut c_char {
     let c = c as c_char;
@@ -154,6 +218,9 @@ pub unsafe extern "C" fn strcoll(s1: *const c_char, s2: *const c_char) -> c_int
 
 #[no_mangle]
 pub unsafe extern "C" fn strcpy(dst: *mut c_char, src: *const c_char) -> *mut c_char {
+    bounds::check_with("strcpy", src as *const c_void, || strlen(src) + 1);
+    bounds::check_with("strcpy", dst as *const c_void, || strlen(src) + 1);
+
     let mut i = 0;
 
     loop {
@@ -226,26 +293,42 @@ pub unsafe extern "C" fn strndup(s1: *const c_char, size: size_t) -> *mut c_char
     buffer
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn strerror(errnum: c_int) -> *mut c_char {
-    use core::fmt::Write;
-
-    static mut strerror_buf: [u8; 256] = [0; 256];
-
-    let mut w = platform::StringWriter(strerror_buf.as_mut_ptr(), strerror_buf.len());
-
+/// Write the message for `errnum` to `w`, returning whether `errnum` is a
+/// known error number
+pub(crate) fn write_strerror<W: fmt::Write>(errnum: c_int, w: &mut W) -> bool {
     if errnum >= 0 && errnum < STR_ERROR.len() as c_int {
         let _ = w.write_str(STR_ERROR[errnum as usize]);
+        true
     } else {
         let _ = w.write_fmt(format_args!("Unknown error {}", errnum));
+        false
     }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn strerror(errnum: c_int) -> *mut c_char {
+    static mut strerror_buf: [u8; 256] = [0; 256];
+
+    let mut w = platform::StringWriter(strerror_buf.as_mut_ptr(), strerror_buf.len());
+    write_strerror(errnum, &mut w);
 
     strerror_buf.as_mut_ptr() as *mut c_char
 }
 
+/// Only the C locale is supported, so this is `strerror`
+#[no_mangle]
+pub unsafe extern "C" fn strerror_l(errnum: c_int, _locale: locale_t) -> *mut c_char {
+    strerror(errnum)
+}
+
+/// The XSI `strerror_r`. A message that doesn't fit is truncated and ERANGE
+/// returned, an unknown `errnum` still gets its message but returns EINVAL.
 #[no_mangle]
 pub unsafe extern "C" fn strerror_r(errnum: c_int, buf: *mut c_char, buflen: size_t) -> c_int {
-    let msg = strerror(errnum);
+    let mut msg = [0u8; 256];
+    let mut w = platform::StringWriter(msg.as_mut_ptr(), msg.len());
+    let known = write_strerror(errnum, &mut w);
+    let msg = msg.as_ptr() as *const c_char;
     let len = strlen(msg);
 
     if len >= buflen {
@@ -257,7 +340,45 @@ pub unsafe extern "C" fn strerror_r(errnum: c_int, buf: *mut c_char, buflen: siz
     }
     memcpy(buf as *mut c_void, msg as *const c_void, len + 1);
 
-    0
+    if known {
+        0
+    } else {
+        EINVAL
+    }
+}
+
+/// The GNU `strerror_r`, which string.h maps `strerror_r` to with
+/// _GNU_SOURCE. The message is put in `buf`, truncated if needed.
+#[no_mangle]
+pub unsafe extern "C" fn __gnu_strerror_r(
+    errnum: c_int,
+    buf: *mut c_char,
+    buflen: size_t,
+) -> *mut c_char {
+    strerror_r(errnum, buf, buflen);
+    buf
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn strlcat(dst: *mut c_char, src: *const c_char, size: size_t) -> size_t {
+    let len = strnlen(dst, size);
+    // Without a NUL within size there is no room to append, and dst is left
+    // as it is
+    if len == size {
+        return size + strlen(src);
+    }
+    len + strlcpy(dst.add(len), src, size - len)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn strlcpy(dst: *mut c_char, src: *const c_char, size: size_t) -> size_t {
+    let len = strlen(src);
+    if size > 0 {
+        let copy = len.min(size - 1);
+        memcpy(dst as *mut c_void, src as *const c_void, copy);
+        *dst.add(copy) = 0;
+    }
+    len
 }
 
 #[no_mangle]
@@ -288,6 +409,11 @@ pub unsafe extern "C" fn strnlen_s(s: *const c_char, size: size_t) -> size_t {
 
 #[no_mangle]
 pub unsafe extern "C" fn strcat(s1: *mut c_char, s2: *const c_char) -> *mut c_char {
+    bounds::check_with("strcat", s2 as *const c_void, || strlen(s2) + 1);
+    bounds::check_with("strcat", s1 as *const c_void, || {
+        strlen(s1) + strlen(s2) + 1
+    });
+
     strncat(s1, s2, usize::MAX)
 }
 
@@ -377,11 +503,8 @@ pub unsafe extern "C" fn strspn(s1: *const c_char, s2: *const c_char) -> size_t
     inner_strspn(s1, s2, true)
 }
 
-unsafe fn inner_strstr(
-    mut haystack: *const c_char,
-    needle: *const c_char,
-    mask: c_char,
-) -> *mut c_char {
+#[no_mangle]
+pub unsafe extern "C" fn strstr(mut haystack: *const c_char, needle: *const c_char) -> *mut c_char {
     while *haystack != 0 {
         let mut i = 0;
         loop {
@@ -389,7 +512,7 @@ unsafe fn inner_strstr(
                 // We reached the end of the needle, everything matches this far
                 return haystack as *mut c_char;
             }
-            if *haystack.offset(i) & mask != *needle.offset(i) & mask {
+            if *haystack.offset(i) != *needle.offset(i) {
                 break;
             }
 
@@ -402,12 +525,24 @@ unsafe fn inner_strstr(
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn strstr(haystack: *const c_char, needle: *const c_char) -> *mut c_char {
-    inner_strstr(haystack, needle, !0)
-}
-#[no_mangle]
-pub unsafe extern "C" fn strcasestr(haystack: *const c_char, needle: *const c_char) -> *mut c_char {
-    inner_strstr(haystack, needle, !32)
+pub unsafe extern "C" fn strcasestr(
+    mut haystack: *const c_char,
+    needle: *const c_char,
+) -> *mut c_char {
+    let len = strlen(needle);
+    if len == 0 {
+        return haystack as *mut c_char;
+    }
+
+    // Case is folded like strncasecmp does, which also stops at the end of
+    // the haystack
+    while *haystack != 0 {
+        if strings::strncasecmp(haystack, needle, len) == 0 {
+            return haystack as *mut c_char;
+        }
+        haystack = haystack.offset(1);
+    }
+    ptr::null_mut()
 }
 
 #[no_mangle]
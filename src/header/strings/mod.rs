@@ -1,6 +1,6 @@
 //! strings implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/strings.h.html
 
-use core::ptr;
+use core::{mem, ptr};
 
 use crate::{
     header::{ctype, string},
@@ -30,6 +30,33 @@ pub extern "C" fn ffs(i: c_int) -> c_int {
     1 + i.trailing_zeros() as c_int
 }
 
+#[no_mangle]
+pub extern "C" fn ffsl(i: c_long) -> c_int {
+    if i == 0 {
+        return 0;
+    }
+    1 + i.trailing_zeros() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn ffsll(i: c_longlong) -> c_int {
+    if i == 0 {
+        return 0;
+    }
+    1 + i.trailing_zeros() as c_int
+}
+
+/// Find the 1-based position of the most significant bit set in `i`, or 0
+#[no_mangle]
+pub extern "C" fn fls(i: c_int) -> c_int {
+    (8 * mem::size_of::<c_int>()) as c_int - i.leading_zeros() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn flsl(i: c_long) -> c_int {
+    (8 * mem::size_of::<c_long>()) as c_int - i.leading_zeros() as c_int
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn index(s: *const c_char, c: c_int) -> *mut c_char {
     string::strchr(s, c)
@@ -51,8 +78,12 @@ pub unsafe extern "C" fn strncasecmp(
     mut second: *const c_char,
     mut n: size_t,
 ) -> c_int {
+    // relibc only has the C and UTF-8 locales. In both, only A-Z have a lower
+    // case byte, and everything else, including each byte of a UTF-8 sequence,
+    // is compared as an unsigned char.
     while n > 0 && (*first != 0 || *second != 0) {
-        let cmp = ctype::tolower(*first as c_int) - ctype::tolower(*second as c_int);
+        let cmp = ctype::tolower(*first as c_uchar as c_int)
+            - ctype::tolower(*second as c_uchar as c_int);
         if cmp != 0 {
             return cmp;
         }
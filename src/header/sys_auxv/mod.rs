@@ -1,6 +1,9 @@
 //! sys/auxv.h implementation
 
-use crate::platform::types::*;
+use crate::{
+    header::errno,
+    platform::{self, types::*},
+};
 
 pub const AT_NULL: usize = 0; /* End of vector */
 pub const AT_IGNORE: usize = 1; /* Entry should be ignored */
@@ -25,12 +28,35 @@ pub const AT_DCACHEBSIZE: usize = 19; /* Data cache block size.  */
 pub const AT_ICACHEBSIZE: usize = 20; /* Instruction cache block size.  */
 pub const AT_UCACHEBSIZE: usize = 21; /* Unified cache block size.  */
 pub const AT_IGNOREPPC: usize = 22; /* Entry should be ignored.  */
+pub const AT_SECURE: usize = 23; /* Boolean, was exec setuid-like?  */
 pub const AT_BASE_PLATFORM: usize = 24; /* String identifying real platforms.*/
 pub const AT_RANDOM: usize = 25; /* Address of 16 random bytes.  */
 pub const AT_HWCAP2: usize = 26; /* More machine-dependent hints about*/
 pub const AT_EXECFN: usize = 31; /* Filename of executable.  */
 
+/// The value of the entry `kind` of the auxiliary vector
+pub unsafe fn find_auxv(kind: usize) -> Option<usize> {
+    let mut auxv = platform::auxv;
+    if !auxv.is_null() {
+        while (*auxv).0 != AT_NULL {
+            if (*auxv).0 == kind {
+                return Some((*auxv).1);
+            }
+            auxv = auxv.add(1);
+        }
+    }
+    None
+}
+
+/// The value of the entry `t` of the auxiliary vector the kernel gave the
+/// program, or 0 with `errno` set to `ENOENT` if there is none
 #[no_mangle]
-pub extern "C" fn getauxval(_t: c_ulong) -> c_ulong {
-    0
+pub unsafe extern "C" fn getauxval(t: c_ulong) -> c_ulong {
+    match find_auxv(t as usize) {
+        Some(value) => value as c_ulong,
+        None => {
+            platform::errno = errno::ENOENT;
+            0
+        }
+    }
 }
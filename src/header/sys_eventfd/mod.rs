@@ -0,0 +1,44 @@
+//! sys/eventfd.h implementation, following the Linux eventfd(2) interface
+
+use core::mem;
+
+use crate::{
+    header::unistd,
+    platform::{types::*, Pal, Sys},
+};
+
+pub const EFD_SEMAPHORE: c_int = 0x1;
+pub const EFD_NONBLOCK: c_int = 0x800;
+pub const EFD_CLOEXEC: c_int = 0x8_0000;
+
+pub type eventfd_t = u64;
+
+#[no_mangle]
+pub extern "C" fn eventfd(initval: c_uint, flags: c_int) -> c_int {
+    trace_expr!(
+        Sys::eventfd(initval, flags),
+        "eventfd({}, {:#x})",
+        initval,
+        flags
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn eventfd_read(fd: c_int, value: *mut eventfd_t) -> c_int {
+    let size = mem::size_of::<eventfd_t>();
+    if unistd::read(fd, value as *mut c_void, size) == size as ssize_t {
+        0
+    } else {
+        -1
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn eventfd_write(fd: c_int, value: eventfd_t) -> c_int {
+    let size = mem::size_of::<eventfd_t>();
+    if unistd::write(fd, &value as *const eventfd_t as *const c_void, size) == size as ssize_t {
+        0
+    } else {
+        -1
+    }
+}
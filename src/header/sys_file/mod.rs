@@ -2,10 +2,10 @@
 
 use crate::platform::{types::*, Pal, Sys};
 
-pub const LOCK_SH: usize = 1;
-pub const LOCK_EX: usize = 2;
-pub const LOCK_NB: usize = 4;
-pub const LOCK_UN: usize = 8;
+pub const LOCK_SH: c_int = 1;
+pub const LOCK_EX: c_int = 2;
+pub const LOCK_NB: c_int = 4;
+pub const LOCK_UN: c_int = 8;
 
 pub const L_SET: usize = 0;
 pub const L_INCR: usize = 1;
@@ -65,7 +65,7 @@ pub unsafe extern "C" fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) ->
             } else {
                 flags | fcntl::O_NONBLOCK
             };
-            if fcntl::sys_fcntl(fd, fcntl::F_SETFL, flags) < 0 {
+            if fcntl::sys_fcntl(fd, fcntl::F_SETFL, flags as c_ulong) < 0 {
                 -1
             } else {
                 0
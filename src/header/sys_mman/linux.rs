@@ -7,3 +7,6 @@ pub const PROT_NONE: c_int = 0x0000;
 
 pub const MAP_FIXED: c_int = 0x0010;
 pub const MAP_FIXED_NOREPLACE: c_int = 0x100000;
+
+pub const MREMAP_MAYMOVE: c_int = 0x0001;
+pub const MREMAP_FIXED: c_int = 0x0002;
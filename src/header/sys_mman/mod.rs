@@ -1,7 +1,9 @@
+use core::{ffi::VaList, ptr};
+
 use crate::{
     c_str::{CStr, CString},
-    header::{fcntl, unistd},
-    platform::{types::*, Pal, Sys},
+    header::{errno, fcntl, unistd},
+    platform::{self, types::*, Pal, Sys},
 };
 
 pub use self::sys::*;
@@ -20,18 +22,34 @@ pub const MAP_TYPE: c_int = 0x000F;
 pub const MAP_ANON: c_int = 0x0020;
 pub const MAP_ANONYMOUS: c_int = MAP_ANON;
 
+pub const MCL_CURRENT: c_int = 0x0001;
+pub const MCL_FUTURE: c_int = 0x0002;
+pub const MCL_ONFAULT: c_int = 0x0004;
+
 pub const MS_ASYNC: c_int = 0x0001;
 pub const MS_INVALIDATE: c_int = 0x0002;
 pub const MS_SYNC: c_int = 0x0004;
 
-// #[no_mangle]
-pub extern "C" fn mlock(addr: *const c_void, len: usize) -> c_int {
-    unimplemented!();
+/// Widen a range to the pages it touches, as locking works on whole pages
+fn page_range(addr: *const c_void, len: size_t) -> (*const c_void, size_t) {
+    let page_size = unistd::getpagesize() as usize;
+    let offset = addr as usize % page_size;
+    ((addr as usize - offset) as *const c_void, len + offset)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mlock(addr: *const c_void, len: size_t) -> c_int {
+    let (addr, len) = page_range(addr, len);
+    Sys::mlock(addr, len)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn mlockall(flags: c_int) -> c_int {
-    unimplemented!();
+    if flags == 0 || flags & !(MCL_CURRENT | MCL_FUTURE | MCL_ONFAULT) != 0 {
+        unsafe { platform::errno = errno::EINVAL };
+        return -1;
+    }
+    Sys::mlockall(flags)
 }
 
 #[no_mangle]
@@ -43,7 +61,25 @@ pub unsafe extern "C" fn mmap(
     fildes: c_int,
     off: off_t,
 ) -> *mut c_void {
-    Sys::mmap(addr, len, prot, flags, fildes, off)
+    let map_failed = !0 as *mut c_void;
+
+    let fixed = flags & (MAP_FIXED | MAP_FIXED_NOREPLACE) != 0;
+    if len == 0 || (fixed && addr as usize % unistd::getpagesize() as usize != 0) {
+        platform::errno = errno::EINVAL;
+        return map_failed;
+    }
+
+    let ptr = Sys::mmap(addr, len, prot, flags, fildes, off);
+
+    // Kernels predating MAP_FIXED_NOREPLACE take the address as a hint, and
+    // map elsewhere if the range is occupied
+    if flags & MAP_FIXED_NOREPLACE == MAP_FIXED_NOREPLACE && ptr != map_failed && ptr != addr {
+        Sys::munmap(ptr, len);
+        platform::errno = errno::EEXIST;
+        return map_failed;
+    }
+
+    ptr
 }
 
 #[no_mangle]
@@ -51,19 +87,47 @@ pub unsafe extern "C" fn mprotect(addr: *mut c_void, len: size_t, prot: c_int) -
     Sys::mprotect(addr, len, prot)
 }
 
+/// `mremap`, given its variadic arguments by the wrapper in C. Only Linux can
+/// remap, elsewhere this fails with ENOSYS.
+#[no_mangle]
+pub unsafe extern "C" fn sys_mremap(
+    old_address: *mut c_void,
+    old_size: size_t,
+    new_size: size_t,
+    flags: c_int,
+    mut ap: VaList,
+) -> *mut c_void {
+    // The new address is only passed with MREMAP_FIXED
+    let new_address = if flags & MREMAP_FIXED == MREMAP_FIXED {
+        ap.arg::<*mut c_void>()
+    } else {
+        ptr::null_mut()
+    };
+    Sys::mremap(old_address, old_size, new_size, flags, new_address)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn msync(addr: *mut c_void, len: size_t, flags: c_int) -> c_int {
+    let sync_flags = flags & (MS_SYNC | MS_ASYNC);
+    if addr as usize % unistd::getpagesize() as usize != 0
+        || flags & !(MS_SYNC | MS_ASYNC | MS_INVALIDATE) != 0
+        || sync_flags == MS_SYNC | MS_ASYNC
+    {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
     Sys::msync(addr, len, flags)
 }
 
-// #[no_mangle]
-pub extern "C" fn munlock(addr: *const c_void, len: usize) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn munlock(addr: *const c_void, len: size_t) -> c_int {
+    let (addr, len) = page_range(addr, len);
+    Sys::munlock(addr, len)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn munlockall() -> c_int {
-    unimplemented!();
+    Sys::munlockall()
 }
 
 #[no_mangle]
@@ -77,34 +141,45 @@ static SHM_PATH: &'static [u8] = b"/dev/shm/";
 #[cfg(target_os = "redox")]
 static SHM_PATH: &'static [u8] = b"shm:";
 
-unsafe fn shm_path(name: *const c_char) -> CString {
-    let name_c = CStr::from_ptr(name);
-
-    let mut path = SHM_PATH.to_vec();
-
-    let mut skip_slash = true;
-    for &b in name_c.to_bytes() {
-        if skip_slash {
-            if b == b'/' {
-                continue;
-            } else {
-                skip_slash = false;
-            }
-        }
-        path.push(b);
+/// The file backing a shared memory object, or with a `prefix` another
+/// object kept in the same directory. Leading slashes are dropped, and no
+/// others may follow, so that objects cannot escape the directory
+pub(crate) unsafe fn shm_path(name: *const c_char, prefix: &[u8]) -> Result<CString, c_int> {
+    let name = CStr::from_ptr(name).to_bytes();
+    let slashes = name.iter().take_while(|&&b| b == b'/').count();
+    let name = &name[slashes..];
+    if name.is_empty() || name == b"." || name == b".." || name.contains(&b'/') {
+        return Err(errno::EINVAL);
+    }
+    if prefix.len() + name.len() > 255 {
+        return Err(errno::ENAMETOOLONG);
     }
 
-    CString::from_vec_unchecked(path)
+    let mut path = SHM_PATH.to_vec();
+    path.extend_from_slice(prefix);
+    path.extend_from_slice(name);
+    Ok(CString::from_vec_unchecked(path))
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn shm_open(name: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
-    let path = shm_path(name);
-    fcntl::sys_open(path.as_ptr(), oflag, mode)
+    match shm_path(name, b"") {
+        // POSIX requires FD_CLOEXEC on the new descriptor
+        Ok(path) => Sys::open(&path, oflag | fcntl::O_CLOEXEC, mode),
+        Err(err) => {
+            platform::errno = err;
+            -1
+        }
+    }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn shm_unlink(name: *const c_char) -> c_int {
-    let path = shm_path(name);
-    unistd::unlink(path.as_ptr())
+    match shm_path(name, b"") {
+        Ok(path) => Sys::unlink(&path),
+        Err(err) => {
+            platform::errno = err;
+            -1
+        }
+    }
 }
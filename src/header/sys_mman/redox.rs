@@ -7,3 +7,7 @@ pub const PROT_READ: c_int = 0x0004;
 
 pub const MAP_FIXED: c_int = 0x0004;
 pub const MAP_FIXED_NOREPLACE: c_int = 0x000C;
+
+// mremap always fails with ENOSYS, these only let callers compile
+pub const MREMAP_MAYMOVE: c_int = 0x0001;
+pub const MREMAP_FIXED: c_int = 0x0002;
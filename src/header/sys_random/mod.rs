@@ -1,14 +1,63 @@
 use core::slice;
 
-use crate::platform::{types::*, Pal, Sys};
+use crate::{
+    header::{errno, fcntl},
+    platform::{self, types::*, Pal, Sys},
+};
 
 pub const GRND_NONBLOCK: c_uint = 1;
 pub const GRND_RANDOM: c_uint = 2;
 
 #[no_mangle]
 pub unsafe extern "C" fn getrandom(buf: *mut c_void, buflen: size_t, flags: c_uint) -> ssize_t {
-    Sys::getrandom(
-        slice::from_raw_parts_mut(buf as *mut u8, buflen as usize),
-        flags,
-    )
+    if flags & !(GRND_NONBLOCK | GRND_RANDOM) != 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    let buf = slice::from_raw_parts_mut(buf as *mut u8, buflen as usize);
+
+    let errno_backup = platform::errno;
+    let res = fill(buf, |buf| Sys::getrandom(buf, flags));
+    if res >= 0 || platform::errno != errno::ENOSYS {
+        return res;
+    }
+    platform::errno = errno_backup;
+
+    getrandom_fallback(buf, flags)
+}
+
+/// Read from the random devices where the kernel has no getrandom
+unsafe fn getrandom_fallback(buf: &mut [u8], flags: c_uint) -> ssize_t {
+    let path = if flags & GRND_RANDOM != 0 {
+        c_str!("/dev/random")
+    } else {
+        c_str!("/dev/urandom")
+    };
+    let mut oflag = fcntl::O_RDONLY | fcntl::O_CLOEXEC;
+    if flags & GRND_NONBLOCK != 0 {
+        oflag |= fcntl::O_NONBLOCK;
+    }
+
+    let fd = Sys::open(path, oflag, 0);
+    if fd < 0 {
+        return -1;
+    }
+    let res = fill(buf, |buf| Sys::read(fd, buf));
+    Sys::close(fd);
+    res
+}
+
+/// Call `read` until `buf` is full, retrying when interrupted. An error after
+/// some bytes were read, such as EAGAIN with GRND_NONBLOCK, gives a short count.
+unsafe fn fill<F: FnMut(&mut [u8]) -> ssize_t>(buf: &mut [u8], mut read: F) -> ssize_t {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match read(&mut buf[filled..]) {
+            -1 if platform::errno == errno::EINTR => continue,
+            -1 if filled == 0 => return -1,
+            res if res <= 0 => break,
+            res => filled += res as usize,
+        }
+    }
+    filled as ssize_t
 }
@@ -7,10 +7,12 @@ use crate::{
 };
 
 // Exported in bits file
-// const RUSAGE_SELF: c_int = 0;
-// const RUSAGE_CHILDREN: c_int = -1;
-// const RUSAGE_BOTH: c_int = -2;
-// const RUSAGE_THREAD: c_int = 1;
+#[cfg(target_os = "redox")]
+pub(crate) const RUSAGE_SELF: c_int = 0;
+#[cfg(target_os = "redox")]
+pub(crate) const RUSAGE_CHILDREN: c_int = -1;
+#[cfg(target_os = "redox")]
+pub(crate) const RUSAGE_THREAD: c_int = 1;
 
 pub const RLIM_INFINITY: u64 = 0xFFFF_FFFF_FFFF_FFFF;
 pub const RLIM_SAVED_CUR: u64 = RLIM_INFINITY;
@@ -71,18 +73,29 @@ pub unsafe extern "C" fn getrlimit(resource: c_int, rlp: *mut rlimit) -> c_int {
     Sys::getrlimit(resource, rlp)
 }
 
-// #[no_mangle]
-// pub unsafe extern "C" fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
-//     // Sys::getrusage(who, r_usage)
-//     unimplemented!();
-// }
-//
+#[no_mangle]
+pub unsafe extern "C" fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
+    Sys::getrusage(who, r_usage)
+}
+
+/// Get and set a limit of another process (or this one, if `pid` is 0) at
+/// once. Either of the limits may be null.
+#[no_mangle]
+pub unsafe extern "C" fn prlimit(
+    pid: pid_t,
+    resource: c_int,
+    new_limit: *const rlimit,
+    old_limit: *mut rlimit,
+) -> c_int {
+    Sys::prlimit(pid, resource, new_limit, old_limit)
+}
+
 // #[no_mangle]
 // pub unsafe extern "C" fn setpriority(which: c_int, who: id_t, nice: c_int) -> c_int {
 //     unimplemented!();
 // }
-//
-// #[no_mangle]
-// pub unsafe extern "C" fn setrlimit(resource: c_int, rlp: *const rlimit) -> c_int {
-//     unimplemented!();
-// }
+
+#[no_mangle]
+pub unsafe extern "C" fn setrlimit(resource: c_int, rlp: *const rlimit) -> c_int {
+    Sys::setrlimit(resource, rlp)
+}
@@ -1,6 +1,6 @@
 //! sys/select.h implementation
 
-use core::mem;
+use core::{mem, ptr};
 
 use cbitset::BitSet;
 
@@ -8,11 +8,14 @@ use crate::{
     fs::File,
     header::{
         errno,
+        poll::timespec_to_ms,
+        signal::sigset_t,
         sys_epoll::{
-            epoll_create1, epoll_ctl, epoll_data, epoll_event, epoll_wait, EPOLLERR, EPOLLIN,
+            epoll_create1, epoll_ctl, epoll_data, epoll_event, epoll_pwait, EPOLLERR, EPOLLIN,
             EPOLLOUT, EPOLL_CLOEXEC, EPOLL_CTL_ADD,
         },
         sys_time::timeval,
+        time::timespec,
     },
     platform::{self, types::*},
 };
@@ -32,7 +35,8 @@ pub fn select_epoll(
     readfds: Option<&mut fd_set>,
     writefds: Option<&mut fd_set>,
     exceptfds: Option<&mut fd_set>,
-    timeout: Option<&mut timeval>,
+    timeout: c_int,
+    sigmask: *const sigset_t,
 ) -> c_int {
     if nfds < 0 || nfds > FD_SETSIZE as i32 {
         unsafe { platform::errno = errno::EINVAL };
@@ -113,19 +117,15 @@ pub fn select_epoll(
         // Do not wait if any non-epoll file descriptors were found
         0
     } else {
-        match timeout {
-            Some(timeout) => {
-                //TODO: Check for overflow
-                ((timeout.tv_sec as c_int) * 1000) + ((timeout.tv_usec as c_int) / 1000)
-            }
-            None => -1,
-        }
+        timeout
     };
-    let res = epoll_wait(
+    // The kernel installs sigmask only for the duration of the wait
+    let res = epoll_pwait(
         *ep,
         events.as_mut_ptr(),
         events.len() as c_int,
         epoll_timeout,
+        sigmask,
     );
     if res < 0 {
         return -1;
@@ -186,10 +186,12 @@ pub unsafe extern "C" fn select(
                 Some(&mut *exceptfds)
             },
             if timeout.is_null() {
-                None
+                -1
             } else {
-                Some(&mut *timeout)
-            }
+                //TODO: Check for overflow
+                ((*timeout).tv_sec as c_int) * 1000 + ((*timeout).tv_usec as c_int) / 1000
+            },
+            ptr::null()
         ),
         "select({}, {:p}, {:p}, {:p}, {:p})",
         nfds,
@@ -199,3 +201,50 @@ pub unsafe extern "C" fn select(
         timeout
     )
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn pselect(
+    nfds: c_int,
+    readfds: *mut fd_set,
+    writefds: *mut fd_set,
+    exceptfds: *mut fd_set,
+    timeout: *const timespec,
+    sigmask: *const sigset_t,
+) -> c_int {
+    let timeout_ms = match timespec_to_ms(timeout) {
+        Some(ms) => ms,
+        None => {
+            platform::errno = errno::EINVAL;
+            return -1;
+        }
+    };
+    trace_expr!(
+        select_epoll(
+            nfds,
+            if readfds.is_null() {
+                None
+            } else {
+                Some(&mut *readfds)
+            },
+            if writefds.is_null() {
+                None
+            } else {
+                Some(&mut *writefds)
+            },
+            if exceptfds.is_null() {
+                None
+            } else {
+                Some(&mut *exceptfds)
+            },
+            timeout_ms,
+            sigmask
+        ),
+        "pselect({}, {:p}, {:p}, {:p}, {:p}, {:p})",
+        nfds,
+        readfds,
+        writefds,
+        exceptfds,
+        timeout,
+        sigmask
+    )
+}
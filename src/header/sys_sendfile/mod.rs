@@ -0,0 +1,81 @@
+//! sys/sendfile.h implementation, following the Linux sendfile(2) interface
+
+use crate::{
+    header::{errno, unistd},
+    platform::{self, types::*, Pal, Sys},
+};
+
+#[no_mangle]
+pub unsafe extern "C" fn sendfile(
+    out_fd: c_int,
+    in_fd: c_int,
+    offset: *mut off_t,
+    count: size_t,
+) -> ssize_t {
+    let errno_backup = platform::errno;
+    let res = Sys::sendfile(out_fd, in_fd, offset, count);
+    if res >= 0 || platform::errno != errno::ENOSYS {
+        return res;
+    }
+    platform::errno = errno_backup;
+
+    trace_expr!(
+        sendfile_fallback(out_fd, in_fd, offset, count),
+        "sendfile_fallback({}, {}, {:p}, {})",
+        out_fd,
+        in_fd,
+        offset,
+        count
+    )
+}
+
+/// Copy through a buffer where the kernel cannot. Like sendfile, a non-null
+/// offset is read from and advanced instead of the file position of in_fd.
+unsafe fn sendfile_fallback(
+    out_fd: c_int,
+    in_fd: c_int,
+    offset: *mut off_t,
+    count: size_t,
+) -> ssize_t {
+    let mut buf = [0u8; 8192];
+    let mut total: size_t = 0;
+
+    while total < count {
+        let len = buf.len().min(count - total);
+        let buf_ptr = buf.as_mut_ptr() as *mut c_void;
+        let read = if offset.is_null() {
+            unistd::read(in_fd, buf_ptr, len)
+        } else {
+            unistd::pread(in_fd, buf_ptr, len, *offset)
+        };
+        if read <= 0 {
+            if read < 0 && total == 0 {
+                return -1;
+            }
+            break;
+        }
+
+        let written = unistd::write(out_fd, buf_ptr, read as size_t);
+        if written <= 0 {
+            if written < 0 && total == 0 {
+                return -1;
+            }
+            break;
+        }
+        total += written as size_t;
+        if !offset.is_null() {
+            *offset += written as off_t;
+        }
+
+        if written < read {
+            // Without an offset, the unwritten bytes were consumed from in_fd
+            // and are lost, so put the file position back
+            if offset.is_null() {
+                unistd::lseek(in_fd, (written - read) as off_t, unistd::SEEK_CUR);
+            }
+            break;
+        }
+    }
+
+    total as ssize_t
+}
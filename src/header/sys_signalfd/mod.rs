@@ -0,0 +1,47 @@
+//! sys/signalfd.h implementation, following the Linux signalfd(2) interface
+
+use crate::{
+    header::signal::sigset_t,
+    platform::{types::*, Pal, Sys},
+};
+
+pub const SFD_NONBLOCK: c_int = 0x800;
+pub const SFD_CLOEXEC: c_int = 0x8_0000;
+
+/// The record read from a signalfd for each pending signal, 128 bytes long
+#[repr(C)]
+pub struct signalfd_siginfo {
+    pub ssi_signo: u32,
+    pub ssi_errno: i32,
+    pub ssi_code: i32,
+    pub ssi_pid: u32,
+    pub ssi_uid: u32,
+    pub ssi_fd: i32,
+    pub ssi_tid: u32,
+    pub ssi_band: u32,
+    pub ssi_overrun: u32,
+    pub ssi_trapno: u32,
+    pub ssi_status: i32,
+    pub ssi_int: i32,
+    pub ssi_ptr: u64,
+    pub ssi_utime: u64,
+    pub ssi_stime: u64,
+    pub ssi_addr: u64,
+    pub ssi_addr_lsb: u16,
+    pub __pad2: u16,
+    pub ssi_syscall: i32,
+    pub ssi_call_addr: u64,
+    pub ssi_arch: u32,
+    pub __pad: [u8; 28],
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn signalfd(fd: c_int, mask: *const sigset_t, flags: c_int) -> c_int {
+    trace_expr!(
+        Sys::signalfd(fd, mask, flags),
+        "signalfd({}, {:p}, {:#x})",
+        fd,
+        mask,
+        flags
+    )
+}
@@ -2,6 +2,7 @@ use crate::platform::types::*;
 
 pub const SOCK_STREAM: c_int = 1;
 pub const SOCK_DGRAM: c_int = 2;
+pub const SOCK_RAW: c_int = 3;
 pub const SOCK_NONBLOCK: c_int = 0o4_000;
 pub const SOCK_CLOEXEC: c_int = 0o2_000_000;
 
@@ -43,6 +44,7 @@ pub const SOMAXCONN: c_int = 128;
 pub const MSG_CTRUNC: c_int = 8;
 pub const MSG_DONTROUTE: c_int = 4;
 pub const MSG_EOR: c_int = 128;
+pub const MSG_NOSIGNAL: c_int = 0x4000;
 pub const MSG_OOB: c_int = 1;
 pub const MSG_PEEK: c_int = 2;
 pub const MSG_TRUNC: c_int = 32;
@@ -50,11 +52,13 @@ pub const MSG_WAITALL: c_int = 256;
 
 pub const AF_INET: c_int = 2;
 pub const AF_INET6: c_int = 10;
+pub const AF_NETLINK: c_int = 16;
 pub const AF_UNIX: c_int = 1;
 pub const AF_UNSPEC: c_int = 0;
 
 pub const PF_INET: c_int = 2;
 pub const PF_INET6: c_int = 10;
+pub const PF_NETLINK: c_int = 16;
 pub const PF_UNIX: c_int = 1;
 pub const PF_UNSPEC: c_int = 0;
 
@@ -3,10 +3,11 @@
 use crate::{
     c_str::CStr,
     header::{
+        errno,
         fcntl::{O_NOFOLLOW, O_PATH},
         time::timespec,
     },
-    platform::{types::*, Pal, Sys},
+    platform::{self, types::*, Pal, Sys},
 };
 
 pub const S_IFMT: c_int = 0o0_170_000;
@@ -37,6 +38,9 @@ pub const S_ISUID: c_int = 0o4_000;
 pub const S_ISGID: c_int = 0o2_000;
 pub const S_ISVTX: c_int = 0o1_000;
 
+pub const UTIME_NOW: c_long = 0x3FFF_FFFF;
+pub const UTIME_OMIT: c_long = 0x3FFF_FFFE;
+
 #[repr(C)]
 #[derive(Default)]
 pub struct stat {
@@ -77,6 +81,17 @@ pub extern "C" fn fstat(fildes: c_int, buf: *mut stat) -> c_int {
     Sys::fstat(fildes, buf)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn fstatat(
+    dirfd: c_int,
+    path: *const c_char,
+    buf: *mut stat,
+    flag: c_int,
+) -> c_int {
+    let path = CStr::from_ptr(path);
+    Sys::fstatat(dirfd, path, buf, flag)
+}
+
 #[no_mangle]
 pub extern "C" fn __fxstat(_ver: c_int, fildes: c_int, buf: *mut stat) -> c_int {
     fstat(fildes, buf)
@@ -108,6 +123,12 @@ pub unsafe extern "C" fn mkdir(path: *const c_char, mode: mode_t) -> c_int {
     Sys::mkdir(path, mode)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn mkdirat(dirfd: c_int, path: *const c_char, mode: mode_t) -> c_int {
+    let path = CStr::from_ptr(path);
+    Sys::mkdirat(dirfd, path, mode)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn mkfifo(path: *const c_char, mode: mode_t) -> c_int {
     let path = CStr::from_ptr(path);
@@ -138,3 +159,18 @@ pub unsafe extern "C" fn stat(file: *const c_char, buf: *mut stat) -> c_int {
 pub extern "C" fn umask(mask: mode_t) -> mode_t {
     Sys::umask(mask)
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn utimensat(
+    dirfd: c_int,
+    path: *const c_char,
+    times: *const timespec,
+    flag: c_int,
+) -> c_int {
+    if path.is_null() {
+        platform::errno = errno::EFAULT;
+        return -1;
+    }
+    let path = CStr::from_ptr(path);
+    Sys::utimensat(dirfd, path, times, flag)
+}
@@ -6,8 +6,8 @@ use crate::{
     platform::{types::*, Pal, Sys},
 };
 
-//pub const ST_RDONLY
-//pub const ST_NOSUID
+pub const ST_RDONLY: c_ulong = 1;
+pub const ST_NOSUID: c_ulong = 2;
 
 #[repr(C)]
 #[derive(Default)]
@@ -1,8 +1,10 @@
 //! sys/time implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/systime.h.html
 
+use core::ptr;
+
 use crate::{
     c_str::CStr,
-    header::time::timespec,
+    header::{fcntl::AT_FDCWD, time::timespec},
     platform::{types::*, Pal, PalSignal, Sys},
 };
 
@@ -57,6 +59,9 @@ pub extern "C" fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int {
 #[no_mangle]
 pub unsafe extern "C" fn utimes(path: *const c_char, times: *const timeval) -> c_int {
     let path = CStr::from_ptr(path);
+    if times.is_null() {
+        return Sys::utimensat(AT_FDCWD, path, ptr::null(), 0);
+    }
     let times_spec = [
         timespec {
             tv_sec: (*times.offset(0)).tv_sec,
@@ -67,5 +72,5 @@ pub unsafe extern "C" fn utimes(path: *const c_char, times: *const timeval) -> c
             tv_nsec: ((*times.offset(1)).tv_usec as i64) * 1000,
         },
     ];
-    Sys::utimens(path, times_spec.as_ptr())
+    Sys::utimensat(AT_FDCWD, path, times_spec.as_ptr(), 0)
 }
@@ -0,0 +1,48 @@
+//! sys/timerfd.h implementation, following the Linux timerfd_create(2)
+//! interface. Reading a timerfd yields the number of expirations as a u64.
+
+use crate::{
+    header::time::itimerspec,
+    platform::{types::*, Pal, Sys},
+};
+
+pub const TFD_TIMER_ABSTIME: c_int = 0x1;
+pub const TFD_NONBLOCK: c_int = 0x800;
+pub const TFD_CLOEXEC: c_int = 0x8_0000;
+
+#[no_mangle]
+pub extern "C" fn timerfd_create(clockid: clockid_t, flags: c_int) -> c_int {
+    trace_expr!(
+        Sys::timerfd_create(clockid, flags),
+        "timerfd_create({}, {:#x})",
+        clockid,
+        flags
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int {
+    trace_expr!(
+        Sys::timerfd_gettime(fd, curr_value),
+        "timerfd_gettime({}, {:p})",
+        fd,
+        curr_value
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn timerfd_settime(
+    fd: c_int,
+    flags: c_int,
+    new_value: *const itimerspec,
+    old_value: *mut itimerspec,
+) -> c_int {
+    trace_expr!(
+        Sys::timerfd_settime(fd, flags, new_value, old_value),
+        "timerfd_settime({}, {:#x}, {:p}, {:p})",
+        fd,
+        flags,
+        new_value,
+        old_value
+    )
+}
@@ -1,59 +1,132 @@
 //! sys/uio implementation for Redox, following http://pubs.opengroup.org/onlinepubs/007904875/basedefs/sys/uio.h.html
 
-use alloc::vec::Vec;
-use core::slice;
-
 use crate::{
-    header::{errno, unistd},
-    platform::{self, types::*},
+    header::errno,
+    platform::{self, types::*, Pal, Sys},
 };
 
 pub const IOV_MAX: c_int = 1024;
 
+pub const RWF_HIPRI: c_int = 0x0000_0001;
+pub const RWF_DSYNC: c_int = 0x0000_0002;
+pub const RWF_SYNC: c_int = 0x0000_0004;
+pub const RWF_NOWAIT: c_int = 0x0000_0008;
+pub const RWF_APPEND: c_int = 0x0000_0010;
+
 #[repr(C)]
 pub struct iovec {
-    iov_base: *mut c_void,
-    iov_len: size_t,
+    pub iov_base: *mut c_void,
+    pub iov_len: size_t,
 }
 
-impl iovec {
-    unsafe fn to_slice(&self) -> &mut [u8] {
-        slice::from_raw_parts_mut(self.iov_base as *mut u8, self.iov_len as usize)
+#[no_mangle]
+pub unsafe extern "C" fn preadv(
+    fd: c_int,
+    iov: *const iovec,
+    iovcnt: c_int,
+    offset: off_t,
+) -> ssize_t {
+    // Unlike preadv2, -1 is not a valid offset here
+    if iovcnt < 0 || iovcnt > IOV_MAX || offset < 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
     }
-}
 
-unsafe fn gather(iovs: &[iovec]) -> Vec<u8> {
-    let mut vec = Vec::new();
-    for iov in iovs.iter() {
-        vec.extend_from_slice(iov.to_slice());
-    }
-    vec
+    trace_expr!(
+        Sys::preadv(fd, iov, iovcnt, offset),
+        "preadv({}, {:p}, {}, {})",
+        fd,
+        iov,
+        iovcnt,
+        offset
+    )
 }
 
-unsafe fn scatter(iovs: &[iovec], vec: Vec<u8>) {
-    let mut i = 0;
-    for iov in iovs.iter() {
-        let slice = iov.to_slice();
-        slice.copy_from_slice(&vec[i..]);
-        i += slice.len();
+#[no_mangle]
+pub unsafe extern "C" fn preadv2(
+    fd: c_int,
+    iov: *const iovec,
+    iovcnt: c_int,
+    offset: off_t,
+    flags: c_int,
+) -> ssize_t {
+    if iovcnt < 0 || iovcnt > IOV_MAX {
+        platform::errno = errno::EINVAL;
+        return -1;
     }
+
+    trace_expr!(
+        Sys::preadv2(fd, iov, iovcnt, offset, flags),
+        "preadv2({}, {:p}, {}, {}, {:#x})",
+        fd,
+        iov,
+        iovcnt,
+        offset,
+        flags
+    )
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn readv(fd: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+pub unsafe extern "C" fn pwritev2(
+    fd: c_int,
+    iov: *const iovec,
+    iovcnt: c_int,
+    offset: off_t,
+    flags: c_int,
+) -> ssize_t {
     if iovcnt < 0 || iovcnt > IOV_MAX {
         platform::errno = errno::EINVAL;
         return -1;
     }
 
-    let iovs = slice::from_raw_parts(iov, iovcnt as usize);
-    let mut vec = gather(iovs);
+    trace_expr!(
+        Sys::pwritev2(fd, iov, iovcnt, offset, flags),
+        "pwritev2({}, {:p}, {}, {}, {:#x})",
+        fd,
+        iov,
+        iovcnt,
+        offset,
+        flags
+    )
+}
 
-    let ret = unistd::read(fd, vec.as_mut_ptr() as *mut c_void, vec.len());
+#[no_mangle]
+pub unsafe extern "C" fn pwritev(
+    fd: c_int,
+    iov: *const iovec,
+    iovcnt: c_int,
+    offset: off_t,
+) -> ssize_t {
+    // Unlike pwritev2, -1 is not a valid offset here
+    if iovcnt < 0 || iovcnt > IOV_MAX || offset < 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
 
-    scatter(iovs, vec);
+    trace_expr!(
+        Sys::pwritev(fd, iov, iovcnt, offset),
+        "pwritev({}, {:p}, {}, {})",
+        fd,
+        iov,
+        iovcnt,
+        offset
+    )
+}
 
-    ret
+#[no_mangle]
+pub unsafe extern "C" fn readv(fd: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+    if iovcnt < 0 || iovcnt > IOV_MAX {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+
+    trace_expr!(
+        Sys::readv(fd, iov, iovcnt),
+        "readv({}, {:p}, {})",
+        fd,
+        iov,
+        iovcnt
+    )
 }
 
 #[no_mangle]
@@ -63,8 +136,11 @@ pub unsafe extern "C" fn writev(fd: c_int, iov: *const iovec, iovcnt: c_int) ->
         return -1;
     }
 
-    let iovs = slice::from_raw_parts(iov, iovcnt as usize);
-    let vec = gather(iovs);
-
-    unistd::write(fd, vec.as_ptr() as *const c_void, vec.len())
+    trace_expr!(
+        Sys::writev(fd, iov, iovcnt),
+        "writev({}, {:p}, {})",
+        fd,
+        iov,
+        iovcnt
+    )
 }
@@ -1,8 +1,10 @@
 //! sys/wait.h implementation for Redox, following
 //! http://pubs.opengroup.org/onlinepubs/7908799/xsh/syswait.h.html
 
-//use header::sys_resource::rusage;
-use crate::platform::{types::*, Pal, Sys};
+use crate::{
+    header::{signal::siginfo_t, sys_resource::rusage},
+    platform::{self, types::*, Pal, Sys},
+};
 
 pub const WNOHANG: c_int = 1;
 pub const WUNTRACED: c_int = 2;
@@ -17,35 +19,47 @@ pub const __WALL: c_int = 0x4000_0000;
 #[allow(overflowing_literals)]
 pub const __WCLONE: c_int = 0x8000_0000;
 
+pub type idtype_t = c_int;
+
+pub const P_ALL: idtype_t = 0;
+pub const P_PID: idtype_t = 1;
+pub const P_PGID: idtype_t = 2;
+
 #[no_mangle]
 pub unsafe extern "C" fn wait(stat_loc: *mut c_int) -> pid_t {
     waitpid(!0, stat_loc, 0)
 }
 
-// #[no_mangle]
-// pub unsafe extern "C" fn wait3(
-//     stat_loc: *mut c_int,
-//     options: c_int,
-//     resource_usage: *mut rusage,
-// ) -> pid_t {
-//     unimplemented!();
-// }
-
-/*
- * TODO: implement idtype_t, id_t, and siginfo_t
- *
- * #[no_mangle]
- * pub unsafe extern "C" fn waitid(
- *     idtype: idtype_t,
- *     id: id_t,
- *     infop: siginfo_t,
- *     options: c_int
- *  ) -> c_int {
- *      unimplemented!();
- *  }
- */
+#[no_mangle]
+pub unsafe extern "C" fn wait3(
+    stat_loc: *mut c_int,
+    options: c_int,
+    resource_usage: *mut rusage,
+) -> pid_t {
+    wait4(!0, stat_loc, options, resource_usage)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wait4(
+    pid: pid_t,
+    stat_loc: *mut c_int,
+    options: c_int,
+    resource_usage: *mut rusage,
+) -> pid_t {
+    platform::cancellation_point(|| Sys::wait4(pid, stat_loc, options, resource_usage))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn waitid(
+    idtype: idtype_t,
+    id: id_t,
+    infop: *mut siginfo_t,
+    options: c_int,
+) -> c_int {
+    platform::cancellation_point(|| Sys::waitid(idtype, id, infop, options))
+}
 
 #[no_mangle]
 pub unsafe extern "C" fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
-    Sys::waitpid(pid, stat_loc, options)
+    platform::cancellation_point(|| Sys::waitpid(pid, stat_loc, options))
 }
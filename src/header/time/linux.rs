@@ -11,3 +11,14 @@ pub const CLOCK_BOOTTIME: c_int = 7;
 pub const CLOCK_REALTIME_ALARM: c_int = 8;
 pub const CLOCK_BOOTTIME_ALARM: c_int = 9;
 pub const CLOCK_TAI: c_int = 11;
+
+/// The clock for the CPU time of a process or thread, encoded as the kernel
+/// expects: the inverted id above the clock type (CPUCLOCK_SCHED) and the
+/// per thread flag
+pub(crate) fn cpu_clock(id: pid_t, per_thread: bool) -> Option<clockid_t> {
+    const CPUCLOCK_SCHED: clockid_t = 2;
+    const CPUCLOCK_PERTHREAD: clockid_t = 4;
+
+    let flag = if per_thread { CPUCLOCK_PERTHREAD } else { 0 };
+    Some((!id << 3) | CPUCLOCK_SCHED | flag)
+}
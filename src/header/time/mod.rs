@@ -3,14 +3,19 @@
 use core::convert::{TryFrom, TryInto};
 
 use crate::{
-    header::errno::{EIO, EOVERFLOW},
-    platform::{self, types::*, Pal, Sys},
+    header::{
+        errno::{EINTR, EINVAL, EIO, ENOSYS, EOVERFLOW, ESRCH},
+        signal::sigevent,
+    },
+    platform::{self, types::*, Pal, PalSignal, Sys},
 };
 
 pub use self::constants::*;
 
 pub mod constants;
 mod strftime;
+mod timer;
+mod tz;
 
 #[repr(C)]
 #[derive(Default)]
@@ -61,6 +66,13 @@ static mut TM: tm = tm {
     tm_zone: UTC,
 };
 
+#[no_mangle]
+pub static mut daylight: c_int = 0;
+#[no_mangle]
+pub static mut timezone: c_long = 0;
+#[no_mangle]
+pub static mut tzname: [*mut c_char; 2] = [UTC as *mut c_char, UTC as *mut c_char];
+
 // The C Standard says that ctime and asctime return the same pointer.
 static mut ASCTIME: [c_char; 26] = [0; 26];
 
@@ -70,8 +82,6 @@ pub struct itimerspec {
     pub it_value: timespec,
 }
 
-pub struct sigevent;
-
 #[no_mangle]
 pub unsafe extern "C" fn asctime(timeptr: *const tm) -> *mut c_char {
     asctime_r(timeptr, ASCTIME.as_mut_ptr().cast())
@@ -172,6 +182,27 @@ pub extern "C" fn clock() -> clock_t {
     ts.tv_sec * CLOCKS_PER_SEC + ts.tv_nsec / (1_000_000_000 / CLOCKS_PER_SEC)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn clock_getcpuclockid(pid: pid_t, clock_id: *mut clockid_t) -> c_int {
+    let clock = match cpu_clock(pid, false) {
+        Some(clock) => clock,
+        None => return ENOSYS,
+    };
+
+    // Reading the clock checks that the process exists
+    let errno_backup = platform::errno;
+    let mut ts = timespec::default();
+    let ret = Sys::clock_gettime(clock, &mut ts);
+    let err = platform::errno;
+    platform::errno = errno_backup;
+    if ret < 0 {
+        return if err == EINVAL { ESRCH } else { err };
+    }
+
+    *clock_id = clock;
+    0
+}
+
 // #[no_mangle]
 pub extern "C" fn clock_getres(clock_id: clockid_t, res: *mut timespec) -> c_int {
     unimplemented!();
@@ -182,6 +213,21 @@ pub extern "C" fn clock_gettime(clock_id: clockid_t, tp: *mut timespec) -> c_int
     Sys::clock_gettime(clock_id, tp)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn clock_nanosleep(
+    clock_id: clockid_t,
+    flags: c_int,
+    rqtp: *const timespec,
+    rmtp: *mut timespec,
+) -> c_int {
+    // Unlike nanosleep, the error is returned instead of being stored in errno
+    let errno_backup = platform::errno;
+    let ret = platform::cancellation_point(|| Sys::clock_nanosleep(clock_id, flags, rqtp, rmtp));
+    let err = if ret < 0 { platform::errno } else { 0 };
+    platform::errno = errno_backup;
+    err
+}
+
 // #[no_mangle]
 pub extern "C" fn clock_settime(clock_id: clockid_t, tp: *const timespec) -> c_int {
     unimplemented!();
@@ -227,18 +273,6 @@ pub unsafe extern "C" fn gmtime(timer: *const time_t) -> *mut tm {
     gmtime_r(timer, &mut TM)
 }
 
-const MONTH_DAYS: [[c_int; 12]; 2] = [
-    // Non-leap years:
-    [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
-    // Leap years:
-    [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
-];
-
-#[inline(always)]
-fn leap_year(year: c_int) -> bool {
-    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
-}
-
 #[no_mangle]
 pub unsafe extern "C" fn gmtime_r(clock: *const time_t, result: *mut tm) -> *mut tm {
     /* For the details of the algorithm used here, see
@@ -346,56 +380,60 @@ pub unsafe extern "C" fn localtime(clock: *const time_t) -> *mut tm {
 
 #[no_mangle]
 pub unsafe extern "C" fn localtime_r(clock: *const time_t, t: *mut tm) -> *mut tm {
-    // TODO: Change tm_isdst, tm_gmtoff, tm_zone
-    gmtime_r(clock, t)
+    tz::with_zone(|zone| {
+        let ltt = zone.find(*clock);
+        let local = match (*clock).checked_add(ltt.utoff) {
+            Some(local) => local,
+            None => {
+                platform::errno = EOVERFLOW;
+                return core::ptr::null_mut();
+            }
+        };
+
+        if gmtime_r(&local, t).is_null() {
+            return core::ptr::null_mut();
+        }
+        (*t).tm_isdst = ltt.isdst as c_int;
+        (*t).tm_gmtoff = ltt.utoff;
+        (*t).tm_zone = zone.abbr(&ltt);
+        t
+    })
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mktime(t: *mut tm) -> time_t {
-    let mut year = (*t).tm_year + 1900;
-    let mut month = (*t).tm_mon;
-    let mut day = (*t).tm_mday as i64 - 1;
-
-    let leap = if leap_year(year) { 1 } else { 0 };
-
-    if year < 1970 {
-        day = MONTH_DAYS[if leap_year(year) { 1 } else { 0 }][(*t).tm_mon as usize] as i64 - day;
-
-        while year < 1969 {
-            year += 1;
-            day += if leap_year(year) { 366 } else { 365 };
-        }
+    let local = tm_to_secs(t);
+    let clock = tz::with_zone(|zone| zone.local_to_utc(local, (*t).tm_isdst));
+    if localtime_r(&clock, t).is_null() {
+        return -1;
+    }
+    clock
+}
 
-        while month < 11 {
-            month += 1;
-            day += MONTH_DAYS[leap][month as usize] as i64;
-        }
+#[no_mangle]
+pub unsafe extern "C" fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
+    if !rmtp.is_null() {
+        return platform::cancellation_point(|| Sys::nanosleep(rqtp, rmtp));
+    }
 
-        -(day * (60 * 60 * 24)
-            - (((*t).tm_hour as i64) * (60 * 60) + ((*t).tm_min as i64) * 60 + (*t).tm_sec as i64))
-    } else {
-        while year > 1970 {
-            year -= 1;
-            day += if leap_year(year) { 366 } else { 365 };
+    // The caller doesn't care about the remaining time, so sleep through any
+    // signal interruptions
+    let mut rqt = timespec {
+        tv_sec: (*rqtp).tv_sec,
+        tv_nsec: (*rqtp).tv_nsec,
+    };
+    loop {
+        let mut rmt = timespec::default();
+        if platform::cancellation_point(|| Sys::nanosleep(&rqt, &mut rmt)) == 0 {
+            return 0;
         }
-
-        while month > 0 {
-            month -= 1;
-            day += MONTH_DAYS[leap][month as usize] as i64;
+        if platform::errno != EINTR {
+            return -1;
         }
-
-        (day * (60 * 60 * 24)
-            + ((*t).tm_hour as i64) * (60 * 60)
-            + ((*t).tm_min as i64) * 60
-            + (*t).tm_sec as i64)
+        rqt = rmt;
     }
 }
 
-#[no_mangle]
-pub extern "C" fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
-    Sys::nanosleep(rqtp, rmtp)
-}
-
 #[no_mangle]
 pub unsafe extern "C" fn strftime(
     s: *mut c_char,
@@ -432,65 +470,102 @@ pub unsafe extern "C" fn time(tloc: *mut time_t) -> time_t {
 
 #[no_mangle]
 pub unsafe extern "C" fn timelocal(tm: *mut tm) -> time_t {
-    //TODO: timezone
-    timegm(tm)
+    mktime(tm)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn timegm(tm: *mut tm) -> time_t {
-    let mut y = (*tm).tm_year as time_t + 1900;
-    let mut m = (*tm).tm_mon as time_t + 1;
-    if m <= 2 {
-        y -= 1;
-        m += 12;
+    let clock = tm_to_secs(tm);
+    if gmtime_r(&clock, tm).is_null() {
+        return -1;
     }
-    let d = (*tm).tm_mday as time_t;
-    let h = (*tm).tm_hour as time_t;
-    let mi = (*tm).tm_min as time_t;
-    let s = (*tm).tm_sec as time_t;
-    (365 * y + y / 4 - y / 100 + y / 400 + 3 * (m + 1) / 5 + 30 * m + d - 719561) * 86400
-        + 3600 * h
-        + 60 * mi
-        + s
+    clock
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_create(
+/// Number of days between 1970-01-01 and the given date in the proleptic
+/// Gregorian calendar. `month` is 0-based and must be in [0, 11], `mday` may
+/// be any value and is simply added to the first day of the month.
+///
+/// This is the inverse of the algorithm used in `gmtime_r`, see
+/// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(year: time_t, month: time_t, mday: time_t) -> time_t {
+    const DAYS_PER_ERA: time_t = 146097;
+
+    // Years start on March 1 in the "transformed" calendar
+    let year_transformed = if month < 2 { year - 1 } else { year };
+    let month_transformed = (month + 10) % 12;
+
+    let era = year_transformed.div_euclid(400);
+    let year_of_era = year_transformed.rem_euclid(400);
+    let day_of_year = (153 * month_transformed + 2) / 5 + mday - 1;
+    let day_of_era = 365 * year_of_era + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    // Shift origin from 0000-03-01 back to 1970-01-01
+    era * DAYS_PER_ERA + day_of_era - 719468
+}
+
+/// Seconds since the epoch for a broken-down UTC time, ignoring tm_wday,
+/// tm_yday and tm_isdst. Out-of-range fields are normalized: excess months
+/// carry into the year, and the remaining fields are accumulated as a plain
+/// offset from the first day of the (normalized) month.
+unsafe fn tm_to_secs(t: *const tm) -> time_t {
+    let mon = (*t).tm_mon as time_t;
+    let year = (*t).tm_year as time_t + 1900 + mon.div_euclid(12);
+    let days = days_from_civil(year, mon.rem_euclid(12), (*t).tm_mday as time_t);
+
+    days * (60 * 60 * 24)
+        + ((*t).tm_hour as time_t) * (60 * 60)
+        + ((*t).tm_min as time_t) * 60
+        + (*t).tm_sec as time_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn timer_create(
     clock_id: clockid_t,
     evp: *mut sigevent,
     timerid: *mut timer_t,
 ) -> c_int {
-    unimplemented!();
+    match timer::create(clock_id, evp) {
+        Some(timer) => {
+            *timerid = timer as timer_t;
+            0
+        }
+        None => -1,
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_delete(timerid: timer_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn timer_delete(timerid: timer_t) -> c_int {
+    timer::delete(timerid as *mut timer::Timer)
 }
 
-// #[no_mangle]
-pub extern "C" fn tzset() {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn tzset() {
+    tz::with_zone(|_| ());
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_settime(
+#[no_mangle]
+pub unsafe extern "C" fn timer_settime(
     timerid: timer_t,
     flags: c_int,
     value: *const itimerspec,
     ovalue: *mut itimerspec,
 ) -> c_int {
-    unimplemented!();
+    let timer = &*(timerid as *const timer::Timer);
+    Sys::timer_settime(timer.id, flags, value, ovalue)
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_gettime(timerid: timer_t, value: *mut itimerspec) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn timer_gettime(timerid: timer_t, value: *mut itimerspec) -> c_int {
+    let timer = &*(timerid as *const timer::Timer);
+    Sys::timer_gettime(timer.id, value)
 }
 
-// #[no_mangle]
-pub extern "C" fn timer_getoverrun(timerid: timer_t) -> c_int {
-    unimplemented!();
+/// Counts the expirations missed before the last one was delivered
+#[no_mangle]
+pub unsafe extern "C" fn timer_getoverrun(timerid: timer_t) -> c_int {
+    let timer = &*(timerid as *const timer::Timer);
+    Sys::timer_getoverrun(timer.id)
 }
 
 /*
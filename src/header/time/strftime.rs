@@ -1,6 +1,9 @@
 use alloc::string::String;
 
-use crate::platform::{self, types::*, WriteByte};
+use crate::{
+    c_str::CStr,
+    platform::{self, types::*, WriteByte},
+};
 
 use super::tm;
 
@@ -111,8 +114,9 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                 b'P' => w!(if (*t).tm_hour < 12 { "am" } else { "pm" }),
                 b'r' => w!(recurse "%I:%M:%S %p"),
                 b'R' => w!(recurse "%H:%M"),
-                // Nothing is modified in mktime, but the C standard of course requires a mutable pointer ._.
-                b's' => w!("{}", super::mktime(t as *mut tm)),
+                // The fields are local time at tm_gmtoff seconds east of UTC.
+                // mktime is not used since it normalizes the struct in place.
+                b's' => w!("{}", super::tm_to_secs(t) - (*t).tm_gmtoff as time_t),
                 b'S' => w!("{:02}", (*t).tm_sec),
                 b'T' => w!(recurse "%H:%M:%S"),
                 b'u' => w!("{}", ((*t).tm_wday + 7 - 1) % 7 + 1),
@@ -121,8 +125,22 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                 b'W' => w!("{}", ((*t).tm_yday + 7 - ((*t).tm_wday + 6) % 7) / 7),
                 b'y' => w!("{:02}", (*t).tm_year % 100),
                 b'Y' => w!("{}", (*t).tm_year + 1900),
-                b'z' => w!("+0000"), // TODO
-                b'Z' => w!("UTC"),   // TODO
+                b'z' => {
+                    let offset = (*t).tm_gmtoff;
+                    let (sign, offset) = if offset < 0 {
+                        ('-', -offset)
+                    } else {
+                        ('+', offset)
+                    };
+                    w!("{}{:02}{:02}", sign, offset / 3600, offset / 60 % 60);
+                }
+                b'Z' => {
+                    if !(*t).tm_zone.is_null() {
+                        for &b in CStr::from_ptr((*t).tm_zone).to_bytes() {
+                            w!(byte b);
+                        }
+                    }
+                }
                 b'+' => w!(recurse "%a %b %d %T %Z %Y"),
                 _ => return false,
             }
@@ -0,0 +1,169 @@
+//! POSIX interval timers, built on the kernel's timers.
+//!
+//! A `timer_t` points to a `Timer`. Timers notifying with `SIGEV_THREAD` get a
+//! helper thread, which the kernel sends `SIGTIMER` to on each expiration and
+//! which then calls the notify function.
+
+use alloc::boxed::Box;
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::{
+    header::{
+        errno::EINVAL,
+        pthread::{pthread_create, pthread_detach, pthread_t, thread_tid},
+        signal::{
+            sigevent, siginfo_t, sigset_t, sigval, SIGALRM, SIGEV_SIGNAL, SIGEV_THREAD, SIGTIMER,
+            SIG_BLOCK, SIG_SETMASK,
+        },
+    },
+    platform::{self, types::*, PalSignal, Sys},
+};
+
+/// Linux extension sending the signal to the thread in `thread_sigevent::tid`
+const SIGEV_THREAD_ID: c_int = 4;
+
+/// `si_code` of signals sent with tkill
+const SI_TKILL: c_int = -6;
+
+/// The kernel's `sigevent` for `SIGEV_THREAD_ID`, which puts the thread id
+/// where `sigevent` has its notify function
+#[repr(C)]
+struct thread_sigevent {
+    value: sigval,
+    signo: c_int,
+    notify: c_int,
+    tid: pid_t,
+    _pad: [c_int; 11],
+}
+
+pub struct Timer {
+    /// The kernel's id for the timer
+    pub id: c_int,
+    /// The helper thread, or 0 unless notifying with `SIGEV_THREAD`
+    thread: pid_t,
+    notify: Option<extern "C" fn(sigval)>,
+    value: sigval,
+    /// Set by timer_delete, so that signals still queued are ignored
+    deleted: AtomicBool,
+}
+
+/// Creates a timer, which is freed with `delete`
+pub unsafe fn create(clock_id: clockid_t, evp: *const sigevent) -> Option<*mut Timer> {
+    let timer = Box::into_raw(Box::new(Timer {
+        id: -1,
+        thread: 0,
+        notify: None,
+        value: sigval {
+            sival_ptr: ptr::null_mut(),
+        },
+        deleted: AtomicBool::new(false),
+    }));
+
+    let event = match evp.as_ref() {
+        Some(event) => *event,
+        None => {
+            // Like a NULL evp to the kernel, but with the timer_t as the value
+            let mut event: sigevent = mem::zeroed();
+            event.sigev_notify = SIGEV_SIGNAL;
+            event.sigev_signo = SIGALRM as c_int;
+            event.sigev_value.sival_ptr = timer as *mut c_void;
+            event
+        }
+    };
+
+    if event.sigev_notify != SIGEV_THREAD {
+        if Sys::timer_create(clock_id, &event, &mut (*timer).id) < 0 {
+            drop(Box::from_raw(timer));
+            return None;
+        }
+        return Some(timer);
+    }
+
+    if event.sigev_notify_function.is_none() {
+        drop(Box::from_raw(timer));
+        platform::errno = EINVAL;
+        return None;
+    }
+    (*timer).notify = event.sigev_notify_function;
+    (*timer).value = event.sigev_value;
+
+    // The helper inherits the mask, so SIGTIMER is blocked before it can arrive
+    let all: sigset_t = !0;
+    let mut old: sigset_t = 0;
+    Sys::sigprocmask(SIG_BLOCK, &all, &mut old);
+    let mut thread: pthread_t = mem::zeroed();
+    let status = pthread_create(&mut thread, ptr::null(), notifier, timer as *mut c_void);
+    Sys::sigprocmask(SIG_SETMASK, &old, ptr::null_mut());
+    if status != 0 {
+        drop(Box::from_raw(timer));
+        platform::errno = status;
+        return None;
+    }
+    pthread_detach(thread);
+    (*timer).thread = thread_tid(thread);
+
+    let event = thread_sigevent {
+        value: sigval {
+            sival_ptr: timer as *mut c_void,
+        },
+        signo: SIGTIMER,
+        notify: SIGEV_THREAD_ID,
+        tid: (*timer).thread,
+        _pad: [0; 11],
+    };
+    let event = &event as *const thread_sigevent as *const sigevent;
+    if Sys::timer_create(clock_id, event, &mut (*timer).id) < 0 {
+        let err = platform::errno;
+        stop(timer);
+        platform::errno = err;
+        return None;
+    }
+    Some(timer)
+}
+
+/// Deletes the kernel's timer and frees the `Timer`
+pub unsafe fn delete(timer: *mut Timer) -> c_int {
+    if Sys::timer_delete((*timer).id) < 0 {
+        return -1;
+    }
+    if (*timer).thread == 0 {
+        drop(Box::from_raw(timer));
+    } else {
+        stop(timer);
+    }
+    0
+}
+
+/// Makes the helper thread exit, which frees the `Timer`
+unsafe fn stop(timer: *mut Timer) {
+    (*timer).deleted.store(true, Ordering::SeqCst);
+    Sys::tkill((*timer).thread, SIGTIMER);
+}
+
+/// The helper thread of a `SIGEV_THREAD` timer
+extern "C" fn notifier(arg: *mut c_void) -> *mut c_void {
+    let timer = arg as *mut Timer;
+    let set: sigset_t = 1 << (SIGTIMER - 1);
+    unsafe {
+        loop {
+            let mut info: siginfo_t = mem::zeroed();
+            if Sys::sigtimedwait(&set, &mut info, ptr::null()) < 0 {
+                continue;
+            }
+            // Only stop sends SIGTIMER with tkill, and only once
+            if info.si_code == SI_TKILL {
+                break;
+            }
+            if !(*timer).deleted.load(Ordering::SeqCst) {
+                if let Some(notify) = (*timer).notify {
+                    notify((*timer).value);
+                }
+            }
+        }
+        drop(Box::from_raw(timer));
+    }
+    ptr::null_mut()
+}
@@ -0,0 +1,657 @@
+//! Time zone support for localtime/mktime, following
+//! https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/V1_chap08.html (the `TZ` variable)
+//! and https://tools.ietf.org/html/rfc8536 (the TZif file format)
+
+use alloc::{boxed::Box, vec::Vec};
+use core::convert::TryInto;
+
+use crate::{
+    c_str::{CStr, CString},
+    fs::File,
+    header::{fcntl, stdlib::getenv},
+    io::Read,
+    platform::types::*,
+    sync::Mutex,
+};
+
+use super::{daylight, days_from_civil, timezone, tzname};
+
+const ZONEINFO_DIR: &[u8] = b"/usr/share/zoneinfo/";
+const LOCALTIME_PATH: &[u8] = b"/etc/localtime";
+
+const SECS_PER_HOUR: time_t = 60 * 60;
+const SECS_PER_DAY: time_t = 24 * SECS_PER_HOUR;
+
+/// Time zone loaded from the `TZ` environment variable, along with the value
+/// of `TZ` it was loaded from, so that changes to the environment are noticed.
+struct State {
+    env: Option<Vec<u8>>,
+    zone: Timezone,
+}
+
+static STATE: Mutex<Option<State>> = Mutex::new(None);
+
+/// Abbreviations handed out through `tzname` and `tm_zone`, which have to stay
+/// valid when the time zone is reloaded. As in musl, each distinct one is kept
+/// forever, and there are only ever a few of them.
+static ABBRS: Mutex<Vec<&'static [u8]>> = Mutex::new(Vec::new());
+
+/// The never freed copy of a NUL-terminated abbreviation
+fn intern(abbr: &[u8]) -> *const c_char {
+    let mut abbrs = ABBRS.lock();
+    let interned: &'static [u8] = match abbrs.iter().find(|interned| **interned == abbr) {
+        Some(&interned) => interned,
+        None => {
+            let interned: &'static [u8] = Box::leak(abbr.to_vec().into_boxed_slice());
+            abbrs.push(interned);
+            interned
+        }
+    };
+    interned.as_ptr() as *const c_char
+}
+
+/// Run `f` with the current time zone, (re)loading it first if `TZ` has
+/// changed since the last call. This also updates the `daylight`, `timezone`
+/// and `tzname` globals, so it is what `tzset` does.
+pub unsafe fn with_zone<T, F: FnOnce(&Timezone) -> T>(f: F) -> T {
+    let mut state = STATE.lock();
+
+    let env = getenv(c_str!("TZ").as_ptr());
+    let env = if env.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(env).to_bytes())
+    };
+
+    let stale = match &*state {
+        Some(state) => state.env.as_deref() != env,
+        None => true,
+    };
+    if stale {
+        let zone = Timezone::load(env);
+
+        let (std, dst) = zone.std_and_dst();
+        timezone = -std.utoff;
+        daylight = dst.is_some() as c_int;
+        tzname = [
+            zone.abbr(&std) as *mut c_char,
+            zone.abbr(&dst.unwrap_or(std)) as *mut c_char,
+        ];
+
+        *state = Some(State {
+            env: env.map(|env| env.to_vec()),
+            zone,
+        });
+    }
+
+    f(&state.as_ref().unwrap().zone)
+}
+
+/// Offset from UTC, in seconds east of Greenwich, along with whether it is
+/// daylight saving time and the index of its abbreviation.
+#[derive(Clone, Copy)]
+pub struct LocalTimeType {
+    pub utoff: time_t,
+    pub isdst: bool,
+    abbr: usize,
+}
+
+#[derive(Clone, Copy)]
+enum RuleDate {
+    /// `Jn`: day n in [1, 365], February 29 is never counted
+    Julian(time_t),
+    /// `n`: zero-based day n in [0, 365], February 29 is counted in leap years
+    ZeroBased(time_t),
+    /// `Mm.w.d`: day d (0 = Sunday) of week w (5 = last) of month m
+    MonthWeekDay {
+        month: time_t,
+        week: time_t,
+        wday: time_t,
+    },
+}
+
+/// Start or end of daylight saving time, with `time` in seconds after local
+/// midnight. It may be negative or exceed 24 hours.
+#[derive(Clone, Copy)]
+struct Rule {
+    date: RuleDate,
+    time: time_t,
+}
+
+impl Rule {
+    /// Local time, in seconds since the epoch, at which the rule applies in
+    /// `year`.
+    fn local_time(&self, year: time_t) -> time_t {
+        let jan1 = days_from_civil(year, 0, 1);
+        let day = match self.date {
+            RuleDate::Julian(n) => {
+                let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+                jan1 + n - 1 + if leap && n >= 60 { 1 } else { 0 }
+            }
+            RuleDate::ZeroBased(n) => jan1 + n,
+            RuleDate::MonthWeekDay { month, week, wday } => {
+                let first = days_from_civil(year, month - 1, 1);
+                let next = if month == 12 {
+                    days_from_civil(year + 1, 0, 1)
+                } else {
+                    days_from_civil(year, month, 1)
+                };
+
+                // 1970-01-01 was a Thursday
+                let first_wday = (first + 4).rem_euclid(7);
+                let mut day = first + (wday - first_wday).rem_euclid(7) + (week - 1) * 7;
+                // Week 5 means the last such day, which may be in week 4
+                while day >= next {
+                    day -= 7;
+                }
+                day
+            }
+        };
+        day * SECS_PER_DAY + self.time
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Dst {
+    ltt: LocalTimeType,
+    start: Rule,
+    end: Rule,
+}
+
+/// The `std offset [dst [offset] [,rule,rule]]` form of `TZ`, which is also
+/// used by TZif files to describe times after the last transition.
+#[derive(Clone, Copy)]
+struct PosixTz {
+    std: LocalTimeType,
+    dst: Option<Dst>,
+}
+
+impl PosixTz {
+    fn find(&self, t: time_t) -> LocalTimeType {
+        let dst = match self.dst {
+            Some(dst) => dst,
+            None => return self.std,
+        };
+
+        // The start rule is given in standard time, the end rule in
+        // daylight saving time
+        let year = year_of(t + self.std.utoff);
+        let start = dst.start.local_time(year) - self.std.utoff;
+        let end = dst.end.local_time(year) - dst.ltt.utoff;
+
+        let in_dst = if start < end {
+            start <= t && t < end
+        } else {
+            // Southern hemisphere, daylight saving time spans the new year
+            !(end <= t && t < start)
+        };
+        if in_dst {
+            dst.ltt
+        } else {
+            self.std
+        }
+    }
+}
+
+/// Year of the given time, in the proleptic Gregorian calendar.
+fn year_of(t: time_t) -> time_t {
+    // See gmtime_r for the details of this algorithm
+    let days_since_origin = t.div_euclid(SECS_PER_DAY) + 719468;
+    let era = days_since_origin.div_euclid(146097);
+    let day_of_era = days_since_origin.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year_transformed =
+        day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_transformed = (5 * day_of_year_transformed + 2) / 153;
+
+    // January and February belong to the next year in the transformed calendar
+    year_of_era + 400 * era + if month_transformed >= 10 { 1 } else { 0 }
+}
+
+pub struct Timezone {
+    /// Transition times, sorted in ascending order
+    transitions: Vec<time_t>,
+    /// Index into `types` for each transition
+    transition_types: Vec<usize>,
+    /// Always contains at least one entry
+    types: Vec<LocalTimeType>,
+    /// NUL-terminated abbreviations, indexed by `LocalTimeType::abbr`
+    abbrs: Vec<u8>,
+    /// Rule for times after the last transition
+    rule: Option<PosixTz>,
+}
+
+impl Timezone {
+    fn utc() -> Self {
+        let utc = LocalTimeType {
+            utoff: 0,
+            isdst: false,
+            abbr: 0,
+        };
+        Self {
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types: vec![utc],
+            abbrs: b"UTC\0".to_vec(),
+            rule: None,
+        }
+    }
+
+    /// Load the time zone described by the value of `TZ`, falling back to UTC
+    /// if it cannot be understood.
+    fn load(env: Option<&[u8]>) -> Self {
+        let zone = match env {
+            // Unset: use the system's local time zone
+            None => Self::from_file(LOCALTIME_PATH),
+            Some(b"") => None,
+            Some(env) if env[0] == b':' => Self::from_zoneinfo(&env[1..]),
+            // Accept zoneinfo names without the leading colon, like glibc
+            Some(env) => Self::from_posix(env).or_else(|| Self::from_zoneinfo(env)),
+        };
+        zone.unwrap_or_else(Self::utc)
+    }
+
+    fn from_posix(env: &[u8]) -> Option<Self> {
+        let mut abbrs = Vec::new();
+        let rule = parse_posix(env, &mut abbrs)?;
+
+        let mut types = vec![rule.std];
+        if let Some(dst) = rule.dst {
+            types.push(dst.ltt);
+        }
+        Some(Self {
+            transitions: Vec::new(),
+            transition_types: Vec::new(),
+            types,
+            abbrs,
+            rule: Some(rule),
+        })
+    }
+
+    fn from_zoneinfo(name: &[u8]) -> Option<Self> {
+        if name.first() == Some(&b'/') {
+            return Self::from_file(name);
+        }
+
+        // Don't allow escaping the zoneinfo directory
+        if name.is_empty() || name.windows(2).any(|w| w == b"..") {
+            return None;
+        }
+        let mut path = ZONEINFO_DIR.to_vec();
+        path.extend_from_slice(name);
+        Self::from_file(&path)
+    }
+
+    fn from_file(path: &[u8]) -> Option<Self> {
+        let path = CString::new(path).ok()?;
+        let mut file = File::open(&path, fcntl::O_RDONLY | fcntl::O_CLOEXEC).ok()?;
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).ok()?;
+        Self::from_tzif(&data)
+    }
+
+    fn from_tzif(data: &[u8]) -> Option<Self> {
+        let (version, counts) = TzifCounts::parse(data)?;
+
+        // Version 1 only has 32-bit transition times. Later versions repeat
+        // the data with 64-bit times after the version 1 block, followed by a
+        // TZ string footer.
+        let (time_size, counts, mut i) = if version >= b'2' {
+            let v2_header = TZIF_HEADER_LEN + counts.block_len(4);
+            let (_, counts) = TzifCounts::parse(data.get(v2_header..)?)?;
+            (8, counts, v2_header + TZIF_HEADER_LEN)
+        } else {
+            (4, counts, TZIF_HEADER_LEN)
+        };
+
+        let block = data.get(i..i + counts.block_len(time_size))?;
+        if counts.typecnt == 0 || counts.charcnt == 0 {
+            return None;
+        }
+
+        let mut transitions = Vec::with_capacity(counts.timecnt);
+        for time in block[..counts.timecnt * time_size].chunks(time_size) {
+            transitions.push(if time_size == 8 {
+                i64::from_be_bytes(time.try_into().unwrap())
+            } else {
+                i32::from_be_bytes(time.try_into().unwrap()) as i64
+            });
+        }
+        let mut pos = counts.timecnt * time_size;
+
+        let mut transition_types = Vec::with_capacity(counts.timecnt);
+        for &index in &block[pos..pos + counts.timecnt] {
+            if index as usize >= counts.typecnt {
+                return None;
+            }
+            transition_types.push(index as usize);
+        }
+        pos += counts.timecnt;
+
+        let mut types = Vec::with_capacity(counts.typecnt);
+        for ttinfo in block[pos..pos + counts.typecnt * 6].chunks(6) {
+            let abbr = ttinfo[5] as usize;
+            if abbr >= counts.charcnt {
+                return None;
+            }
+            types.push(LocalTimeType {
+                utoff: i32::from_be_bytes(ttinfo[..4].try_into().unwrap()) as time_t,
+                isdst: ttinfo[4] != 0,
+                abbr,
+            });
+        }
+        pos += counts.typecnt * 6;
+
+        let mut abbrs = block[pos..pos + counts.charcnt].to_vec();
+        if abbrs.last() != Some(&0) {
+            abbrs.push(0);
+        }
+        i += block.len();
+
+        // The footer is a TZ string between two newlines, possibly empty
+        let mut rule = None;
+        if time_size == 8 && data.get(i) == Some(&b'\n') {
+            let footer = &data[i + 1..];
+            if let Some(end) = footer.iter().position(|&c| c == b'\n') {
+                if end > 0 {
+                    rule = parse_posix(&footer[..end], &mut abbrs);
+                }
+            }
+        }
+
+        Some(Self {
+            transitions,
+            transition_types,
+            types,
+            abbrs,
+            rule,
+        })
+    }
+
+    /// Local time type in effect at `t` seconds since the epoch.
+    pub fn find(&self, t: time_t) -> LocalTimeType {
+        if let Some(rule) = &self.rule {
+            if self.transitions.last().map_or(true, |&last| t >= last) {
+                return rule.find(t);
+            }
+        }
+
+        match self.transitions.binary_search(&t) {
+            Ok(i) => self.types[self.transition_types[i]],
+            // Before the first transition, the first type is used
+            Err(0) => self.types[0],
+            Err(i) => self.types[self.transition_types[i - 1]],
+        }
+    }
+
+    /// Seconds since the epoch for `local` seconds of local time. `isdst` is
+    /// used to pick one if the local time is ambiguous, as with `tm_isdst`.
+    pub fn local_to_utc(&self, local: time_t, isdst: c_int) -> time_t {
+        // Transitions are assumed to be more than a day apart, so the time is
+        // in one of the types in effect a day before or a day after
+        let before = self.find(local - SECS_PER_DAY);
+        let after = self.find(local + SECS_PER_DAY);
+        let valid = |ltt: &LocalTimeType| self.find(local - ltt.utoff).utoff == ltt.utoff;
+
+        if isdst >= 0 {
+            for ltt in [before, after].iter() {
+                if ltt.isdst == (isdst > 0) && valid(ltt) {
+                    return local - ltt.utoff;
+                }
+            }
+        }
+        for ltt in [before, after].iter() {
+            if valid(ltt) {
+                return local - ltt.utoff;
+            }
+        }
+
+        // The local time was skipped by a transition, interpret it with the
+        // offset in effect before it
+        local - before.utoff
+    }
+
+    /// Abbreviation of `ltt`, which stays valid after the zone is dropped
+    pub fn abbr(&self, ltt: &LocalTimeType) -> *const c_char {
+        let abbr = &self.abbrs[ltt.abbr..];
+        let len = abbr.iter().position(|&c| c == 0).unwrap() + 1;
+        intern(&abbr[..len])
+    }
+
+    /// Standard and, if any, daylight saving time types currently used.
+    fn std_and_dst(&self) -> (LocalTimeType, Option<LocalTimeType>) {
+        if let Some(rule) = &self.rule {
+            return (rule.std, rule.dst.map(|dst| dst.ltt));
+        }
+
+        let mut std = None;
+        let mut dst = None;
+        for &index in &self.transition_types {
+            let ltt = self.types[index];
+            if ltt.isdst {
+                dst = Some(ltt);
+            } else {
+                std = Some(ltt);
+            }
+        }
+        (std.unwrap_or(self.types[0]), dst)
+    }
+}
+
+const TZIF_HEADER_LEN: usize = 44;
+
+struct TzifCounts {
+    isutcnt: usize,
+    isstdcnt: usize,
+    leapcnt: usize,
+    timecnt: usize,
+    typecnt: usize,
+    charcnt: usize,
+}
+
+impl TzifCounts {
+    /// Parse a TZif header, returning the version and the counts.
+    fn parse(data: &[u8]) -> Option<(u8, Self)> {
+        let header = data.get(..TZIF_HEADER_LEN)?;
+        if &header[..4] != b"TZif" {
+            return None;
+        }
+
+        let count = |i: usize| {
+            let start = 20 + i * 4;
+            u32::from_be_bytes(header[start..start + 4].try_into().unwrap()) as usize
+        };
+        Some((
+            header[4],
+            Self {
+                isutcnt: count(0),
+                isstdcnt: count(1),
+                leapcnt: count(2),
+                timecnt: count(3),
+                typecnt: count(4),
+                charcnt: count(5),
+            },
+        ))
+    }
+
+    /// Length of the data block following the header.
+    fn block_len(&self, time_size: usize) -> usize {
+        self.timecnt * time_size
+            + self.timecnt
+            + self.typecnt * 6
+            + self.charcnt
+            + self.leapcnt * (time_size + 4)
+            + self.isstdcnt
+            + self.isutcnt
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.s.first().copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        if self.peek() == Some(c) {
+            self.s = &self.s[1..];
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while<F: Fn(u8) -> bool>(&mut self, f: F) -> &'a [u8] {
+        let len = self.s.iter().position(|&c| !f(c)).unwrap_or(self.s.len());
+        let (taken, rest) = self.s.split_at(len);
+        self.s = rest;
+        taken
+    }
+
+    fn num(&mut self, min: time_t, max: time_t) -> Option<time_t> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        if digits.is_empty() || digits.len() > 3 {
+            return None;
+        }
+
+        let n = digits.iter().fold(0, |n, &c| n * 10 + (c - b'0') as time_t);
+        if n < min || n > max {
+            return None;
+        }
+        Some(n)
+    }
+
+    /// Abbreviation, either alphabetic or quoted in angle brackets, which is
+    /// appended NUL-terminated to `abbrs`. Returns its index.
+    fn name(&mut self, abbrs: &mut Vec<u8>) -> Option<usize> {
+        let name = if self.eat(b'<') {
+            let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'+' || c == b'-');
+            if !self.eat(b'>') {
+                return None;
+            }
+            name
+        } else {
+            self.take_while(|c| c.is_ascii_alphabetic())
+        };
+        if name.len() < 3 {
+            return None;
+        }
+
+        let index = abbrs.len();
+        abbrs.extend_from_slice(name);
+        abbrs.push(0);
+        Some(index)
+    }
+
+    /// `[+|-]hh[:mm[:ss]]`, with hours up to `max_hours`.
+    fn time(&mut self, max_hours: time_t) -> Option<time_t> {
+        let sign = if self.eat(b'-') {
+            -1
+        } else {
+            self.eat(b'+');
+            1
+        };
+
+        let mut secs = self.num(0, max_hours)? * SECS_PER_HOUR;
+        if self.eat(b':') {
+            secs += self.num(0, 59)? * 60;
+            if self.eat(b':') {
+                secs += self.num(0, 59)?;
+            }
+        }
+        Some(sign * secs)
+    }
+
+    /// UTC offset, in seconds east of Greenwich. Note that POSIX offsets are
+    /// positive west of Greenwich.
+    fn offset(&mut self) -> Option<time_t> {
+        self.time(24).map(|offset| -offset)
+    }
+
+    fn rule(&mut self) -> Option<Rule> {
+        let date = if self.eat(b'J') {
+            RuleDate::Julian(self.num(1, 365)?)
+        } else if self.eat(b'M') {
+            let month = self.num(1, 12)?;
+            if !self.eat(b'.') {
+                return None;
+            }
+            let week = self.num(1, 5)?;
+            if !self.eat(b'.') {
+                return None;
+            }
+            let wday = self.num(0, 6)?;
+            RuleDate::MonthWeekDay { month, week, wday }
+        } else {
+            RuleDate::ZeroBased(self.num(0, 365)?)
+        };
+
+        // RFC 8536 allows the time to range from -167 to 167 hours
+        let time = if self.eat(b'/') {
+            self.time(167)?
+        } else {
+            2 * SECS_PER_HOUR
+        };
+        Some(Rule { date, time })
+    }
+}
+
+/// Parse the `std offset [dst [offset] [,rule,rule]]` form of `TZ`, appending
+/// the abbreviations to `abbrs`.
+fn parse_posix(s: &[u8], abbrs: &mut Vec<u8>) -> Option<PosixTz> {
+    let mut parser = Parser { s };
+
+    let std = LocalTimeType {
+        abbr: parser.name(abbrs)?,
+        utoff: parser.offset()?,
+        isdst: false,
+    };
+    if parser.peek().is_none() {
+        return Some(PosixTz { std, dst: None });
+    }
+
+    let abbr = parser.name(abbrs)?;
+    let utoff = match parser.peek() {
+        Some(c) if c == b'+' || c == b'-' || c.is_ascii_digit() => parser.offset()?,
+        // Daylight saving time is one hour ahead by default
+        _ => std.utoff + SECS_PER_HOUR,
+    };
+
+    let (start, end) = if parser.eat(b',') {
+        let start = parser.rule()?;
+        if !parser.eat(b',') {
+            return None;
+        }
+        (start, parser.rule()?)
+    } else {
+        // No rules given, use the current US ones like other libcs
+        let mut default = Parser {
+            s: b"M3.2.0,M11.1.0",
+        };
+        let start = default.rule().unwrap();
+        default.eat(b',');
+        (start, default.rule().unwrap())
+    };
+
+    if parser.peek().is_some() {
+        return None;
+    }
+
+    Some(PosixTz {
+        std,
+        dst: Some(Dst {
+            ltt: LocalTimeType {
+                utoff,
+                isdst: true,
+                abbr,
+            },
+            start,
+            end,
+        }),
+    })
+}
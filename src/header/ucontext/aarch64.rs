@@ -0,0 +1,78 @@
+use core::{ffi::VaList, mem};
+
+use crate::{
+    header::signal::{sigset_t, stack_t},
+    platform::types::*,
+};
+
+pub type greg_t = c_ulong;
+
+pub const NGREG: usize = 34;
+pub type gregset_t = [greg_t; NGREG];
+
+/// Where the kernel and getcontext store extensions such as the floating
+/// point registers
+#[repr(C, align(16))]
+struct Reserved([u8; 4096]);
+
+#[repr(C)]
+pub struct mcontext_t {
+    pub fault_address: c_ulonglong,
+    pub regs: [c_ulonglong; 31],
+    pub sp: c_ulonglong,
+    pub pc: c_ulonglong,
+    pub pstate: c_ulonglong,
+    __reserved: Reserved,
+}
+
+#[repr(C)]
+pub struct ucontext_t {
+    pub uc_flags: c_ulong,
+    pub uc_link: *mut ucontext_t,
+    pub uc_stack: stack_t,
+    pub uc_sigmask: sigset_t,
+    __unused: [u8; 128 - mem::size_of::<sigset_t>()],
+    pub uc_mcontext: mcontext_t,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sys_makecontext(
+    ucp: *mut ucontext_t,
+    func: extern "C" fn(),
+    argc: c_int,
+    mut args: VaList,
+) {
+    extern "C" {
+        fn __relibc_start_context();
+    }
+
+    const REG_ARGS: usize = 8;
+
+    let ucp = &mut *ucp;
+    let argc = argc.max(0) as usize;
+    let stack_args = argc.saturating_sub(REG_ARGS);
+
+    // From the bottom of the stack: the arguments that do not fit in
+    // registers and uc_link. The stack pointer stays 16 byte aligned
+    let top = ucp.uc_stack.ss_sp as usize + ucp.uc_stack.ss_size;
+    let sp = (top - (stack_args + 1) * 8) & !15;
+    let slots = sp as *mut usize;
+
+    for i in 0..argc {
+        let arg = args.arg::<c_long>();
+        if i < REG_ARGS {
+            ucp.uc_mcontext.regs[i] = arg as c_ulonglong;
+        } else {
+            *slots.add(i - REG_ARGS) = arg as usize;
+        }
+    }
+    let link = slots.add(stack_args);
+    *link = ucp.uc_link as usize;
+
+    // x19 is callee saved, so it still points at uc_link when func returns
+    ucp.uc_mcontext.regs[19] = link as c_ulonglong;
+    ucp.uc_mcontext.regs[29] = 0;
+    ucp.uc_mcontext.regs[30] = __relibc_start_context as usize as c_ulonglong;
+    ucp.uc_mcontext.sp = sp as c_ulonglong;
+    ucp.uc_mcontext.pc = func as usize as c_ulonglong;
+}
@@ -0,0 +1,131 @@
+/*
+ * Offsets into ucontext_t, see include/sys/ucontext.h:
+ *   uc_sigmask: 40
+ *   uc_mcontext.regs: 184, so x19 is at 336 and x29 at 416
+ *   uc_mcontext.sp: 432, uc_mcontext.pc: 440
+ *   uc_mcontext.__reserved: 464, holding a struct fpsimd_context with fpsr
+ *     at 472, fpcr at 476 and the vector registers from 480, so d8 is at 608
+ */
+
+/* Save the callee saved state into the context in x0, to resume at x30 */
+.macro save_context
+	str xzr,[x0,#184]       /* x0 is 0 when the context resumes */
+	stp x19,x20,[x0,#336]
+	stp x21,x22,[x0,#352]
+	stp x23,x24,[x0,#368]
+	stp x25,x26,[x0,#384]
+	stp x27,x28,[x0,#400]
+	stp x29,x30,[x0,#416]
+	mov x9,sp
+	str x9,[x0,#432]
+	str x30,[x0,#440]
+	mov w9,#0x8001          /* FPSIMD_MAGIC */
+	movk w9,#0x4650,lsl #16
+	mov w10,#528            /* sizeof(struct fpsimd_context) */
+	str w9,[x0,#464]
+	str w10,[x0,#468]
+	mrs x9,fpsr
+	mrs x10,fpcr
+	str w9,[x0,#472]
+	str w10,[x0,#476]
+	str d8,[x0,#608]
+	str d9,[x0,#624]
+	str d10,[x0,#640]
+	str d11,[x0,#656]
+	str d12,[x0,#672]
+	str d13,[x0,#688]
+	str d14,[x0,#704]
+	str d15,[x0,#720]
+	str xzr,[x0,#992]       /* no further extensions */
+.endm
+
+.global getcontext
+.type getcontext,%function
+getcontext:
+	save_context
+	stp x29,x30,[sp,#-16]!
+	add x2,x0,#40           /* sigprocmask(SIG_BLOCK, NULL, &ucp->uc_sigmask) */
+	mov x1,#0
+	mov w0,#0
+	bl sigprocmask
+	ldp x29,x30,[sp],#16
+	ret                     /* 0, or -1 from sigprocmask */
+
+.global setcontext
+.type setcontext,%function
+setcontext:
+	stp x29,x30,[sp,#-32]!
+	str x0,[sp,#16]
+	add x1,x0,#40           /* sigprocmask(SIG_SETMASK, &ucp->uc_sigmask, NULL) */
+	mov x2,#0
+	mov w0,#2
+	bl sigprocmask
+	mov w9,w0
+	ldr x0,[sp,#16]
+	ldp x29,x30,[sp],#32
+	cbz w9,__relibc_restore_context
+	mov w0,w9
+	ret                     /* -1 from sigprocmask */
+
+.global swapcontext
+.type swapcontext,%function
+swapcontext:
+	save_context            /* x0 is oucp, save exactly as getcontext does */
+	stp x29,x30,[sp,#-32]!
+	str x1,[sp,#16]         /* x1 is ucp */
+	add x2,x0,#40           /* sigprocmask(SIG_SETMASK, &ucp->uc_sigmask, &oucp->uc_sigmask) */
+	add x1,x1,#40
+	mov w0,#2
+	bl sigprocmask
+	mov w9,w0
+	ldr x0,[sp,#16]
+	ldp x29,x30,[sp],#32
+	cbz w9,__relibc_restore_context
+	mov w0,w9
+	ret                     /* -1 from sigprocmask */
+
+/* Switch to the context in x0, once its signal mask is in place */
+.type __relibc_restore_context,%function
+__relibc_restore_context:
+	ldr w9,[x0,#472]
+	ldr w10,[x0,#476]
+	msr fpsr,x9
+	msr fpcr,x10
+	ldr d8,[x0,#608]
+	ldr d9,[x0,#624]
+	ldr d10,[x0,#640]
+	ldr d11,[x0,#656]
+	ldr d12,[x0,#672]
+	ldr d13,[x0,#688]
+	ldr d14,[x0,#704]
+	ldr d15,[x0,#720]
+	ldp x19,x20,[x0,#336]
+	ldp x21,x22,[x0,#352]
+	ldp x23,x24,[x0,#368]
+	ldp x25,x26,[x0,#384]
+	ldp x27,x28,[x0,#400]
+	ldp x29,x30,[x0,#416]
+	ldr x9,[x0,#432]
+	mov sp,x9
+	ldr x16,[x0,#440]       /* return into the context */
+	ldp x2,x3,[x0,#200]     /* the arguments set by makecontext */
+	ldp x4,x5,[x0,#216]
+	ldp x6,x7,[x0,#232]
+	ldp x0,x1,[x0,#184]     /* x0 is 0 where getcontext or swapcontext saved it */
+	br x16
+
+/*
+ * Where functions started by makecontext return to. x19 is callee saved, so
+ * it still points at the uc_link stored above the stack arguments
+ */
+.global __relibc_start_context
+.hidden __relibc_start_context
+.type __relibc_start_context,%function
+__relibc_start_context:
+	ldr x0,[x19]
+	cbz x0,1f
+	bl setcontext
+	bl abort                /* setcontext only returns on failure */
+1:
+	mov w0,#0               /* no uc_link, so the thread exits */
+	bl exit
@@ -0,0 +1,19 @@
+//! ucontext implementation for Redox, following http://pubs.opengroup.org/onlinepubs/009695399/basedefs/ucontext.h.html
+//!
+//! Only aarch64 and x86_64 are supported so far. getcontext, setcontext and
+//! swapcontext are written in assembly, as they return twice or not at all.
+
+pub use self::arch::*;
+
+#[cfg(target_arch = "aarch64")]
+#[path = "aarch64.rs"]
+pub mod arch;
+
+#[cfg(target_arch = "x86_64")]
+#[path = "x86_64.rs"]
+pub mod arch;
+
+#[cfg(target_arch = "aarch64")]
+global_asm!(include_str!("aarch64.s"));
+#[cfg(target_arch = "x86_64")]
+global_asm!(include_str!("x86_64.s"));
@@ -0,0 +1,92 @@
+use core::ffi::VaList;
+
+use crate::{
+    header::signal::{sigset_t, stack_t},
+    platform::types::*,
+};
+
+pub type greg_t = c_longlong;
+
+pub const NGREG: usize = 23;
+pub type gregset_t = [greg_t; NGREG];
+
+pub const REG_R8: usize = 0;
+pub const REG_R9: usize = 1;
+pub const REG_R10: usize = 2;
+pub const REG_R11: usize = 3;
+pub const REG_R12: usize = 4;
+pub const REG_R13: usize = 5;
+pub const REG_R14: usize = 6;
+pub const REG_R15: usize = 7;
+pub const REG_RDI: usize = 8;
+pub const REG_RSI: usize = 9;
+pub const REG_RBP: usize = 10;
+pub const REG_RBX: usize = 11;
+pub const REG_RDX: usize = 12;
+pub const REG_RAX: usize = 13;
+pub const REG_RCX: usize = 14;
+pub const REG_RSP: usize = 15;
+pub const REG_RIP: usize = 16;
+pub const REG_EFL: usize = 17;
+pub const REG_CSGSFS: usize = 18;
+pub const REG_ERR: usize = 19;
+pub const REG_TRAPNO: usize = 20;
+pub const REG_OLDMASK: usize = 21;
+pub const REG_CR2: usize = 22;
+
+#[repr(C)]
+pub struct mcontext_t {
+    pub gregs: gregset_t,
+    pub fpregs: *mut c_void,
+    __reserved: [c_ulonglong; 8],
+}
+
+#[repr(C)]
+pub struct ucontext_t {
+    pub uc_flags: c_ulong,
+    pub uc_link: *mut ucontext_t,
+    pub uc_stack: stack_t,
+    pub uc_mcontext: mcontext_t,
+    pub uc_sigmask: sigset_t,
+    __fpregs_mem: [u64; 64],
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sys_makecontext(
+    ucp: *mut ucontext_t,
+    func: extern "C" fn(),
+    argc: c_int,
+    mut args: VaList,
+) {
+    extern "C" {
+        fn __relibc_start_context();
+    }
+
+    const REG_ARGS: [usize; 6] = [REG_RDI, REG_RSI, REG_RDX, REG_RCX, REG_R8, REG_R9];
+
+    let ucp = &mut *ucp;
+    let argc = argc.max(0) as usize;
+    let stack_args = argc.saturating_sub(REG_ARGS.len());
+
+    // From the top of the stack: uc_link, the arguments that do not fit in
+    // registers, and the return address. The function must be entered with
+    // the stack 8 bytes off 16 byte alignment, as if it had been called
+    let top = ucp.uc_stack.ss_sp as usize + ucp.uc_stack.ss_size;
+    let sp = ((top - (stack_args + 2) * 8) & !15) - 8;
+    let slots = sp as *mut usize;
+
+    *slots = __relibc_start_context as usize;
+    for i in 0..argc {
+        let arg = args.arg::<c_long>();
+        match REG_ARGS.get(i) {
+            Some(&reg) => ucp.uc_mcontext.gregs[reg] = arg as greg_t,
+            None => *slots.add(1 + i - REG_ARGS.len()) = arg as usize,
+        }
+    }
+    let link = slots.add(1 + stack_args);
+    *link = ucp.uc_link as usize;
+
+    ucp.uc_mcontext.gregs[REG_RBX] = link as greg_t;
+    ucp.uc_mcontext.gregs[REG_RSP] = sp as greg_t;
+    ucp.uc_mcontext.gregs[REG_RIP] = func as usize as greg_t;
+}
@@ -0,0 +1,128 @@
+/*
+ * Offsets into ucontext_t, see include/sys/ucontext.h:
+ *   uc_mcontext.gregs: r8 40, r9 48, r12 72, r13 80, r14 88, r15 96,
+ *     rdi 104, rsi 112, rbp 120, rbx 128, rdx 136, rcx 152, rsp 160, rip 168
+ *   uc_mcontext.fpregs: 224
+ *   uc_sigmask: 296
+ *   __fpregs_mem: 304, with mxcsr at 328
+ */
+
+.global getcontext
+.type getcontext,@function
+getcontext:
+	mov %rbx,128(%rdi)      /* rdi is ucp, save the callee saved registers */
+	mov %rbp,120(%rdi)
+	mov %r12,72(%rdi)
+	mov %r13,80(%rdi)
+	mov %r14,88(%rdi)
+	mov %r15,96(%rdi)
+	mov %rdi,104(%rdi)      /* and the argument registers, for makecontext */
+	mov %rsi,112(%rdi)
+	mov %rdx,136(%rdi)
+	mov %rcx,152(%rdi)
+	mov %r8,40(%rdi)
+	mov %r9,48(%rdi)
+	mov (%rsp),%rcx         /* resume at our return address */
+	mov %rcx,168(%rdi)
+	lea 8(%rsp),%rcx        /* with the stack as the caller sees it */
+	mov %rcx,160(%rdi)
+	lea 304(%rdi),%rcx      /* fpregs points into the context itself */
+	mov %rcx,224(%rdi)
+	fnstenv (%rcx)          /* x87 control state */
+	fldenv (%rcx)           /* fnstenv masks exceptions, so reload it */
+	stmxcsr 328(%rdi)       /* sse control state */
+	push %rdi
+	lea 296(%rdi),%rdx      /* sigprocmask(SIG_BLOCK, NULL, &ucp->uc_sigmask) */
+	xor %esi,%esi
+	xor %edi,%edi
+	call sigprocmask@PLT
+	pop %rdi
+	ret                     /* 0, or -1 from sigprocmask */
+
+.global setcontext
+.type setcontext,@function
+setcontext:
+	push %rdi
+	lea 296(%rdi),%rsi      /* sigprocmask(SIG_SETMASK, &ucp->uc_sigmask, NULL) */
+	xor %edx,%edx
+	mov $2,%edi
+	call sigprocmask@PLT
+	pop %rdx
+	test %eax,%eax
+	jz __relibc_restore_context
+	ret                     /* -1 from sigprocmask */
+
+.global swapcontext
+.type swapcontext,@function
+swapcontext:
+	mov %rbx,128(%rdi)      /* rdi is oucp, save exactly as getcontext does */
+	mov %rbp,120(%rdi)
+	mov %r12,72(%rdi)
+	mov %r13,80(%rdi)
+	mov %r14,88(%rdi)
+	mov %r15,96(%rdi)
+	mov %rdi,104(%rdi)
+	mov %rsi,112(%rdi)
+	mov %rdx,136(%rdi)
+	mov %rcx,152(%rdi)
+	mov %r8,40(%rdi)
+	mov %r9,48(%rdi)
+	mov (%rsp),%rcx
+	mov %rcx,168(%rdi)
+	lea 8(%rsp),%rcx
+	mov %rcx,160(%rdi)
+	lea 304(%rdi),%rcx
+	mov %rcx,224(%rdi)
+	fnstenv (%rcx)
+	fldenv (%rcx)
+	stmxcsr 328(%rdi)
+	push %rsi               /* rsi is ucp */
+	lea 296(%rsi),%rsi      /* sigprocmask(SIG_SETMASK, &ucp->uc_sigmask, &oucp->uc_sigmask) */
+	lea 296(%rdi),%rdx
+	mov $2,%edi
+	call sigprocmask@PLT
+	pop %rdx
+	test %eax,%eax
+	jz __relibc_restore_context
+	ret                     /* -1 from sigprocmask */
+
+/* Switch to the context in rdx, once its signal mask is in place */
+.type __relibc_restore_context,@function
+__relibc_restore_context:
+	mov 224(%rdx),%rcx
+	fldenv (%rcx)
+	ldmxcsr 24(%rcx)
+	mov 160(%rdx),%rsp
+	mov 128(%rdx),%rbx
+	mov 120(%rdx),%rbp
+	mov 72(%rdx),%r12
+	mov 80(%rdx),%r13
+	mov 88(%rdx),%r14
+	mov 96(%rdx),%r15
+	mov 168(%rdx),%rcx      /* return into the context */
+	push %rcx
+	mov 104(%rdx),%rdi
+	mov 112(%rdx),%rsi
+	mov 152(%rdx),%rcx
+	mov 40(%rdx),%r8
+	mov 48(%rdx),%r9
+	mov 136(%rdx),%rdx
+	xor %eax,%eax           /* returning 0 where getcontext or swapcontext saved it */
+	ret
+
+/*
+ * Where functions started by makecontext return to. rbx is callee saved, so
+ * it still points at the uc_link stored above the stack arguments
+ */
+.global __relibc_start_context
+.hidden __relibc_start_context
+.type __relibc_start_context,@function
+__relibc_start_context:
+	mov (%rbx),%rdi
+	test %rdi,%rdi
+	jz 1f
+	call setcontext@PLT
+	call abort@PLT          /* setcontext only returns on failure */
+1:
+	xor %edi,%edi           /* no uc_link, so the thread exits */
+	call exit@PLT
@@ -0,0 +1,32 @@
+use core::ptr;
+
+use crate::{
+    header::errno,
+    platform::{self, types::*},
+};
+
+pub const _CS_PATH: c_int = 0;
+pub const _CS_GNU_LIBC_VERSION: c_int = 2;
+pub const _CS_GNU_LIBPTHREAD_VERSION: c_int = 3;
+
+/// Copy the string for `name` to `buf`, truncated to `len` bytes including the
+/// NUL. Returns the size needed for the whole string, or 0 for an unknown name.
+#[no_mangle]
+pub unsafe extern "C" fn confstr(name: c_int, buf: *mut c_char, len: size_t) -> size_t {
+    let value: &str = match name {
+        _CS_PATH => "/bin:/usr/bin",
+        _CS_GNU_LIBC_VERSION => concat!("relibc ", env!("CARGO_PKG_VERSION")),
+        _CS_GNU_LIBPTHREAD_VERSION => "pthreads-emb",
+        _ => {
+            platform::errno = errno::EINVAL;
+            return 0;
+        }
+    };
+
+    if !buf.is_null() && len > 0 {
+        let copy = value.len().min(len - 1);
+        ptr::copy_nonoverlapping(value.as_ptr() as *const c_char, buf, copy);
+        *buf.add(copy) = 0;
+    }
+    value.len() + 1
+}
@@ -3,18 +3,21 @@
 use core::{convert::TryFrom, mem, ptr, slice};
 
 use crate::{
-    c_str::CStr,
+    c_str::{CStr, CString},
     header::{
-        errno, fcntl, limits, stdlib::getenv, sys_ioctl, sys_time, sys_utsname, termios,
+        errno, fcntl, limits, signal, stdio,
+        stdlib::{self, getenv},
+        string, sys_ioctl, sys_random, sys_time, sys_utsname, termios,
         time::timespec,
     },
     platform::{self, types::*, Pal, Sys},
 };
-use alloc::collections::LinkedList;
+use alloc::{collections::LinkedList, vec::Vec};
 
-pub use self::{brk::*, getopt::*, pathconf::*, sysconf::*};
+pub use self::{brk::*, confstr::*, getopt::*, pathconf::*, sysconf::*};
 
 mod brk;
+mod confstr;
 mod getopt;
 mod pathconf;
 mod sysconf;
@@ -104,11 +107,6 @@ pub extern "C" fn close(fildes: c_int) -> c_int {
     Sys::close(fildes)
 }
 
-// #[no_mangle]
-pub extern "C" fn confstr(name: c_int, buf: *mut c_char, len: size_t) -> size_t {
-    unimplemented!();
-}
-
 // #[no_mangle]
 pub extern "C" fn crypt(key: *const c_char, salt: *const c_char) -> *mut c_char {
     unimplemented!();
@@ -124,6 +122,11 @@ pub extern "C" fn dup2(fildes: c_int, fildes2: c_int) -> c_int {
     Sys::dup2(fildes, fildes2)
 }
 
+#[no_mangle]
+pub extern "C" fn dup3(fildes: c_int, fildes2: c_int, flags: c_int) -> c_int {
+    Sys::dup3(fildes, fildes2, flags)
+}
+
 // #[no_mangle]
 pub extern "C" fn encrypt(block: [c_char; 64], edflag: c_int) {
     unimplemented!();
@@ -169,39 +172,120 @@ const PATH_SEPARATOR: u8 = b':';
 #[cfg(target_os = "redox")]
 const PATH_SEPARATOR: u8 = b';';
 
-#[no_mangle]
-pub unsafe extern "C" fn execvp(file: *const c_char, argv: *const *mut c_char) -> c_int {
-    let file = CStr::from_ptr(file);
+/// The search path of `execvp` when there is no PATH
+#[cfg(target_os = "linux")]
+const DEFAULT_PATH: &[u8] = b"/bin:/usr/bin";
+
+#[cfg(target_os = "redox")]
+const DEFAULT_PATH: &[u8] = b"/bin";
+
+/// The arguments to run a script with the shell: "sh", the script, and the
+/// arguments after the first of `argv`. The script is filled in by
+/// `execve_or_shell`
+pub(crate) unsafe fn shell_argv(argv: *const *mut c_char) -> Vec<*mut c_char> {
+    let mut shell_argv = vec![c_str!("sh").as_ptr() as *mut c_char, ptr::null_mut()];
+    if !(*argv).is_null() {
+        let mut arg = argv.add(1);
+        while !(*arg).is_null() {
+            shell_argv.push(*arg);
+            arg = arg.add(1);
+        }
+    }
+    shell_argv.push(ptr::null_mut());
+    shell_argv
+}
+
+/// Like `execve`, but run `path` with the shell if it isn't an executable the
+/// system knows, like a script without a `#!` line. Does not allocate, so
+/// that it can be used after fork
+pub(crate) unsafe fn execve_or_shell(
+    path: *const c_char,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    shell_argv: &mut [*mut c_char],
+) -> c_int {
+    execve(path, argv, envp);
+    if platform::errno != errno::ENOEXEC {
+        return -1;
+    }
+
+    shell_argv[1] = path as *mut c_char;
+    execve(c_str!("/bin/sh").as_ptr(), shell_argv.as_ptr(), envp)
+}
+
+/// The programs `execvp` tries for `file`: the file itself if it has a slash,
+/// otherwise the file in each directory of PATH, in order
+pub(crate) unsafe fn search_path(file: &CStr) -> Vec<CString> {
+    if file.to_bytes().is_empty() {
+        return Vec::new();
+    }
 
     if file.to_bytes().contains(&b'/')
         || (cfg!(target_os = "redox") && file.to_bytes().contains(&b':'))
     {
-        execv(file.as_ptr(), argv)
+        return vec![file.to_owned()];
+    }
+
+    let path_env = getenv(c_str!("PATH").as_ptr());
+    let path_env = if path_env.is_null() {
+        DEFAULT_PATH
     } else {
-        let mut error = errno::ENOENT;
+        CStr::from_ptr(path_env).to_bytes()
+    };
 
-        let path_env = getenv(c_str!("PATH\0").as_ptr());
-        if !path_env.is_null() {
-            let path_env = CStr::from_ptr(path_env);
-            for path in path_env.to_bytes().split(|&b| b == PATH_SEPARATOR) {
-                let mut program = path.to_vec();
+    path_env
+        .split(|&b| b == PATH_SEPARATOR)
+        .map(|dir| {
+            // An empty directory is the current one
+            let mut program = dir.to_vec();
+            if !dir.is_empty() {
                 program.push(b'/');
-                program.extend_from_slice(file.to_bytes());
-                program.push(b'\0');
-
-                let program_c = CStr::from_bytes_with_nul(&program).unwrap();
-                execv(program_c.as_ptr(), argv);
-
-                match platform::errno {
-                    errno::ENOENT => (),
-                    other => error = other,
-                }
             }
-        }
+            program.extend_from_slice(file.to_bytes());
+            CString::from_vec_unchecked(program)
+        })
+        .collect()
+}
 
-        platform::errno = error;
-        -1
+/// Run the first of `programs` that can be, with `execve_or_shell`. Only
+/// returns on failure, with errno set. Does not allocate
+pub(crate) unsafe fn execve_first(
+    programs: &[CString],
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+    shell_argv: &mut [*mut c_char],
+) -> c_int {
+    // Keep looking after failures that are specific to one directory, but
+    // report that something was found without permission to run it
+    let mut error = errno::ENOENT;
+    for program in programs {
+        execve_or_shell(program.as_ptr(), argv, envp, shell_argv);
+        match platform::errno {
+            errno::EACCES => error = errno::EACCES,
+            errno::ENOENT | errno::ENOTDIR | errno::ESTALE | errno::ENODEV | errno::ETIMEDOUT => (),
+            _ => return -1,
+        }
     }
+
+    platform::errno = error;
+    -1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn execvp(file: *const c_char, argv: *const *mut c_char) -> c_int {
+    execvpe(file, argv, platform::environ)
+}
+
+/// Like `execvp`, but the program gets the environment `envp`. PATH is still
+/// taken from the current environment.
+#[no_mangle]
+pub unsafe extern "C" fn execvpe(
+    file: *const c_char,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    let programs = search_path(CStr::from_ptr(file));
+    execve_first(&programs, argv, envp, &mut shell_argv(argv))
 }
 
 #[no_mangle]
@@ -295,6 +379,33 @@ pub extern "C" fn geteuid() -> uid_t {
     Sys::geteuid()
 }
 
+/// Fill `buffer` with random bytes, all of them or none. At most 256 bytes can
+/// be requested at once.
+#[no_mangle]
+pub unsafe extern "C" fn getentropy(buffer: *mut c_void, length: size_t) -> c_int {
+    if length > 256 {
+        platform::errno = errno::EIO;
+        return -1;
+    }
+
+    let mut filled = 0;
+    while filled < length {
+        let res = sys_random::getrandom(
+            (buffer as *mut u8).add(filled) as *mut c_void,
+            length - filled,
+            0,
+        );
+        if res < 0 {
+            if platform::errno == errno::EINTR {
+                continue;
+            }
+            return -1;
+        }
+        filled += res as size_t;
+    }
+    0
+}
+
 #[no_mangle]
 pub extern "C" fn getgid() -> gid_t {
     Sys::getgid()
@@ -372,9 +483,122 @@ pub extern "C" fn getpagesize() -> c_int {
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn getpass(prompt: *const c_char) -> *mut c_char {
-    unimplemented!();
+/// Keyboard signals on which getpass restores the terminal settings
+const GETPASS_SIGNALS: [usize; 3] = [signal::SIGINT, signal::SIGQUIT, signal::SIGTSTP];
+
+/// The terminal getpass turned echo off for along with its previous settings,
+/// and the previous actions of GETPASS_SIGNALS it replaced
+static mut GETPASS_TTY: Option<(c_int, termios::termios)> = None;
+static mut GETPASS_ACTIONS: [Option<signal::sigaction>; 3] = [None, None, None];
+
+unsafe fn getpass_restore() {
+    for (&sig, action) in GETPASS_SIGNALS.iter().zip(GETPASS_ACTIONS.iter()) {
+        if let Some(action) = action {
+            signal::sigaction(sig as c_int, action, ptr::null_mut());
+        }
+    }
+    if let Some((fd, mut old)) = GETPASS_TTY {
+        termios::tcsetattr(fd, termios::TCSAFLUSH as c_int, &mut old);
+    }
+}
+
+extern "C" fn getpass_signal(sig: c_int) {
+    unsafe { getpass_restore() };
+    // Blocked until this handler returns, then taken with the previous action
+    signal::raise(sig);
+}
+
+/// Read a password from the controlling terminal, with echo turned off. Without
+/// a terminal, the prompt goes to stderr and the password is read from stdin.
+#[no_mangle]
+pub unsafe extern "C" fn getpass(prompt: *const c_char) -> *mut c_char {
+    static mut PASSWORD: [c_char; 128] = [0; 128];
+
+    let flags = fcntl::O_RDWR | fcntl::O_CLOEXEC;
+    // Redox has no controlling terminals to acquire
+    #[cfg(target_os = "linux")]
+    let flags = flags | fcntl::O_NOCTTY;
+    let tty = Sys::open(c_str!("/dev/tty"), flags, 0);
+    let (input, input_fd, output_fd) = if tty >= 0 {
+        let input = stdio::fdopen(tty, c_str!("r").as_ptr());
+        if input.is_null() {
+            Sys::close(tty);
+            return ptr::null_mut();
+        }
+        (input, tty, tty)
+    } else {
+        (stdio::stdin, STDIN_FILENO, STDERR_FILENO)
+    };
+
+    // The settings are restored whatever happens to the read, including when
+    // a signal from the keyboard interrupts it
+    let mut old = termios::termios::default();
+    let restore = termios::tcgetattr(input_fd, &mut old) == 0;
+    if restore {
+        GETPASS_TTY = Some((input_fd, old));
+        let action = signal::sigaction {
+            sa_handler: Some(getpass_signal),
+            sa_flags: 0,
+            sa_restorer: None,
+            sa_mask: 0,
+        };
+        for (&sig, prev) in GETPASS_SIGNALS.iter().zip(GETPASS_ACTIONS.iter_mut()) {
+            let mut old_action = action.clone();
+            if Sys::sigaction(sig as c_int, None, Some(&mut old_action)) < 0 {
+                continue;
+            }
+            let handler = old_action
+                .sa_handler
+                .map_or(signal::SIG_DFL, |handler| handler as usize);
+            // An ignored signal can't leave the terminal without echo
+            if handler != signal::SIG_IGN
+                && signal::sigaction(sig as c_int, &action, ptr::null_mut()) == 0
+            {
+                *prev = Some(old_action);
+            }
+        }
+
+        let mut new = old;
+        new.c_lflag &= !termios::ECHO as termios::tcflag_t;
+        termios::tcsetattr(input_fd, termios::TCSAFLUSH as c_int, &mut new);
+    }
+
+    if !prompt.is_null() {
+        Sys::write(output_fd, CStr::from_ptr(prompt).to_bytes());
+    }
+
+    let mut line: *mut c_char = ptr::null_mut();
+    let mut size: size_t = 0;
+    let res = stdio::__getdelim(&mut line, &mut size, b'\n' as c_int, input);
+
+    if restore {
+        getpass_restore();
+        GETPASS_TTY = None;
+        GETPASS_ACTIONS = [None, None, None];
+        // The newline ending the password wasn't echoed either
+        Sys::write(output_fd, b"\n");
+    }
+    if tty >= 0 {
+        stdio::fclose(input);
+    }
+    if res < 0 {
+        stdlib::free(line as *mut c_void);
+        return ptr::null_mut();
+    }
+
+    let mut len = string::strlen(line);
+    if len > 0 && *line.add(len - 1) == b'\n' as c_char {
+        len -= 1;
+    }
+    let len = len.min(PASSWORD.len() - 1);
+    ptr::copy_nonoverlapping(line, PASSWORD.as_mut_ptr(), len);
+    PASSWORD[len] = 0;
+
+    // Don't leave the password behind in freed memory
+    ptr::write_bytes(line, 0, size);
+    stdlib::free(line as *mut c_void);
+
+    PASSWORD.as_mut_ptr()
 }
 
 #[no_mangle]
@@ -434,9 +658,56 @@ pub unsafe extern "C" fn link(path1: *const c_char, path2: *const c_char) -> c_i
     Sys::link(path1, path2)
 }
 
-// #[no_mangle]
-pub extern "C" fn lockf(fildes: c_int, function: c_int, size: off_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn linkat(
+    fd1: c_int,
+    path1: *const c_char,
+    fd2: c_int,
+    path2: *const c_char,
+    flag: c_int,
+) -> c_int {
+    let path1 = CStr::from_ptr(path1);
+    let path2 = CStr::from_ptr(path2);
+    Sys::linkat(fd1, path1, fd2, path2, flag)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lockf(fildes: c_int, function: c_int, size: off_t) -> c_int {
+    // The section starts at the current offset and extends size bytes, which
+    // may be negative, or to the end of the file when size is zero
+    let mut lock = fcntl::flock {
+        l_type: fcntl::F_WRLCK as c_short,
+        l_whence: SEEK_CUR as c_short,
+        l_start: 0,
+        l_len: size,
+        l_pid: 0,
+    };
+    let lock_ptr = &mut lock as *mut fcntl::flock as c_ulong;
+
+    match function {
+        F_TEST => {
+            lock.l_type = fcntl::F_RDLCK as c_short;
+            if fcntl::sys_fcntl(fildes, fcntl::F_GETLK, lock_ptr) < 0 {
+                return -1;
+            }
+            if lock.l_type == fcntl::F_UNLCK as c_short || lock.l_pid == Sys::getpid() {
+                0
+            } else {
+                platform::errno = errno::EACCES;
+                -1
+            }
+        }
+        F_ULOCK => {
+            lock.l_type = fcntl::F_UNLCK as c_short;
+            fcntl::sys_fcntl(fildes, fcntl::F_SETLK, lock_ptr)
+        }
+        F_TLOCK => fcntl::sys_fcntl(fildes, fcntl::F_SETLK, lock_ptr),
+        F_LOCK => fcntl::sys_fcntl(fildes, fcntl::F_SETLKW, lock_ptr),
+        _ => {
+            platform::errno = errno::EINVAL;
+            -1
+        }
+    }
 }
 
 #[no_mangle]
@@ -534,7 +805,7 @@ pub extern "C" fn pwrite(
 pub extern "C" fn read(fildes: c_int, buf: *const c_void, nbyte: size_t) -> ssize_t {
     let buf = unsafe { slice::from_raw_parts_mut(buf as *mut u8, nbyte as usize) };
     trace_expr!(
-        Sys::read(fildes, buf),
+        unsafe { platform::cancellation_point(|| Sys::read(fildes, buf)) },
         "read({}, {:p}, {})",
         fildes,
         buf,
@@ -601,7 +872,7 @@ pub extern "C" fn sleep(seconds: c_uint) -> c_uint {
         tv_nsec: 0,
     };
     let rmtp = ptr::null_mut();
-    Sys::nanosleep(&rqtp, rmtp);
+    unsafe { platform::cancellation_point(|| Sys::nanosleep(&rqtp, rmtp)) };
     0
 }
 
@@ -628,6 +899,13 @@ pub unsafe extern "C" fn symlink(path1: *const c_char, path2: *const c_char) ->
     Sys::symlink(path1, path2)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn symlinkat(path1: *const c_char, fd: c_int, path2: *const c_char) -> c_int {
+    let path1 = CStr::from_ptr(path1);
+    let path2 = CStr::from_ptr(path2);
+    Sys::symlinkat(path1, fd, path2)
+}
+
 // #[no_mangle]
 pub extern "C" fn sync() {
     unimplemented!();
@@ -667,27 +945,42 @@ pub extern "C" fn truncate(path: *const c_char, length: off_t) -> c_int {
 
 #[no_mangle]
 pub unsafe extern "C" fn ttyname(fildes: c_int) -> *mut c_char {
-    static mut TTYNAME: [c_char; 4096] = [0; 4096];
-    if ttyname_r(fildes, TTYNAME.as_mut_ptr(), TTYNAME.len()) == 0 {
-        TTYNAME.as_mut_ptr()
-    } else {
-        ptr::null_mut()
+    static mut TTYNAME: [c_char; limits::PATH_MAX] = [0; limits::PATH_MAX];
+    match ttyname_r(fildes, TTYNAME.as_mut_ptr(), TTYNAME.len()) {
+        0 => TTYNAME.as_mut_ptr(),
+        err => {
+            platform::errno = err;
+            ptr::null_mut()
+        }
     }
 }
 
+/// Find the path of the terminal open as `fildes`. Returns an error number,
+/// ENOTTY if it is not a terminal and ERANGE if the path doesn't fit.
 #[no_mangle]
-pub extern "C" fn ttyname_r(fildes: c_int, name: *mut c_char, namesize: size_t) -> c_int {
-    let name = unsafe { slice::from_raw_parts_mut(name as *mut u8, namesize) };
-    if name.is_empty() {
-        return errno::ERANGE;
+pub unsafe extern "C" fn ttyname_r(fildes: c_int, name: *mut c_char, namesize: size_t) -> c_int {
+    let errno_backup = platform::errno;
+    if isatty(fildes) == 0 {
+        let err = platform::errno;
+        platform::errno = errno_backup;
+        return err;
     }
 
-    let len = Sys::fpath(fildes, &mut name[..namesize - 1]);
+    let mut path = [0; limits::PATH_MAX];
+    let len = Sys::fpath(fildes, &mut path);
     if len < 0 {
-        return unsafe { -platform::errno };
+        let err = platform::errno;
+        platform::errno = errno_backup;
+        return err;
+    }
+    let len = len as usize;
+    if len >= namesize {
+        return errno::ERANGE;
     }
-    name[len as usize] = 0;
 
+    let name = slice::from_raw_parts_mut(name as *mut u8, namesize);
+    name[..len].copy_from_slice(&path[..len]);
+    name[len] = 0;
     0
 }
 
@@ -722,6 +1015,12 @@ pub unsafe extern "C" fn unlink(path: *const c_char) -> c_int {
     Sys::unlink(path)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn unlinkat(fd: c_int, path: *const c_char, flag: c_int) -> c_int {
+    let path = CStr::from_ptr(path);
+    Sys::unlinkat(fd, path, flag)
+}
+
 #[no_mangle]
 pub extern "C" fn usleep(useconds: useconds_t) -> c_int {
     let rqtp = timespec {
@@ -729,7 +1028,7 @@ pub extern "C" fn usleep(useconds: useconds_t) -> c_int {
         tv_nsec: ((useconds % 1_000_000) * 1000) as i64,
     };
     let rmtp = ptr::null_mut();
-    Sys::nanosleep(&rqtp, rmtp)
+    unsafe { platform::cancellation_point(|| Sys::nanosleep(&rqtp, rmtp)) }
 }
 
 // #[no_mangle]
@@ -740,5 +1039,5 @@ pub extern "C" fn vfork() -> pid_t {
 #[no_mangle]
 pub extern "C" fn write(fildes: c_int, buf: *const c_void, nbyte: size_t) -> ssize_t {
     let buf = unsafe { slice::from_raw_parts(buf as *const u8, nbyte as usize) };
-    Sys::write(fildes, buf)
+    unsafe { platform::cancellation_point(|| Sys::write(fildes, buf)) }
 }
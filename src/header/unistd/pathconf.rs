@@ -1,5 +1,7 @@
+use core::mem;
+
 use crate::{
-    header::errno,
+    header::{errno, sys_statvfs},
     platform::{self, types::*},
 };
 
@@ -25,13 +27,28 @@ pub const _PC_ALLOC_SIZE_MIN: c_int = 18;
 pub const _PC_SYMLINK_MAX: c_int = 19;
 pub const _PC_2_SYMLINKS: c_int = 20;
 
-fn pc(name: c_int) -> c_long {
+/// Look up `name`, calling `stat` to fill in a `statvfs` for the file system
+/// the file is on, where the value depends on it
+fn pc<F>(name: c_int, stat: F) -> c_long
+where
+    F: FnOnce(&mut sys_statvfs::statvfs) -> c_int,
+{
     // Settings from musl, some adjusted
     match name {
         _PC_LINK_MAX => 127,
         _PC_MAX_CANON => 255,
         _PC_MAX_INPUT => 255,
-        _PC_NAME_MAX => 255,
+        _PC_NAME_MAX => {
+            let mut buf: sys_statvfs::statvfs = unsafe { mem::zeroed() };
+            if stat(&mut buf) < 0 {
+                return -1;
+            }
+            if buf.f_namemax == 0 {
+                255
+            } else {
+                buf.f_namemax as c_long
+            }
+        }
         _PC_PATH_MAX => 4096,
         _PC_PIPE_BUF => 4096,
         _PC_CHOWN_RESTRICTED => 1,
@@ -59,11 +76,11 @@ fn pc(name: c_int) -> c_long {
 }
 
 #[no_mangle]
-pub extern "C" fn fpathconf(_fildes: c_int, name: c_int) -> c_long {
-    pc(name)
+pub extern "C" fn fpathconf(fildes: c_int, name: c_int) -> c_long {
+    pc(name, |buf| sys_statvfs::fstatvfs(fildes, buf))
 }
 
 #[no_mangle]
-pub extern "C" fn pathconf(_path: *const c_char, name: c_int) -> c_long {
-    pc(name)
+pub unsafe extern "C" fn pathconf(path: *const c_char, name: c_int) -> c_long {
+    pc(name, |buf| sys_statvfs::statvfs(path, buf))
 }
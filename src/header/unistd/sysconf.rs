@@ -1,6 +1,15 @@
+use alloc::vec::Vec;
+
 use crate::{
-    header::errno,
-    platform::{self, types::*},
+    c_str::CStr,
+    fs::File,
+    header::{
+        errno, fcntl,
+        sys_auxv::{find_auxv, AT_CLKTCK, AT_PAGESZ},
+        sys_resource::{rlimit, RLIMIT_NOFILE, RLIM_INFINITY},
+    },
+    io::Read,
+    platform::{self, types::*, Pal, Sys},
 };
 
 // POSIX.1 {
@@ -21,6 +30,10 @@ pub const _SC_RE_DUP_MAX: c_int = 44;
 pub const _SC_LOGIN_NAME_MAX: c_int = 71;
 pub const _SC_TTY_NAME_MAX: c_int = 72;
 // ...
+pub const _SC_NPROCESSORS_CONF: c_int = 83;
+pub const _SC_NPROCESSORS_ONLN: c_int = 84;
+pub const _SC_PHYS_PAGES: c_int = 85;
+// ...
 pub const _SC_SYMLOOP_MAX: c_int = 173;
 // ...
 pub const _SC_HOST_NAME_MAX: c_int = 180;
@@ -28,20 +41,23 @@ pub const _SC_HOST_NAME_MAX: c_int = 180;
 
 #[no_mangle]
 pub extern "C" fn sysconf(name: c_int) -> c_long {
-    //TODO: Real values
     match name {
+        //TODO: Get ARG_MAX, CHILD_MAX and NGROUPS_MAX from the kernel
         _SC_ARG_MAX => 4096,
         _SC_CHILD_MAX => 65536,
-        _SC_CLK_TCK => 100,
+        _SC_CLK_TCK => auxv_or(AT_CLKTCK, 100),
         _SC_NGROUPS_MAX => 65536,
-        _SC_OPEN_MAX => 1024,
+        _SC_OPEN_MAX => open_max(),
         _SC_STREAM_MAX => 16,
         _SC_TZNAME_MAX => -1,
         _SC_VERSION => 200809,
-        _SC_PAGESIZE => 4096,
+        _SC_PAGESIZE => auxv_or(AT_PAGESZ, 4096),
         _SC_RE_DUP_MAX => 32767,
         _SC_LOGIN_NAME_MAX => 256,
         _SC_TTY_NAME_MAX => 32,
+        _SC_NPROCESSORS_CONF => possible_processors(),
+        _SC_NPROCESSORS_ONLN => processors(),
+        _SC_PHYS_PAGES => phys_pages(),
         _SC_SYMLOOP_MAX => -1,
         _SC_HOST_NAME_MAX => 64,
         _ => {
@@ -52,3 +68,104 @@ pub extern "C" fn sysconf(name: c_int) -> c_long {
         }
     }
 }
+
+/// Look up a value the kernel passed in the auxiliary vector
+fn auxv_or(kind: usize, default: c_long) -> c_long {
+    match unsafe { find_auxv(kind) } {
+        Some(value) if value != 0 => value as c_long,
+        _ => default,
+    }
+}
+
+fn open_max() -> c_long {
+    let mut rlim = rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { Sys::getrlimit(RLIMIT_NOFILE as c_int, &mut rlim) } < 0
+        || rlim.rlim_cur == RLIM_INFINITY
+    {
+        // Indeterminate
+        return -1;
+    }
+    rlim.rlim_cur as c_long
+}
+
+fn read_file(path: &CStr) -> Option<Vec<u8>> {
+    let mut file = File::open(path, fcntl::O_RDONLY | fcntl::O_CLOEXEC).ok()?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+/// Count the processors listed in /proc/cpuinfo, at least one
+fn processors() -> c_long {
+    let count = match read_file(c_str!("/proc/cpuinfo")) {
+        Some(data) => data
+            .split(|&b| b == b'\n')
+            .filter(|line| line.starts_with(b"processor"))
+            .count(),
+        None => 0,
+    };
+    count.max(1) as c_long
+}
+
+/// Count the processors the kernel could bring online, from the list in
+/// /sys/devices/system/cpu/possible such as "0-3,8-11", like glibc. Falls back
+/// to the online processors if that cannot be read.
+fn possible_processors() -> c_long {
+    let data = match read_file(c_str!("/sys/devices/system/cpu/possible")) {
+        Some(data) => data,
+        None => return processors(),
+    };
+
+    let parse = |num: &[u8]| -> Option<c_long> {
+        if num.is_empty() || !num.iter().all(u8::is_ascii_digit) {
+            return None;
+        }
+        Some(
+            num.iter()
+                .fold(0, |num: c_long, &b| num * 10 + (b - b'0') as c_long),
+        )
+    };
+
+    let mut count = 0;
+    let list = data.split(|&b| b == b'\n').next().unwrap_or(&[]);
+    for range in list.split(|&b| b == b',') {
+        let mut bounds = range.splitn(2, |&b| b == b'-');
+        let first = bounds.next().and_then(parse);
+        let last = match bounds.next() {
+            Some(last) => parse(last),
+            None => first,
+        };
+        match (first, last) {
+            (Some(first), Some(last)) if first <= last => count += last - first + 1,
+            _ => return processors(),
+        }
+    }
+    count.max(1)
+}
+
+/// Total memory from the MemTotal line of /proc/meminfo, in pages
+fn phys_pages() -> c_long {
+    let data = match read_file(c_str!("/proc/meminfo")) {
+        Some(data) => data,
+        None => return -1,
+    };
+    let line = match data
+        .split(|&b| b == b'\n')
+        .find(|line| line.starts_with(b"MemTotal:"))
+    {
+        Some(line) => line,
+        None => return -1,
+    };
+    // In bytes the total overflows a 32-bit c_long, so count in u64
+    let kib = line
+        .iter()
+        .filter(|b| b.is_ascii_digit())
+        .fold(0, |num: u64, &b| {
+            num.saturating_mul(10).saturating_add((b - b'0') as u64)
+        });
+    let pages = kib.saturating_mul(1024) / sysconf(_SC_PAGESIZE) as u64;
+    pages.min(c_long::MAX as u64) as c_long
+}
@@ -1,8 +1,10 @@
 //! utime implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/utime.h.html
 
+use core::ptr;
+
 use crate::{
     c_str::CStr,
-    header::time::timespec,
+    header::{fcntl::AT_FDCWD, time::timespec},
     platform::{types::*, Pal, Sys},
 };
 
@@ -16,6 +18,9 @@ pub struct utimbuf {
 #[no_mangle]
 pub unsafe extern "C" fn utime(filename: *const c_char, times: *const utimbuf) -> c_int {
     let filename = CStr::from_ptr(filename);
+    if times.is_null() {
+        return Sys::utimensat(AT_FDCWD, filename, ptr::null(), 0);
+    }
     let times_spec = [
         timespec {
             tv_sec: (*times).actime,
@@ -26,5 +31,5 @@ pub unsafe extern "C" fn utime(filename: *const c_char, times: *const utimbuf) -
             tv_nsec: 0,
         },
     ];
-    Sys::utimens(filename, times_spec.as_ptr())
+    Sys::utimensat(AT_FDCWD, filename, times_spec.as_ptr(), 0)
 }
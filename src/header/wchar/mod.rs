@@ -1,18 +1,38 @@
 //! wchar implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/wchar.h.html
 
+use alloc::{string::String, vec::Vec};
 use core::{char, ffi::VaList as va_list, mem, ptr, slice, usize};
 
 use crate::{
     header::{
-        ctype::isspace, errno::ERANGE, stdio::*, stdlib::MB_CUR_MAX, string, time::*, wctype::*,
+        ctype::isspace,
+        errno::{EILSEQ, ENOMEM, ERANGE},
+        stdio::{printf, *},
+        stdlib::MB_CUR_MAX,
+        string,
+        time::*,
+        wctype::*,
     },
+    io::{Read, Write},
     platform::{self, types::*},
 };
 
 mod utf8;
+mod wcwidth;
+/// Conversion state between multibyte and wide characters. UTF-8 has no
+/// shift states, so the state only holds the bytes of a partial character.
+/// Defined for C in bits/mbstate.h, as stdio.h needs it for fpos_t.
 #[repr(C)]
 #[derive(Clone, Copy)]
-pub struct mbstate_t;
+pub struct mbstate_t {
+    pub __state: c_uint,
+}
+
+impl mbstate_t {
+    pub const fn new() -> Self {
+        mbstate_t { __state: 0 }
+    }
+}
 
 #[no_mangle]
 pub unsafe extern "C" fn btowc(c: c_int) -> wint_t {
@@ -23,7 +43,7 @@ pub unsafe extern "C" fn btowc(c: c_int) -> wint_t {
 
     let uc = c as u8;
     let c = uc as c_char;
-    let mut ps: mbstate_t = mbstate_t;
+    let mut ps: mbstate_t = mbstate_t::new();
     let mut wc: wchar_t = 0;
     let saved_errno = platform::errno;
     let status = mbrtowc(&mut wc, &c as *const c_char, 1, &mut ps);
@@ -36,8 +56,38 @@ pub unsafe extern "C" fn btowc(c: c_int) -> wint_t {
 
 #[no_mangle]
 pub unsafe extern "C" fn fgetwc(stream: *mut FILE) -> wint_t {
-    //TODO: Real multibyte
-    btowc(fgetc(stream))
+    let mut stream = (*stream).lock();
+    if stream.try_set_orientation_unlocked(1) < 0 {
+        return WEOF;
+    }
+
+    // Bytes are converted as they are read, so that a partial character is
+    // kept in the stream's conversion state, where fgetpos finds it
+    let mut wc: wchar_t = 0;
+    loop {
+        let mut byte = [0; 1];
+        match stream.read(&mut byte) {
+            Ok(1) => (),
+            _ => {
+                if stream.mbstate.__state != 0 {
+                    platform::errno = EILSEQ;
+                }
+                return WEOF;
+            }
+        }
+        let amount = mbrtowc(
+            &mut wc,
+            byte.as_ptr() as *const c_char,
+            1,
+            &mut stream.mbstate,
+        );
+        if amount == -1isize as usize {
+            return WEOF;
+        }
+        if amount != -2isize as usize {
+            return wc as wint_t;
+        }
+    }
 }
 
 #[no_mangle]
@@ -61,16 +111,23 @@ pub unsafe extern "C" fn fgetws(ws: *mut wchar_t, n: c_int, stream: *mut FILE) -
 
 #[no_mangle]
 pub unsafe extern "C" fn fputwc(wc: wchar_t, stream: *mut FILE) -> wint_t {
+    let mut stream = (*stream).lock();
+    if stream.try_set_orientation_unlocked(1) < 0 {
+        return WEOF;
+    }
+
     //Convert wchar_t to multibytes first
-    static mut INTERNAL: mbstate_t = mbstate_t;
     let mut bytes: [c_char; MB_CUR_MAX as usize] = [0; MB_CUR_MAX as usize];
 
-    let amount = wcrtomb(bytes.as_mut_ptr(), wc, &mut INTERNAL);
-
-    for i in 0..amount {
-        fputc(bytes[i] as c_int, &mut *stream);
+    let amount = wcrtomb(bytes.as_mut_ptr(), wc, &mut stream.mbstate);
+    if amount == -1isize as usize {
+        return WEOF;
     }
 
+    let bytes = slice::from_raw_parts(bytes.as_ptr() as *const u8, amount);
+    if stream.write_all(bytes).is_err() {
+        return WEOF;
+    }
     wc as wint_t
 }
 
@@ -106,18 +163,18 @@ pub unsafe extern "C" fn getwchar() -> wint_t {
 
 #[no_mangle]
 pub unsafe extern "C" fn mbsinit(ps: *const mbstate_t) -> c_int {
-    //Add a check for the state maybe
-    if ps.is_null() {
-        1
-    } else {
-        0
-    }
+    (ps.is_null() || (*ps).__state == 0) as c_int
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn mbrlen(s: *const c_char, n: size_t, ps: *mut mbstate_t) -> size_t {
-    static mut INTERNAL: mbstate_t = mbstate_t;
-    mbrtowc(ptr::null_mut(), s, n, &mut INTERNAL)
+    static mut INTERNAL: mbstate_t = mbstate_t::new();
+    let ps = if ps.is_null() {
+        &mut INTERNAL as *mut _
+    } else {
+        ps
+    };
+    mbrtowc(ptr::null_mut(), s, n, ps)
 }
 
 //Only works for UTF8 at the moment
@@ -128,11 +185,13 @@ pub unsafe extern "C" fn mbrtowc(
     n: size_t,
     ps: *mut mbstate_t,
 ) -> size_t {
-    static mut INTERNAL: mbstate_t = mbstate_t;
+    static mut INTERNAL: mbstate_t = mbstate_t::new();
 
-    if ps.is_null() {
-        let ps = &mut INTERNAL;
-    }
+    let ps = if ps.is_null() {
+        &mut INTERNAL as *mut _
+    } else {
+        ps
+    };
     if s.is_null() {
         let xs: [c_char; 1] = [0];
         utf8::mbrtowc(pwc, &xs[0] as *const c_char, 1, ps)
@@ -151,11 +210,13 @@ pub unsafe extern "C" fn mbsnrtowcs(
     dst_len: size_t,
     ps: *mut mbstate_t,
 ) -> size_t {
-    static mut INTERNAL: mbstate_t = mbstate_t;
+    static mut INTERNAL: mbstate_t = mbstate_t::new();
 
-    if ps.is_null() {
-        let ps = &mut INTERNAL;
-    }
+    let ps = if ps.is_null() {
+        &mut INTERNAL as *mut _
+    } else {
+        ps
+    };
 
     let mut src = *src_ptr;
 
@@ -222,16 +283,6 @@ pub unsafe extern "C" fn putwchar(wc: wchar_t) -> wint_t {
     fputwc(wc, &mut *stdout)
 }
 
-// #[no_mangle]
-pub extern "C" fn swprintf(
-    s: *mut wchar_t,
-    n: size_t,
-    format: *const wchar_t,
-    ap: va_list,
-) -> c_int {
-    unimplemented!();
-}
-
 // #[no_mangle]
 pub extern "C" fn swscanf(s: *const wchar_t, format: *const wchar_t, ap: va_list) -> c_int {
     unimplemented!();
@@ -242,24 +293,98 @@ pub extern "C" fn ungetwc(wc: wint_t, stream: *mut FILE) -> wint_t {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn vfwprintf(stream: *mut FILE, format: *const wchar_t, arg: va_list) -> c_int {
-    unimplemented!();
+/// Format with the narrow printf, which gets the format encoded as UTF-8.
+/// Narrow strings and characters in the output are decoded like mbrtowc
+/// would, wide ones were encoded by printf.
+unsafe fn wide_printf(format: *const wchar_t, ap: va_list) -> Option<String> {
+    let mut utf8_format = Vec::new();
+    let mut i = 0;
+    loop {
+        let wc = *format.add(i);
+        if wc == 0 {
+            break;
+        }
+        match char::from_u32(wc as u32) {
+            Some(c) => {
+                let mut buf = [0; 4];
+                utf8_format.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            }
+            None => {
+                platform::errno = EILSEQ;
+                return None;
+            }
+        }
+        i += 1;
+    }
+    utf8_format.push(0);
+
+    let mut out = Vec::new();
+    if printf::printf_wide(&mut out, utf8_format.as_ptr() as *const c_char, ap) < 0 {
+        return None;
+    }
+    match String::from_utf8(out) {
+        Ok(out) => Some(out),
+        Err(_) => {
+            platform::errno = EILSEQ;
+            None
+        }
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn vwprintf(format: *const wchar_t, arg: va_list) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn vfwprintf(
+    stream: *mut FILE,
+    format: *const wchar_t,
+    arg: va_list,
+) -> c_int {
+    let mut stream = (*stream).lock();
+    if stream.try_set_orientation_unlocked(1) < 0 {
+        return -1;
+    }
+
+    let out = match wide_printf(format, arg) {
+        Some(out) => out,
+        None => return -1,
+    };
+    if stream.write_all(out.as_bytes()).is_err() {
+        return -1;
+    }
+    out.chars().count() as c_int
 }
 
-// #[no_mangle]
-pub extern "C" fn vswprintf(
+#[no_mangle]
+pub unsafe extern "C" fn vwprintf(format: *const wchar_t, arg: va_list) -> c_int {
+    vfwprintf(&mut *stdout, format, arg)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vswprintf(
     s: *mut wchar_t,
     n: size_t,
     format: *const wchar_t,
     arg: va_list,
 ) -> c_int {
-    unimplemented!();
+    let out = match wide_printf(format, arg) {
+        Some(out) => out,
+        None => return -1,
+    };
+
+    // Write as much as fits, but it is an error if the NUL does not
+    let mut len = 0;
+    for c in out.chars() {
+        if len + 1 < n {
+            *s.add(len) = c as wchar_t;
+        }
+        len += 1;
+    }
+    if n > 0 {
+        *s.add(len.min(n - 1)) = 0;
+    }
+    if len >= n {
+        -1
+    } else {
+        len as c_int
+    }
 }
 
 //widechar to multibyte
@@ -300,7 +425,8 @@ pub unsafe extern "C" fn wcscmp(ws1: *const wchar_t, ws2: *const wchar_t) -> c_i
 
 #[no_mangle]
 pub unsafe extern "C" fn wcscoll(ws1: *const wchar_t, ws2: *const wchar_t) -> c_int {
-    //TODO: locale comparison
+    // relibc has no locale stuff (yet), so this is the same as strcoll: code
+    // points compare in order
     wcscmp(ws1, ws2)
 }
 
@@ -331,6 +457,20 @@ pub unsafe extern "C" fn wcscspn(wcs: *const wchar_t, set: *const wchar_t) -> si
     inner_wcsspn(wcs, set, true)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wcsdup(ws: *const wchar_t) -> *mut wchar_t {
+    let len = wcslen(ws) + 1;
+
+    let buffer = platform::alloc(len * mem::size_of::<wchar_t>()) as *mut wchar_t;
+    if buffer.is_null() {
+        platform::errno = ENOMEM;
+    } else {
+        wmemcpy(buffer, ws, len);
+    }
+
+    buffer
+}
+
 // #[no_mangle]
 pub extern "C" fn wcsftime(
     wcs: *mut wchar_t,
@@ -637,14 +777,30 @@ pub extern "C" fn wcswcs(ws1: *const wchar_t, ws2: *const wchar_t) -> *mut wchar
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn wcswidth(pwcs: *const wchar_t, n: size_t) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcswidth(pwcs: *const wchar_t, n: size_t) -> c_int {
+    let mut total = 0;
+    for i in 0..n {
+        let wc = *pwcs.add(i);
+        if wc == 0 {
+            break;
+        }
+        match wcwidth(wc) {
+            -1 => return -1,
+            width => total += width,
+        }
+    }
+    total
 }
 
-// #[no_mangle]
-pub extern "C" fn wcsxfrm(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) -> size_t {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn wcsxfrm(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) -> size_t {
+    // relibc has no locale stuff (yet)
+    let len = wcslen(ws2);
+    if len < n {
+        wcscpy(ws1, ws2);
+    }
+    len
 }
 
 #[no_mangle]
@@ -656,9 +812,9 @@ pub extern "C" fn wctob(c: wint_t) -> c_int {
     }
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn wcwidth(wc: wchar_t) -> c_int {
-    unimplemented!();
+    wcwidth::width(wc as u32)
 }
 
 #[no_mangle]
@@ -717,11 +873,6 @@ pub unsafe extern "C" fn wmemset(ws: *mut wchar_t, wc: wchar_t, n: size_t) -> *m
     ws
 }
 
-// #[no_mangle]
-pub extern "C" fn wprintf(format: *const wchar_t, ap: va_list) -> c_int {
-    unimplemented!();
-}
-
 // #[no_mangle]
 pub extern "C" fn wscanf(format: *const wchar_t, ap: va_list) -> c_int {
     unimplemented!();
@@ -10,20 +10,53 @@ use crate::{
 
 use super::mbstate_t;
 
+// The state holds the bytes of a partial character: their count in the
+// lowest byte, followed by the bytes themselves
+fn pending(ps: &mbstate_t) -> ([u8; 4], usize) {
+    let count = (ps.__state & 0xFF) as usize;
+    let mut bytes = [0; 4];
+    for (i, byte) in bytes.iter_mut().enumerate().take(count) {
+        *byte = (ps.__state >> (8 * (i + 1))) as u8;
+    }
+    (bytes, count)
+}
+
+fn set_pending(ps: &mut mbstate_t, bytes: &[u8]) {
+    ps.__state = bytes.len() as c_uint;
+    for (i, &byte) in bytes.iter().enumerate() {
+        ps.__state |= (byte as c_uint) << (8 * (i + 1));
+    }
+}
+
 //It's guaranteed that we don't have any nullpointers here
 pub unsafe fn mbrtowc(pwc: *mut wchar_t, s: *const c_char, n: usize, ps: *mut mbstate_t) -> usize {
-    let size = str::utf8_char_width(*s as u8);
-    if size > n {
-        platform::errno = errno::EILSEQ;
-        return -2isize as usize;
+    let ps = &mut *ps;
+    let (mut bytes, count) = pending(ps);
+    if count == 0 {
+        if n == 0 {
+            return -2isize as usize;
+        }
+        bytes[0] = *s as u8;
     }
+
+    let size = str::utf8_char_width(bytes[0]);
     if size == 0 {
+        ps.__state = 0;
         platform::errno = errno::EILSEQ;
         return -1isize as usize;
     }
 
-    let slice = slice::from_raw_parts(s as *const u8, size);
-    let decoded = str::from_utf8(slice);
+    // Take what the character still needs, or keep all there is for later
+    let needed = size - count;
+    let input = slice::from_raw_parts(s as *const u8, needed.min(n));
+    bytes[count..count + input.len()].copy_from_slice(input);
+    if n < needed {
+        set_pending(ps, &bytes[..count + n]);
+        return -2isize as usize;
+    }
+    ps.__state = 0;
+
+    let decoded = str::from_utf8(&bytes[..size]);
     if decoded.is_err() {
         platform::errno = errno::EILSEQ;
         return -1isize as usize;
@@ -38,7 +71,7 @@ pub unsafe fn mbrtowc(pwc: *mut wchar_t, s: *const c_char, n: usize, ps: *mut mb
     }
 
     if result != 0 {
-        size
+        needed
     } else {
         0
     }
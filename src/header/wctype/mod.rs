@@ -1,11 +1,145 @@
-//! wchar implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/wchar.h.html
+//! wctype implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/wctype.h.html
 
-use crate::platform::types::*;
+use core::ptr;
+
+use crate::{c_str::CStr, platform::types::*};
 
 mod casecmp;
+pub(crate) mod classify;
 use casecmp::casemap;
 pub const WEOF: wint_t = 0xFFFF_FFFFu32;
 
+pub type wctrans_t = *const i32;
+
+// The descriptors returned by wctype, zero is an invalid class
+const WCTYPE_ALNUM: wctype_t = 1;
+const WCTYPE_ALPHA: wctype_t = 2;
+const WCTYPE_BLANK: wctype_t = 3;
+const WCTYPE_CNTRL: wctype_t = 4;
+const WCTYPE_DIGIT: wctype_t = 5;
+const WCTYPE_GRAPH: wctype_t = 6;
+const WCTYPE_LOWER: wctype_t = 7;
+const WCTYPE_PRINT: wctype_t = 8;
+const WCTYPE_PUNCT: wctype_t = 9;
+const WCTYPE_SPACE: wctype_t = 10;
+const WCTYPE_UPPER: wctype_t = 11;
+const WCTYPE_XDIGIT: wctype_t = 12;
+
+// The descriptors returned by wctrans, null is an invalid mapping
+const WCTRANS_TOLOWER: wctrans_t = 1 as wctrans_t;
+const WCTRANS_TOUPPER: wctrans_t = 2 as wctrans_t;
+
+#[no_mangle]
+pub extern "C" fn iswalnum(wc: wint_t) -> c_int {
+    c_int::from(iswalpha(wc) != 0 || iswdigit(wc) != 0)
+}
+
+#[no_mangle]
+pub extern "C" fn iswalpha(wc: wint_t) -> c_int {
+    c_int::from(classify::is_alpha(wc))
+}
+
+#[no_mangle]
+pub extern "C" fn iswblank(wc: wint_t) -> c_int {
+    c_int::from(wc == '\t' as wint_t || is_space_separator(wc))
+}
+
+#[no_mangle]
+pub extern "C" fn iswcntrl(wc: wint_t) -> c_int {
+    c_int::from(
+        wc < 0x20
+            || (wc >= 0x7F && wc < 0xA0)
+            || wc == 0x2028
+            || wc == 0x2029
+            || (wc >= 0xFFF9 && wc <= 0xFFFB),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn iswctype(wc: wint_t, desc: wctype_t) -> c_int {
+    match desc {
+        WCTYPE_ALNUM => iswalnum(wc),
+        WCTYPE_ALPHA => iswalpha(wc),
+        WCTYPE_BLANK => iswblank(wc),
+        WCTYPE_CNTRL => iswcntrl(wc),
+        WCTYPE_DIGIT => iswdigit(wc),
+        WCTYPE_GRAPH => iswgraph(wc),
+        WCTYPE_LOWER => iswlower(wc),
+        WCTYPE_PRINT => iswprint(wc),
+        WCTYPE_PUNCT => iswpunct(wc),
+        WCTYPE_SPACE => iswspace(wc),
+        WCTYPE_UPPER => iswupper(wc),
+        WCTYPE_XDIGIT => iswxdigit(wc),
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn iswdigit(wc: wint_t) -> c_int {
+    // Only the ASCII digits, as POSIX requires
+    c_int::from(wc >= '0' as wint_t && wc <= '9' as wint_t)
+}
+
+#[no_mangle]
+pub extern "C" fn iswgraph(wc: wint_t) -> c_int {
+    c_int::from(iswprint(wc) != 0 && iswspace(wc) == 0)
+}
+
+#[no_mangle]
+pub extern "C" fn iswlower(wc: wint_t) -> c_int {
+    // U+00DF LATIN SMALL LETTER SHARP S has no single upper case character
+    c_int::from(towupper(wc) != wc || wc == 0xDF)
+}
+
+#[no_mangle]
+pub extern "C" fn iswprint(wc: wint_t) -> c_int {
+    c_int::from(iswcntrl(wc) == 0 && classify::is_print(wc))
+}
+
+#[no_mangle]
+pub extern "C" fn iswpunct(wc: wint_t) -> c_int {
+    c_int::from(iswgraph(wc) != 0 && iswalnum(wc) == 0)
+}
+
+#[no_mangle]
+pub extern "C" fn iswspace(wc: wint_t) -> c_int {
+    c_int::from(match wc {
+        0x09..=0x0D | 0x20 | 0x2028 | 0x2029 => true,
+        _ => is_space_separator(wc),
+    })
+}
+
+#[no_mangle]
+pub extern "C" fn iswupper(wc: wint_t) -> c_int {
+    c_int::from(towlower(wc) != wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswxdigit(wc: wint_t) -> c_int {
+    let lower = wc | 0x20;
+    c_int::from(iswdigit(wc) != 0 || (lower >= 'a' as wint_t && lower <= 'f' as wint_t))
+}
+
+/// The space separators, except the no-break spaces, which are not spaces for
+/// iswspace
+fn is_space_separator(wc: wint_t) -> bool {
+    match wc {
+        0x20 | 0x1680 | 0x2000..=0x2006 | 0x2008..=0x200A | 0x205F | 0x3000 => true,
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn towctrans(wc: wint_t, desc: wctrans_t) -> wint_t {
+    if desc == WCTRANS_TOLOWER {
+        towlower(wc)
+    } else if desc == WCTRANS_TOUPPER {
+        towupper(wc)
+    } else {
+        wc
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn towlower(wc: wint_t) -> wint_t {
     casemap(wc, 0)
@@ -15,3 +149,31 @@ pub extern "C" fn towlower(wc: wint_t) -> wint_t {
 pub extern "C" fn towupper(wc: wint_t) -> wint_t {
     casemap(wc, 1)
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn wctrans(property: *const c_char) -> wctrans_t {
+    match CStr::from_ptr(property).to_bytes() {
+        b"tolower" => WCTRANS_TOLOWER,
+        b"toupper" => WCTRANS_TOUPPER,
+        _ => ptr::null(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wctype(property: *const c_char) -> wctype_t {
+    match CStr::from_ptr(property).to_bytes() {
+        b"alnum" => WCTYPE_ALNUM,
+        b"alpha" => WCTYPE_ALPHA,
+        b"blank" => WCTYPE_BLANK,
+        b"cntrl" => WCTYPE_CNTRL,
+        b"digit" => WCTYPE_DIGIT,
+        b"graph" => WCTYPE_GRAPH,
+        b"lower" => WCTYPE_LOWER,
+        b"print" => WCTYPE_PRINT,
+        b"punct" => WCTYPE_PUNCT,
+        b"space" => WCTYPE_SPACE,
+        b"upper" => WCTYPE_UPPER,
+        b"xdigit" => WCTYPE_XDIGIT,
+        _ => 0,
+    }
+}
@@ -1,5 +1,5 @@
-use super::linker::{Linker, Symbol, DSO};
-use alloc::boxed::Box;
+use super::linker::{AddrInfo, Linker, ObjectInfo, Symbol, DSO};
+use alloc::{boxed::Box, vec::Vec};
 use goblin::error::Result;
 
 pub struct LinkerCallbacks {
@@ -8,8 +8,12 @@ pub struct LinkerCallbacks {
     pub link:
         Box<dyn Fn(&mut Linker, Option<&str>, Option<DSO>, Option<usize>) -> Result<Option<usize>>>,
     pub get_sym: Box<dyn Fn(&Linker, &str, Option<usize>) -> Option<Symbol>>,
+    pub get_next_sym: Box<dyn Fn(&Linker, &str, usize) -> Option<Symbol>>,
+    pub addr_info: Box<dyn Fn(&Linker, usize) -> Option<AddrInfo<'_>>>,
+    pub objects_info: Box<dyn Fn(&Linker) -> Vec<ObjectInfo<'_>>>,
     pub run_init: Box<dyn Fn(&Linker, Option<usize>) -> Result<()>>,
     pub run_fini: Box<dyn Fn(&Linker, Option<usize>) -> Result<()>>,
+    pub mappings: Box<dyn Fn(&Linker, usize) -> Vec<(usize, usize)>>,
 }
 
 impl LinkerCallbacks {
@@ -19,8 +23,12 @@ impl LinkerCallbacks {
             load_library: Box::new(load_library),
             link: Box::new(link),
             get_sym: Box::new(get_sym),
+            get_next_sym: Box::new(get_next_sym),
+            addr_info: Box::new(addr_info),
+            objects_info: Box::new(objects_info),
             run_init: Box::new(run_init),
             run_fini: Box::new(run_fini),
+            mappings: Box::new(mappings),
         }
     }
 }
@@ -45,9 +53,21 @@ fn link(
 fn get_sym(linker: &Linker, name: &str, libspace: Option<usize>) -> Option<Symbol> {
     linker.get_sym(name, libspace)
 }
+fn get_next_sym(linker: &Linker, name: &str, caller: usize) -> Option<Symbol> {
+    linker.get_next_sym(name, caller)
+}
+fn addr_info(linker: &Linker, addr: usize) -> Option<AddrInfo<'_>> {
+    linker.addr_info(addr)
+}
+fn objects_info(linker: &Linker) -> Vec<ObjectInfo<'_>> {
+    linker.objects_info()
+}
 fn run_init(linker: &Linker, libspace: Option<usize>) -> Result<()> {
     linker.run_init(libspace)
 }
 fn run_fini(linker: &Linker, libspace: Option<usize>) -> Result<()> {
     linker.run_fini(libspace)
 }
+fn mappings(linker: &Linker, libspace: usize) -> Vec<(usize, usize)> {
+    linker.mappings(libspace)
+}
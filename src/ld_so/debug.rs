@@ -62,6 +62,29 @@ impl RTLDDebug {
         }
         return;
     }
+    /// Unlink and free the entry of the object at `l_addr`
+    pub fn remove(&mut self, l_addr: usize) {
+        let mut map = self.r_map;
+        while !map.is_null() {
+            unsafe {
+                if (*map).l_addr == l_addr {
+                    let (prev, next) = ((*map).l_prev, (*map).l_next);
+                    if prev.is_null() {
+                        self.r_map = next;
+                    } else {
+                        (*prev).l_next = next;
+                    }
+                    if !next.is_null() {
+                        (*next).l_prev = prev;
+                    }
+                    drop(CString::from_raw((*map).l_name as *mut c_char));
+                    drop(Box::from_raw(map));
+                    return;
+                }
+                map = (*map).l_next;
+            }
+        }
+    }
 }
 
 #[repr(C)]
@@ -0,0 +1,40 @@
+//! relibc's export table: the C functions of relibc, by name, in a layout
+//! that stays the same across relibc versions. The dynamic linker registers
+//! the table of the libc it loads into the program's globals, so that
+//! `dlsym(RTLD_DEFAULT, name)` finds them.
+
+use crate::platform::types::{c_char, c_void};
+
+/// Version of the layout of `ExportTable` and `Export`, to be incremented
+/// whenever either changes
+pub const EXPORT_TABLE_VERSION: u32 = 1;
+
+/// Name of the symbol of the export table
+pub const EXPORT_TABLE_SYMBOL: &str = "__relibc_exports";
+
+#[repr(C)]
+pub struct Export {
+    pub name: *const c_char,
+    pub addr: *const c_void,
+}
+
+unsafe impl Sync for Export {}
+
+#[repr(C)]
+pub struct ExportTable {
+    pub version: u32,
+    pub len: usize,
+    pub entries: *const Export,
+}
+
+unsafe impl Sync for ExportTable {}
+
+// ENTRIES, sorted by name, as generated by build.rs
+include!(concat!(env!("OUT_DIR"), "/exports.rs"));
+
+#[no_mangle]
+pub static __relibc_exports: ExportTable = ExportTable {
+    version: EXPORT_TABLE_VERSION,
+    len: ENTRIES.len(),
+    entries: ENTRIES.as_ptr(),
+};
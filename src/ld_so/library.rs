@@ -1,7 +1,8 @@
 use super::linker::Symbol;
+use crate::c_str::CString;
 use alloc::{
     boxed::Box,
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     string::String,
     vec::Vec,
 };
@@ -21,6 +22,13 @@ impl DepTree {
     }
 }
 
+/// A symbol definition, as found by address
+pub struct SymbolDef {
+    pub addr: usize,
+    pub size: usize,
+    pub name: CString,
+}
+
 /// Use to represnt a library as well as all th symbols that is loaded withen it.
 #[derive(Default)]
 pub struct Library {
@@ -37,6 +45,10 @@ pub struct Library {
     /// A set used to detect circular dependencies in the Linker::load function
     pub cir_dep: BTreeSet<String>,
     pub runpath: Option<String>,
+    /// Paths the objects were loaded from
+    pub paths: BTreeMap<String, CString>,
+    /// Definitions of each mapped object, sorted by address
+    pub symbols_by_addr: BTreeMap<String, Vec<SymbolDef>>,
 }
 impl Library {
     pub fn new() -> Library {
@@ -51,4 +63,30 @@ impl Library {
             None
         }
     }
+    /// Names of the loaded objects in symbol search order: a breadth first
+    /// walk of the dependency tree, so LD_PRELOAD libraries (the first
+    /// dependencies of the program) come right after the program itself
+    pub fn load_order(&self) -> Vec<String> {
+        let mut order = Vec::new();
+        let mut queue = VecDeque::new();
+        queue.push_back(&self.dep_tree);
+        while let Some(node) = queue.pop_front() {
+            if !node.name.is_empty() && !order.contains(&node.name) {
+                order.push(node.name.clone());
+            }
+            queue.extend(node.deps.iter());
+        }
+        order
+    }
+    /// Find the object whose memory contains `addr`
+    pub fn object_at(&self, addr: usize) -> Option<&str> {
+        self.mmaps.iter().find_map(|(name, (_, mmap))| {
+            let start = mmap.as_ptr() as usize;
+            if addr >= start && addr < start + mmap.len() {
+                Some(name.as_str())
+            } else {
+                None
+            }
+        })
+    }
 }
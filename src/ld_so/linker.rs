@@ -7,6 +7,7 @@ use alloc::{
 };
 use core::{
     cell::RefCell,
+    iter,
     mem::{size_of, swap, transmute},
     ptr, slice,
 };
@@ -15,13 +16,13 @@ use goblin::{
         header::ET_DYN,
         program_header,
         r#dyn::{Dyn, DT_DEBUG, DT_RUNPATH},
-        reloc, sym, Elf,
+        reloc, section_header, sym, Elf,
     },
     error::{Error, Result},
 };
 
 use crate::{
-    c_str::CString,
+    c_str::{CStr, CString},
     fs::File,
     header::{errno::STR_ERROR, fcntl, sys_mman, unistd},
     io::Read,
@@ -32,7 +33,8 @@ use super::{
     access::accessible,
     callbacks::LinkerCallbacks,
     debug::{RTLDDebug, RTLDState, _dl_debug_state, _r_debug},
-    library::{DepTree, Library},
+    exports::{ExportTable, EXPORT_TABLE_SYMBOL, EXPORT_TABLE_VERSION},
+    library::{DepTree, Library, SymbolDef},
     tcb::{Master, Tcb},
     PAGE_SIZE,
 };
@@ -53,6 +55,7 @@ pub struct Symbol {
     pub value: usize,
     pub base: usize,
     pub size: usize,
+    pub sym_type: u8,
 }
 impl Symbol {
     pub fn as_ptr(self) -> *mut c_void {
@@ -60,11 +63,30 @@ impl Symbol {
     }
 }
 
+/// The object containing an address, and the symbol of that object whose
+/// definition contains it, if any
+pub struct AddrInfo<'a> {
+    pub path: &'a CStr,
+    pub base: usize,
+    pub symbol: Option<&'a SymbolDef>,
+}
+
+/// A loaded object and where its program headers are, as for
+/// `dl_iterate_phdr`
+pub struct ObjectInfo<'a> {
+    pub path: &'a CStr,
+    pub base: usize,
+    pub phdr: usize,
+    pub phnum: usize,
+}
+
 pub struct Linker {
     // Used by load
     /// Library path to search when loading library by name
     default_library_path: String,
     ld_library_path: Option<String>,
+    /// Libraries to load before the dependencies of the program
+    ld_preload: Option<String>,
     root: Library,
     verbose: bool,
     tls_index_offset: usize,
@@ -73,18 +95,30 @@ pub struct Linker {
     pub cbs: Rc<RefCell<LinkerCallbacks>>,
 }
 
-const root_id: usize = 1;
+const ROOT_ID: usize = 1;
+
+/// What a link attempt has done so far, for `link_or_unmap` to undo
+#[derive(Default)]
+struct LinkUndo {
+    /// Regions mapped for the objects
+    mapped: Vec<(usize, usize)>,
+    /// `l_addr` of the entries added to `_r_debug`
+    link_maps: Vec<usize>,
+    /// The TCB allocated for the program, until it is activated
+    tcb: Option<*mut Tcb>,
+}
 
 impl Linker {
-    pub fn new(ld_library_path: Option<String>, verbose: bool) -> Self {
+    pub fn new(ld_library_path: Option<String>, ld_preload: Option<String>, verbose: bool) -> Self {
         Self {
             default_library_path: "/lib".to_string(),
             ld_library_path: ld_library_path,
+            ld_preload: ld_preload,
             root: Library::new(),
             verbose,
             tls_index_offset: 0,
             lib_spaces: BTreeMap::new(),
-            counter: root_id + 1,
+            counter: ROOT_ID + 1,
             cbs: Rc::new(RefCell::new(LinkerCallbacks::new())),
         }
     }
@@ -92,6 +126,18 @@ impl Linker {
         let mut lib: Library = Library::new();
         swap(&mut lib, &mut self.root);
         lib.dep_tree = self.load_recursive(name, path, &mut lib)?;
+        if let Some(ld_preload) = self.ld_preload.clone() {
+            // Preloaded libraries go first in the dependency tree, so their
+            // symbols take precedence over those of the other dependencies
+            let names = ld_preload
+                .split(|c| c == ' ' || c == ':')
+                .filter(|name| !name.is_empty());
+            for (i, name) in names.enumerate() {
+                if let Some(dep) = self._load_library(name, &mut lib)? {
+                    lib.dep_tree.deps.insert(i, dep);
+                }
+            }
+        }
         swap(&mut lib, &mut self.root);
         if self.verbose {
             println!("Dep tree: {:#?}", self.root.dep_tree);
@@ -105,6 +151,22 @@ impl Linker {
             }
         }
     }
+
+    /// Address ranges the objects of a library space are mapped at
+    pub fn mappings(&self, libspace: usize) -> Vec<(usize, usize)> {
+        match self.lib_spaces.get(&libspace) {
+            Some(lib) => lib
+                .mmaps
+                .values()
+                .map(|(_, mmap)| {
+                    let start = mmap.as_ptr() as usize;
+                    (start, start + mmap.len())
+                })
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
     fn load_recursive(&mut self, name: &str, path: &str, lib: &mut Library) -> Result<DepTree> {
         if self.verbose {
             println!("load {}: {}", name, path);
@@ -130,7 +192,7 @@ impl Linker {
             file.read_to_end(&mut data)
                 .map_err(|err| Error::Malformed(format!("failed to read '{}': {}", path, err)))?;
         }
-        deps.deps = self.load_data(name, data.into_boxed_slice(), lib)?;
+        deps.deps = self.load_data(name, path_c, data.into_boxed_slice(), lib)?;
         lib.cir_dep.remove(name);
         Ok(deps)
     }
@@ -138,6 +200,7 @@ impl Linker {
     fn load_data(
         &mut self,
         name: &str,
+        path: CString,
         data: Box<[u8]>,
         lib: &mut Library,
     ) -> Result<Vec<DepTree>> {
@@ -173,6 +236,7 @@ impl Linker {
         };
         if !lib.objects.contains_key(key) {
             lib.objects.insert(key.to_string(), data);
+            lib.paths.insert(key.to_string(), path);
         }
         return Ok(deps);
     }
@@ -181,13 +245,15 @@ impl Linker {
         match name {
             Some(name) => {
                 let mut lib = Library::new();
-                self._load_library(name, &mut lib)?;
+                if let Some(dep_tree) = self._load_library(name, &mut lib)? {
+                    lib.dep_tree = dep_tree;
+                }
                 let ret = self.counter;
                 self.lib_spaces.insert(ret, lib);
                 self.counter += 1;
                 return Ok(ret);
             }
-            None => return Ok(root_id),
+            None => return Ok(ROOT_ID),
         }
     }
     fn _load_library(&mut self, name: &str, lib: &mut Library) -> Result<Option<DepTree>> {
@@ -236,6 +302,11 @@ impl Linker {
             if sym.st_value == 0 || ![sym::STB_GLOBAL, sym::STB_WEAK].contains(&bind) {
                 continue;
             }
+            // Undefined symbols of a non-PIE program can still have a value,
+            // the address of their PLT entry, but they are not definitions
+            if sym.st_shndx == section_header::SHN_UNDEF as usize {
+                continue;
+            }
             let name: String;
             let value: Symbol;
             if let Some(name_res) = elf.dynstrtab.get(sym.st_name) {
@@ -245,12 +316,14 @@ impl Linker {
                         base: mmap.as_ptr() as usize,
                         value: sym.st_value as usize,
                         size: sym.st_size as usize,
+                        sym_type: sym.st_type(),
                     }
                 } else {
                     Symbol {
                         base: 0,
                         value: sym.st_value as usize,
                         size: sym.st_size as usize,
+                        sym_type: sym.st_type(),
                     }
                 };
             } else {
@@ -275,14 +348,209 @@ impl Linker {
         return Ok((globals, weak_syms));
     }
 
+    /// Sort the definitions of an object by address, for `addr_info`
+    fn symbols_by_addr(
+        globals: &BTreeMap<String, Symbol>,
+        weak_syms: &BTreeMap<String, Symbol>,
+    ) -> Vec<SymbolDef> {
+        let mut defs: Vec<SymbolDef> = globals
+            .iter()
+            .chain(weak_syms.iter())
+            .filter_map(|(name, sym)| {
+                Some(SymbolDef {
+                    addr: sym.as_ptr() as usize,
+                    size: sym.size,
+                    name: CString::new(name.as_str()).ok()?,
+                })
+            })
+            .collect();
+        defs.sort_by_key(|def| def.addr);
+        defs
+    }
+
+    /// Register the functions of the export table of the program's libc into
+    /// its globals, for `dlsym(RTLD_DEFAULT, name)`. Definitions already
+    /// there come first in load order, so they are kept.
+    pub fn register_exports(&mut self) {
+        let table = match self.root.get_sym(EXPORT_TABLE_SYMBOL) {
+            Some(sym) => unsafe { &*(sym.as_ptr() as *const ExportTable) },
+            None => return,
+        };
+        if table.version != EXPORT_TABLE_VERSION {
+            if self.verbose {
+                println!("export table version {} not supported", table.version);
+            }
+            return;
+        }
+        let entries = unsafe { slice::from_raw_parts(table.entries, table.len) };
+        for entry in entries {
+            let name = unsafe { CStr::from_ptr(entry.name) };
+            if let Ok(name) = name.to_str() {
+                self.root.globals.entry(name.to_string()).or_insert(Symbol {
+                    value: entry.addr as usize,
+                    base: 0,
+                    size: 0,
+                    sym_type: sym::STT_FUNC,
+                });
+            }
+        }
+    }
+
+    /// Look up a symbol in a library space. Without one, the program and its
+    /// dependencies (including libc) are searched first, then every library
+    /// space in the order it was loaded, as for `dlsym(RTLD_DEFAULT, name)`.
     pub fn get_sym(&self, name: &str, libspace: Option<usize>) -> Option<Symbol> {
         match libspace {
-            None | Some(root_id) => self.root.get_sym(name),
+            None => self.root.get_sym(name).or_else(|| {
+                self.lib_spaces
+                    .values()
+                    .filter_map(|lib| lib.get_sym(name))
+                    .next()
+            }),
+            Some(ROOT_ID) => self.root.get_sym(name),
             Some(id) => {
                 let lib = self.lib_spaces.get(&id)?;
-                lib.get_sym(name)
+                // Dependencies already loaded with the program, such as libc,
+                // are not loaded again into the library space
+                lib.get_sym(name).or_else(|| self.root.get_sym(name))
+            }
+        }
+    }
+
+    /// Look up the next definition of a symbol after the object containing
+    /// `caller`, as for `dlsym(RTLD_NEXT, name)`. Objects are searched in load
+    /// order, and a library space continues into the program's objects.
+    pub fn get_next_sym(&self, name: &str, caller: usize) -> Option<Symbol> {
+        let (lib, caller_name) = iter::once(&self.root)
+            .chain(self.lib_spaces.values())
+            .find_map(|lib| Some((lib, lib.object_at(caller)?)))?;
+
+        let mut order: Vec<(&Library, String)> = lib
+            .load_order()
+            .into_iter()
+            .map(|obj_name| (lib, obj_name))
+            .collect();
+        if !ptr::eq(lib, &self.root) {
+            order.extend(
+                self.root
+                    .load_order()
+                    .into_iter()
+                    .map(|obj_name| (&self.root, obj_name)),
+            );
+        }
+        let start = order
+            .iter()
+            .position(|(_, obj_name)| obj_name == caller_name)?
+            + 1;
+
+        for (lib, obj_name) in order[start..].iter() {
+            // Objects shared with the program are only mapped there
+            let (_, mmap) = match lib.mmaps.get(obj_name) {
+                Some(some) => some,
+                None => continue,
+            };
+            let elf = Elf::parse(lib.objects.get(obj_name)?).ok()?;
+            let (globals, weak_syms) = Linker::collect_syms(&elf, mmap, false).ok()?;
+            if let Some(sym) = globals.get(name).or_else(|| weak_syms.get(name)) {
+                return Some(*sym);
+            }
+        }
+        None
+    }
+
+    /// Look up the object containing `addr`, and the nearest symbol before it
+    /// whose definition contains `addr`, as for `dladdr`
+    pub fn addr_info(&self, addr: usize) -> Option<AddrInfo> {
+        let (lib, obj_name) = iter::once(&self.root)
+            .chain(self.lib_spaces.values())
+            .find_map(|lib| Some((lib, lib.object_at(addr)?)))?;
+        let (_, mmap) = lib.mmaps.get(obj_name)?;
+        let path = lib.paths.get(obj_name)?;
+
+        let symbol = lib.symbols_by_addr.get(obj_name).and_then(|defs| {
+            let i = match defs.binary_search_by_key(&addr, |def| def.addr) {
+                Ok(i) => i,
+                Err(0) => return None,
+                Err(i) => i - 1,
+            };
+            // Symbols without a size only match their own address
+            let def = &defs[i];
+            if addr - def.addr < def.size.max(1) {
+                Some(def)
+            } else {
+                None
+            }
+        });
+
+        Some(AddrInfo {
+            path: path.as_c_str(),
+            base: mmap.as_ptr() as usize,
+            symbol,
+        })
+    }
+
+    /// Describe every mapped object, the program and its dependencies first,
+    /// then each library space in the order it was loaded
+    pub fn objects_info(&self) -> Vec<ObjectInfo> {
+        let mut infos = Vec::new();
+        for lib in iter::once(&self.root).chain(self.lib_spaces.values()) {
+            // Objects keyed by soname may be missing from the load order
+            let mut names = lib.load_order();
+            let unordered: Vec<String> = lib
+                .mmaps
+                .keys()
+                .filter(|name| !names.contains(name))
+                .cloned()
+                .collect();
+            names.extend(unordered);
+
+            for obj_name in names.iter() {
+                // Objects shared with the program are only mapped there
+                let (_, mmap) = match lib.mmaps.get(obj_name) {
+                    Some(some) => some,
+                    None => continue,
+                };
+                let (path, data) = match (lib.paths.get(obj_name), lib.objects.get(obj_name)) {
+                    (Some(path), Some(data)) => (path, data),
+                    _ => continue,
+                };
+                let elf = match Elf::parse(data) {
+                    Ok(elf) => elf,
+                    Err(_) => continue,
+                };
+                let base = if is_pie_enabled(&elf) {
+                    mmap.as_ptr() as usize
+                } else {
+                    0
+                };
+
+                // Point at the loaded copy of the headers, or at the file data
+                // if no segment loads them
+                let phoff = elf.header.e_phoff as usize;
+                let phdr = elf
+                    .program_headers
+                    .iter()
+                    .find_map(|ph| match ph.p_type {
+                        program_header::PT_PHDR => Some(base + ph.p_vaddr as usize),
+                        program_header::PT_LOAD
+                            if phoff >= ph.p_offset as usize
+                                && phoff < (ph.p_offset + ph.p_filesz) as usize =>
+                        {
+                            Some(base + ph.p_vaddr as usize + phoff - ph.p_offset as usize)
+                        }
+                        _ => None,
+                    })
+                    .unwrap_or(data.as_ptr() as usize + phoff);
+
+                infos.push(ObjectInfo {
+                    path: path.as_c_str(),
+                    base,
+                    phdr,
+                    phnum: elf.program_headers.len(),
+                });
             }
         }
+        infos
     }
 
     pub fn run_init(&self, libspace: Option<usize>) -> Result<()> {
@@ -297,11 +565,12 @@ impl Linker {
 
     pub fn run_fini(&self, libspace: Option<usize>) -> Result<()> {
         match libspace {
-            Some(root_id) => return Ok(()),
-            Some(id) => {
-                let lib = self.lib_spaces.get(&id).unwrap();
-                self.run_tree(&lib, &lib.dep_tree, ".fini_array")
-            }
+            // The program is only finalized at exit
+            Some(ROOT_ID) => Ok(()),
+            Some(id) => match self.lib_spaces.get(&id) {
+                Some(lib) => self.run_tree(&lib, &lib.dep_tree, ".fini_array"),
+                None => Err(Error::Malformed(format!("invalid handle {}", id))),
+            },
             None => {
                 //TODO we first need to deinitialize all the loaded libraries first!
                 self.run_tree(&self.root, &self.root.dep_tree, ".fini_array")
@@ -352,25 +621,76 @@ impl Linker {
         match libspace {
             Some(id) => {
                 let mut lib = self.lib_spaces.remove(&id).unwrap();
-                let res = self._link(primary_opt, dso, &mut lib);
+                let res = self.link_or_unmap(primary_opt, dso, &mut lib);
                 self.lib_spaces.insert(id, lib);
                 res
             }
             None => {
                 let mut lib = Library::new();
                 swap(&mut lib, &mut self.root);
-                let res = self._link(primary_opt, dso, &mut lib);
+                let res = self.link_or_unmap(primary_opt, dso, &mut lib);
                 swap(&mut lib, &mut self.root);
                 res
             }
         }
     }
 
-    pub fn _link(
+    /// Link `lib`, undoing every step of this attempt if it fails, so that a
+    /// failed link (and a retrying dlopen caller) leaks no address space and
+    /// leaves no stale entries for debuggers
+    fn link_or_unmap(
+        &mut self,
+        primary_opt: Option<&str>,
+        dso: Option<DSO>,
+        lib: &mut Library,
+    ) -> Result<Option<usize>> {
+        let old_mmaps: BTreeSet<String> = lib.mmaps.keys().cloned().collect();
+        let old_globals = lib.globals.clone();
+        let old_weak_syms = lib.weak_syms.clone();
+        let old_tls_index_offset = self.tls_index_offset;
+
+        let mut undo = LinkUndo::default();
+        let res = self._link(primary_opt, dso, lib, &mut undo);
+        if res.is_err() {
+            if let Some(tcb) = undo.tcb {
+                unsafe { (*tcb).unmap() };
+            }
+            for l_addr in undo.link_maps {
+                unsafe { _r_debug.remove(l_addr) };
+            }
+            for (addr, len) in undo.mapped {
+                if self.verbose {
+                    println!("munmap({:#x}, {})", addr, len);
+                }
+                unsafe { sys_mman::munmap(addr as *mut c_void, len) };
+            }
+            let new_mmaps: Vec<String> = lib
+                .mmaps
+                .keys()
+                .filter(|name| !old_mmaps.contains(*name))
+                .cloned()
+                .collect();
+            for name in new_mmaps {
+                lib.mmaps.remove(&name);
+                lib.symbols_by_addr.remove(&name);
+            }
+            lib.globals = old_globals;
+            lib.weak_syms = old_weak_syms;
+            self.tls_index_offset = old_tls_index_offset;
+            unsafe { _r_debug.state = RTLDState::RT_CONSISTENT };
+            _dl_debug_state();
+        }
+        res
+    }
+
+    /// Everything done here that outlives a failure is recorded in `undo`, for
+    /// `link_or_unmap` to undo if linking fails
+    fn _link(
         &mut self,
         primary_opt: Option<&str>,
         dso: Option<DSO>,
         lib: &mut Library,
+        undo: &mut LinkUndo,
     ) -> Result<Option<usize>> {
         unsafe { _r_debug.state = RTLDState::RT_ADD };
         _dl_debug_state();
@@ -389,6 +709,7 @@ impl Linker {
         };
 
         // Load all ELF files into memory and find all globals
+        let mut syms = BTreeMap::new();
         let mut tls_primary = 0;
         let mut tls_size = 0;
         for (elf_name, elf) in elfs.iter() {
@@ -514,6 +835,7 @@ impl Linker {
                                     elf_name, STR_ERROR[errno as usize]
                                 )));
                             }
+                            undo.mapped.push((ptr as usize, addr + vaddr - start));
                             if start as *mut c_void != ptr::null_mut() {
                                 assert_eq!(
                                     ptr, start as *mut c_void,
@@ -529,6 +851,7 @@ impl Linker {
                         sys_mman::PROT_READ | sys_mman::PROT_WRITE,
                     );
                     _r_debug.insert_first(addr as usize, &elf_name, addr + l_ld as usize);
+                    undo.link_maps.push(addr as usize);
                     (
                         addr as usize,
                         slice::from_raw_parts_mut(addr as *mut u8, size),
@@ -560,6 +883,7 @@ impl Linker {
                             elf_name, STR_ERROR[errno as usize]
                         )));
                     }
+                    undo.mapped.push((ptr as usize, size));
                     if start as *mut c_void != ptr::null_mut() {
                         assert_eq!(
                             ptr, start as *mut c_void,
@@ -568,6 +892,7 @@ impl Linker {
                     }
                     ptr::write_bytes(ptr as *mut u8, 0, size);
                     _r_debug.insert(ptr as usize, &elf_name, ptr as usize + l_ld as usize);
+                    undo.link_maps.push(ptr as usize);
                     (start, slice::from_raw_parts_mut(ptr as *mut u8, size))
                 }
             };
@@ -575,14 +900,44 @@ impl Linker {
                 println!("  mmap {:p}, {:#x}", mmap.1.as_mut_ptr(), mmap.1.len());
             }
             let (globals, weak_syms) = Linker::collect_syms(&elf, &mmap.1, self.verbose)?;
-            lib.globals.extend(globals.into_iter());
-            lib.weak_syms.extend(weak_syms.into_iter());
+            lib.symbols_by_addr.insert(
+                elf_name.to_string(),
+                Linker::symbols_by_addr(&globals, &weak_syms),
+            );
+            syms.insert(*elf_name, (globals, weak_syms));
             lib.mmaps.insert(elf_name.to_string(), mmap);
         }
 
+        // The first definition in load order wins, so the program and
+        // preloaded libraries can interpose on symbols of later objects
+        let mut sym_order: Vec<&str> = Vec::new();
+        for obj_name in lib.load_order() {
+            if let Some((&obj_name, _)) = syms.get_key_value(obj_name.as_str()) {
+                sym_order.push(obj_name);
+            }
+        }
+        // Objects missing from the dependency tree, as when it names them
+        // differently from their soname, come last
+        for &obj_name in syms.keys() {
+            if !sym_order.contains(&obj_name) {
+                sym_order.push(obj_name);
+            }
+        }
+        for obj_name in sym_order.iter() {
+            let (globals, weak_syms) = &syms[obj_name];
+            for (name, sym) in globals.iter() {
+                lib.globals.entry(name.clone()).or_insert(*sym);
+            }
+            for (name, sym) in weak_syms.iter() {
+                lib.weak_syms.entry(name.clone()).or_insert(*sym);
+            }
+        }
+
         // Allocate TLS
         let mut tcb_opt = if primary_opt.is_some() {
-            Some(unsafe { Tcb::new(tls_size)? })
+            let tcb = unsafe { Tcb::new(tls_size)? };
+            undo.tcb = Some(&mut *tcb as *mut Tcb);
+            Some(tcb)
         } else {
             None
         };
@@ -732,6 +1087,8 @@ impl Linker {
         }
 
         // Perform relocations, and protect pages
+        let mut copies = Vec::new();
+        let mut ifuncs = BTreeMap::new();
         for (elf_name, elf) in elfs.iter() {
             if skip_list.contains(elf_name) {
                 continue;
@@ -772,6 +1129,11 @@ impl Linker {
                     .as_ref()
                     .map(|sym| sym.as_ptr() as usize)
                     .unwrap_or(0);
+                // The value of an IFUNC symbol is its resolver, which has to be
+                // called to get the address
+                let ifunc = symbol
+                    .as_ref()
+                    .map_or(false, |sym| sym.sym_type == sym::STT_GNU_IFUNC);
 
                 let a = rel.r_addend.unwrap_or(0) as usize;
 
@@ -802,7 +1164,14 @@ impl Linker {
 
                 match rel.r_type {
                     reloc::R_X86_64_64 => {
-                        set_u64((s + a) as u64);
+                        if ifunc {
+                            ifuncs
+                                .entry(*elf_name)
+                                .or_insert_with(Vec::new)
+                                .push((ptr, s, a));
+                        } else {
+                            set_u64((s + a) as u64);
+                        }
                     }
                     reloc::R_X86_64_DTPMOD64 => {
                         set_u64(tm as u64);
@@ -815,7 +1184,14 @@ impl Linker {
                         }
                     }
                     reloc::R_X86_64_GLOB_DAT | reloc::R_X86_64_JUMP_SLOT => {
-                        set_u64(s as u64);
+                        if ifunc {
+                            ifuncs
+                                .entry(*elf_name)
+                                .or_insert_with(Vec::new)
+                                .push((ptr, s, 0));
+                        } else {
+                            set_u64(s as u64);
+                        }
                     }
                     reloc::R_X86_64_RELATIVE => {
                         set_u64((b + a) as u64);
@@ -823,14 +1199,42 @@ impl Linker {
                     reloc::R_X86_64_TPOFF64 => {
                         set_u64((s + a).wrapping_sub(t) as u64);
                     }
-                    reloc::R_X86_64_IRELATIVE => (), // Handled below
-                    reloc::R_X86_64_COPY => unsafe {
-                        // TODO: Make this work
-                        let sym = symbol
-                            .as_ref()
-                            .expect("R_X86_64_COPY called without valid symbol");
-                        ptr::copy_nonoverlapping(sym.as_ptr() as *const u8, ptr, sym.size as usize);
-                    },
+                    reloc::R_X86_64_IRELATIVE => {
+                        let resolver = if is_pie_enabled(&elf) { b + a } else { a };
+                        ifuncs
+                            .entry(*elf_name)
+                            .or_insert_with(Vec::new)
+                            .push((ptr, resolver, 0));
+                    }
+                    reloc::R_X86_64_COPY => {
+                        // The symbol resolves to the copy in this object, which
+                        // all other objects use too. The data is copied from the
+                        // definition found when this object is left out, once
+                        // that has been relocated.
+                        let sym = elf.dynsyms.get(rel.r_sym).ok_or(Error::Malformed(format!(
+                            "missing symbol for relocation {:?}",
+                            rel
+                        )))?;
+                        let name =
+                            elf.dynstrtab
+                                .get(sym.st_name)
+                                .ok_or(Error::Malformed(format!(
+                                    "missing name for symbol {:?}",
+                                    sym
+                                )))??;
+                        let source = sym_order
+                            .iter()
+                            .filter(|obj_name| *obj_name != elf_name)
+                            .find_map(|obj_name| {
+                                let (globals, weak_syms) = &syms[obj_name];
+                                globals.get(name).or_else(|| weak_syms.get(name))
+                            })
+                            .ok_or(Error::Malformed(format!(
+                                "missing definition for copy relocation of {}",
+                                name
+                            )))?;
+                        copies.push((source.as_ptr() as *const u8, ptr, sym.st_size as usize));
+                    }
                     _ => {
                         panic!(
                             "    {} unsupported",
@@ -922,14 +1326,38 @@ impl Linker {
             }
         }
 
+        // Perform copy relocations
+        for (src, dst, size) in copies {
+            if self.verbose {
+                println!("copy {:p} to {:p}, {:#x}", src, dst, size);
+            }
+            unsafe { ptr::copy_nonoverlapping(src, dst, size) };
+        }
+
         // Activate TLS
         if let Some(ref mut tcb) = tcb_opt {
             unsafe {
                 tcb.activate();
             }
+            undo.tcb = None;
+        }
+
+        // Perform indirect relocations (necessary evil). Resolvers only run
+        // once every other relocation is done, so they can rely on them, and
+        // in reverse load order, so dependencies are resolved first.
+        for obj_name in sym_order.iter().rev() {
+            for (ptr, resolver, addend) in ifuncs.remove(obj_name).unwrap_or_default() {
+                if self.verbose {
+                    println!("ifunc {} {:#x} to {:p}", obj_name, resolver, ptr);
+                }
+                unsafe {
+                    let f: unsafe extern "C" fn() -> usize = transmute(resolver);
+                    *(ptr as *mut u64) = f().wrapping_add(addend) as u64;
+                }
+            }
         }
 
-        // Perform indirect relocations (necessary evil), gather entry point
+        // Gather entry point
         let mut entry_opt = None;
         for (elf_name, elf) in elfs.iter() {
             if skip_list.contains(elf_name) {
@@ -950,38 +1378,6 @@ impl Linker {
                 }
             }
 
-            // Relocate
-            for rel in elf
-                .dynrelas
-                .iter()
-                .chain(elf.dynrels.iter())
-                .chain(elf.pltrelocs.iter())
-            {
-                // println!("  rel {}: {:x?}",
-                //     reloc::r_to_str(rel.r_type, elf.header.e_machine),
-                //     rel
-                // );
-
-                let a = rel.r_addend.unwrap_or(0) as usize;
-
-                let b = mmap.as_mut_ptr() as usize;
-
-                let ptr = unsafe { mmap.as_mut_ptr().add(rel.r_offset as usize) };
-
-                let set_u64 = |value| {
-                    // println!("    set_u64 {:#x}", value);
-                    unsafe {
-                        *(ptr as *mut u64) = value;
-                    }
-                };
-
-                if rel.r_type == reloc::R_X86_64_IRELATIVE {
-                    unsafe {
-                        let f: unsafe extern "C" fn() -> u64 = transmute(b + a);
-                        set_u64(f());
-                    }
-                }
-            }
             // Protect pages
             for ph in elf.program_headers.iter() {
                 if let program_header::PT_LOAD = ph.p_type {
@@ -9,6 +9,7 @@ pub const PAGE_SIZE: usize = 4096;
 mod access;
 pub mod callbacks;
 pub mod debug;
+pub mod exports;
 mod library;
 pub mod linker;
 pub mod start;
@@ -23,7 +23,7 @@ use super::{
     linker::{Linker, DSO, PATH_SEP},
     tcb::Tcb,
 };
-use crate::header::sys_auxv::{AT_ENTRY, AT_PHDR};
+use crate::header::sys_auxv::{AT_ENTRY, AT_PHDR, AT_SECURE};
 
 unsafe fn get_argv(mut ptr: *const usize) -> (Vec<String>, *const usize) {
     //traverse the stack and collect argument vector
@@ -183,7 +183,17 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
     }
 
     // Some variables that will be overridden by environment and auxiliary vectors
-    let ld_library_path = envs.get("LD_LIBRARY_PATH").map(|s| s.to_owned());
+    // They are ignored for setuid and setgid programs, which would otherwise
+    // load libraries chosen by whoever runs them
+    let secure = auxv.get(&AT_SECURE).map_or(false, |&secure| secure != 0);
+    let (ld_library_path, ld_preload) = if secure {
+        (None, None)
+    } else {
+        (
+            envs.get("LD_LIBRARY_PATH").map(|s| s.to_owned()),
+            envs.get("LD_PRELOAD").map(|s| s.to_owned()),
+        )
+    };
 
     let name_or_path = if is_manual {
         // ld.so is run directly by user and not via execve() or similar systemcall
@@ -231,7 +241,7 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
         }
         pr
     };
-    let mut linker = Linker::new(ld_library_path, false);
+    let mut linker = Linker::new(ld_library_path, ld_preload, false);
     match linker.load(&path, &path) {
         Ok(()) => (),
         Err(err) => {
@@ -256,6 +266,7 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
             loop {}
         }
     };
+    linker.register_exports();
     if let Err(e) = linker.run_init(None) {
         eprintln!("ld.so: failed to run .init_array");
         unistd::_exit(1);
@@ -2,10 +2,15 @@ use alloc::boxed::Box;
 use core::{mem, ops::Range, ptr, slice};
 use goblin::error::{Error, Result};
 
-use crate::{header::sys_mman, ld_so::linker::Linker, sync::mutex::Mutex};
+use crate::{header::sys_mman, ld_so::linker::Linker, platform::types::pid_t, sync::mutex::Mutex};
 
 use super::PAGE_SIZE;
 
+extern "C" {
+    /// The canary chosen at startup, shared by all threads
+    static __stack_chk_guard: usize;
+}
+
 #[repr(C)]
 pub struct Master {
     /// Pointer to initial data
@@ -41,12 +46,16 @@ pub struct Tcb {
     pub tcb_len: usize,
     /// Pointer to a list of initial TLS data
     pub masters_ptr: *mut Master,
+    /// Stack protector canary, read by compilers from fs:0x28 on x86_64. Must stay at this offset
+    pub stack_guard: usize,
     /// Size of the masters list in bytes (multiple of mem::size_of::<Master>())
     pub masters_len: usize,
     /// Pointer to dynamic linker
     pub linker_ptr: *const Mutex<Linker>,
     /// pointer to rust memory allocator structure
     pub mspace: usize,
+    /// Name set by pthread_setname_np, NUL terminated. Only used on Redox
+    pub name: [u8; 16],
 }
 
 impl Tcb {
@@ -64,9 +73,11 @@ impl Tcb {
                 tcb_ptr,
                 tcb_len: tcb_page.len(),
                 masters_ptr: ptr::null_mut(),
+                stack_guard: __stack_chk_guard,
                 masters_len: 0,
                 linker_ptr: ptr::null(),
                 mspace: 0,
+                name: [0; 16],
             },
         );
 
@@ -84,6 +95,17 @@ impl Tcb {
         }
     }
 
+    /// Get the TCB of another thread in this process - Redox
+    #[cfg(target_os = "redox")]
+    pub unsafe fn of_thread(tid: pid_t) -> Option<&'static mut Self> {
+        let tcb_ptr = Self::os_tcb_addr(tid) as *mut Self;
+        if (*tcb_ptr).tcb_ptr != tcb_ptr || (*tcb_ptr).tcb_len < mem::size_of::<Self>() {
+            None
+        } else {
+            Some(&mut *tcb_ptr)
+        }
+    }
+
     /// A slice for all of the TLS data
     pub unsafe fn tls(&self) -> Option<&'static mut [u8]> {
         if self.tls_end.is_null() || self.tls_len == 0 {
@@ -147,6 +169,14 @@ impl Tcb {
         Self::os_arch_activate(self.tcb_ptr as usize);
     }
 
+    /// Free a TCB that was never activated, along with its TLS and masters
+    pub unsafe fn unmap(&mut self) {
+        if let Some(masters) = self.masters() {
+            drop(Box::from_raw(masters as *mut [Master]));
+        }
+        self.os_unmap();
+    }
+
     /// Mapping with correct flags for TCB and TLS
     unsafe fn map(size: usize) -> Result<&'static mut [u8]> {
         let ptr = sys_mman::mmap(
@@ -173,13 +203,18 @@ impl Tcb {
         Ok(tls_tcb.split_at_mut(size))
     }
 
+    /// OS specific code to unmap a TLS and TCB - Linux
+    #[cfg(target_os = "linux")]
+    unsafe fn os_unmap(&mut self) {
+        let tls_start = self.tls_end.sub(self.tls_len);
+        sys_mman::munmap(tls_start as *mut _, self.tls_len + self.tcb_len);
+    }
+
     /// OS specific code to create a new TLS and TCB - Redox
     #[cfg(target_os = "redox")]
     unsafe fn os_new(size: usize) -> Result<(&'static mut [u8], &'static mut [u8])> {
         use crate::header::unistd;
-        //TODO: better method of finding fs offset
-        let pid = unistd::getpid();
-        let tcb_addr = 0xB000_0000 + pid as usize * PAGE_SIZE;
+        let tcb_addr = Self::os_tcb_addr(unistd::getpid());
         let tls = Self::map(size)?;
         Ok((
             tls,
@@ -188,6 +223,21 @@ impl Tcb {
         ))
     }
 
+    /// OS specific code to unmap a TLS and TCB - Redox
+    #[cfg(target_os = "redox")]
+    unsafe fn os_unmap(&mut self) {
+        // The TCB page is at a fixed address, so only the TLS is unmapped
+        let tls_start = self.tls_end.sub(self.tls_len);
+        sys_mman::munmap(tls_start as *mut _, self.tls_len);
+    }
+
+    /// OS specific code to find the TCB of a thread - Redox
+    #[cfg(target_os = "redox")]
+    fn os_tcb_addr(pid: pid_t) -> usize {
+        //TODO: better method of finding fs offset
+        0xB000_0000 + pid as usize * PAGE_SIZE
+    }
+
     /// Architecture specific code to read a usize from the TCB - x86_64
     #[inline(always)]
     #[cfg(target_arch = "x86_64")]
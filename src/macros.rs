@@ -178,8 +178,12 @@ macro_rules! strto_impl {
         // convert the string to a number
         let num_str = $s.offset(idx);
         let res = match $base {
-            0 => detect_base(num_str)
-                .and_then(|($base, i)| convert_integer(num_str.offset(i), $base)),
+            0 => match detect_base(num_str) {
+                Some((16, _)) => convert_hex(num_str),
+                Some((8, _)) => convert_octal(num_str),
+                Some(_) => convert_integer(num_str, 10),
+                None => None,
+            },
             8 => convert_octal(num_str),
             16 => convert_hex(num_str),
             _ => convert_integer(num_str, $base),
@@ -196,8 +200,9 @@ macro_rules! strto_impl {
         };
         idx += i;
 
+        // the magnitude of the minimum is one more than the maximum
         let overflow = if CHECK_SIGN {
-            overflow || (num as c_long).is_negative()
+            overflow || num > (MAX_VAL as c_ulong).wrapping_add(!positive as c_ulong)
         } else {
             overflow
         };
@@ -228,65 +233,3 @@ macro_rules! strto_impl {
         num
     }};
 }
-#[macro_export]
-macro_rules! strto_float_impl {
-    ($type:ident, $s:expr, $endptr:expr) => {{
-        let mut s = $s;
-        let endptr = $endptr;
-
-        while ctype::isspace(*s as c_int) != 0 {
-            s = s.offset(1);
-        }
-
-        let mut result: $type = 0.0;
-        let mut radix = 10;
-
-        let negative = match *s as u8 {
-            b'-' => {
-                s = s.offset(1);
-                true
-            }
-            b'+' => {
-                s = s.offset(1);
-                false
-            }
-            _ => false,
-        };
-
-        if *s as u8 == b'0' && *s.offset(1) as u8 == b'x' {
-            s = s.offset(2);
-            radix = 16;
-        }
-
-        while let Some(digit) = (*s as u8 as char).to_digit(radix) {
-            result *= radix as $type;
-            result += digit as $type;
-            s = s.offset(1);
-        }
-
-        if *s as u8 == b'.' {
-            s = s.offset(1);
-
-            let mut i = 1.0;
-            while let Some(digit) = (*s as u8 as char).to_digit(radix) {
-                i *= radix as $type;
-                result += digit as $type / i;
-                s = s.offset(1);
-            }
-        }
-
-        if !endptr.is_null() {
-            // This is stupid, but apparently strto* functions want
-            // const input but mut output, yet the man page says
-            // "stores the address of the first invalid character in *endptr"
-            // so obviously it doesn't want us to clone it.
-            *endptr = s as *mut _;
-        }
-
-        if negative {
-            -result
-        } else {
-            result
-        }
-    }};
-}
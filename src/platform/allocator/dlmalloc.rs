@@ -12,6 +12,12 @@ extern "C" {
     fn mspace_memalign(msp: usize, alignment: size_t, bytes: size_t) -> *mut c_void;
     fn mspace_realloc(msp: usize, oldmem: *mut c_void, bytes: size_t) -> *mut c_void;
     fn mspace_free(msp: usize, mem: *mut c_void);
+    #[cfg(feature = "check_bounds")]
+    fn mspace_inspect_all(
+        msp: usize,
+        handler: extern "C" fn(*mut c_void, *mut c_void, size_t, *mut c_void),
+        arg: *mut c_void,
+    );
 //fn dlmalloc(bytes: size_t) -> *mut c_void;
 //fn dlmemalign(alignment: size_t, bytes: size_t) -> *mut c_void;
 //fn dlrealloc(oldmem: *mut c_void, bytes: size_t) -> *mut c_void;
@@ -60,6 +66,36 @@ pub unsafe fn free(ptr: *mut c_void) {
     mspace_free(ALLOCATOR.get_book_keeper(), ptr)
 }
 
+/// Return the usable range of the heap allocation containing `ptr`, if any.
+///
+/// This walks every chunk of the heap, so it is only meant for debugging.
+#[cfg(feature = "check_bounds")]
+pub unsafe fn allocation_containing(ptr: *const c_void) -> Option<(usize, usize)> {
+    struct Search {
+        addr: usize,
+        found: Option<(usize, usize)>,
+    }
+
+    extern "C" fn handler(start: *mut c_void, _end: *mut c_void, used: size_t, arg: *mut c_void) {
+        let search = unsafe { &mut *(arg as *mut Search) };
+        let start = start as usize;
+        if used != 0 && search.addr >= start && search.addr < start + used {
+            search.found = Some((start, start + used));
+        }
+    }
+
+    let msp = ALLOCATOR.get_book_keeper();
+    if msp == 0 {
+        return None;
+    }
+    let mut search = Search {
+        addr: ptr as usize,
+        found: None,
+    };
+    mspace_inspect_all(msp, handler, &mut search as *mut Search as *mut c_void);
+    search.found
+}
+
 pub fn new_mspace() -> usize {
     unsafe { create_mspace(0, 0) }
 }
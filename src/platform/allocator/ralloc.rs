@@ -46,3 +46,10 @@ pub unsafe fn free(ptr: *mut c_void) {
     let _align = *(ptr as *mut u64).offset(1);
     ralloc::free(ptr, size as usize);
 }
+
+/// ralloc keeps no per-allocation metadata that could be searched, so no
+/// pointer is ever known to belong to a heap allocation.
+#[cfg(feature = "check_bounds")]
+pub unsafe fn allocation_containing(_ptr: *const c_void) -> Option<(usize, usize)> {
+    None
+}
@@ -3,6 +3,7 @@ use super::{
     e, Sys,
 };
 use crate::header::{signal::sigset_t, sys_epoll::epoll_event};
+use core::mem;
 
 impl PalEpoll for Sys {
     fn epoll_create1(flags: c_int) -> c_int {
@@ -27,7 +28,8 @@ impl PalEpoll for Sys {
                 events,
                 maxevents,
                 timeout,
-                sigmask
+                sigmask,
+                mem::size_of::<sigset_t>()
             )) as c_int
         }
     }
@@ -1,35 +1,42 @@
-use core::ptr;
+use core::{mem, ptr};
 use core_io::Write;
 
 use super::{errno, types::*, Pal};
 use crate::{
     c_str::CStr,
-    header::{dirent::dirent, signal::SIGCHLD, sys_stat::S_IFIFO},
+    header::{
+        dirent::dirent,
+        fcntl::{AT_EMPTY_PATH, AT_FDCWD, AT_REMOVEDIR, F_GETFD, O_CLOEXEC, O_RDONLY, O_WRONLY},
+        mqueue::{mq_attr, mqd_t},
+        signal::{sigevent, siginfo_t, sigset_t, SIGCHLD},
+        spawn::sched_param,
+        sys_resource::{rlimit, rusage},
+        sys_stat::{stat, S_IFIFO},
+        sys_statvfs::statvfs,
+        sys_time::{timeval, timezone},
+        sys_wait::idtype_t,
+    },
 };
-// use header::sys_resource::rusage;
+// use header::sys_times::tms;
 use crate::header::{
-    sys_resource::rlimit,
-    sys_stat::stat,
-    sys_statvfs::statvfs,
-    sys_time::{timeval, timezone},
+    sys_uio::iovec,
+    sys_utsname::utsname,
+    time::{itimerspec, timespec},
 };
-// use header::sys_times::tms;
-use crate::header::{sys_utsname::utsname, time::timespec};
 
 mod epoll;
 mod ptrace;
 mod signal;
 mod socket;
 
-const AT_FDCWD: c_int = -100;
-const AT_EMPTY_PATH: c_int = 0x1000;
-const AT_REMOVEDIR: c_int = 0x200;
+const ST_VALID: c_long = 0x20;
 
 const SYS_CLONE: usize = 56;
 const CLONE_VM: usize = 0x0100;
 const CLONE_FS: usize = 0x0200;
 const CLONE_FILES: usize = 0x0400;
 const CLONE_SIGHAND: usize = 0x0800;
+const CLONE_VFORK: usize = 0x4000;
 
 #[repr(C)]
 #[derive(Default)]
@@ -59,13 +66,33 @@ pub fn e(sys: usize) -> usize {
     }
 }
 
+/// Like `syscall!`, for the system calls that are cancellation points
+macro_rules! syscall_cp {
+    ($nr:ident, $a:expr, $b:expr) => {
+        syscall_cp!($nr, $a, $b, 0)
+    };
+    ($nr:ident, $a:expr, $b:expr, $c:expr) => {
+        syscall_cp!($nr, $a, $b, $c, 0)
+    };
+    ($nr:ident, $a:expr, $b:expr, $c:expr, $d:expr) => {
+        syscall_cp!($nr, $a, $b, $c, $d, 0)
+    };
+    ($nr:ident, $a:expr, $b:expr, $c:expr, $d:expr, $e:expr) => {
+        super::pte::syscall_cp(
+            sc::nr::$nr,
+            $a as usize,
+            $b as usize,
+            $c as usize,
+            $d as usize,
+            $e as usize,
+            0,
+        )
+    };
+}
+
 pub struct Sys;
 
 impl Sys {
-    // fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
-    //     e(unsafe { syscall!(GETRUSAGE, who, r_usage) }) as c_int
-    // }
-
     pub unsafe fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
         // TODO: Somehow support varargs to syscall??
         e(syscall!(IOCTL, fd, request, out)) as c_int
@@ -109,6 +136,15 @@ impl Pal for Sys {
         e(unsafe { syscall!(CLOCK_GETTIME, clk_id, tp) }) as c_int
     }
 
+    fn clock_nanosleep(
+        clk_id: clockid_t,
+        flags: c_int,
+        rqtp: *const timespec,
+        rmtp: *mut timespec,
+    ) -> c_int {
+        e(unsafe { syscall_cp!(CLOCK_NANOSLEEP, clk_id, flags, rqtp, rmtp) }) as c_int
+    }
+
     fn close(fildes: c_int) -> c_int {
         e(unsafe { syscall!(CLOSE, fildes) }) as c_int
     }
@@ -118,9 +154,24 @@ impl Pal for Sys {
     }
 
     fn dup2(fildes: c_int, fildes2: c_int) -> c_int {
+        if fildes == fildes2 {
+            // dup3 rejects this, but dup2 only checks that fildes is open
+            if e(unsafe { syscall!(FCNTL, fildes, F_GETFD) }) == !0 {
+                return -1;
+            }
+            return fildes;
+        }
         e(unsafe { syscall!(DUP3, fildes, fildes2, 0) }) as c_int
     }
 
+    fn dup3(fildes: c_int, fildes2: c_int, flags: c_int) -> c_int {
+        e(unsafe { syscall!(DUP3, fildes, fildes2, flags) }) as c_int
+    }
+
+    fn eventfd(initval: c_uint, flags: c_int) -> c_int {
+        e(unsafe { syscall!(EVENTFD2, initval, flags) }) as c_int
+    }
+
     unsafe fn execve(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
         e(syscall!(EXECVE, path.as_ptr(), argv, envp)) as c_int
     }
@@ -132,6 +183,14 @@ impl Pal for Sys {
         loop {}
     }
 
+    fn fadvise(fildes: c_int, offset: off_t, len: off_t, advice: c_int) -> c_int {
+        e(unsafe { syscall!(FADVISE64, fildes, offset, len, advice) }) as c_int
+    }
+
+    fn fallocate(fildes: c_int, mode: c_int, offset: off_t, len: off_t) -> c_int {
+        e(unsafe { syscall!(FALLOCATE, fildes, mode, offset, len) }) as c_int
+    }
+
     fn fchdir(fildes: c_int) -> c_int {
         e(unsafe { syscall!(FCHDIR, fildes) }) as c_int
     }
@@ -154,6 +213,10 @@ impl Pal for Sys {
         e(unsafe { syscall!(NEWFSTATAT, fildes, empty_ptr, buf, AT_EMPTY_PATH) }) as c_int
     }
 
+    fn fstatat(dirfd: c_int, path: &CStr, buf: *mut stat, flag: c_int) -> c_int {
+        e(unsafe { syscall!(NEWFSTATAT, dirfd, path.as_ptr(), buf, flag) }) as c_int
+    }
+
     fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int {
         let mut kbuf = linux_statfs::default();
         let kbuf_ptr = &mut kbuf as *mut linux_statfs;
@@ -174,7 +237,8 @@ impl Pal for Sys {
                     (*buf).f_ffree = kbuf.f_ffree;
                     (*buf).f_favail = kbuf.f_ffree;
                     (*buf).f_fsid = kbuf.f_fsid as c_ulong;
-                    (*buf).f_flag = kbuf.f_flags as c_ulong;
+                    // ST_VALID only says that f_flags was filled in
+                    (*buf).f_flag = (kbuf.f_flags & !ST_VALID) as c_ulong;
                     (*buf).f_namemax = kbuf.f_namelen as c_ulong;
                 }
             }
@@ -182,7 +246,7 @@ impl Pal for Sys {
         res
     }
 
-    fn fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
+    fn fcntl(fildes: c_int, cmd: c_int, arg: c_ulong) -> c_int {
         e(unsafe { syscall!(FCNTL, fildes, cmd, arg) }) as c_int
     }
 
@@ -206,16 +270,16 @@ impl Pal for Sys {
         e(unsafe { syscall!(FTRUNCATE, fildes, length) }) as c_int
     }
 
-    fn futex(addr: *mut c_int, op: c_int, val: c_int) -> c_int {
-        unsafe { syscall!(FUTEX, addr, op, val, 0, 0, 0) as c_int }
+    fn futex(addr: *mut c_int, op: c_int, val: c_int, timeout: *const timespec) -> c_int {
+        unsafe { syscall!(FUTEX, addr, op, val, timeout, 0, 0) as c_int }
     }
 
     fn futimens(fd: c_int, times: *const timespec) -> c_int {
         e(unsafe { syscall!(UTIMENSAT, fd, ptr::null::<c_char>(), times, 0) }) as c_int
     }
 
-    fn utimens(path: &CStr, times: *const timespec) -> c_int {
-        e(unsafe { syscall!(UTIMENSAT, AT_FDCWD, path.as_ptr(), times, 0) }) as c_int
+    fn utimensat(dirfd: c_int, path: &CStr, times: *const timespec, flag: c_int) -> c_int {
+        e(unsafe { syscall!(UTIMENSAT, dirfd, path.as_ptr(), times, flag) }) as c_int
     }
 
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
@@ -262,6 +326,10 @@ impl Pal for Sys {
         e(syscall!(GETRLIMIT, resource, rlim)) as c_int
     }
 
+    unsafe fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
+        e(syscall!(GETRUSAGE, who, r_usage)) as c_int
+    }
+
     fn gettid() -> pid_t {
         e(unsafe { syscall!(GETTID) }) as pid_t
     }
@@ -274,6 +342,18 @@ impl Pal for Sys {
         e(unsafe { syscall!(GETUID) }) as uid_t
     }
 
+    fn inotify_add_watch(fd: c_int, path: &CStr, mask: u32) -> c_int {
+        e(unsafe { syscall!(INOTIFY_ADD_WATCH, fd, path.as_ptr(), mask) }) as c_int
+    }
+
+    fn inotify_init1(flags: c_int) -> c_int {
+        e(unsafe { syscall!(INOTIFY_INIT1, flags) }) as c_int
+    }
+
+    fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int {
+        e(unsafe { syscall!(INOTIFY_RM_WATCH, fd, wd) }) as c_int
+    }
+
     fn link(path1: &CStr, path2: &CStr) -> c_int {
         e(unsafe {
             syscall!(
@@ -287,6 +367,10 @@ impl Pal for Sys {
         }) as c_int
     }
 
+    fn linkat(fd1: c_int, path1: &CStr, fd2: c_int, path2: &CStr, flag: c_int) -> c_int {
+        e(unsafe { syscall!(LINKAT, fd1, path1.as_ptr(), fd2, path2.as_ptr(), flag) }) as c_int
+    }
+
     fn lseek(fildes: c_int, offset: off_t, whence: c_int) -> off_t {
         e(unsafe { syscall!(LSEEK, fildes, offset, whence) }) as off_t
     }
@@ -295,10 +379,22 @@ impl Pal for Sys {
         e(unsafe { syscall!(MKDIRAT, AT_FDCWD, path.as_ptr(), mode) }) as c_int
     }
 
+    fn mkdirat(dirfd: c_int, path: &CStr, mode: mode_t) -> c_int {
+        e(unsafe { syscall!(MKDIRAT, dirfd, path.as_ptr(), mode) }) as c_int
+    }
+
     fn mkfifo(path: &CStr, mode: mode_t) -> c_int {
         e(unsafe { syscall!(MKNODAT, AT_FDCWD, path.as_ptr(), mode | S_IFIFO, 0) }) as c_int
     }
 
+    unsafe fn mlock(addr: *const c_void, len: usize) -> c_int {
+        e(syscall!(MLOCK, addr, len)) as c_int
+    }
+
+    fn mlockall(flags: c_int) -> c_int {
+        e(unsafe { syscall!(MLOCKALL, flags) }) as c_int
+    }
+
     unsafe fn mmap(
         addr: *mut c_void,
         len: usize,
@@ -314,26 +410,136 @@ impl Pal for Sys {
         e(syscall!(MPROTECT, addr, len, prot)) as c_int
     }
 
+    unsafe fn mq_getsetattr(mqdes: mqd_t, new: *const mq_attr, old: *mut mq_attr) -> c_int {
+        e(syscall!(MQ_GETSETATTR, mqdes, new, old)) as c_int
+    }
+
+    unsafe fn mq_notify(mqdes: mqd_t, notification: *const sigevent) -> c_int {
+        e(syscall!(MQ_NOTIFY, mqdes, notification)) as c_int
+    }
+
+    unsafe fn mq_open(name: &CStr, oflag: c_int, mode: mode_t, attr: *const mq_attr) -> mqd_t {
+        e(syscall!(MQ_OPEN, name.as_ptr(), oflag, mode, attr)) as mqd_t
+    }
+
+    unsafe fn mq_timedreceive(
+        mqdes: mqd_t,
+        msg: &mut [u8],
+        msg_prio: *mut c_uint,
+        abstime: *const timespec,
+    ) -> ssize_t {
+        e(syscall_cp!(
+            MQ_TIMEDRECEIVE,
+            mqdes,
+            msg.as_mut_ptr(),
+            msg.len(),
+            msg_prio,
+            abstime
+        )) as ssize_t
+    }
+
+    unsafe fn mq_timedsend(
+        mqdes: mqd_t,
+        msg: &[u8],
+        msg_prio: c_uint,
+        abstime: *const timespec,
+    ) -> c_int {
+        e(syscall_cp!(
+            MQ_TIMEDSEND,
+            mqdes,
+            msg.as_ptr(),
+            msg.len(),
+            msg_prio,
+            abstime
+        )) as c_int
+    }
+
+    fn mq_unlink(name: &CStr) -> c_int {
+        e(unsafe { syscall!(MQ_UNLINK, name.as_ptr()) }) as c_int
+    }
+
+    unsafe fn mremap(
+        addr: *mut c_void,
+        len: usize,
+        new_len: usize,
+        flags: c_int,
+        new_addr: *mut c_void,
+    ) -> *mut c_void {
+        e(syscall!(MREMAP, addr, len, new_len, flags, new_addr)) as *mut c_void
+    }
+
     unsafe fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int {
         e(syscall!(MSYNC, addr, len, flags)) as c_int
     }
 
+    unsafe fn munlock(addr: *const c_void, len: usize) -> c_int {
+        e(syscall!(MUNLOCK, addr, len)) as c_int
+    }
+
+    fn munlockall() -> c_int {
+        e(unsafe { syscall!(MUNLOCKALL) }) as c_int
+    }
+
     unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int {
         e(syscall!(MUNMAP, addr, len)) as c_int
     }
 
     fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
-        e(unsafe { syscall!(NANOSLEEP, rqtp, rmtp) }) as c_int
+        e(unsafe { syscall_cp!(NANOSLEEP, rqtp, rmtp) }) as c_int
     }
 
     fn open(path: &CStr, oflag: c_int, mode: mode_t) -> c_int {
         e(unsafe { syscall!(OPENAT, AT_FDCWD, path.as_ptr(), oflag, mode) }) as c_int
     }
 
+    fn openat(dirfd: c_int, path: &CStr, oflag: c_int, mode: mode_t) -> c_int {
+        e(unsafe { syscall!(OPENAT, dirfd, path.as_ptr(), oflag, mode) }) as c_int
+    }
+
     fn pipe2(fildes: &mut [c_int], flags: c_int) -> c_int {
         e(unsafe { syscall!(PIPE2, fildes.as_mut_ptr(), flags) }) as c_int
     }
 
+    unsafe fn preadv(fildes: c_int, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t {
+        // The offset is split into low and high words
+        e(syscall!(
+            PREADV,
+            fildes,
+            iov,
+            iovcnt,
+            offset as usize,
+            ((offset as u64) >> 32) as usize
+        )) as ssize_t
+    }
+
+    unsafe fn preadv2(
+        fildes: c_int,
+        iov: *const iovec,
+        iovcnt: c_int,
+        offset: off_t,
+        flags: c_int,
+    ) -> ssize_t {
+        // The offset is split into low and high words, -1 uses the file position
+        e(syscall!(
+            PREADV2,
+            fildes,
+            iov,
+            iovcnt,
+            offset as usize,
+            ((offset as u64) >> 32) as usize,
+            flags
+        )) as ssize_t
+    }
+
+    unsafe fn prlimit(
+        pid: pid_t,
+        resource: c_int,
+        new_limit: *const rlimit,
+        old_limit: *mut rlimit,
+    ) -> c_int {
+        e(syscall!(PRLIMIT64, pid, resource, new_limit, old_limit)) as c_int
+    }
+
     #[cfg(target_arch = "x86_64")]
     unsafe fn pte_clone(stack: *mut usize) -> pid_t {
         let flags = CLONE_VM | CLONE_FS | CLONE_FILES | CLONE_SIGHAND;
@@ -378,8 +584,88 @@ impl Pal for Sys {
         e(pid) as pid_t
     }
 
+    fn pthread_getname(tid: pid_t, out: &mut [u8]) -> ssize_t {
+        let mut comm_path = b"/proc/self/task/".to_vec();
+        write!(comm_path, "{}/comm", tid).unwrap();
+        comm_path.push(0);
+
+        let fd = Self::open(
+            CStr::from_bytes_with_nul(&comm_path).unwrap(),
+            O_RDONLY | O_CLOEXEC,
+            0,
+        );
+        if fd < 0 {
+            return -1;
+        }
+        let mut count = Self::read(fd, out);
+        Self::close(fd);
+
+        // The kernel ends the name with a newline
+        if count > 0 && out[count as usize - 1] == b'\n' {
+            count -= 1;
+        }
+        count
+    }
+
+    fn pthread_setname(tid: pid_t, name: &CStr) -> c_int {
+        const PR_SET_NAME: c_int = 15;
+
+        if tid == Self::gettid() {
+            return e(unsafe { syscall!(PRCTL, PR_SET_NAME, name.as_ptr()) }) as c_int;
+        }
+
+        let mut comm_path = b"/proc/self/task/".to_vec();
+        write!(comm_path, "{}/comm", tid).unwrap();
+        comm_path.push(0);
+
+        let fd = Self::open(
+            CStr::from_bytes_with_nul(&comm_path).unwrap(),
+            O_WRONLY | O_CLOEXEC,
+            0,
+        );
+        if fd < 0 {
+            return -1;
+        }
+        let count = Self::write(fd, name.to_bytes());
+        Self::close(fd);
+        if count < 0 {
+            -1
+        } else {
+            0
+        }
+    }
+
+    unsafe fn pwritev(fildes: c_int, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t {
+        e(syscall!(
+            PWRITEV,
+            fildes,
+            iov,
+            iovcnt,
+            offset as usize,
+            ((offset as u64) >> 32) as usize
+        )) as ssize_t
+    }
+
+    unsafe fn pwritev2(
+        fildes: c_int,
+        iov: *const iovec,
+        iovcnt: c_int,
+        offset: off_t,
+        flags: c_int,
+    ) -> ssize_t {
+        e(syscall!(
+            PWRITEV2,
+            fildes,
+            iov,
+            iovcnt,
+            offset as usize,
+            ((offset as u64) >> 32) as usize,
+            flags
+        )) as ssize_t
+    }
+
     fn read(fildes: c_int, buf: &mut [u8]) -> ssize_t {
-        e(unsafe { syscall!(READ, fildes, buf.as_mut_ptr(), buf.len()) }) as ssize_t
+        e(unsafe { syscall_cp!(READ, fildes, buf.as_mut_ptr(), buf.len()) }) as ssize_t
     }
 
     fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t {
@@ -394,18 +680,38 @@ impl Pal for Sys {
         }) as ssize_t
     }
 
+    unsafe fn readv(fildes: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+        e(syscall!(READV, fildes, iov, iovcnt)) as ssize_t
+    }
+
     fn rename(old: &CStr, new: &CStr) -> c_int {
         e(unsafe { syscall!(RENAMEAT, AT_FDCWD, old.as_ptr(), AT_FDCWD, new.as_ptr()) }) as c_int
     }
 
+    fn renameat(olddirfd: c_int, old: &CStr, newdirfd: c_int, new: &CStr) -> c_int {
+        e(unsafe { syscall!(RENAMEAT, olddirfd, old.as_ptr(), newdirfd, new.as_ptr()) }) as c_int
+    }
+
     fn rmdir(path: &CStr) -> c_int {
         e(unsafe { syscall!(UNLINKAT, AT_FDCWD, path.as_ptr(), AT_REMOVEDIR) }) as c_int
     }
 
+    fn sched_setparam(pid: pid_t, param: *const sched_param) -> c_int {
+        e(unsafe { syscall!(SCHED_SETPARAM, pid, param) }) as c_int
+    }
+
+    fn sched_setscheduler(pid: pid_t, policy: c_int, param: *const sched_param) -> c_int {
+        e(unsafe { syscall!(SCHED_SETSCHEDULER, pid, policy, param) }) as c_int
+    }
+
     fn sched_yield() -> c_int {
         e(unsafe { syscall!(SCHED_YIELD) }) as c_int
     }
 
+    fn sendfile(out_fd: c_int, in_fd: c_int, offset: *mut off_t, count: size_t) -> ssize_t {
+        e(unsafe { syscall!(SENDFILE, out_fd, in_fd, offset, count) }) as ssize_t
+    }
+
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
         e(unsafe { syscall!(SETPGID, pid, pgid) }) as c_int
     }
@@ -418,10 +724,89 @@ impl Pal for Sys {
         e(unsafe { syscall!(SETREUID, ruid, euid) }) as c_int
     }
 
+    unsafe fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+        e(syscall!(SETRLIMIT, resource, rlim)) as c_int
+    }
+
+    unsafe fn signalfd(fd: c_int, mask: *const sigset_t, flags: c_int) -> c_int {
+        e(syscall!(
+            SIGNALFD4,
+            fd,
+            mask,
+            mem::size_of::<sigset_t>(),
+            flags
+        )) as c_int
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn spawn_clone(stack: *mut usize) -> pid_t {
+        let flags = CLONE_VM | CLONE_VFORK | SIGCHLD;
+        let pid;
+        llvm_asm!("
+            # Call clone syscall
+            syscall
+
+            # Check if child or parent
+            test rax, rax
+            jnz .spawn_parent
+
+            # Load registers
+            pop rax
+            pop rdi
+            pop rsi
+            pop rdx
+            pop rcx
+            pop r8
+            pop r9
+
+            # Call entry point
+            call rax
+
+            # Exit
+            mov rax, 60
+            mov rdi, 127
+            syscall
+
+            # Invalid instruction on failure to exit
+            ud2
+
+            # Return PID if parent
+            .spawn_parent:
+            "
+            : "={rax}"(pid)
+            : "{rax}"(SYS_CLONE), "{rdi}"(flags), "{rsi}"(stack), "{rdx}"(0), "{rcx}"(0), "{r8}"(0)
+            : "memory", "rbx", "rcx", "rdx", "rsi", "rdi", "r8",
+              "r9", "r10", "r11", "r12", "r13", "r14", "r15"
+            : "intel", "volatile"
+        );
+        e(pid) as pid_t
+    }
+
     fn symlink(path1: &CStr, path2: &CStr) -> c_int {
         e(unsafe { syscall!(SYMLINKAT, path1.as_ptr(), AT_FDCWD, path2.as_ptr()) }) as c_int
     }
 
+    fn symlinkat(path1: &CStr, dirfd: c_int, path2: &CStr) -> c_int {
+        e(unsafe { syscall!(SYMLINKAT, path1.as_ptr(), dirfd, path2.as_ptr()) }) as c_int
+    }
+
+    fn timerfd_create(clockid: clockid_t, flags: c_int) -> c_int {
+        e(unsafe { syscall!(TIMERFD_CREATE, clockid, flags) }) as c_int
+    }
+
+    unsafe fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int {
+        e(syscall!(TIMERFD_GETTIME, fd, curr_value)) as c_int
+    }
+
+    unsafe fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int {
+        e(syscall!(TIMERFD_SETTIME, fd, flags, new_value, old_value)) as c_int
+    }
+
     fn umask(mask: mode_t) -> mode_t {
         unsafe { syscall!(UMASK, mask) as mode_t }
     }
@@ -434,12 +819,33 @@ impl Pal for Sys {
         e(unsafe { syscall!(UNLINKAT, AT_FDCWD, path.as_ptr(), 0) }) as c_int
     }
 
+    fn unlinkat(dirfd: c_int, path: &CStr, flag: c_int) -> c_int {
+        e(unsafe { syscall!(UNLINKAT, dirfd, path.as_ptr(), flag) }) as c_int
+    }
+
+    unsafe fn wait4(
+        pid: pid_t,
+        stat_loc: *mut c_int,
+        options: c_int,
+        rusage: *mut rusage,
+    ) -> pid_t {
+        e(syscall_cp!(WAIT4, pid, stat_loc, options, rusage)) as pid_t
+    }
+
+    unsafe fn waitid(idtype: idtype_t, id: id_t, infop: *mut siginfo_t, options: c_int) -> c_int {
+        e(syscall_cp!(WAITID, idtype, id, infop, options, 0)) as c_int
+    }
+
     fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
-        e(unsafe { syscall!(WAIT4, pid, stat_loc, options, 0) }) as pid_t
+        e(unsafe { syscall_cp!(WAIT4, pid, stat_loc, options, 0) }) as pid_t
     }
 
     fn write(fildes: c_int, buf: &[u8]) -> ssize_t {
-        e(unsafe { syscall!(WRITE, fildes, buf.as_ptr(), buf.len()) }) as ssize_t
+        e(unsafe { syscall_cp!(WRITE, fildes, buf.as_ptr(), buf.len()) }) as ssize_t
+    }
+
+    unsafe fn writev(fildes: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+        e(syscall!(WRITEV, fildes, iov, iovcnt)) as ssize_t
     }
 
     fn verify() -> bool {
@@ -5,8 +5,9 @@ use super::{
     e, Sys,
 };
 use crate::header::{
-    signal::{sigaction, sigset_t, stack_t},
+    signal::{sigaction, sigevent, siginfo_t, sigset_t, stack_t},
     sys_time::itimerval,
+    time::{itimerspec, timespec},
 };
 
 impl PalSignal for Sys {
@@ -27,7 +28,7 @@ impl PalSignal for Sys {
         if tid == !0 {
             -1
         } else {
-            e(unsafe { syscall!(TKILL, tid, sig) }) as c_int
+            Self::tkill(tid, sig)
         }
     }
 
@@ -54,4 +55,51 @@ impl PalSignal for Sys {
     fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
         e(unsafe { syscall!(RT_SIGPROCMASK, how, set, oset, mem::size_of::<sigset_t>()) }) as c_int
     }
+
+    unsafe fn sigtimedwait(
+        set: *const sigset_t,
+        info: *mut siginfo_t,
+        timeout: *const timespec,
+    ) -> c_int {
+        e(syscall!(
+            RT_SIGTIMEDWAIT,
+            set,
+            info,
+            timeout,
+            mem::size_of::<sigset_t>()
+        )) as c_int
+    }
+
+    unsafe fn timer_create(
+        clock_id: clockid_t,
+        evp: *const sigevent,
+        timerid: *mut c_int,
+    ) -> c_int {
+        e(syscall!(TIMER_CREATE, clock_id, evp, timerid)) as c_int
+    }
+
+    fn timer_delete(timerid: c_int) -> c_int {
+        e(unsafe { syscall!(TIMER_DELETE, timerid) }) as c_int
+    }
+
+    fn timer_getoverrun(timerid: c_int) -> c_int {
+        e(unsafe { syscall!(TIMER_GETOVERRUN, timerid) }) as c_int
+    }
+
+    unsafe fn timer_gettime(timerid: c_int, value: *mut itimerspec) -> c_int {
+        e(syscall!(TIMER_GETTIME, timerid, value)) as c_int
+    }
+
+    unsafe fn timer_settime(
+        timerid: c_int,
+        flags: c_int,
+        value: *const itimerspec,
+        ovalue: *mut itimerspec,
+    ) -> c_int {
+        e(syscall!(TIMER_SETTIME, timerid, flags, value, ovalue)) as c_int
+    }
+
+    fn tkill(tid: pid_t, sig: c_int) -> c_int {
+        e(unsafe { syscall!(TKILL, tid, sig) }) as c_int
+    }
 }
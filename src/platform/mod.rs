@@ -29,6 +29,8 @@ mod sys;
 #[cfg(test)]
 mod test;
 
+pub use self::pte::{cancellable_wait, cancellation_point, with_thread_stack};
+
 mod pte;
 
 pub use self::rlb::{Line, RawLineBuffer};
@@ -53,6 +55,16 @@ pub static mut environ: *mut *mut c_char = ptr::null_mut();
 #[allow(non_upper_case_globals)]
 pub static mut inner_environ: Vec<*mut c_char> = Vec::new();
 
+// The auxiliary vector passed by the kernel, pairs of a type and a value
+// ending with AT_NULL
+#[allow(non_upper_case_globals)]
+pub static mut auxv: *const (usize, usize) = ptr::null();
+
+// Whether the program got privileges its user doesn't have, like from setuid,
+// so that it shouldn't trust its environment
+#[allow(non_upper_case_globals)]
+pub static mut secure_execution: bool = false;
+
 pub trait WriteByte: fmt::Write {
     fn write_u8(&mut self, byte: u8) -> fmt::Result;
 }
@@ -3,12 +3,17 @@ use crate::{
     c_str::CStr,
     header::{
         dirent::dirent,
-        sys_resource::rlimit,
+        mqueue::{mq_attr, mqd_t},
+        signal::{sigevent, siginfo_t, sigset_t},
+        spawn::sched_param,
+        sys_resource::{rlimit, rusage},
         sys_stat::stat,
         sys_statvfs::statvfs,
         sys_time::{timeval, timezone},
+        sys_uio::iovec,
         sys_utsname::utsname,
-        time::timespec,
+        sys_wait::idtype_t,
+        time::{itimerspec, timespec},
     },
 };
 
@@ -37,16 +42,31 @@ pub trait Pal {
 
     fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int;
 
+    fn clock_nanosleep(
+        clk_id: clockid_t,
+        flags: c_int,
+        rqtp: *const timespec,
+        rmtp: *mut timespec,
+    ) -> c_int;
+
     fn close(fildes: c_int) -> c_int;
 
     fn dup(fildes: c_int) -> c_int;
 
     fn dup2(fildes: c_int, fildes2: c_int) -> c_int;
 
+    fn dup3(fildes: c_int, fildes2: c_int, flags: c_int) -> c_int;
+
+    fn eventfd(initval: c_uint, flags: c_int) -> c_int;
+
     unsafe fn execve(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int;
 
     fn exit(status: c_int) -> !;
 
+    fn fadvise(fildes: c_int, offset: off_t, len: off_t, advice: c_int) -> c_int;
+
+    fn fallocate(fildes: c_int, mode: c_int, offset: off_t, len: off_t) -> c_int;
+
     fn fchdir(fildes: c_int) -> c_int;
 
     fn fchmod(fildes: c_int, mode: mode_t) -> c_int;
@@ -57,9 +77,11 @@ pub trait Pal {
 
     fn fstat(fildes: c_int, buf: *mut stat) -> c_int;
 
+    fn fstatat(dirfd: c_int, path: &CStr, buf: *mut stat, flag: c_int) -> c_int;
+
     fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int;
 
-    fn fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int;
+    fn fcntl(fildes: c_int, cmd: c_int, arg: c_ulong) -> c_int;
 
     fn fork() -> pid_t;
 
@@ -69,11 +91,11 @@ pub trait Pal {
 
     fn ftruncate(fildes: c_int, length: off_t) -> c_int;
 
-    fn futex(addr: *mut c_int, op: c_int, val: c_int) -> c_int;
+    fn futex(addr: *mut c_int, op: c_int, val: c_int, timeout: *const timespec) -> c_int;
 
     fn futimens(fd: c_int, times: *const timespec) -> c_int;
 
-    fn utimens(path: &CStr, times: *const timespec) -> c_int;
+    fn utimensat(dirfd: c_int, path: &CStr, times: *const timespec, flag: c_int) -> c_int;
 
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char;
 
@@ -95,20 +117,36 @@ pub trait Pal {
 
     unsafe fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int;
 
+    unsafe fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int;
+
     fn gettid() -> pid_t;
 
     fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int;
 
     fn getuid() -> uid_t;
 
+    fn inotify_add_watch(fd: c_int, path: &CStr, mask: u32) -> c_int;
+
+    fn inotify_init1(flags: c_int) -> c_int;
+
+    fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int;
+
     fn link(path1: &CStr, path2: &CStr) -> c_int;
 
+    fn linkat(fd1: c_int, path1: &CStr, fd2: c_int, path2: &CStr, flag: c_int) -> c_int;
+
     fn lseek(fildes: c_int, offset: off_t, whence: c_int) -> off_t;
 
     fn mkdir(path: &CStr, mode: mode_t) -> c_int;
 
+    fn mkdirat(dirfd: c_int, path: &CStr, mode: mode_t) -> c_int;
+
     fn mkfifo(path: &CStr, mode: mode_t) -> c_int;
 
+    unsafe fn mlock(addr: *const c_void, len: usize) -> c_int;
+
+    fn mlockall(flags: c_int) -> c_int;
+
     unsafe fn mmap(
         addr: *mut c_void,
         len: usize,
@@ -120,45 +158,153 @@ pub trait Pal {
 
     unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
 
+    unsafe fn mq_getsetattr(mqdes: mqd_t, new: *const mq_attr, old: *mut mq_attr) -> c_int;
+
+    unsafe fn mq_notify(mqdes: mqd_t, notification: *const sigevent) -> c_int;
+
+    unsafe fn mq_open(name: &CStr, oflag: c_int, mode: mode_t, attr: *const mq_attr) -> mqd_t;
+
+    unsafe fn mq_timedreceive(
+        mqdes: mqd_t,
+        msg: &mut [u8],
+        msg_prio: *mut c_uint,
+        abstime: *const timespec,
+    ) -> ssize_t;
+
+    unsafe fn mq_timedsend(
+        mqdes: mqd_t,
+        msg: &[u8],
+        msg_prio: c_uint,
+        abstime: *const timespec,
+    ) -> c_int;
+
+    fn mq_unlink(name: &CStr) -> c_int;
+
+    unsafe fn mremap(
+        addr: *mut c_void,
+        len: usize,
+        new_len: usize,
+        flags: c_int,
+        new_addr: *mut c_void,
+    ) -> *mut c_void;
+
     unsafe fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int;
 
+    unsafe fn munlock(addr: *const c_void, len: usize) -> c_int;
+
+    fn munlockall() -> c_int;
+
     unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int;
 
     fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int;
 
     fn open(path: &CStr, oflag: c_int, mode: mode_t) -> c_int;
 
+    fn openat(dirfd: c_int, path: &CStr, oflag: c_int, mode: mode_t) -> c_int;
+
     fn pipe2(fildes: &mut [c_int], flags: c_int) -> c_int;
 
+    unsafe fn preadv(fildes: c_int, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t;
+
+    unsafe fn preadv2(
+        fildes: c_int,
+        iov: *const iovec,
+        iovcnt: c_int,
+        offset: off_t,
+        flags: c_int,
+    ) -> ssize_t;
+
+    unsafe fn prlimit(
+        pid: pid_t,
+        resource: c_int,
+        new_limit: *const rlimit,
+        old_limit: *mut rlimit,
+    ) -> c_int;
+
     unsafe fn pte_clone(stack: *mut usize) -> pid_t;
 
+    fn pthread_getname(tid: pid_t, out: &mut [u8]) -> ssize_t;
+
+    fn pthread_setname(tid: pid_t, name: &CStr) -> c_int;
+
+    unsafe fn pwritev(fildes: c_int, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t;
+
+    unsafe fn pwritev2(
+        fildes: c_int,
+        iov: *const iovec,
+        iovcnt: c_int,
+        offset: off_t,
+        flags: c_int,
+    ) -> ssize_t;
+
     fn read(fildes: c_int, buf: &mut [u8]) -> ssize_t;
 
     fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t;
 
+    unsafe fn readv(fildes: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t;
+
     fn rename(old: &CStr, new: &CStr) -> c_int;
 
+    fn renameat(olddirfd: c_int, old: &CStr, newdirfd: c_int, new: &CStr) -> c_int;
+
     fn rmdir(path: &CStr) -> c_int;
 
+    fn sched_setparam(pid: pid_t, param: *const sched_param) -> c_int;
+
+    fn sched_setscheduler(pid: pid_t, policy: c_int, param: *const sched_param) -> c_int;
+
     fn sched_yield() -> c_int;
 
+    fn sendfile(out_fd: c_int, in_fd: c_int, offset: *mut off_t, count: size_t) -> ssize_t;
+
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int;
 
     fn setregid(rgid: gid_t, egid: gid_t) -> c_int;
 
     fn setreuid(ruid: uid_t, euid: uid_t) -> c_int;
 
+    unsafe fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int;
+
+    unsafe fn signalfd(fd: c_int, mask: *const sigset_t, flags: c_int) -> c_int;
+
+    /// Start a child process like vfork, suspending the caller until the child
+    /// execs or exits. The child runs on `stack`, which is set up like for
+    /// `pte_clone`, and may share the caller's memory.
+    unsafe fn spawn_clone(stack: *mut usize) -> pid_t;
+
     fn symlink(path1: &CStr, path2: &CStr) -> c_int;
 
+    fn symlinkat(path1: &CStr, dirfd: c_int, path2: &CStr) -> c_int;
+
+    fn timerfd_create(clockid: clockid_t, flags: c_int) -> c_int;
+
+    unsafe fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int;
+
+    unsafe fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int;
+
     fn umask(mask: mode_t) -> mode_t;
 
     fn uname(utsname: *mut utsname) -> c_int;
 
     fn unlink(path: &CStr) -> c_int;
 
+    fn unlinkat(dirfd: c_int, path: &CStr, flag: c_int) -> c_int;
+
+    unsafe fn wait4(pid: pid_t, stat_loc: *mut c_int, options: c_int, rusage: *mut rusage)
+        -> pid_t;
+
+    unsafe fn waitid(idtype: idtype_t, id: id_t, infop: *mut siginfo_t, options: c_int) -> c_int;
+
     fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t;
 
     fn write(fildes: c_int, buf: &[u8]) -> ssize_t;
 
+    unsafe fn writev(fildes: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t;
+
     fn verify() -> bool;
 }
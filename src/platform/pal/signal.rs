@@ -1,7 +1,8 @@
 use super::super::{types::*, Pal};
 use crate::header::{
-    signal::{sigaction, sigset_t, stack_t},
+    signal::{sigaction, sigevent, siginfo_t, sigset_t, stack_t},
     sys_time::itimerval,
+    time::{itimerspec, timespec},
 };
 
 pub trait PalSignal: Pal {
@@ -20,4 +21,28 @@ pub trait PalSignal: Pal {
     fn sigaltstack(ss: *const stack_t, old_ss: *mut stack_t) -> c_int;
 
     fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int;
+
+    unsafe fn sigtimedwait(
+        set: *const sigset_t,
+        info: *mut siginfo_t,
+        timeout: *const timespec,
+    ) -> c_int;
+
+    unsafe fn timer_create(clock_id: clockid_t, evp: *const sigevent, timerid: *mut c_int)
+        -> c_int;
+
+    fn timer_delete(timerid: c_int) -> c_int;
+
+    fn timer_getoverrun(timerid: c_int) -> c_int;
+
+    unsafe fn timer_gettime(timerid: c_int, value: *mut itimerspec) -> c_int;
+
+    unsafe fn timer_settime(
+        timerid: c_int,
+        flags: c_int,
+        value: *const itimerspec,
+        ovalue: *mut itimerspec,
+    ) -> c_int;
+
+    fn tkill(tid: pid_t, sig: c_int) -> c_int;
 }
@@ -1,30 +1,34 @@
 #![allow(non_snake_case)]
 
-use alloc::{boxed::Box, collections::BTreeMap};
+use alloc::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
 use core::{
-    intrinsics, ptr,
-    sync::atomic::{AtomicU32, Ordering},
+    intrinsics, mem, ptr,
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
 };
 
 use crate::{
-    header::{sys_mman, time::timespec},
+    header::{
+        pthread::{StackAttr, DEFAULT_STACK_SIZE},
+        signal::{self, siginfo_t, SIGCANCEL},
+        sys_mman,
+        time::{timespec, CLOCK_REALTIME},
+    },
     ld_so::{
         linker::Linker,
         tcb::{Master, Tcb},
+        PAGE_SIZE,
     },
     platform::{
-        types::{c_int, c_uint, c_void, pid_t, size_t},
-        Pal, Sys,
+        types::{c_int, c_uint, c_ulong, c_void, pid_t, size_t},
+        Pal, PalSignal, Sys,
     },
-    sync::Mutex,
+    sync::{Mutex, Semaphore},
     ALLOCATOR,
 };
 
-pub struct Semaphore {
-    lock: Mutex<()>,
-    count: i32,
-}
-
 type pte_osThreadHandle = pid_t;
 type pte_osMutexHandle = *mut Mutex<()>;
 type pte_osSemaphoreHandle = *mut Semaphore;
@@ -50,6 +54,36 @@ static mut pid_mutexes_lock: Mutex<()> = Mutex::new(());
 static mut pid_stacks: Option<BTreeMap<pte_osThreadHandle, (*mut c_void, size_t)>> = None;
 static mut pid_stacks_lock: Mutex<()> = Mutex::new(());
 
+// Threads pte_osThreadCancel was called on, which pthreads-emb has marked as
+// having a cancellation pending
+static mut pid_cancels: Option<BTreeSet<pte_osThreadHandle>> = None;
+static mut pid_cancels_lock: Mutex<()> = Mutex::new(());
+static PENDING_CANCELS: AtomicUsize = AtomicUsize::new(0);
+
+/// Set when the first thread is created, as only then can a thread be canceled
+static THREADS_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Set while the thread is in a cancellation point, for the SIGCANCEL handler
+#[thread_local]
+static IN_CANCELLATION_POINT: AtomicBool = AtomicBool::new(false);
+
+/// Set by the SIGCANCEL handler, for the cancellation point it interrupted. `syscall_cp` fails
+/// instead of making its call while it is set.
+#[thread_local]
+static CANCEL_SIGNALED: AtomicBool = AtomicBool::new(false);
+
+/// Set when the call in a cancellation point failed with EINTR after a request was signaled
+#[thread_local]
+static CANCEL_INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+extern "C" {
+    fn pthread_testcancel();
+}
+
+/// Stack attributes for the thread pthread_create is creating
+#[thread_local]
+static mut THREAD_STACK: Option<StackAttr> = None;
+
 #[thread_local]
 static mut LOCALS: *mut BTreeMap<c_uint, *mut c_void> = ptr::null_mut();
 
@@ -62,6 +96,285 @@ unsafe fn locals() -> &'static mut BTreeMap<c_uint, *mut c_void> {
     &mut *LOCALS
 }
 
+unsafe fn cancel_requested(handle: pte_osThreadHandle) -> bool {
+    if PENDING_CANCELS.load(Ordering::SeqCst) == 0 {
+        return false;
+    }
+
+    pte_osMutexLock(&mut pid_cancels_lock);
+    let requested = pid_cancels
+        .as_ref()
+        .map_or(false, |cancels| cancels.contains(&handle));
+    pte_osMutexUnlock(&mut pid_cancels_lock);
+    requested
+}
+
+// The system calls that are cancellation points are made from this stub, like in musl. It
+// checks the flag the SIGCANCEL handler sets and then makes the call, and the handler moves a
+// thread it interrupts anywhere from the check to the call, or in the call if it would be
+// restarted, to the cancel path, which fails the call with EINTR. A request can then neither
+// arrive just before a call that blocks nor restart one. Calls from outside cancellation points
+// aren't affected, as the handler only sets the flag and moves threads inside them.
+//
+// __relibc_syscall_cp(flag, n, a, b, c, d, e, f)
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+global_asm!(
+    "
+    .global __relibc_syscall_cp
+    .hidden __relibc_syscall_cp
+    .global __relibc_cp_begin
+    .hidden __relibc_cp_begin
+    .global __relibc_cp_end
+    .hidden __relibc_cp_end
+    .global __relibc_cp_cancel
+    .hidden __relibc_cp_cancel
+    .type __relibc_syscall_cp,@function
+    __relibc_syscall_cp:
+    __relibc_cp_begin:
+        cmpb $0, (%rdi)
+        jne __relibc_cp_cancel
+        mov %rsi, %rax
+        mov %rdx, %rdi
+        mov %rcx, %rsi
+        mov %r8, %rdx
+        mov %r9, %r10
+        mov 8(%rsp), %r8
+        mov 16(%rsp), %r9
+        syscall
+    __relibc_cp_end:
+        ret
+    __relibc_cp_cancel:
+        mov $-4, %rax
+        ret
+"
+);
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+global_asm!(
+    "
+    .global __relibc_syscall_cp
+    .hidden __relibc_syscall_cp
+    .global __relibc_cp_begin
+    .hidden __relibc_cp_begin
+    .global __relibc_cp_end
+    .hidden __relibc_cp_end
+    .global __relibc_cp_cancel
+    .hidden __relibc_cp_cancel
+    .type __relibc_syscall_cp,@function
+    __relibc_syscall_cp:
+    __relibc_cp_begin:
+        ldrb w8, [x0]
+        cbnz w8, __relibc_cp_cancel
+        mov x8, x1
+        mov x0, x2
+        mov x1, x3
+        mov x2, x4
+        mov x3, x5
+        mov x4, x6
+        mov x5, x7
+        svc #0
+    __relibc_cp_end:
+        ret
+    __relibc_cp_cancel:
+        mov x0, #-4
+        ret
+"
+);
+
+#[cfg(target_os = "linux")]
+extern "C" {
+    fn __relibc_syscall_cp(
+        flag: *const u8,
+        n: usize,
+        a: usize,
+        b: usize,
+        c: usize,
+        d: usize,
+        e: usize,
+        f: usize,
+    ) -> usize;
+    static __relibc_cp_begin: u8;
+    static __relibc_cp_end: u8;
+    static __relibc_cp_cancel: u8;
+}
+
+/// Make system call `n`, which is a cancellation point when the thread is in one
+#[cfg(target_os = "linux")]
+pub unsafe fn syscall_cp(
+    n: usize,
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    e: usize,
+    f: usize,
+) -> usize {
+    use crate::header::errno::EINTR;
+
+    let flag = &CANCEL_SIGNALED as *const AtomicBool as *const u8;
+    let ret = __relibc_syscall_cp(flag, n, a, b, c, d, e, f);
+    if ret == -EINTR as usize && CANCEL_SIGNALED.load(Ordering::SeqCst) {
+        CANCEL_INTERRUPTED.store(true, Ordering::SeqCst);
+    }
+    ret
+}
+
+/// Wait on the futex at `addr` while it holds `val`, as a cancellation point
+#[cfg(target_os = "linux")]
+fn futex_wait_cp(addr: *mut c_int, val: c_int, timeout: *const timespec) -> c_int {
+    use crate::sync::FUTEX_WAIT;
+
+    unsafe {
+        syscall_cp(
+            sc::nr::FUTEX,
+            addr as usize,
+            FUTEX_WAIT as usize,
+            val as usize,
+            timeout as usize,
+            0,
+            0,
+        ) as c_int
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn futex_wait_cp(addr: *mut c_int, val: c_int, timeout: *const timespec) -> c_int {
+    Sys::futex(addr, crate::sync::FUTEX_WAIT, val, timeout)
+}
+
+/// Whether `pc` is in `__relibc_syscall_cp`, from its check of the flag to the end of its system
+/// call. A call is restarted from its system call instruction.
+#[cfg(target_os = "linux")]
+unsafe fn in_syscall_cp(pc: usize) -> bool {
+    let begin = &__relibc_cp_begin as *const u8 as usize;
+    let end = &__relibc_cp_end as *const u8 as usize;
+    pc >= begin && pc < end
+}
+
+/// Move the thread of an interrupted context to the cancel path of `__relibc_syscall_cp` if it
+/// is in its call. Returns whether it did so.
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+unsafe fn cancel_syscall(context: *mut c_void) -> bool {
+    use crate::header::ucontext::{greg_t, ucontext_t, REG_RIP};
+
+    let gregs = &mut (*(context as *mut ucontext_t)).uc_mcontext.gregs;
+    if !in_syscall_cp(gregs[REG_RIP] as usize) {
+        return false;
+    }
+    gregs[REG_RIP] = &__relibc_cp_cancel as *const u8 as greg_t;
+    true
+}
+
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+unsafe fn cancel_syscall(context: *mut c_void) -> bool {
+    use crate::{header::ucontext::ucontext_t, platform::types::c_ulonglong};
+
+    let mcontext = &mut (*(context as *mut ucontext_t)).uc_mcontext;
+    if !in_syscall_cp(mcontext.pc as usize) {
+        return false;
+    }
+    mcontext.pc = &__relibc_cp_cancel as *const u8 as c_ulonglong;
+    true
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    any(target_arch = "aarch64", target_arch = "x86_64")
+)))]
+unsafe fn cancel_syscall(_context: *mut c_void) -> bool {
+    false
+}
+
+extern "C" fn cancel_handler(_sig: c_int, _info: *mut siginfo_t, context: *mut c_void) {
+    // Acting on the request here could unwind the thread while it holds a lock, so this only
+    // makes the call in a cancellation point fail with EINTR, or not be made, rather than
+    // letting SA_RESTART restart it. The cancellation point then acts on it. Elsewhere the
+    // request waits for the next cancellation point, which checks for it before its call.
+    if IN_CANCELLATION_POINT.load(Ordering::SeqCst) {
+        CANCEL_SIGNALED.store(true, Ordering::SeqCst);
+        unsafe {
+            cancel_syscall(context);
+        }
+    }
+}
+
+/// Mark the thread as in a cancellation point, acting on a request made before it was
+unsafe fn enter_cancellation_point(thread: pte_osThreadHandle) {
+    CANCEL_SIGNALED.store(false, Ordering::SeqCst);
+    CANCEL_INTERRUPTED.store(false, Ordering::SeqCst);
+    // Marked first, so that a request is either seen here or signaled afterwards
+    IN_CANCELLATION_POINT.store(true, Ordering::SeqCst);
+    if cancel_requested(thread) || CANCEL_SIGNALED.load(Ordering::SeqCst) {
+        IN_CANCELLATION_POINT.store(false, Ordering::SeqCst);
+        pthread_testcancel();
+        // Cancellation is disabled, so the call is made after all
+        CANCEL_SIGNALED.store(false, Ordering::SeqCst);
+        IN_CANCELLATION_POINT.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Leave a cancellation point, acting on a request signaled during it. Returns whether one was,
+/// in which case pthread_testcancel returned as cancellation is disabled.
+unsafe fn leave_cancellation_point() -> bool {
+    IN_CANCELLATION_POINT.store(false, Ordering::SeqCst);
+    // Cleared, as system calls outside cancellation points check it too
+    if CANCEL_SIGNALED.swap(false, Ordering::SeqCst) {
+        pthread_testcancel();
+        true
+    } else {
+        false
+    }
+}
+
+/// Run a call that may block as a cancellation point, acting on a pending cancellation request
+/// before it starts or after it was interrupted by one
+pub unsafe fn cancellation_point<T>(mut f: impl FnMut() -> T) -> T {
+    if !THREADS_STARTED.load(Ordering::SeqCst) {
+        return f();
+    }
+
+    let thread = Sys::gettid();
+    loop {
+        enter_cancellation_point(thread);
+        let ret = f();
+        leave_cancellation_point();
+        // Cancellation is disabled, so make the call the handler interrupted after all
+        if !CANCEL_INTERRUPTED.load(Ordering::SeqCst) {
+            return ret;
+        }
+    }
+}
+
+/// Wait on `sem` as a cancellation point, until the absolute CLOCK_REALTIME `deadline` passes.
+/// Returns false on timeout.
+pub unsafe fn cancellable_wait(sem: &Semaphore, deadline: Option<&timespec>) -> bool {
+    if !THREADS_STARTED.load(Ordering::SeqCst) {
+        return sem.wait(deadline);
+    }
+
+    let thread = Sys::gettid();
+    loop {
+        enter_cancellation_point(thread);
+        let acquired = sem.wait_unless(
+            deadline,
+            || CANCEL_SIGNALED.load(Ordering::SeqCst),
+            futex_wait_cp,
+        );
+        // Stopped only to act on a cancellation request, so wait again if cancellation is
+        // disabled
+        if !leave_cancellation_point() || acquired {
+            return acquired;
+        }
+    }
+}
+
+/// Run `f`, which creates a thread through pthreads-emb, giving that thread `stack`
+pub unsafe fn with_thread_stack<T>(stack: StackAttr, f: impl FnOnce() -> T) -> T {
+    THREAD_STACK = Some(stack);
+    let ret = f();
+    THREAD_STACK = None;
+    ret
+}
+
 // pte_osResult pte_osInit(void)
 #[no_mangle]
 pub unsafe extern "C" fn pte_osInit() -> pte_osResult {
@@ -105,27 +418,64 @@ pub unsafe extern "C" fn pte_osThreadCreate(
     argv: *mut c_void,
     ppte_osThreadHandle: *mut pte_osThreadHandle,
 ) -> pte_osResult {
+    if !THREADS_STARTED.swap(true, Ordering::SeqCst) {
+        let mut act: signal::sigaction = mem::zeroed();
+        act.sa_handler = Some(mem::transmute(
+            cancel_handler as extern "C" fn(c_int, *mut siginfo_t, *mut c_void),
+        ));
+        act.sa_flags = signal::SA_SIGINFO as c_ulong;
+        // Restart interrupted calls, so that only cancellation points fail with EINTR. That needs
+        // the handler to interrupt the calls in them itself.
+        if cfg!(all(
+            target_os = "linux",
+            any(target_arch = "aarch64", target_arch = "x86_64")
+        )) {
+            act.sa_flags |= signal::SA_RESTART as c_ulong;
+        }
+        signal::sigaction_unchecked(SIGCANCEL, &act, ptr::null_mut());
+    }
+
     // Create a locked mutex, unlocked by pte_osThreadStart
     let mutex: pte_osMutexHandle = Box::into_raw(Box::new(Mutex::locked(())));
 
-    let stack_size = if stackSize == 0 {
-        1024 * 1024
+    let attr = THREAD_STACK.take().unwrap_or_else(|| StackAttr {
+        size: if stackSize == 0 {
+            DEFAULT_STACK_SIZE
+        } else {
+            stackSize as usize
+        },
+        ..StackAttr::default()
+    });
+    let (mapping, stack_end) = if attr.addr != 0 {
+        // A stack from pthread_attr_setstack is used as is, without a guard
+        (None, (attr.addr + attr.size) & !15)
     } else {
-        stackSize as usize
+        // Overflowing the stack should fault in the guard at its low end,
+        // rather than corrupt whatever is mapped below it
+        let guard_size = (attr.guard + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+        let stack_size = attr.size;
+        let map_base = sys_mman::mmap(
+            ptr::null_mut(),
+            guard_size + stack_size,
+            sys_mman::PROT_READ | sys_mman::PROT_WRITE,
+            sys_mman::MAP_SHARED | sys_mman::MAP_ANONYMOUS,
+            -1,
+            0,
+        );
+        if map_base as isize == -1 {
+            return PTE_OS_GENERAL_FAILURE;
+        }
+        if guard_size > 0 && sys_mman::mprotect(map_base, guard_size, sys_mman::PROT_NONE) < 0 {
+            sys_mman::munmap(map_base, guard_size + stack_size);
+            return PTE_OS_GENERAL_FAILURE;
+        }
+        let stack_base = map_base.add(guard_size);
+        ptr::write_bytes(stack_base as *mut u8, 0, stack_size);
+        (
+            Some((map_base, guard_size + stack_size)),
+            (stack_base as usize + stack_size) & !15,
+        )
     };
-    let stack_base = sys_mman::mmap(
-        ptr::null_mut(),
-        stack_size,
-        sys_mman::PROT_READ | sys_mman::PROT_WRITE,
-        sys_mman::MAP_SHARED | sys_mman::MAP_ANONYMOUS,
-        -1,
-        0,
-    );
-    if stack_base as isize == -1 {
-        return PTE_OS_GENERAL_FAILURE;
-    }
-    ptr::write_bytes(stack_base as *mut u8, 0, stack_size);
-    let stack_end = stack_base.add(stack_size);
     let mut stack = stack_end as *mut usize;
     {
         let mut push = |value: usize| {
@@ -168,15 +518,15 @@ pub unsafe extern "C" fn pte_osThreadCreate(
     pid_mutexes.as_mut().unwrap().insert(id, mutex);
     pte_osMutexUnlock(&mut pid_mutexes_lock);
 
-    pte_osMutexLock(&mut pid_stacks_lock);
-    if pid_stacks.is_none() {
-        pid_stacks = Some(BTreeMap::new());
+    // Stacks given by the caller are not ours to unmap
+    if let Some(mapping) = mapping {
+        pte_osMutexLock(&mut pid_stacks_lock);
+        if pid_stacks.is_none() {
+            pid_stacks = Some(BTreeMap::new());
+        }
+        pid_stacks.as_mut().unwrap().insert(id, mapping);
+        pte_osMutexUnlock(&mut pid_stacks_lock);
     }
-    pid_stacks
-        .as_mut()
-        .unwrap()
-        .insert(id, (stack_base, stack_size));
-    pte_osMutexUnlock(&mut pid_stacks_lock);
 
     *ppte_osThreadHandle = id;
 
@@ -231,6 +581,14 @@ pub unsafe extern "C" fn pte_osThreadDelete(handle: pte_osThreadHandle) -> pte_o
     }
     pte_osMutexUnlock(&mut pid_stacks_lock);
 
+    pte_osMutexLock(&mut pid_cancels_lock);
+    if let Some(ref mut cancels) = pid_cancels {
+        if cancels.remove(&handle) {
+            PENDING_CANCELS.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+    pte_osMutexUnlock(&mut pid_cancels_lock);
+
     PTE_OS_OK
 }
 
@@ -243,13 +601,29 @@ pub unsafe extern "C" fn pte_osThreadWaitForEnd(handle: pte_osThreadHandle) -> p
 
 #[no_mangle]
 pub unsafe extern "C" fn pte_osThreadCancel(handle: pte_osThreadHandle) -> pte_osResult {
-    //TODO: allow cancel of thread
+    pte_osMutexLock(&mut pid_cancels_lock);
+    if pid_cancels.is_none() {
+        pid_cancels = Some(BTreeSet::new());
+    }
+    if pid_cancels.as_mut().unwrap().insert(handle) {
+        PENDING_CANCELS.fetch_add(1, Ordering::SeqCst);
+    }
+    pte_osMutexUnlock(&mut pid_cancels_lock);
+
+    // Interrupt the thread if it is blocked in a cancellation point
+    if Sys::tkill(handle, SIGCANCEL) < 0 {
+        return PTE_OS_GENERAL_FAILURE;
+    }
     PTE_OS_OK
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn pte_osThreadCheckCancel(handle: pte_osThreadHandle) -> pte_osResult {
-    PTE_OS_OK
+    if cancel_requested(handle) {
+        PTE_OS_INTERRUPTED
+    } else {
+        PTE_OS_OK
+    }
 }
 
 #[no_mangle]
@@ -324,10 +698,7 @@ pub unsafe extern "C" fn pte_osSemaphoreCreate(
     initialValue: c_int,
     pHandle: *mut pte_osSemaphoreHandle,
 ) -> pte_osResult {
-    *pHandle = Box::into_raw(Box::new(Semaphore {
-        lock: Mutex::new(()),
-        count: initialValue,
-    }));
+    *pHandle = Box::into_raw(Box::new(Semaphore::new(initialValue)));
     PTE_OS_OK
 }
 
@@ -342,30 +713,36 @@ pub unsafe extern "C" fn pte_osSemaphorePost(
     handle: pte_osSemaphoreHandle,
     count: c_int,
 ) -> pte_osResult {
-    let semaphore = &mut *handle;
-    let _guard = semaphore.lock.lock();
-    intrinsics::atomic_xadd(&mut semaphore.count, 1);
+    (*handle).post(count);
     PTE_OS_OK
 }
 
+/// The CLOCK_REALTIME deadline for a timeout in milliseconds, where NULL means
+/// waiting forever
+unsafe fn pend_deadline(pTimeout: *mut c_uint) -> Option<timespec> {
+    let msecs = *pTimeout.as_ref()?;
+    let mut deadline = timespec::default();
+    Sys::clock_gettime(CLOCK_REALTIME, &mut deadline);
+    deadline.tv_sec += (msecs / 1000) as i64;
+    deadline.tv_nsec += (msecs % 1000) as i64 * 1_000_000;
+    if deadline.tv_nsec >= 1_000_000_000 {
+        deadline.tv_sec += 1;
+        deadline.tv_nsec -= 1_000_000_000;
+    }
+    Some(deadline)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn pte_osSemaphorePend(
     handle: pte_osSemaphoreHandle,
     pTimeout: *mut c_uint,
 ) -> pte_osResult {
-    //TODO: pTimeout
-    let semaphore = &mut *handle;
-    loop {
-        {
-            let _guard = semaphore.lock.lock();
-            if intrinsics::atomic_load(&semaphore.count) > 0 {
-                intrinsics::atomic_xsub(&mut semaphore.count, 1);
-                break;
-            }
-        }
-        Sys::sched_yield();
+    let deadline = pend_deadline(pTimeout);
+    if (*handle).wait(deadline.as_ref()) {
+        PTE_OS_OK
+    } else {
+        PTE_OS_TIMEOUT
     }
-    PTE_OS_OK
 }
 
 #[no_mangle]
@@ -373,8 +750,26 @@ pub unsafe extern "C" fn pte_osSemaphoreCancellablePend(
     handle: pte_osSemaphoreHandle,
     pTimeout: *mut c_uint,
 ) -> pte_osResult {
-    //TODO
-    pte_osSemaphorePend(handle, pTimeout)
+    let deadline = pend_deadline(pTimeout);
+    let thread = Sys::gettid();
+    // A cancellation request wakes the thread with SIGCANCEL, which only interrupts the wait
+    // while the thread is marked as in a cancellation point
+    CANCEL_SIGNALED.store(false, Ordering::SeqCst);
+    IN_CANCELLATION_POINT.store(true, Ordering::SeqCst);
+    let acquired = (*handle).wait_unless(
+        deadline.as_ref(),
+        || cancel_requested(thread),
+        futex_wait_cp,
+    );
+    IN_CANCELLATION_POINT.store(false, Ordering::SeqCst);
+    CANCEL_SIGNALED.store(false, Ordering::SeqCst);
+    if acquired {
+        PTE_OS_OK
+    } else if cancel_requested(thread) {
+        PTE_OS_INTERRUPTED
+    } else {
+        PTE_OS_TIMEOUT
+    }
 }
 
 #[no_mangle]
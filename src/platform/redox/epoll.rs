@@ -1,15 +1,20 @@
 use super::{
-    super::{types::*, Pal, PalEpoll},
+    super::{types::*, Pal, PalEpoll, PalSignal},
     Sys,
 };
 
 use crate::{
     fs::File,
-    header::{errno::*, fcntl::*, signal::sigset_t, sys_epoll::*},
+    header::{
+        errno::*,
+        fcntl::*,
+        signal::{sigset_t, SIG_SETMASK},
+        sys_epoll::*,
+    },
     io::prelude::*,
     platform,
 };
-use core::{mem, slice};
+use core::{mem, ptr, slice};
 use syscall::{
     data::{Event, TimeSpec},
     flag::EVENT_READ,
@@ -58,9 +63,8 @@ impl PalEpoll for Sys {
         events: *mut epoll_event,
         maxevents: c_int,
         timeout: c_int,
-        _sigset: *const sigset_t,
+        sigset: *const sigset_t,
     ) -> c_int {
-        // TODO: sigset
         assert_eq!(mem::size_of::<epoll_event>(), mem::size_of::<Event>());
 
         let timer_opt = if timeout != -1 {
@@ -96,9 +100,24 @@ impl PalEpoll for Sys {
             None
         };
 
+        // Redox has no way to wait with a temporary signal mask, so the mask
+        // is swapped around the wait. A signal arriving between the swap and
+        // the read runs its handler without interrupting the wait.
+        let mut old_sigset: sigset_t = 0;
+        if !sigset.is_null() && Sys::sigprocmask(SIG_SETMASK, sigset, &mut old_sigset) < 0 {
+            return -1;
+        }
+
         let bytes_read = Sys::read(epfd, unsafe {
             slice::from_raw_parts_mut(events as *mut u8, maxevents as usize)
         });
+
+        if !sigset.is_null() {
+            let errno = unsafe { platform::errno };
+            Sys::sigprocmask(SIG_SETMASK, &old_sigset, ptr::null_mut());
+            unsafe { platform::errno = errno };
+        }
+
         if bytes_read == -1 {
             return -1;
         }
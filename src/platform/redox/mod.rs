@@ -10,20 +10,30 @@ use crate::{
     fs::File,
     header::{
         dirent::dirent,
-        errno::{EINVAL, EIO, ENOMEM, EPERM, ERANGE},
+        errno::{EINVAL, EIO, ENOMEM, EOPNOTSUPP, EPERM, ERANGE, ESRCH},
         fcntl,
+        mqueue::{mq_attr, mqd_t},
+        signal::{
+            sigevent, siginfo_t, sigset_t, CLD_CONTINUED, CLD_DUMPED, CLD_EXITED, CLD_KILLED,
+            CLD_STOPPED, SIGCHLD, SIGCONT,
+        },
+        spawn::sched_param,
         sys_mman::{MAP_ANONYMOUS, PROT_READ, PROT_WRITE},
         sys_random,
-        sys_resource::{rlimit, RLIM_INFINITY},
-        sys_stat::stat,
+        sys_resource::{
+            rlimit, rusage, RLIM_INFINITY, RUSAGE_CHILDREN, RUSAGE_SELF, RUSAGE_THREAD,
+        },
+        sys_stat::{stat, UTIME_NOW, UTIME_OMIT},
         sys_statvfs::statvfs,
         sys_time::{timeval, timezone},
+        sys_uio::iovec,
         sys_utsname::{utsname, UTSLENGTH},
-        sys_wait,
-        time::timespec,
-        unistd::{F_OK, R_OK, W_OK, X_OK},
+        sys_wait::{self, idtype_t},
+        time::{itimerspec, timespec, CLOCK_REALTIME, TIMER_ABSTIME},
+        unistd::{F_OK, R_OK, SEEK_CUR, SEEK_SET, W_OK, X_OK},
     },
     io::{self, prelude::*, BufReader, SeekFrom},
+    ld_so::tcb::Tcb,
 };
 
 use super::{errno, types::*, Pal, Read};
@@ -31,12 +41,116 @@ use super::{errno, types::*, Pal, Read};
 static mut BRK_CUR: *mut c_void = ptr::null_mut();
 static mut BRK_END: *mut c_void = ptr::null_mut();
 
+/// The path that `path` names relative to the directory `dirfd`, for the
+/// `*at` functions. Redox has no such calls, so relative paths are joined to
+/// the full path of the directory.
+fn at_path(dirfd: c_int, path: &CStr) -> Option<CString> {
+    if dirfd == fcntl::AT_FDCWD || path.to_bytes().first() == Some(&b'/') {
+        return Some(path.to_owned());
+    }
+
+    let mut dir = [0; 4096];
+    let len = e(syscall::fpath(dirfd as usize, &mut dir));
+    if len == !0 {
+        return None;
+    }
+    let mut full = dir[..len].to_vec();
+    full.push(b'/');
+    full.extend_from_slice(path.to_bytes());
+    // Neither part can contain a nul byte
+    CString::new(full).ok()
+}
+
+/// The times to pass to the kernel for `futimens`, which does not know about
+/// `UTIME_NOW` and `UTIME_OMIT`. Null times mean now for both.
+unsafe fn utime_times(fd: c_int, times: *const timespec) -> Option<[redox_timespec; 2]> {
+    let mut now = timespec::default();
+    if Sys::clock_gettime(CLOCK_REALTIME, &mut now) < 0 {
+        return None;
+    }
+    let mut old = stat::default();
+    if Sys::fstat(fd, &mut old) < 0 {
+        return None;
+    }
+
+    let mut res = [redox_timespec::default(), redox_timespec::default()];
+    let current = [old.st_atim, old.st_mtim];
+    for (i, (out, current)) in res.iter_mut().zip(&current).enumerate() {
+        let time = if times.is_null() {
+            &now
+        } else {
+            let time = &*times.add(i);
+            match time.tv_nsec {
+                UTIME_NOW => &now,
+                UTIME_OMIT => current,
+                0..=999_999_999 => time,
+                _ => {
+                    errno = EINVAL;
+                    return None;
+                }
+            }
+        };
+        *out = redox_timespec::from(time);
+    }
+    Some(res)
+}
+
 mod epoll;
 mod extra;
 mod ptrace;
 mod signal;
 mod socket;
 
+/// Redox has no vectored I/O, so transfer one buffer at a time with `f`,
+/// stopping at the first short transfer. An offset other than -1 is used
+/// in place of the file position, which is restored afterwards.
+unsafe fn vectored<F>(
+    fd: c_int,
+    iov: *const iovec,
+    iovcnt: c_int,
+    offset: off_t,
+    mut f: F,
+) -> ssize_t
+where
+    F: FnMut(&mut [u8]) -> ssize_t,
+{
+    let previous = if offset != -1 {
+        let previous = Sys::lseek(fd, 0, SEEK_CUR);
+        if previous < 0 || Sys::lseek(fd, offset, SEEK_SET) < 0 {
+            return -1;
+        }
+        Some(previous)
+    } else {
+        None
+    };
+
+    let mut total: ssize_t = 0;
+    for iov in slice::from_raw_parts(iov, iovcnt as usize) {
+        let buf = slice::from_raw_parts_mut(iov.iov_base as *mut u8, iov.iov_len);
+        let res = f(buf);
+        if res < 0 {
+            if total == 0 {
+                total = -1;
+            }
+            break;
+        }
+        total += res;
+        if (res as usize) < buf.len() {
+            break;
+        }
+    }
+
+    if let Some(previous) = previous {
+        let saved = errno;
+        if Sys::lseek(fd, previous, SEEK_SET) < 0 {
+            return -1;
+        }
+        errno = saved;
+    }
+
+    total
+}
+
 pub fn e(sys: Result<usize>) -> usize {
     match sys {
         Ok(ok) => ok,
@@ -169,6 +283,37 @@ impl Pal for Sys {
         }
     }
 
+    fn clock_nanosleep(
+        clk_id: clockid_t,
+        flags: c_int,
+        rqtp: *const timespec,
+        rmtp: *mut timespec,
+    ) -> c_int {
+        if flags & TIMER_ABSTIME == 0 {
+            // Relative sleeps don't depend on the clock
+            return Self::nanosleep(rqtp, rmtp);
+        }
+
+        let mut now = timespec::default();
+        if Self::clock_gettime(clk_id, &mut now) < 0 {
+            return -1;
+        }
+
+        let (mut tv_sec, mut tv_nsec) =
+            unsafe { ((*rqtp).tv_sec - now.tv_sec, (*rqtp).tv_nsec - now.tv_nsec) };
+        if tv_nsec < 0 {
+            tv_sec -= 1;
+            tv_nsec += 1_000_000_000;
+        }
+        if tv_sec < 0 {
+            // The deadline has already passed
+            return 0;
+        }
+
+        // The remaining time is not reported for absolute sleeps
+        Self::nanosleep(&timespec { tv_sec, tv_nsec }, ptr::null_mut())
+    }
+
     fn close(fd: c_int) -> c_int {
         e(syscall::close(fd as usize)) as c_int
     }
@@ -181,6 +326,23 @@ impl Pal for Sys {
         e(syscall::dup2(fd1 as usize, fd2 as usize, &[])) as c_int
     }
 
+    fn dup3(fd1: c_int, fd2: c_int, flags: c_int) -> c_int {
+        if fd1 == fd2 || flags & !fcntl::O_CLOEXEC != 0 {
+            unsafe { errno = EINVAL };
+            return -1;
+        }
+        let fd = Self::dup2(fd1, fd2);
+        //TODO: Set close-on-exec atomically
+        if fd >= 0 && flags & fcntl::O_CLOEXEC != 0 {
+            Self::fcntl(fd, fcntl::F_SETFD, fcntl::FD_CLOEXEC as c_ulong);
+        }
+        fd
+    }
+
+    fn eventfd(_initval: c_uint, _flags: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     fn exit(status: c_int) -> ! {
         let _ = syscall::exit(status as usize);
         loop {}
@@ -324,6 +486,15 @@ impl Pal for Sys {
         e(syscall::fexec(*file as usize, &args, &envs)) as c_int
     }
 
+    fn fadvise(_fd: c_int, _offset: off_t, _len: off_t, _advice: c_int) -> c_int {
+        // TODO: Redox takes no I/O hints, which are only advisory anyway
+        0
+    }
+
+    fn fallocate(_fd: c_int, _mode: c_int, _offset: off_t, _len: off_t) -> c_int {
+        e(Err(syscall::Error::new(syscall::EOPNOTSUPP))) as c_int
+    }
+
     fn fchdir(fd: c_int) -> c_int {
         let mut buf = [0; 4096];
         let res = e(syscall::fpath(fd as usize, &mut buf));
@@ -342,7 +513,7 @@ impl Pal for Sys {
         e(syscall::fchown(fd as usize, owner as u32, group as u32)) as c_int
     }
 
-    fn fcntl(fd: c_int, cmd: c_int, args: c_int) -> c_int {
+    fn fcntl(fd: c_int, cmd: c_int, args: c_ulong) -> c_int {
         e(syscall::fcntl(fd as usize, cmd as usize, args as usize)) as c_int
     }
 
@@ -389,6 +560,29 @@ impl Pal for Sys {
         }
     }
 
+    fn fstatat(dirfd: c_int, path: &CStr, buf: *mut stat, flag: c_int) -> c_int {
+        if flag & !(fcntl::AT_SYMLINK_NOFOLLOW | fcntl::AT_EMPTY_PATH) != 0 {
+            unsafe { errno = EINVAL };
+            return -1;
+        }
+        if flag & fcntl::AT_EMPTY_PATH != 0 && path.to_bytes().is_empty() {
+            return Self::fstat(dirfd, buf);
+        }
+
+        let mut oflag = fcntl::O_PATH | fcntl::O_CLOEXEC;
+        if flag & fcntl::AT_SYMLINK_NOFOLLOW != 0 {
+            oflag |= fcntl::O_NOFOLLOW;
+        }
+        let path = match at_path(dirfd, path) {
+            Some(path) => path,
+            None => return -1,
+        };
+        match File::open(&path, oflag) {
+            Ok(file) => Self::fstat(*file, buf),
+            Err(_) => -1,
+        }
+    }
+
     fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int {
         let mut kbuf: redox_statvfs = redox_statvfs::default();
         match e(syscall::fstatvfs(fildes as usize, &mut kbuf)) {
@@ -423,13 +617,16 @@ impl Pal for Sys {
         e(syscall::ftruncate(fd as usize, len as usize)) as c_int
     }
 
-    fn futex(addr: *mut c_int, op: c_int, val: c_int) -> c_int {
+    fn futex(addr: *mut c_int, op: c_int, val: c_int, timeout: *const timespec) -> c_int {
+        let timeout = unsafe { timeout.as_ref() }.map(redox_timespec::from);
         match unsafe {
             syscall::futex(
                 addr as *mut i32,
                 op as usize,
                 val as i32,
-                0,
+                timeout
+                    .as_ref()
+                    .map_or(0, |timeout| timeout as *const redox_timespec as usize),
                 ptr::null_mut(),
             )
         } {
@@ -439,14 +636,28 @@ impl Pal for Sys {
     }
 
     fn futimens(fd: c_int, times: *const timespec) -> c_int {
-        let times = [unsafe { redox_timespec::from(&*times) }, unsafe {
-            redox_timespec::from(&*times.offset(1))
-        }];
+        let times = match unsafe { utime_times(fd, times) } {
+            Some(times) => times,
+            None => return -1,
+        };
         e(syscall::futimens(fd as usize, &times)) as c_int
     }
 
-    fn utimens(path: &CStr, times: *const timespec) -> c_int {
-        match File::open(path, fcntl::O_PATH | fcntl::O_CLOEXEC) {
+    fn utimensat(dirfd: c_int, path: &CStr, times: *const timespec, flag: c_int) -> c_int {
+        if flag & !fcntl::AT_SYMLINK_NOFOLLOW != 0 {
+            unsafe { errno = EINVAL };
+            return -1;
+        }
+        let mut oflag = fcntl::O_PATH | fcntl::O_CLOEXEC;
+        if flag & fcntl::AT_SYMLINK_NOFOLLOW != 0 {
+            oflag |= fcntl::O_NOFOLLOW;
+        }
+
+        let path = match at_path(dirfd, path) {
+            Some(path) => path,
+            None => return -1,
+        };
+        match File::open(&path, oflag) {
             Ok(file) => Self::futimens(*file, times),
             Err(_) => -1,
         }
@@ -631,6 +842,16 @@ impl Pal for Sys {
         0
     }
 
+    unsafe fn getrusage(who: c_int, r_usage: *mut rusage) -> c_int {
+        if who != RUSAGE_SELF && who != RUSAGE_CHILDREN && who != RUSAGE_THREAD {
+            errno = EINVAL;
+            return -1;
+        }
+        //TODO: Fill in what the kernel keeps track of
+        ptr::write_bytes(r_usage, 0, 1);
+        0
+    }
+
     fn gettid() -> pid_t {
         //TODO
         Self::getpid()
@@ -661,11 +882,34 @@ impl Pal for Sys {
         e(syscall::getuid()) as pid_t
     }
 
+    fn inotify_add_watch(_fd: c_int, _path: &CStr, _mask: u32) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn inotify_init1(_flags: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn inotify_rm_watch(_fd: c_int, _wd: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     fn link(path1: &CStr, path2: &CStr) -> c_int {
         e(unsafe { syscall::link(path1.as_ptr() as *const u8, path2.as_ptr() as *const u8) })
             as c_int
     }
 
+    fn linkat(fd1: c_int, path1: &CStr, fd2: c_int, path2: &CStr, flag: c_int) -> c_int {
+        if flag & !fcntl::AT_SYMLINK_FOLLOW != 0 {
+            unsafe { errno = EINVAL };
+            return -1;
+        }
+        match (at_path(fd1, path1), at_path(fd2, path2)) {
+            (Some(path1), Some(path2)) => Self::link(&path1, &path2),
+            _ => -1,
+        }
+    }
+
     fn lseek(fd: c_int, offset: off_t, whence: c_int) -> off_t {
         e(syscall::lseek(
             fd as usize,
@@ -685,6 +929,13 @@ impl Pal for Sys {
         }
     }
 
+    fn mkdirat(dirfd: c_int, path: &CStr, mode: mode_t) -> c_int {
+        match at_path(dirfd, path) {
+            Some(path) => Self::mkdir(&path, mode),
+            None => -1,
+        }
+    }
+
     fn mkfifo(path: &CStr, mode: mode_t) -> c_int {
         match File::create(
             path,
@@ -696,6 +947,14 @@ impl Pal for Sys {
         }
     }
 
+    unsafe fn mlock(addr: *const c_void, len: usize) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn mlockall(flags: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     unsafe fn mmap(
         addr: *mut c_void,
         len: usize,
@@ -741,6 +1000,50 @@ impl Pal for Sys {
         )) as c_int
     }
 
+    unsafe fn mq_getsetattr(_mqdes: mqd_t, _new: *const mq_attr, _old: *mut mq_attr) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn mq_notify(_mqdes: mqd_t, _notification: *const sigevent) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn mq_open(_name: &CStr, _oflag: c_int, _mode: mode_t, _attr: *const mq_attr) -> mqd_t {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as mqd_t
+    }
+
+    unsafe fn mq_timedreceive(
+        _mqdes: mqd_t,
+        _msg: &mut [u8],
+        _msg_prio: *mut c_uint,
+        _abstime: *const timespec,
+    ) -> ssize_t {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as ssize_t
+    }
+
+    unsafe fn mq_timedsend(
+        _mqdes: mqd_t,
+        _msg: &[u8],
+        _msg_prio: c_uint,
+        _abstime: *const timespec,
+    ) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn mq_unlink(_name: &CStr) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn mremap(
+        addr: *mut c_void,
+        len: usize,
+        new_len: usize,
+        flags: c_int,
+        new_addr: *mut c_void,
+    ) -> *mut c_void {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as *mut c_void
+    }
+
     unsafe fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int {
         eprintln!("msync {:p} {:x} {:x}", addr, len, flags);
         e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
@@ -753,6 +1056,14 @@ impl Pal for Sys {
         */
     }
 
+    unsafe fn munlock(addr: *const c_void, len: usize) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn munlockall() -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int {
         if e(syscall::funmap(addr as usize, len)) == !0 {
             return !0;
@@ -789,6 +1100,13 @@ impl Pal for Sys {
         )) as c_int
     }
 
+    fn openat(dirfd: c_int, path: &CStr, oflag: c_int, mode: mode_t) -> c_int {
+        match at_path(dirfd, path) {
+            Some(path) => Self::open(&path, oflag, mode),
+            None => -1,
+        }
+    }
+
     fn pipe2(fds: &mut [c_int], flags: c_int) -> c_int {
         let mut usize_fds: [usize; 2] = [0; 2];
         let res = e(syscall::pipe2(&mut usize_fds, flags as usize));
@@ -797,6 +1115,34 @@ impl Pal for Sys {
         res as c_int
     }
 
+    unsafe fn preadv(fd: c_int, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t {
+        vectored(fd, iov, iovcnt, offset, |buf| Sys::read(fd, buf))
+    }
+
+    unsafe fn preadv2(
+        fd: c_int,
+        iov: *const iovec,
+        iovcnt: c_int,
+        offset: off_t,
+        flags: c_int,
+    ) -> ssize_t {
+        if flags != 0 {
+            errno = EOPNOTSUPP;
+            return -1;
+        }
+        vectored(fd, iov, iovcnt, offset, |buf| Sys::read(fd, buf))
+    }
+
+    unsafe fn prlimit(
+        pid: pid_t,
+        resource: c_int,
+        new_limit: *const rlimit,
+        old_limit: *mut rlimit,
+    ) -> c_int {
+        //TODO
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     #[cfg(target_arch = "x86_64")]
     unsafe fn pte_clone(stack: *mut usize) -> pid_t {
         let flags = syscall::CLONE_VM
@@ -845,6 +1191,59 @@ impl Pal for Sys {
         e(syscall::Error::demux(pid)) as pid_t
     }
 
+    fn pthread_getname(tid: pid_t, out: &mut [u8]) -> ssize_t {
+        match unsafe { Tcb::of_thread(tid) } {
+            Some(tcb) => {
+                let len = tcb
+                    .name
+                    .iter()
+                    .position(|&b| b == 0)
+                    .unwrap_or(tcb.name.len());
+                let len = len.min(out.len());
+                out[..len].copy_from_slice(&tcb.name[..len]);
+                len as ssize_t
+            }
+            None => {
+                unsafe { errno = ESRCH };
+                -1
+            }
+        }
+    }
+
+    fn pthread_setname(tid: pid_t, name: &CStr) -> c_int {
+        match unsafe { Tcb::of_thread(tid) } {
+            Some(tcb) => {
+                let name = name.to_bytes_with_nul();
+                let len = name.len().min(tcb.name.len());
+                tcb.name = [0; 16];
+                tcb.name[..len].copy_from_slice(&name[..len]);
+                0
+            }
+            None => {
+                unsafe { errno = ESRCH };
+                -1
+            }
+        }
+    }
+
+    unsafe fn pwritev(fd: c_int, iov: *const iovec, iovcnt: c_int, offset: off_t) -> ssize_t {
+        vectored(fd, iov, iovcnt, offset, |buf| Sys::write(fd, buf))
+    }
+
+    unsafe fn pwritev2(
+        fd: c_int,
+        iov: *const iovec,
+        iovcnt: c_int,
+        offset: off_t,
+        flags: c_int,
+    ) -> ssize_t {
+        if flags != 0 {
+            errno = EOPNOTSUPP;
+            return -1;
+        }
+        vectored(fd, iov, iovcnt, offset, |buf| Sys::write(fd, buf))
+    }
+
     fn read(fd: c_int, buf: &mut [u8]) -> ssize_t {
         e(syscall::read(fd as usize, buf)) as ssize_t
     }
@@ -876,6 +1275,10 @@ impl Pal for Sys {
         0
     }
 
+    unsafe fn readv(fd: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+        vectored(fd, iov, iovcnt, -1, |buf| Sys::read(fd, buf))
+    }
+
     fn rename(oldpath: &CStr, newpath: &CStr) -> c_int {
         match File::open(oldpath, fcntl::O_PATH | fcntl::O_CLOEXEC) {
             Ok(file) => e(syscall::frename(*file as usize, newpath.to_bytes())) as c_int,
@@ -883,14 +1286,33 @@ impl Pal for Sys {
         }
     }
 
+    fn renameat(olddirfd: c_int, oldpath: &CStr, newdirfd: c_int, newpath: &CStr) -> c_int {
+        match (at_path(olddirfd, oldpath), at_path(newdirfd, newpath)) {
+            (Some(oldpath), Some(newpath)) => Self::rename(&oldpath, &newpath),
+            _ => -1,
+        }
+    }
+
     fn rmdir(path: &CStr) -> c_int {
         e(syscall::rmdir(path.to_bytes())) as c_int
     }
 
+    fn sched_setparam(_pid: pid_t, _param: *const sched_param) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn sched_setscheduler(_pid: pid_t, _policy: c_int, _param: *const sched_param) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     fn sched_yield() -> c_int {
         e(syscall::sched_yield()) as c_int
     }
 
+    fn sendfile(_out_fd: c_int, _in_fd: c_int, _offset: *mut off_t, _count: size_t) -> ssize_t {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as ssize_t
+    }
+
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
         e(syscall::setpgid(pid as usize, pgid as usize)) as c_int
     }
@@ -903,6 +1325,35 @@ impl Pal for Sys {
         e(syscall::setreuid(ruid as usize, euid as usize)) as c_int
     }
 
+    unsafe fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+        //TODO
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn signalfd(_fd: c_int, _mask: *const sigset_t, _flags: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn spawn_clone(stack: *mut usize) -> pid_t {
+        // There is no vfork, so fork and call the entry point on the child's
+        // copy of the stack it was given
+        let pid = Self::fork();
+        if pid == 0 {
+            let entry: extern "C" fn(usize, usize, usize, usize, usize, usize) =
+                mem::transmute(*stack);
+            entry(
+                *stack.add(1),
+                *stack.add(2),
+                *stack.add(3),
+                *stack.add(4),
+                *stack.add(5),
+                *stack.add(6),
+            );
+            Self::exit(127);
+        }
+        pid
+    }
+
     fn symlink(path1: &CStr, path2: &CStr) -> c_int {
         let mut file = match File::create(
             path2,
@@ -920,6 +1371,30 @@ impl Pal for Sys {
         0
     }
 
+    fn symlinkat(path1: &CStr, dirfd: c_int, path2: &CStr) -> c_int {
+        match at_path(dirfd, path2) {
+            Some(path2) => Self::symlink(path1, &path2),
+            None => -1,
+        }
+    }
+
+    fn timerfd_create(_clockid: clockid_t, _flags: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn timerfd_gettime(_fd: c_int, _curr_value: *mut itimerspec) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn timerfd_settime(
+        _fd: c_int,
+        _flags: c_int,
+        _new_value: *const itimerspec,
+        _old_value: *mut itimerspec,
+    ) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
     fn umask(mask: mode_t) -> mode_t {
         e(syscall::umask(mask as usize)) as mode_t
     }
@@ -1013,6 +1488,98 @@ impl Pal for Sys {
         e(syscall::unlink(path.to_bytes())) as c_int
     }
 
+    fn unlinkat(dirfd: c_int, path: &CStr, flag: c_int) -> c_int {
+        if flag & !fcntl::AT_REMOVEDIR != 0 {
+            unsafe { errno = EINVAL };
+            return -1;
+        }
+        let path = match at_path(dirfd, path) {
+            Some(path) => path,
+            None => return -1,
+        };
+        if flag & fcntl::AT_REMOVEDIR != 0 {
+            Self::rmdir(&path)
+        } else {
+            Self::unlink(&path)
+        }
+    }
+
+    unsafe fn wait4(
+        pid: pid_t,
+        stat_loc: *mut c_int,
+        options: c_int,
+        rusage: *mut rusage,
+    ) -> pid_t {
+        let res = Self::waitpid(pid, stat_loc, options);
+        if res > 0 && !rusage.is_null() {
+            //TODO: Fill in what the kernel keeps track of
+            ptr::write_bytes(rusage, 0, 1);
+        }
+        res
+    }
+
+    unsafe fn waitid(idtype: idtype_t, id: id_t, infop: *mut siginfo_t, options: c_int) -> c_int {
+        let events = sys_wait::WEXITED | sys_wait::WSTOPPED | sys_wait::WCONTINUED;
+        if options & events == 0 {
+            errno = EINVAL;
+            return -1;
+        }
+        // Redox cannot leave a child waitable after reporting it
+        if options & sys_wait::WNOWAIT != 0 {
+            errno = EOPNOTSUPP;
+            return -1;
+        }
+        let pid = match idtype {
+            sys_wait::P_ALL => !0,
+            sys_wait::P_PID => id as pid_t,
+            sys_wait::P_PGID => -(id as pid_t),
+            _ => {
+                errno = EINVAL;
+                return -1;
+            }
+        };
+
+        let mut flags = options & sys_wait::WNOHANG;
+        if options & sys_wait::WSTOPPED != 0 {
+            flags |= sys_wait::WUNTRACED;
+        }
+        if options & sys_wait::WCONTINUED != 0 {
+            flags |= sys_wait::WCONTINUED;
+        }
+        let mut status = 0;
+        let res = Self::waitpid(pid, &mut status, flags);
+        if res < 0 {
+            return -1;
+        }
+
+        // With WNOHANG and no child to report, si_pid is left at zero
+        ptr::write_bytes(infop, 0, 1);
+        if res > 0 {
+            let info = &mut *infop;
+            let status = status as usize;
+            info.si_signo = SIGCHLD as c_int;
+            info.si_pid = res;
+            if syscall::wifexited(status) {
+                info.si_code = CLD_EXITED;
+                info.si_status = syscall::wexitstatus(status) as c_int;
+            } else if syscall::wifsignaled(status) {
+                info.si_code = if syscall::wcoredump(status) {
+                    CLD_DUMPED
+                } else {
+                    CLD_KILLED
+                };
+                info.si_status = syscall::wtermsig(status) as c_int;
+            } else if syscall::wifstopped(status) {
+                info.si_code = CLD_STOPPED;
+                info.si_status = syscall::wstopsig(status) as c_int;
+            } else {
+                info.si_code = CLD_CONTINUED;
+                info.si_status = SIGCONT as c_int;
+            }
+        }
+        0
+    }
+
     fn waitpid(mut pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
         if pid == !0 {
             pid = 0;
@@ -1078,6 +1645,10 @@ impl Pal for Sys {
         e(syscall::write(fd as usize, buf)) as ssize_t
     }
 
+    unsafe fn writev(fd: c_int, iov: *const iovec, iovcnt: c_int) -> ssize_t {
+        vectored(fd, iov, iovcnt, -1, |buf| Sys::write(fd, buf))
+    }
+
     fn verify() -> bool {
         // GETPID on Redox is 20, which is WRITEV on Linux
         e(unsafe { syscall::syscall5(syscall::number::SYS_GETPID, !0, !0, !0, !0, !0) }) != !0
@@ -8,8 +8,9 @@ use super::{
 use crate::{
     header::{
         errno::EINVAL,
-        signal::{sigaction, sigset_t, stack_t},
+        signal::{sigaction, sigevent, siginfo_t, sigset_t, stack_t},
         sys_time::{itimerval, ITIMER_REAL},
+        time::{itimerspec, timespec},
     },
     platform::errno,
 };
@@ -152,4 +153,46 @@ impl PalSignal for Sys {
         }
         ret
     }
+
+    unsafe fn sigtimedwait(
+        _set: *const sigset_t,
+        _info: *mut siginfo_t,
+        _timeout: *const timespec,
+    ) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn timer_create(
+        _clock_id: clockid_t,
+        _evp: *const sigevent,
+        _timerid: *mut c_int,
+    ) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn timer_delete(_timerid: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn timer_getoverrun(_timerid: c_int) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn timer_gettime(_timerid: c_int, _value: *mut itimerspec) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    unsafe fn timer_settime(
+        _timerid: c_int,
+        _flags: c_int,
+        _value: *const itimerspec,
+        _ovalue: *mut itimerspec,
+    ) -> c_int {
+        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+    }
+
+    fn tkill(tid: pid_t, sig: c_int) -> c_int {
+        // Threads have process ids of their own
+        Self::kill(tid, sig)
+    }
 }
@@ -1,9 +1,43 @@
-use crate::platform::{Pal, Sys};
+use crate::{
+    header::errno::ENOSYS,
+    platform::{types::*, Pal, Sys},
+};
 
 // Stub for call used in exit
 #[no_mangle]
 pub extern "C" fn pthread_terminate() {}
 
+// Stub for call used in cancellation points
+#[no_mangle]
+pub extern "C" fn pthread_testcancel() {}
+
+// Stubs for the pthreads-emb calls wrapped by header::pthread
+#[no_mangle]
+pub extern "C" fn __pte_pthread_attr_destroy(_attr: *mut c_void) -> c_int {
+    ENOSYS
+}
+
+#[no_mangle]
+pub extern "C" fn __pte_pthread_attr_init(_attr: *mut c_void) -> c_int {
+    ENOSYS
+}
+
+#[no_mangle]
+pub extern "C" fn __pte_pthread_create(
+    _thread: *mut c_void,
+    _attr: *const c_void,
+    _start: extern "C" fn(*mut c_void) -> *mut c_void,
+    _arg: *mut c_void,
+) -> c_int {
+    ENOSYS
+}
+
+// Stub for call used by timer_create
+#[no_mangle]
+pub extern "C" fn pthread_detach(_thread: *mut c_void) -> c_int {
+    ENOSYS
+}
+
 mod epoll;
 
 #[test]
@@ -1,8 +1,8 @@
 use alloc::vec::Vec;
-use core::{intrinsics, ptr};
+use core::{intrinsics, mem, ptr};
 
 use crate::{
-    header::{stdio, stdlib},
+    header::{stdio, stdlib, sys_auxv},
     ld_so,
     platform::{self, new_mspace, types::*, Pal, Sys},
     ALLOCATOR,
@@ -104,6 +104,30 @@ extern "C" fn init_array() {
         init_complete = true
     }
 }
+unsafe fn stack_guard_init() {
+    extern "C" {
+        static mut __stack_chk_guard: usize;
+    }
+
+    let mut guard = [0u8; mem::size_of::<usize>()];
+    match sys_auxv::find_auxv(sys_auxv::AT_RANDOM) {
+        // The kernel provides 16 random bytes for exactly this purpose
+        Some(random) if random != 0 => {
+            ptr::copy_nonoverlapping(random as *const u8, guard.as_mut_ptr(), guard.len());
+        }
+        _ => {
+            Sys::getrandom(&mut guard, 0);
+        }
+    }
+
+    // Keep the lowest byte zero, so that string functions reading past a buffer stop at the
+    // canary instead of leaking it, and writing strings over it cannot reproduce it
+    __stack_chk_guard = usize::from_ne_bytes(guard) & !0xff;
+    if let Some(tcb) = ld_so::tcb::Tcb::current() {
+        tcb.stack_guard = __stack_chk_guard;
+    }
+}
+
 fn io_init() {
     unsafe {
         // Initialize stdin/stdout/stderr, see https://github.com/rust-lang/rust/issues/51718
@@ -151,6 +175,16 @@ pub unsafe extern "C" fn relibc_start(sp: &'static Stack) -> ! {
     platform::inner_environ = copy_string_array(envp, len);
     platform::environ = platform::inner_environ.as_mut_ptr();
 
+    // Set up auxv
+    platform::auxv = sp.auxv();
+    platform::secure_execution = match sys_auxv::find_auxv(sys_auxv::AT_SECURE) {
+        Some(secure) => secure != 0,
+        None => Sys::getuid() != Sys::geteuid() || Sys::getgid() != Sys::getegid(),
+    };
+
+    // Choose the stack protector canary before any protected code runs
+    stack_guard_init();
+
     init_array();
 
     // Run preinit array
@@ -1,19 +1,22 @@
 pub mod mutex;
 pub mod once;
+pub mod semaphore;
 
 pub use self::{
     mutex::{Mutex, MutexGuard},
     once::Once,
+    semaphore::Semaphore,
 };
 
 use crate::platform::{types::*, Pal, Sys};
 use core::{
     cell::UnsafeCell,
     ops::Deref,
+    ptr,
     sync::atomic::{self, AtomicI32 as AtomicInt},
 };
 
-const FUTEX_WAIT: c_int = 0;
+pub(crate) const FUTEX_WAIT: c_int = 0;
 const FUTEX_WAKE: c_int = 1;
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -35,13 +38,19 @@ impl AtomicLock {
         }
     }
     pub fn notify_one(&self) {
-        Sys::futex(unsafe { &mut *self.atomic.get() }.get_mut(), FUTEX_WAKE, 1);
+        Sys::futex(
+            unsafe { &mut *self.atomic.get() }.get_mut(),
+            FUTEX_WAKE,
+            1,
+            ptr::null(),
+        );
     }
     pub fn notify_all(&self) {
         Sys::futex(
             unsafe { &mut *self.atomic.get() }.get_mut(),
             FUTEX_WAKE,
             c_int::max_value(),
+            ptr::null(),
         );
     }
     pub fn wait_if(&self, value: c_int) {
@@ -49,6 +58,7 @@ impl AtomicLock {
             unsafe { &mut *self.atomic.get() }.get_mut(),
             FUTEX_WAIT,
             value,
+            ptr::null(),
         );
     }
     /// A general way to efficiently wait for what might be a long time, using two closures:
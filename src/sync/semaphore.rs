@@ -0,0 +1,133 @@
+use super::{AtomicLock, FUTEX_WAIT, FUTEX_WAKE};
+use crate::{
+    header::time::{timespec, CLOCK_REALTIME},
+    platform::{types::*, Pal, Sys},
+};
+use core::{ptr, sync::atomic::Ordering};
+
+/// A counting semaphore on a single futex word. It does not rely on any
+/// process local state, so it works across processes when placed in shared
+/// memory
+#[repr(C)]
+pub struct Semaphore {
+    lock: AtomicLock,
+}
+impl Semaphore {
+    pub const fn new(value: c_int) -> Self {
+        Self {
+            lock: AtomicLock::new(value),
+        }
+    }
+
+    pub fn value(&self) -> c_int {
+        self.lock.load(Ordering::SeqCst)
+    }
+
+    pub fn post(&self, count: c_int) {
+        self.lock.fetch_add(count, Ordering::SeqCst);
+        Sys::futex(
+            unsafe { &mut *self.lock.atomic.get() }.get_mut(),
+            FUTEX_WAKE,
+            count,
+            ptr::null(),
+        );
+    }
+
+    /// Like `post`, but fails without changing the value if that would take
+    /// it above `max`
+    pub fn try_post(&self, count: c_int, max: c_int) -> bool {
+        let mut value = self.lock.load(Ordering::SeqCst);
+        loop {
+            if value > max - count {
+                return false;
+            }
+            match self.lock.compare_exchange_weak(
+                value,
+                value + count,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => break,
+                Err(current) => value = current,
+            }
+        }
+        Sys::futex(
+            unsafe { &mut *self.lock.atomic.get() }.get_mut(),
+            FUTEX_WAKE,
+            count,
+            ptr::null(),
+        );
+        true
+    }
+
+    /// Decrement the value if it is positive, without waiting
+    pub fn try_wait(&self) -> bool {
+        let mut value = self.lock.load(Ordering::SeqCst);
+        while value > 0 {
+            match self.lock.compare_exchange_weak(
+                value,
+                value - 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(_) => return true,
+                Err(current) => value = current,
+            }
+        }
+        false
+    }
+
+    /// Wait until the value can be decremented, or until the absolute
+    /// CLOCK_REALTIME `deadline` passes. Returns false on timeout
+    pub fn wait(&self, deadline: Option<&timespec>) -> bool {
+        self.wait_unless(
+            deadline,
+            || false,
+            |addr, val, timeout| Sys::futex(addr, FUTEX_WAIT, val, timeout),
+        )
+    }
+
+    /// Like `wait`, but also gives up when `stop` returns true. It is checked
+    /// before blocking and whenever the thread is woken, as by a signal. The
+    /// thread blocks in `futex_wait`, which waits on the futex word while it
+    /// holds a value, for at most a relative timeout. Returns false on timeout
+    /// or when stopped
+    pub fn wait_unless<F: FnMut() -> bool>(
+        &self,
+        deadline: Option<&timespec>,
+        mut stop: F,
+        futex_wait: fn(*mut c_int, c_int, *const timespec) -> c_int,
+    ) -> bool {
+        loop {
+            if self.try_wait() {
+                return true;
+            }
+            if stop() {
+                return false;
+            }
+
+            // The futex takes a relative timeout
+            let mut relative = timespec::default();
+            if let Some(deadline) = deadline {
+                let mut now = timespec::default();
+                Sys::clock_gettime(CLOCK_REALTIME, &mut now);
+                relative.tv_sec = deadline.tv_sec - now.tv_sec;
+                relative.tv_nsec = deadline.tv_nsec - now.tv_nsec;
+                if relative.tv_nsec < 0 {
+                    relative.tv_sec -= 1;
+                    relative.tv_nsec += 1_000_000_000;
+                }
+                if relative.tv_sec < 0 {
+                    return false;
+                }
+            }
+
+            // Returns early if the value is no longer 0, or on a signal
+            futex_wait(
+                unsafe { &mut *self.lock.atomic.get() }.get_mut(),
+                0,
+                deadline.map_or(ptr::null(), |_| &relative),
+            );
+        }
+    }
+}
@@ -0,0 +1,39 @@
+#include <signal.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Compiled out, so the condition must not be evaluated
+#define NDEBUG
+#include <assert.h>
+
+static int evaluated = 0;
+
+static void disabled(void) {
+    assert(++evaluated == 42);
+}
+
+// Including again without NDEBUG turns assertions back on
+#undef NDEBUG
+#include <assert.h>
+
+static void failing(int value) {
+    assert(value + 1 == 3);
+    puts("unreachable");
+}
+
+static void failing_child(void *arg) {
+    (void) arg;
+    failing(1);
+}
+
+int main(void) {
+    disabled();
+    printf("evaluated with NDEBUG: %d\n", evaluated);
+
+    char message[256];
+    int wstatus = run_child_capture(STDERR_FILENO, failing_child, NULL, message, sizeof(message));
+    printf("stderr: %s", message);
+    printf("aborted: %d\n", WIFSIGNALED(wstatus) && WTERMSIG(wstatus) == SIGABRT);
+}
@@ -0,0 +1,50 @@
+#include <dirent.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static int compare(const void *a, const void *b) {
+    return strcmp(*(char * const *) a, *(char * const *) b);
+}
+
+int main(void) {
+    int fd = open("example_dir", O_RDONLY | O_DIRECTORY);
+    ERROR_IF(open, fd, == -1);
+
+    DIR *dir = fdopendir(fd);
+    ERROR_IF(fdopendir, dir, == NULL);
+    printf("dirfd returns the descriptor: %d\n", dirfd(dir) == fd);
+
+    // Collect and sort the names, as the order depends on the file system
+    char *names[32];
+    size_t count = 0;
+    struct dirent *entry;
+    while ((entry = readdir(dir)) && count < 32) {
+        names[count++] = strdup(entry->d_name);
+    }
+    qsort(names, count, sizeof(char *), compare);
+    for (size_t i = 0; i < count; i++) {
+        puts(names[i]);
+        free(names[i]);
+    }
+
+    // closedir closes the descriptor it was given
+    int status = closedir(dir);
+    ERROR_IF(closedir, status, == -1);
+    errno = 0;
+    status = close(fd);
+    printf("close after closedir: %d, EBADF %d\n", status, errno == EBADF);
+
+    // Only directories can be wrapped
+    fd = open("dirent/fdopendir.c", O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+    errno = 0;
+    dir = fdopendir(fd);
+    printf("fdopendir on a file: NULL %d, ENOTDIR %d\n", dir == NULL, errno == ENOTDIR);
+    close(fd);
+}
@@ -0,0 +1,71 @@
+#include <dirent.h>
+#include <fcntl.h>
+#include <limits.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// readdir reads records of varying length into a buffer of a few entries, so
+// names of every length from 1 to NAME_MAX make records end at every offset,
+// including right at the end of the buffer, and exercise refilling it.
+
+static const char *dir = "long_names_dir";
+
+static void path_of(char *path, size_t len) {
+    char name[NAME_MAX + 1];
+    memset(name, 'x', len);
+    name[len] = '\0';
+    snprintf(path, PATH_MAX, "%s/%s", dir, name);
+}
+
+int main(void) {
+    int status = mkdir(dir, 0755);
+    ERROR_IF(mkdir, status, == -1);
+
+    char path[PATH_MAX];
+    for (size_t len = 1; len <= NAME_MAX; len++) {
+        path_of(path, len);
+        int fd = open(path, O_WRONLY | O_CREAT | O_EXCL, 0644);
+        ERROR_IF(open, fd, == -1);
+        close(fd);
+    }
+
+    int seen[NAME_MAX + 1] = { 0 };
+    int others = 0;
+
+    DIR *d = opendir(dir);
+    ERROR_IF(opendir, d, == NULL);
+    errno = 0;
+    struct dirent *entry;
+    while ((entry = readdir(d)) != NULL) {
+        size_t len = strlen(entry->d_name);
+        if (len > NAME_MAX) {
+            printf("overlong name of length %zu\n", len);
+        } else if (strspn(entry->d_name, "x") == len) {
+            seen[len]++;
+        } else if (strcmp(entry->d_name, ".") != 0 && strcmp(entry->d_name, "..") != 0) {
+            others++;
+        }
+    }
+    ERROR_IF(readdir, errno, != 0);
+    closedir(d);
+
+    int found = 0;
+    for (size_t len = 1; len <= NAME_MAX; len++) {
+        if (seen[len] == 1) {
+            found++;
+        } else {
+            printf("name of length %zu seen %d times\n", len, seen[len]);
+        }
+    }
+    printf("found %d of %d names, %d unexpected entries\n", found, NAME_MAX, others);
+
+    for (size_t len = 1; len <= NAME_MAX; len++) {
+        path_of(path, len);
+        unlink(path);
+    }
+    rmdir(dir);
+}
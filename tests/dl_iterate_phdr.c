@@ -0,0 +1,68 @@
+#include <elf.h>
+#include <fcntl.h>
+#include <link.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+struct state {
+    int calls;
+    int program_found;
+    int program_phnum;
+    int phnum_matches;
+};
+
+static int program_data = 1;
+
+static int callback(struct dl_phdr_info *info, size_t size, void *data) {
+    struct state *state = data;
+    state->calls++;
+    ERROR_IF(dl_iterate_phdr, size, < sizeof(struct dl_phdr_info));
+    ERROR_IF(dl_iterate_phdr, info->dlpi_name, == NULL);
+
+    // The program is the object with a loaded segment containing its data
+    for (int i = 0; i < info->dlpi_phnum; i++) {
+        const ElfW(Phdr) *phdr = &info->dlpi_phdr[i];
+        if (phdr->p_type != PT_LOAD) {
+            continue;
+        }
+        ElfW(Addr) start = info->dlpi_addr + phdr->p_vaddr;
+        ElfW(Addr) addr = (ElfW(Addr)) &program_data;
+        if (addr >= start && addr < start + phdr->p_memsz) {
+            state->program_found++;
+            state->phnum_matches = info->dlpi_phnum == state->program_phnum;
+        }
+    }
+    return 0;
+}
+
+static int stop(struct dl_phdr_info *info, size_t size, void *data) {
+    (void) info;
+    (void) size;
+    int *calls = data;
+    (*calls)++;
+    return 7;
+}
+
+int main(void) {
+    // The program headers of the running binary
+    int fd = open("/proc/self/exe", O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+    ElfW(Ehdr) ehdr;
+    ssize_t count = read(fd, &ehdr, sizeof(ehdr));
+    ERROR_IF(read, count, != (ssize_t) sizeof(ehdr));
+    close(fd);
+
+    struct state state = { 0 };
+    state.program_phnum = ehdr.e_phnum;
+    int ret = dl_iterate_phdr(callback, &state);
+    printf("ret %d, at least two objects: %d\n", ret, state.calls >= 2);
+    printf("program found once: %d, phnum matches: %d\n",
+        state.program_found == 1, state.phnum_matches);
+
+    // Iteration stops at the first nonzero return
+    int calls = 0;
+    ret = dl_iterate_phdr(stop, &calls);
+    printf("stopped: ret %d, calls %d\n", ret, calls);
+}
@@ -0,0 +1,39 @@
+#include <link.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+struct state {
+    int program_found;
+    int phnum;
+};
+
+static int program_data = 1;
+
+static int callback(struct dl_phdr_info *info, size_t size, void *data) {
+    struct state *state = data;
+    ERROR_IF(dl_iterate_phdr, size, < sizeof(struct dl_phdr_info));
+    ERROR_IF(dl_iterate_phdr, info->dlpi_name, == NULL);
+
+    // Without the dynamic linker, the program comes from the auxiliary vector
+    for (int i = 0; i < info->dlpi_phnum; i++) {
+        const ElfW(Phdr) *phdr = &info->dlpi_phdr[i];
+        if (phdr->p_type != PT_LOAD) {
+            continue;
+        }
+        ElfW(Addr) start = info->dlpi_addr + phdr->p_vaddr;
+        ElfW(Addr) addr = (ElfW(Addr)) &program_data;
+        if (addr >= start && addr < start + phdr->p_memsz) {
+            state->program_found++;
+            state->phnum = info->dlpi_phnum;
+        }
+    }
+    return 0;
+}
+
+int main(void) {
+    struct state state = { 0 };
+    int ret = dl_iterate_phdr(callback, &state);
+    printf("ret %d, program found once: %d, with headers: %d\n",
+        ret, state.program_found == 1, state.phnum > 0);
+}
@@ -0,0 +1,62 @@
+#include <dlfcn.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define LIBRARY "dlfcn_addr_lib.so"
+
+static int ends_with(const char *s, const char *suffix) {
+    size_t len = strlen(s);
+    size_t suffix_len = strlen(suffix);
+    return len >= suffix_len && strcmp(s + len - suffix_len, suffix) == 0;
+}
+
+int program_data = 1;
+
+int main(int argc, char **argv) {
+    // The library sits next to this binary
+    char path[256];
+    char *slash = strrchr(argv[0], '/');
+    int dir_len = slash ? (int) (slash - argv[0] + 1) : 0;
+    int len = snprintf(path, sizeof(path), "%.*s%s", dir_len, argv[0], LIBRARY);
+    ERROR_IF(snprintf, len, >= (int) sizeof(path));
+
+    void *handle = dlopen(path, RTLD_NOW);
+    ERROR_IF(dlopen, handle, == NULL);
+    void *function = dlsym(handle, "dlfcn_addr_function");
+    ERROR_IF(dlsym, function, == NULL);
+    void *data = dlsym(handle, "dlfcn_addr_data");
+    ERROR_IF(dlsym, data, == NULL);
+
+    Dl_info info;
+    int ret = dladdr(function, &info);
+    printf("function: %d, object %d, symbol '%s', address %d\n", ret,
+        ends_with(info.dli_fname, LIBRARY), info.dli_sname, info.dli_saddr == function);
+    printf("base before symbol: %d, ELF header at base: %d\n",
+        (char *) info.dli_fbase <= (char *) function,
+        memcmp(info.dli_fbase, "\x7f" "ELF", 4) == 0);
+    void *base = info.dli_fbase;
+
+    // An address inside a definition finds its start
+    ret = dladdr((char *) function + 1, &info);
+    printf("inside function: %d, symbol '%s', address %d\n", ret,
+        info.dli_sname, info.dli_saddr == function);
+
+    ret = dladdr(data, &info);
+    printf("data: %d, symbol '%s', same base %d\n", ret, info.dli_sname, info.dli_fbase == base);
+
+    // The program and libc are found too
+    ret = dladdr(&program_data, &info);
+    printf("program: %d, symbol '%s', other base %d\n", ret, info.dli_sname, info.dli_fbase != base);
+    ret = dladdr(&stdout, &info);
+    printf("libc: %d, symbol '%s'\n", ret, info.dli_sname);
+
+    // The stack doesn't belong to any object
+    int local = 0;
+    ret = dladdr(&local, &info);
+    printf("stack: %d\n", ret);
+
+    dlclose(handle);
+}
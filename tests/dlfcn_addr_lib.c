@@ -0,0 +1,6 @@
+// Loaded by dlfcn_addr
+int dlfcn_addr_data = 42;
+
+int dlfcn_addr_function(int x) {
+    return x * 2;
+}
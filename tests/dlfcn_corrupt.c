@@ -0,0 +1,118 @@
+#include <dlfcn.h>
+#include <elf.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define CORRUPT_PATH "./dlfcn_corrupt.so"
+
+// Count the lines of /proc/self/maps, one per mapping
+int count_mappings(void) {
+    FILE *maps = fopen("/proc/self/maps", "r");
+    ERROR_IF(fopen, maps, == NULL);
+
+    int count = 0;
+    int c;
+    while ((c = fgetc(maps)) != EOF) {
+        if (c == '\n') {
+            count++;
+        }
+    }
+    fclose(maps);
+    return count;
+}
+
+// Find a shared object mapped into this process to use as a template
+void find_library(char *path, size_t size) {
+    FILE *maps = fopen("/proc/self/maps", "r");
+    ERROR_IF(fopen, maps, == NULL);
+
+    char line[512];
+    path[0] = 0;
+    while (fgets(line, sizeof(line), maps)) {
+        char *name = strchr(line, '/');
+        if (name && strstr(name, "libc.so")) {
+            name[strcspn(name, "\n")] = 0;
+            strncpy(path, name, size - 1);
+            break;
+        }
+    }
+    fclose(maps);
+
+    if (!path[0]) {
+        puts("libc.so is not mapped");
+        exit(EXIT_FAILURE);
+    }
+}
+
+// Copy the library, making its first loadable segment claim more file data
+// than there is. It still parses and gets mapped, but fails while linking.
+void write_corrupt_copy(const char *template) {
+    FILE *in = fopen(template, "rb");
+    ERROR_IF(fopen, in, == NULL);
+    fseek(in, 0, SEEK_END);
+    long size = ftell(in);
+    ERROR_IF(ftell, size, == -1);
+    fseek(in, 0, SEEK_SET);
+
+    char *data = malloc(size);
+    ERROR_IF(malloc, data, == NULL);
+    size_t read = fread(data, 1, size, in);
+    ERROR_IF(fread, read, != (size_t) size);
+    fclose(in);
+
+    Elf64_Ehdr *ehdr = (Elf64_Ehdr *) data;
+    for (int i = 0; i < ehdr->e_phnum; i++) {
+        Elf64_Phdr *phdr = (Elf64_Phdr *) (data + ehdr->e_phoff + i * ehdr->e_phentsize);
+        if (phdr->p_type == PT_LOAD) {
+            phdr->p_filesz = size * 2;
+            break;
+        }
+    }
+
+    FILE *out = fopen(CORRUPT_PATH, "wb");
+    ERROR_IF(fopen, out, == NULL);
+    size_t written = fwrite(data, 1, size, out);
+    ERROR_IF(fwrite, written, != (size_t) size);
+    fclose(out);
+    free(data);
+}
+
+int main(void) {
+    char template[256];
+    find_library(template, sizeof(template));
+    write_corrupt_copy(template);
+
+    // Silence the dlopen diagnostics, their details depend on the template
+    fflush(stderr);
+    int saved_stderr = dup(STDERR_FILENO);
+    ERROR_IF(dup, saved_stderr, == -1);
+    int devnull = open("/dev/null", O_WRONLY);
+    ERROR_IF(open, devnull, == -1);
+    dup2(devnull, STDERR_FILENO);
+
+    // Warm up, so allocations made by the loader don't show up as mappings
+    void *handle = dlopen(CORRUPT_PATH, RTLD_NOW);
+    int failed = handle == NULL;
+
+    int before = count_mappings();
+    for (int i = 0; i < 16; i++) {
+        handle = dlopen(CORRUPT_PATH, RTLD_NOW);
+        failed &= handle == NULL;
+    }
+    int after = count_mappings();
+
+    dup2(saved_stderr, STDERR_FILENO);
+    close(devnull);
+    close(saved_stderr);
+
+    printf("dlopen failed: %d\n", failed);
+    printf("leaked mappings: %d\n", after - before);
+
+    int status = unlink(CORRUPT_PATH);
+    ERROR_IF(unlink, status, == -1);
+}
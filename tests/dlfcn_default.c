@@ -0,0 +1,58 @@
+#include <dlfcn.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// Layout of version 1 of relibc's export table
+struct export {
+    const char *name;
+    void *addr;
+};
+
+struct export_table {
+    uint32_t version;
+    size_t len;
+    const struct export *entries;
+};
+
+int main(void) {
+    // libc's own symbols are found without loading anything
+    int (*printf_ptr)(const char *, ...) = dlsym(RTLD_DEFAULT, "printf");
+    if (!printf_ptr) {
+        printf("dlsym(RTLD_DEFAULT, printf) failed\n");
+        exit(1);
+    }
+    printf_ptr("printf through dlsym: %d\n", 42);
+
+    void *(*malloc_ptr)(size_t) = dlsym(RTLD_DEFAULT, "malloc");
+    if (!malloc_ptr) {
+        printf("dlsym(RTLD_DEFAULT, malloc) failed\n");
+        exit(1);
+    }
+    char *buf = malloc_ptr(16);
+    strcpy(buf, "malloc works");
+    puts(buf);
+    free(buf);
+
+    // The export table holds the same functions
+    const struct export_table *table = dlsym(RTLD_DEFAULT, "__relibc_exports");
+    if (!table) {
+        printf("dlsym(RTLD_DEFAULT, __relibc_exports) failed\n");
+        exit(1);
+    }
+    printf("export table version: %u\n", table->version);
+    int found = 0;
+    for (size_t i = 0; i < table->len; i++) {
+        if (strcmp(table->entries[i].name, "printf") == 0) {
+            found = 1;
+            printf("printf in export table: %d\n", table->entries[i].addr == (void *) printf_ptr);
+        }
+    }
+    if (!found) {
+        printf("printf missing from export table\n");
+    }
+
+    void *missing = dlsym(RTLD_DEFAULT, "relibc_no_such_symbol");
+    printf("missing symbol is NULL: %d\n", missing == NULL);
+}
@@ -0,0 +1,35 @@
+#include <dlfcn.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define LIBRARY "dlfcn_dlclose_lib.so"
+
+static void program_handler(void) {
+    puts("program handler");
+}
+
+int main(int argc, char **argv) {
+    int ret = atexit(program_handler);
+    ERROR_IF(atexit, ret, != 0);
+
+    // The library sits next to this binary
+    char path[256];
+    char *slash = strrchr(argv[0], '/');
+    int dir_len = slash ? (int) (slash - argv[0] + 1) : 0;
+    int len = snprintf(path, sizeof(path), "%.*s%s", dir_len, argv[0], LIBRARY);
+    ERROR_IF(snprintf, len, >= (int) sizeof(path));
+
+    void *handle = dlopen(path, RTLD_NOW);
+    ERROR_IF(dlopen, handle, == NULL);
+
+    // The library's handlers run before its finalizers, not at exit
+    puts("closing");
+    ret = dlclose(handle);
+    ERROR_IF(dlclose, ret, != 0);
+    puts("closed");
+
+    return 0;
+}
@@ -0,0 +1,25 @@
+// Loaded by dlfcn_dlclose
+#include <stdio.h>
+#include <stdlib.h>
+
+int __cxa_atexit(void (*func)(void *), void *arg, void *dso);
+
+// Any address within the library identifies it
+static char object;
+
+static void object_destructor(void *arg) {
+    printf("library object destructor: %s\n", (char *) arg);
+}
+
+static void atexit_handler(void) {
+    puts("library atexit handler");
+}
+
+__attribute__((constructor)) static void init(void) {
+    __cxa_atexit(object_destructor, "object", &object);
+    atexit(atexit_handler);
+}
+
+__attribute__((destructor)) static void fini(void) {
+    puts("fini of the library");
+}
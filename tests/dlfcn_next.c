@@ -0,0 +1,42 @@
+#include <dlfcn.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define INTERPOSER "dlfcn_next_interposer.so"
+
+// Run again with the interposer, which sits next to this binary, preloaded
+void preload_interposer(char **argv) {
+    char path[256];
+    char *slash = strrchr(argv[0], '/');
+    int dir_len = slash ? (int) (slash - argv[0] + 1) : 0;
+    int len = snprintf(path, sizeof(path), "%.*s%s", dir_len, argv[0], INTERPOSER);
+    ERROR_IF(snprintf, len, >= (int) sizeof(path));
+
+    int status = setenv("LD_PRELOAD", path, 1);
+    ERROR_IF(setenv, status, == -1);
+    execv(argv[0], argv);
+    perror("execv");
+    exit(EXIT_FAILURE);
+}
+
+int main(int argc, char **argv) {
+    if (getenv("LD_PRELOAD") == NULL) {
+        preload_interposer(argv);
+    }
+
+    // The interposer's atoi adds one to what the real atoi returns
+    printf("atoi: %d\n", atoi("41"));
+
+    int (*default_atoi)(const char *) = dlsym(RTLD_DEFAULT, "atoi");
+    ERROR_IF(dlsym, default_atoi, == NULL);
+    printf("RTLD_DEFAULT atoi: %d\n", default_atoi("41"));
+
+    // The interposer is the next object after the program
+    int (*next_atoi)(const char *) = dlsym(RTLD_NEXT, "atoi");
+    ERROR_IF(dlsym, next_atoi, == NULL);
+    printf("RTLD_NEXT atoi: %d\n", next_atoi("41"));
+}
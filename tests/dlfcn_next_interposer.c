@@ -0,0 +1,11 @@
+// Preloaded by dlfcn_next, interposes on atoi
+#include <dlfcn.h>
+#include <stddef.h>
+
+int atoi(const char *s) {
+    int (*next)(const char *) = dlsym(RTLD_NEXT, "atoi");
+    if (next == NULL) {
+        return -1;
+    }
+    return next(s) + 1;
+}
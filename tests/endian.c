@@ -0,0 +1,56 @@
+#include <endian.h>
+#include <inttypes.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+#if BYTE_ORDER == LITTLE_ENDIAN
+    puts("BYTE_ORDER: little endian");
+#elif BYTE_ORDER == BIG_ENDIAN
+    puts("BYTE_ORDER: big endian");
+#else
+#error "unknown byte order"
+#endif
+
+    // The in-memory representation of the converted values is independent
+    // of the host endianness
+    uint16_t be16 = htobe16(0x0102);
+    uint32_t be32 = htobe32(0x01020304);
+    uint64_t be64 = htobe64(0x0102030405060708);
+    uint16_t le16 = htole16(0x0102);
+    uint32_t le32 = htole32(0x01020304);
+    uint64_t le64 = htole64(0x0102030405060708);
+
+    unsigned char bytes[8];
+    memcpy(bytes, &be16, sizeof(be16));
+    printf("htobe16: %02x %02x\n", bytes[0], bytes[1]);
+    memcpy(bytes, &le16, sizeof(le16));
+    printf("htole16: %02x %02x\n", bytes[0], bytes[1]);
+    memcpy(bytes, &be32, sizeof(be32));
+    printf("htobe32: %02x %02x %02x %02x\n", bytes[0], bytes[1], bytes[2], bytes[3]);
+    memcpy(bytes, &le32, sizeof(le32));
+    printf("htole32: %02x %02x %02x %02x\n", bytes[0], bytes[1], bytes[2], bytes[3]);
+    memcpy(bytes, &be64, sizeof(be64));
+    printf(
+        "htobe64: %02x %02x %02x %02x %02x %02x %02x %02x\n",
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]
+    );
+    memcpy(bytes, &le64, sizeof(le64));
+    printf(
+        "htole64: %02x %02x %02x %02x %02x %02x %02x %02x\n",
+        bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5], bytes[6], bytes[7]
+    );
+
+    // Round trips
+    printf("be16toh: %#" PRIx16 "\n", be16toh(be16));
+    printf("le16toh: %#" PRIx16 "\n", le16toh(le16));
+    printf("be32toh: %#" PRIx32 "\n", be32toh(be32));
+    printf("le32toh: %#" PRIx32 "\n", le32toh(le32));
+    printf("be64toh: %#" PRIx64 "\n", be64toh(be64));
+    printf("le64toh: %#" PRIx64 "\n", le64toh(le64));
+
+    uint64_t x = 0xdeadbeefcafebabe;
+    printf("be64toh(htobe64(x)) == x: %d\n", be64toh(htobe64(x)) == x);
+    printf("le64toh(htole64(x)) == x: %d\n", le64toh(htole64(x)) == x);
+}
@@ -0,0 +1,48 @@
+#include <execinfo.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+// Print the function name of a "object(function+0x1a) [0x...]" line
+static void print_function(const char *line) {
+    const char *start = strchr(line, '(');
+    const char *end = start ? strchr(start, '+') : NULL;
+    if (start && end && end > start + 1) {
+        printf("  %.*s\n", (int) (end - start - 1), start + 1);
+    } else {
+        printf("  unresolved\n");
+    }
+}
+
+void level3(void) {
+    void *buffer[64];
+    int depth = backtrace(buffer, 64);
+    printf("depth at least 4: %d\n", depth >= 4);
+
+    char **symbols = backtrace_symbols(buffer, depth);
+    ERROR_IF(backtrace_symbols, symbols, == NULL);
+    for (int i = 0; i < 4 && i < depth; i++) {
+        print_function(symbols[i]);
+    }
+    free(symbols);
+
+    // A smaller buffer is filled to its size, with the innermost frames
+    void *small[2];
+    depth = backtrace(small, 2);
+    printf("limited depth: %d, same frames: %d\n", depth,
+        small[1] == buffer[1]);
+}
+
+void level2(void) {
+    level3();
+}
+
+void level1(void) {
+    level2();
+}
+
+int main(void) {
+    level1();
+}
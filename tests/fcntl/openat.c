@@ -0,0 +1,90 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define DIR "openat.dir"
+
+int main(void) {
+    int status = mkdir(DIR, 0755);
+    ERROR_IF(mkdir, status, == -1);
+
+    int dirfd = open(DIR, O_RDONLY | O_DIRECTORY);
+    ERROR_IF(open, dirfd, == -1);
+
+    // Create a file relative to the directory
+    int fd = openat(dirfd, "file", O_WRONLY | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(openat, fd, == -1);
+    ssize_t len = write(fd, "hello", 5);
+    ERROR_IF(write, len, == -1);
+    close(fd);
+
+    struct stat st;
+    status = stat(DIR "/file", &st);
+    ERROR_IF(stat, status, == -1);
+    printf("created in directory, size %ld\n", (long) st.st_size);
+
+    status = fstatat(dirfd, "file", &st, 0);
+    ERROR_IF(fstatat, status, == -1);
+    printf("fstatat: size %ld, regular %d\n", (long) st.st_size, S_ISREG(st.st_mode));
+
+    // AT_FDCWD means the working directory
+    fd = openat(AT_FDCWD, DIR "/file", O_RDONLY);
+    ERROR_IF(openat, fd, == -1);
+    char buf[6] = { 0 };
+    len = read(fd, buf, 5);
+    ERROR_IF(read, len, == -1);
+    printf("openat AT_FDCWD read '%s'\n", buf);
+
+    // AT_EMPTY_PATH stats the descriptor itself
+    status = fstatat(fd, "", &st, AT_EMPTY_PATH);
+    ERROR_IF(fstatat, status, == -1);
+    printf("fstatat AT_EMPTY_PATH: size %ld\n", (long) st.st_size);
+    close(fd);
+
+    status = renameat(dirfd, "file", dirfd, "renamed");
+    ERROR_IF(renameat, status, == -1);
+    status = linkat(dirfd, "renamed", AT_FDCWD, DIR "/link", 0);
+    ERROR_IF(linkat, status, == -1);
+    status = fstatat(dirfd, "link", &st, 0);
+    ERROR_IF(fstatat, status, == -1);
+    printf("renameat and linkat: links %ld\n", (long) st.st_nlink);
+
+    status = symlinkat("renamed", dirfd, "symlink");
+    ERROR_IF(symlinkat, status, == -1);
+    status = fstatat(dirfd, "symlink", &st, AT_SYMLINK_NOFOLLOW);
+    ERROR_IF(fstatat, status, == -1);
+    printf("symlinkat: symlink %d\n", S_ISLNK(st.st_mode));
+    status = fstatat(dirfd, "symlink", &st, 0);
+    ERROR_IF(fstatat, status, == -1);
+    printf("followed: regular %d\n", S_ISREG(st.st_mode));
+
+    status = mkdirat(dirfd, "subdir", 0755);
+    ERROR_IF(mkdirat, status, == -1);
+
+    // Directories need AT_REMOVEDIR, and files must not have it
+    errno = 0;
+    status = unlinkat(dirfd, "subdir", 0);
+    printf("unlinkat directory without AT_REMOVEDIR: %d, failed %d\n", status, errno != 0);
+    status = unlinkat(dirfd, "subdir", AT_REMOVEDIR);
+    ERROR_IF(unlinkat, status, == -1);
+
+    status = unlinkat(dirfd, "renamed", 0);
+    ERROR_IF(unlinkat, status, == -1);
+    status = unlinkat(dirfd, "link", 0);
+    ERROR_IF(unlinkat, status, == -1);
+    status = unlinkat(dirfd, "symlink", 0);
+    ERROR_IF(unlinkat, status, == -1);
+
+    errno = 0;
+    status = fstatat(dirfd, "renamed", &st, 0);
+    printf("after unlinkat: %d, ENOENT %d\n", status, errno == ENOENT);
+
+    close(dirfd);
+    status = unlinkat(AT_FDCWD, DIR, AT_REMOVEDIR);
+    ERROR_IF(unlinkat, status, == -1);
+}
@@ -0,0 +1,51 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "posix_fallocate.out"
+
+int main(void) {
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+
+    ssize_t len = write(fd, "abc", 3);
+    ERROR_IF(write, len, == -1);
+
+    int status = posix_fadvise(fd, 0, 0, POSIX_FADV_SEQUENTIAL);
+    printf("posix_fadvise: %d\n", status);
+
+    // Errors are returned rather than stored in errno
+    errno = 0;
+    status = posix_fallocate(fd, 0, 0);
+    printf("posix_fallocate with zero length: EINVAL %d, errno %d\n", status == EINVAL, errno);
+
+    status = posix_fallocate(fd, 0, 10000);
+    printf("posix_fallocate: %d\n", status);
+
+    struct stat st;
+    status = fstat(fd, &st);
+    ERROR_IF(fstat, status, == -1);
+    printf("size: %ld\n", (long) st.st_size);
+
+    char buf[10000];
+    len = pread(fd, buf, sizeof(buf), 0);
+    ERROR_IF(pread, len, == -1);
+    printf("read %zd bytes, existing data kept: %d\n", len, memcmp(buf, "abc", 3) == 0);
+
+    int zeros = 1;
+    for (size_t i = 3; i < sizeof(buf); i++) {
+        if (buf[i] != 0) {
+            zeros = 0;
+        }
+    }
+    printf("rest is zeros: %d\n", zeros);
+
+    close(fd);
+    status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
@@ -0,0 +1,29 @@
+#include <limits.h>
+#include <stdio.h>
+#include <strings.h>
+
+int main(void) {
+    printf("zero: %d %d %d %d %d\n", ffs(0), ffsl(0), ffsll(0), fls(0), flsl(0));
+
+    // A single bit at each position finds that position
+    int ffs_ok = 1;
+    int fls_ok = 1;
+    for (int i = 0; i < (int) sizeof(int) * CHAR_BIT; i++) {
+        int value = (int) (1u << i);
+        ffs_ok &= ffs(value) == i + 1;
+        fls_ok &= fls(value) == i + 1;
+    }
+    for (int i = 0; i < (int) sizeof(long) * CHAR_BIT; i++) {
+        long value = (long) (1ul << i);
+        ffs_ok &= ffsl(value) == i + 1;
+        fls_ok &= flsl(value) == i + 1;
+    }
+    for (int i = 0; i < (int) sizeof(long long) * CHAR_BIT; i++) {
+        ffs_ok &= ffsll((long long) (1ull << i)) == i + 1;
+    }
+    printf("single bits: ffs %d, fls %d\n", ffs_ok, fls_ok);
+
+    // All bits set, and some bits set
+    printf("full width: %d %d %d %d %d\n", ffs(-1), ffsl(-1), ffsll(-1), fls(-1), flsl(-1));
+    printf("0x0ff0: %d %d %d %d %d\n", ffs(0x0ff0), ffsl(0x0ff0), ffsll(0x0ff0), fls(0x0ff0), flsl(0x0ff0));
+}
@@ -0,0 +1,19 @@
+#include <stdio.h>
+
+// Defined in ld_so_copy_lib.so. As this program is not position independent,
+// they are copied into it and the library is made to use the copies.
+extern int copy_value;
+extern char copy_name[16];
+
+int get_copy_value(void);
+void set_copy_name(const char *name);
+
+int main(void) {
+    printf("copied: %d '%s'\n", copy_value, copy_name);
+
+    copy_value = 7;
+    printf("library sees program write: %d\n", get_copy_value());
+
+    set_copy_name("changed");
+    printf("program sees library write: '%s'\n", copy_name);
+}
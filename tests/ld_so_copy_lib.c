@@ -0,0 +1,15 @@
+// Linked into ld_so_copy, whose references to these are copy relocated
+int copy_value = 42;
+char copy_name[16] = "library";
+
+int get_copy_value(void) {
+    return copy_value;
+}
+
+void set_copy_name(const char *name) {
+    int i;
+    for (i = 0; name[i] && i < 15; i++) {
+        copy_name[i] = name[i];
+    }
+    copy_name[i] = 0;
+}
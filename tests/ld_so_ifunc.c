@@ -0,0 +1,33 @@
+#include <stdio.h>
+
+// Read by the resolver in ld_so_ifunc_lib.so
+int ifunc_choice = 2;
+
+int lib_version(void);
+
+// A pointer, so reading it in the resolver needs a relative relocation
+static int local_value = 2;
+static int *local_choice = &local_value;
+
+static int local_one(void) {
+    return 1;
+}
+
+static int local_two(void) {
+    return 2;
+}
+
+static int (*resolve_local(void))(void) {
+    return *local_choice == 2 ? local_two : local_one;
+}
+
+int local_version(void) __attribute__((ifunc("resolve_local")));
+
+int main(void) {
+    printf("library ifunc called: %d\n", lib_version());
+
+    int (*lib_ptr)(void) = lib_version;
+    printf("library ifunc through a pointer: %d\n", lib_ptr());
+
+    printf("local ifunc called: %d\n", local_version());
+}
@@ -0,0 +1,17 @@
+// Linked into ld_so_ifunc. The resolver of lib_version reads a variable of the
+// program through the GOT, which is filled in by another relocation.
+extern int ifunc_choice;
+
+static int version_one(void) {
+    return 1;
+}
+
+static int version_two(void) {
+    return 2;
+}
+
+static int (*resolve_version(void))(void) {
+    return ifunc_choice == 2 ? version_two : version_one;
+}
+
+int lib_version(void) __attribute__((ifunc("resolve_version")));
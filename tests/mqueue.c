@@ -0,0 +1,89 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <mqueue.h>
+#include <semaphore.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+#define NAME "/relibc-test-mqueue"
+
+static sem_t notified;
+
+static void notify(union sigval value) {
+    printf("notified with %d\n", value.sival_int);
+    sem_post(&notified);
+}
+
+int main(void) {
+    mq_unlink(NAME);
+
+    struct mq_attr attr = { 0 };
+    attr.mq_maxmsg = 4;
+    attr.mq_msgsize = 32;
+    mqd_t mq = mq_open(NAME, O_RDWR | O_CREAT | O_EXCL, 0600, &attr);
+    ERROR_IF(mq_open, mq, == (mqd_t) -1);
+
+    const char *messages[] = { "low", "high", "middle" };
+    unsigned int priorities[] = { 1, 7, 4 };
+    for (int i = 0; i < 3; i++) {
+        int status = mq_send(mq, messages[i], strlen(messages[i]) + 1, priorities[i]);
+        ERROR_IF(mq_send, status, == -1);
+    }
+
+    int status = mq_getattr(mq, &attr);
+    ERROR_IF(mq_getattr, status, == -1);
+    printf("maxmsg %ld, msgsize %ld, curmsgs %ld\n", attr.mq_maxmsg, attr.mq_msgsize, attr.mq_curmsgs);
+
+    // Highest priority first
+    for (int i = 0; i < 3; i++) {
+        char buf[32];
+        unsigned int prio;
+        ssize_t len = mq_receive(mq, buf, sizeof(buf), &prio);
+        ERROR_IF(mq_receive, len, == -1);
+        printf("received \"%s\" (%zd bytes) at priority %u\n", buf, len, prio);
+    }
+
+    // The deadline is absolute, so one in the past times out at once
+    struct timespec deadline;
+    status = clock_gettime(CLOCK_REALTIME, &deadline);
+    ERROR_IF(clock_gettime, status, == -1);
+    deadline.tv_sec -= 1;
+    char buf[32];
+    ssize_t len = mq_timedreceive(mq, buf, sizeof(buf), NULL, &deadline);
+    printf("timed receive on an empty queue: %s\n", len == -1 && errno == ETIMEDOUT ? "ETIMEDOUT" : "succeeded");
+
+    // Receiving needs room for the largest message
+    len = mq_receive(mq, buf, 16, NULL);
+    printf("receive into a small buffer: %s\n", len == -1 && errno == EMSGSIZE ? "EMSGSIZE" : "succeeded");
+
+    // A thread is started once a message arrives on the empty queue
+    status = sem_init(&notified, 0, 0);
+    ERROR_IF(sem_init, status, == -1);
+    struct sigevent event = { 0 };
+    event.sigev_notify = SIGEV_THREAD;
+    event.sigev_notify_function = notify;
+    event.sigev_value.sival_int = 42;
+    status = mq_notify(mq, &event);
+    ERROR_IF(mq_notify, status, == -1);
+    status = mq_notify(mq, &event);
+    printf("second registration: %s\n", status == -1 && errno == EBUSY ? "EBUSY" : "succeeded");
+    status = mq_send(mq, "wake", 5, 0);
+    ERROR_IF(mq_send, status, == -1);
+    status = sem_wait(&notified);
+    ERROR_IF(sem_wait, status, == -1);
+    len = mq_receive(mq, buf, sizeof(buf), NULL);
+    ERROR_IF(mq_receive, len, == -1);
+    printf("received \"%s\" after the notification\n", buf);
+
+    status = mq_close(mq);
+    ERROR_IF(mq_close, status, == -1);
+    status = mq_unlink(NAME);
+    ERROR_IF(mq_unlink, status, == -1);
+
+    mq = mq_open("no-slash", O_RDWR | O_CREAT, 0600, NULL);
+    printf("name without a slash: %s\n", mq == (mqd_t) -1 && errno == EINVAL ? "EINVAL" : "succeeded");
+}
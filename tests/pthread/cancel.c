@@ -0,0 +1,123 @@
+#include <pthread.h>
+#include <semaphore.h>
+#include <stdio.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static int fds[2];
+static sem_t sem;
+static volatile int cleaned_up = 0;
+
+static void cleanup(void *arg) {
+    (void) arg;
+    cleaned_up = 1;
+}
+
+// Blocks in read forever, as nothing is written to the pipe
+static void *reader(void *arg) {
+    (void) arg;
+    pthread_cleanup_push(cleanup, NULL);
+    char c;
+    read(fds[0], &c, 1);
+    puts("read returned");
+    pthread_cleanup_pop(0);
+    return NULL;
+}
+
+// Ignores cancellation until it reenables it, then stops at the next
+// cancellation point
+static void *sleeper(void *arg) {
+    (void) arg;
+    int oldstate;
+    pthread_setcancelstate(PTHREAD_CANCEL_DISABLE, &oldstate);
+    struct timespec delay = { .tv_sec = 0, .tv_nsec = 200000000 };
+    nanosleep(&delay, NULL);
+    puts("slept through the cancellation request");
+    pthread_setcancelstate(oldstate, NULL);
+    nanosleep(&delay, NULL);
+    puts("unreachable");
+    return NULL;
+}
+
+// Keeps reading with cancellation disabled, so the request must neither stop
+// nor interrupt the read
+static void *disabled_reader(void *arg) {
+    (void) arg;
+    int oldstate;
+    pthread_setcancelstate(PTHREAD_CANCEL_DISABLE, &oldstate);
+    char c = 0;
+    ssize_t count = read(fds[0], &c, 1);
+    printf("read with cancellation disabled: %zd '%c'\n", count, c);
+    pthread_setcancelstate(oldstate, NULL);
+    pthread_testcancel();
+    puts("unreachable");
+    return NULL;
+}
+
+// Blocks in sem_wait forever, as the semaphore is never posted
+static void *waiter(void *arg) {
+    (void) arg;
+    sem_wait(&sem);
+    puts("sem_wait returned");
+    return NULL;
+}
+
+// Cancels threads right after creating them, so that the request arrives
+// anywhere on the way to the call that blocks, which must not lose it
+static void run_right_away(const char *name, void *(*start)(void *)) {
+    int canceled = 0;
+    for (int i = 0; i < 100; i++) {
+        pthread_t thread;
+        int status = pthread_create(&thread, NULL, start, NULL);
+        ERROR_IF(pthread_create, status, != 0);
+        status = pthread_cancel(thread);
+        ERROR_IF(pthread_cancel, status, != 0);
+        void *result;
+        status = pthread_join(thread, &result);
+        ERROR_IF(pthread_join, status, != 0);
+        canceled += result == PTHREAD_CANCELED;
+    }
+    printf("%s right away: canceled %d of 100\n", name, canceled);
+}
+
+// Cancels the thread once it blocked, then writes feed to the pipe if given
+static void run(const char *name, void *(*start)(void *), const char *feed) {
+    pthread_t thread;
+    int status = pthread_create(&thread, NULL, start, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+
+    // Give the thread time to block
+    struct timespec delay = { .tv_sec = 0, .tv_nsec = 50000000 };
+    nanosleep(&delay, NULL);
+
+    status = pthread_cancel(thread);
+    ERROR_IF(pthread_cancel, status, != 0);
+    if (feed) {
+        nanosleep(&delay, NULL);
+        ssize_t count = write(fds[1], feed, 1);
+        ERROR_IF(write, count, == -1);
+    }
+    void *result;
+    status = pthread_join(thread, &result);
+    ERROR_IF(pthread_join, status, != 0);
+    printf("%s: canceled %d\n", name, result == PTHREAD_CANCELED);
+}
+
+int main(void) {
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    run("read", reader, NULL);
+    printf("read: cleaned up %d\n", cleaned_up);
+
+    run("nanosleep", sleeper, NULL);
+
+    run("disabled read", disabled_reader, "x");
+
+    run_right_away("read", reader);
+    status = sem_init(&sem, 0, 0);
+    ERROR_IF(sem_init, status, == -1);
+    run_right_away("sem_wait", waiter);
+}
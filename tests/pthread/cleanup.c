@@ -0,0 +1,73 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+static pthread_key_t key;
+
+static void destructor(void *value) {
+    printf("destructor for %s\n", (const char *) value);
+}
+
+static void release(void *arg) {
+    char **resource = arg;
+    printf("cleanup freed %s\n", *resource);
+    free(*resource);
+    *resource = NULL;
+}
+
+static void say(void *arg) {
+    printf("cleanup %s\n", (const char *) arg);
+}
+
+// Waits at a cancellation point until canceled
+static void *canceled(void *arg) {
+    pthread_setspecific(key, "canceled");
+    pthread_cleanup_push(release, arg);
+    pthread_cleanup_push(say, "pushed last, runs first");
+    for (;;) {
+        pthread_testcancel();
+    }
+    pthread_cleanup_pop(0);
+    pthread_cleanup_pop(0);
+    return NULL;
+}
+
+static void *exiting(void *arg) {
+    (void) arg;
+    pthread_setspecific(key, "exiting");
+    pthread_cleanup_push(say, "popped without running");
+    pthread_cleanup_pop(0);
+    pthread_cleanup_push(say, "popped and run");
+    pthread_cleanup_pop(1);
+    pthread_cleanup_push(say, "run by pthread_exit");
+    pthread_exit((void *) 7);
+    pthread_cleanup_pop(0);
+    return NULL;
+}
+
+int main(void) {
+    int status = pthread_key_create(&key, destructor);
+    ERROR_IF(pthread_key_create, status, != 0);
+
+    char *resource = malloc(16);
+    ERROR_IF(malloc, resource, == NULL);
+    snprintf(resource, 16, "the resource");
+
+    pthread_t thread;
+    status = pthread_create(&thread, NULL, canceled, &resource);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_cancel(thread);
+    ERROR_IF(pthread_cancel, status, != 0);
+    void *result;
+    status = pthread_join(thread, &result);
+    ERROR_IF(pthread_join, status, != 0);
+    printf("canceled: %d, resource freed: %d\n", result == PTHREAD_CANCELED, resource == NULL);
+
+    status = pthread_create(&thread, NULL, exiting, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_join(thread, &result);
+    ERROR_IF(pthread_join, status, != 0);
+    printf("exited with %ld\n", (long) result);
+}
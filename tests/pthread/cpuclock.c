@@ -0,0 +1,50 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static long long nanoseconds(clockid_t clock) {
+    struct timespec ts;
+    int status = clock_gettime(clock, &ts);
+    ERROR_IF(clock_gettime, status, == -1);
+    return ts.tv_sec * 1000000000LL + ts.tv_nsec;
+}
+
+// Burns CPU time for 20ms of wall clock time
+static void spin(void) {
+    long long start = nanoseconds(CLOCK_MONOTONIC);
+    while (nanoseconds(CLOCK_MONOTONIC) - start < 20000000LL) {
+    }
+}
+
+static void check(const char *name, clockid_t clock) {
+    long long before = nanoseconds(clock);
+    spin();
+    long long after = nanoseconds(clock);
+    printf("%s clock advanced by at least 10ms: %d\n", name, after - before >= 10000000LL);
+}
+
+int main(void) {
+    clockid_t clock;
+    int status = pthread_getcpuclockid(pthread_self(), &clock);
+    ERROR_IF(pthread_getcpuclockid, status, != 0);
+    check("thread", clock);
+
+    status = clock_getcpuclockid(0, &clock);
+    ERROR_IF(clock_getcpuclockid, status, != 0);
+    check("process", clock);
+
+    status = clock_getcpuclockid(getpid(), &clock);
+    ERROR_IF(clock_getcpuclockid, status, != 0);
+    check("process by pid", clock);
+
+    // The thread's clock is its own, and does not count time slept
+    status = pthread_getcpuclockid(pthread_self(), &clock);
+    ERROR_IF(pthread_getcpuclockid, status, != 0);
+    long long before = nanoseconds(clock);
+    usleep(50000);
+    long long after = nanoseconds(clock);
+    printf("thread clock advanced by less than 10ms while sleeping: %d\n", after - before < 10000000LL);
+}
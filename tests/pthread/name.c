@@ -0,0 +1,57 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+static pthread_barrier_t barrier;
+
+static void *worker(void *arg) {
+    (void) arg;
+    // Wait while the main thread names this one, then until it is done
+    pthread_barrier_wait(&barrier);
+    pthread_barrier_wait(&barrier);
+    return NULL;
+}
+
+static void check(pthread_t thread, const char *name) {
+    int status = pthread_setname_np(thread, name);
+    ERROR_IF(pthread_setname_np, status, != 0);
+
+    char buf[16];
+    status = pthread_getname_np(thread, buf, sizeof(buf));
+    ERROR_IF(pthread_getname_np, status, != 0);
+    printf("set \"%s\", got \"%s\" (%zu characters)\n", name, buf, strlen(buf));
+}
+
+int main(void) {
+    pthread_t self = pthread_self();
+
+    // 15 characters fit with the NUL terminator, longer names are truncated
+    check(self, "fourteen chars");
+    check(self, "fifteen chars!!");
+    check(self, "sixteen chars!!!");
+    check(self, "a much longer thread name");
+
+    // The buffer must fit the name and its terminator
+    char small[15];
+    int status = pthread_getname_np(self, small, sizeof(small));
+    printf("15 byte buffer: %s\n", status == ERANGE ? "ERANGE" : "fits");
+
+    // Naming another thread
+    status = pthread_barrier_init(&barrier, NULL, 2);
+    ERROR_IF(pthread_barrier_init, status, != 0);
+    pthread_t thread;
+    status = pthread_create(&thread, NULL, worker, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+    pthread_barrier_wait(&barrier);
+    check(thread, "worker");
+    check(thread, "worker with a long name");
+    pthread_barrier_wait(&barrier);
+    status = pthread_join(thread, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+
+    check(self, "main");
+}
@@ -0,0 +1,124 @@
+#include <errno.h>
+#include <pthread.h>
+#include <signal.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// The smallest stack accepted
+#define STACK_SIZE (16 * 1024)
+#define GUARD_SIZE (64 * 1024)
+#define PAGE 4096
+
+static uintptr_t stack_top;
+
+static void segv_handler(int sig, siginfo_t *info, void *context) {
+    (void) sig;
+    (void) context;
+    // Frames larger than a page skip over a single guard page, but the last
+    // one must still land in the guard below the stack
+    uintptr_t depth = stack_top - (uintptr_t) info->si_addr;
+    int in_guard = depth > STACK_SIZE - 2 * PAGE && depth <= STACK_SIZE + GUARD_SIZE;
+    const char *msg = in_guard ? "overflow faulted in the guard\n" : "overflow faulted elsewhere\n";
+    write(STDOUT_FILENO, msg, strlen(msg));
+    _exit(EXIT_SUCCESS);
+}
+
+// Deep enough to run off any stack this test creates
+static int recurse(volatile char *prev, int depth) {
+    volatile char frame[2 * PAGE];
+    frame[0] = prev ? prev[0] + 1 : 0;
+    if (depth == 0) {
+        return frame[0];
+    }
+    return recurse(frame, depth - 1) + frame[1];
+}
+
+static void *overflow(void *arg) {
+    (void) arg;
+    volatile char top;
+    stack_top = (uintptr_t) &top;
+
+    // The handler cannot run on the overflowed stack
+    static char altstack[64 * 1024];
+    stack_t ss = { .ss_sp = altstack, .ss_size = sizeof(altstack), .ss_flags = 0 };
+    int status = sigaltstack(&ss, NULL);
+    ERROR_IF(sigaltstack, status, == -1);
+
+    recurse(NULL, 1 << 20);
+    puts("unreachable");
+    return NULL;
+}
+
+static void *on_stack(void *arg) {
+    volatile char local;
+    char *stack = arg;
+    int inside = (char *) &local > stack && (char *) &local < stack + STACK_SIZE;
+    printf("thread ran on the given stack: %d\n", inside);
+    return NULL;
+}
+
+static void check_attributes(void) {
+    pthread_attr_t attr;
+    int status = pthread_attr_init(&attr);
+    ERROR_IF(pthread_attr_init, status, != 0);
+
+    status = pthread_attr_setstacksize(&attr, 1);
+    printf("stack size of 1 rejected: %d\n", status == EINVAL);
+
+    size_t size;
+    status = pthread_attr_setguardsize(&attr, GUARD_SIZE);
+    ERROR_IF(pthread_attr_setguardsize, status, != 0);
+    status = pthread_attr_getguardsize(&attr, &size);
+    ERROR_IF(pthread_attr_getguardsize, status, != 0);
+    printf("guard size: %zu\n", size);
+
+    char *stack = malloc(STACK_SIZE);
+    ERROR_IF(malloc, stack, == NULL);
+    status = pthread_attr_setstack(&attr, stack, STACK_SIZE);
+    ERROR_IF(pthread_attr_setstack, status, != 0);
+    void *addr;
+    status = pthread_attr_getstack(&attr, &addr, &size);
+    ERROR_IF(pthread_attr_getstack, status, != 0);
+    printf("stack: %d, size %zu\n", addr == stack, size);
+
+    pthread_t thread;
+    status = pthread_create(&thread, &attr, on_stack, stack);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_join(thread, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+
+    free(stack);
+    status = pthread_attr_destroy(&attr);
+    ERROR_IF(pthread_attr_destroy, status, != 0);
+}
+
+int main(void) {
+    setvbuf(stdout, NULL, _IONBF, 0);
+    check_attributes();
+
+    struct sigaction act;
+    memset(&act, 0, sizeof(act));
+    act.sa_sigaction = segv_handler;
+    act.sa_flags = SA_ONSTACK | SA_SIGINFO;
+    int status = sigaction(SIGSEGV, &act, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    pthread_attr_t attr;
+    status = pthread_attr_init(&attr);
+    ERROR_IF(pthread_attr_init, status, != 0);
+    status = pthread_attr_setstacksize(&attr, STACK_SIZE);
+    ERROR_IF(pthread_attr_setstacksize, status, != 0);
+    status = pthread_attr_setguardsize(&attr, GUARD_SIZE);
+    ERROR_IF(pthread_attr_setguardsize, status, != 0);
+
+    pthread_t thread;
+    status = pthread_create(&thread, &attr, overflow, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+    pthread_join(thread, NULL);
+    puts("unreachable");
+}
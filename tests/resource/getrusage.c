@@ -20,7 +20,7 @@ int main(void) {
     ptimeval(&r_usage.ru_utime);
 
     printf("ru_stime:");
-    ptimeval(&r_usage.ru_utime);
+    ptimeval(&r_usage.ru_stime);
 
     printf("ru_maxrss: %ld\n", r_usage.ru_maxrss);
     printf("ru_ixrss: %ld\n", r_usage.ru_ixrss);
@@ -0,0 +1,96 @@
+#include <errno.h>
+#include <search.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define N 5000
+
+static char keys[N][16];
+
+int main(void) {
+    // Far more entries than asked for, so the table has to grow
+    if (hcreate(10) == 0) {
+        puts("hcreate failed");
+        exit(EXIT_FAILURE);
+    }
+    ENTRY *first = NULL;
+    for (int i = 0; i < N; i++) {
+        snprintf(keys[i], sizeof(keys[i]), "key%d", i);
+        ENTRY item = { keys[i], (void *) (size_t) i };
+        ENTRY *entry = hsearch(item, ENTER);
+        if (entry == NULL || entry->key != keys[i]) {
+            printf("hsearch(\"%s\", ENTER) failed\n", keys[i]);
+            exit(EXIT_FAILURE);
+        }
+        if (i == 0) {
+            first = entry;
+        }
+    }
+
+    int found = 0;
+    for (int i = 0; i < N; i++) {
+        // A copy of the key, since keys are compared as strings
+        char key[16];
+        strcpy(key, keys[i]);
+        ENTRY item = { key, NULL };
+        ENTRY *entry = hsearch(item, FIND);
+        if (entry != NULL && (size_t) entry->data == (size_t) i) {
+            found++;
+        }
+    }
+    printf("found %d of %d entries\n", found, N);
+
+    // Entering an existing key returns the existing entry
+    ENTRY item = { "key0", (void *) 1234 };
+    ENTRY *entry = hsearch(item, ENTER);
+    printf("hsearch(\"key0\", ENTER): data %zu, %s\n", (size_t) entry->data,
+        entry == first ? "same entry" : "moved");
+
+    item.key = "missing";
+    errno = 0;
+    entry = hsearch(item, FIND);
+    printf("hsearch(\"missing\", FIND): %s, %s\n", entry == NULL ? "NULL" : "found",
+        errno == ENOENT ? "ENOENT" : "wrong errno");
+    hdestroy();
+
+    // A reentrant table, independent of the global one
+    struct hsearch_data htab;
+    memset(&htab, 0, sizeof(htab));
+    if (hcreate_r(4, &htab) == 0) {
+        puts("hcreate_r failed");
+        exit(EXIT_FAILURE);
+    }
+    printf("hcreate_r again: %d\n", hcreate_r(4, &htab));
+    if (hcreate(4) == 0) {
+        puts("hcreate failed");
+        exit(EXIT_FAILURE);
+    }
+    const char *words[] = { "one", "two", "three", "four", "five", "six", "seven" };
+    for (size_t i = 0; i < sizeof(words) / sizeof(*words); i++) {
+        ENTRY item = { (char *) words[i], (void *) (i + 1) };
+        if (hsearch_r(item, ENTER, &entry, &htab) == 0) {
+            printf("hsearch_r(\"%s\", ENTER) failed\n", words[i]);
+            exit(EXIT_FAILURE);
+        }
+    }
+    item.key = "one";
+    item.data = (void *) 100;
+    hsearch(item, ENTER);
+    for (size_t i = 0; i < sizeof(words) / sizeof(*words); i++) {
+        ENTRY item = { (char *) words[i], NULL };
+        int ret = hsearch_r(item, FIND, &entry, &htab);
+        ENTRY *global = hsearch(item, FIND);
+        printf("%s: reentrant %zu (%d), global %zu\n", words[i],
+            ret ? (size_t) entry->data : 0, ret, global ? (size_t) global->data : 0);
+    }
+    errno = 0;
+    item.key = "eight";
+    int ret = hsearch_r(item, FIND, &entry, &htab);
+    printf("hsearch_r(\"eight\", FIND): %d, %s, %s\n", ret, entry == NULL ? "NULL" : "found",
+        errno == ENOENT ? "ENOENT" : "wrong errno");
+    hdestroy_r(&htab);
+    hdestroy();
+}
@@ -0,0 +1,75 @@
+#include <search.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+struct node {
+    struct node *next;
+    struct node *prev;
+    char name;
+};
+
+static void print_list(struct node *head) {
+    printf("list:");
+    for (struct node *node = head; node != NULL; node = node->next) {
+        if (node->prev != NULL && node->prev->next != node) {
+            puts(" broken links");
+            exit(EXIT_FAILURE);
+        }
+        printf(" %c", node->name);
+    }
+    printf("\n");
+}
+
+static int compare(const void *a, const void *b) {
+    return *(const int *) a - *(const int *) b;
+}
+
+int main(void) {
+    struct node a = { .name = 'a' };
+    struct node b = { .name = 'b' };
+    struct node c = { .name = 'c' };
+    struct node d = { .name = 'd' };
+
+    // A null predecessor starts a new list
+    a.next = &b;
+    a.prev = &c;
+    insque(&a, NULL);
+    printf("new list: next %s, prev %s\n", a.next ? "set" : "NULL", a.prev ? "set" : "NULL");
+    insque(&c, &a);
+    insque(&b, &a);
+    insque(&d, &c);
+    print_list(&a);
+
+    remque(&b);
+    print_list(&a);
+    remque(&d);
+    print_list(&a);
+    remque(&a);
+    printf("head removed: c.prev %s\n", c.prev ? "set" : "NULL");
+    print_list(&c);
+
+    int array[8] = { 3, 1, 4 };
+    size_t nel = 3;
+    int key = 4;
+    int *found = lfind(&key, array, &nel, sizeof(int), compare);
+    printf("lfind(4): index %d, nel %zu\n", found ? (int) (found - array) : -1, nel);
+    key = 5;
+    found = lfind(&key, array, &nel, sizeof(int), compare);
+    printf("lfind(5): %s, nel %zu\n", found ? "found" : "NULL", nel);
+
+    found = lsearch(&key, array, &nel, sizeof(int), compare);
+    printf("lsearch(5): index %d, nel %zu\n", (int) (found - array), nel);
+    found = lsearch(&key, array, &nel, sizeof(int), compare);
+    printf("lsearch(5) again: index %d, nel %zu\n", (int) (found - array), nel);
+    key = 1;
+    found = lsearch(&key, array, &nel, sizeof(int), compare);
+    printf("lsearch(1): index %d, nel %zu\n", (int) (found - array), nel);
+
+    nel = 0;
+    key = 9;
+    found = lsearch(&key, array, &nel, sizeof(int), compare);
+    printf("lsearch(9) in an empty array: index %d, nel %zu, array[0] %d\n",
+        (int) (found - array), nel, array[0]);
+}
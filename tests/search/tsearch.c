@@ -0,0 +1,109 @@
+#include <search.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+#define N 1000
+
+static int compare(const void *a, const void *b) {
+    int x = *(const int *) a;
+    int y = *(const int *) b;
+    return (x > y) - (x < y);
+}
+
+static int visited[N];
+static int visited_count;
+static int max_depth;
+
+static void record(const void *node, VISIT which, int depth) {
+    if (depth > max_depth) {
+        max_depth = depth;
+    }
+    if (which == postorder || which == leaf) {
+        visited[visited_count++] = **(int *const *) node;
+    }
+}
+
+static void print(const void *node, VISIT which, int depth) {
+    const char *names[] = { "preorder", "postorder", "endorder", "leaf" };
+    printf("%*s%d %s\n", 2 * depth, "", **(int *const *) node, names[which]);
+}
+
+static void walk(void *root, int expected) {
+    visited_count = 0;
+    max_depth = 0;
+    twalk(root, record);
+    // Balanced trees have a depth of a small multiple of log2(n)
+    printf("walked %d keys, %s\n", visited_count, max_depth <= 20 ? "balanced" : "unbalanced");
+    if (visited_count != expected) {
+        puts("wrong number of keys");
+        exit(EXIT_FAILURE);
+    }
+    for (int i = 1; i < visited_count; i++) {
+        if (visited[i - 1] >= visited[i]) {
+            printf("keys out of order at %d\n", i);
+            exit(EXIT_FAILURE);
+        }
+    }
+}
+
+int main(void) {
+    static int keys[N];
+    void *root = NULL;
+
+    // Insert the keys in an order that would unbalance a plain tree
+    for (int i = 0; i < N; i++) {
+        keys[i] = i;
+        int **node = tsearch(&keys[i], &root, compare);
+        if (node == NULL || *node != &keys[i]) {
+            printf("tsearch(%d) failed\n", i);
+            exit(EXIT_FAILURE);
+        }
+    }
+    walk(root, N);
+
+    // Existing keys are found, not inserted again
+    int key = 500;
+    int **node = tsearch(&key, &root, compare);
+    printf("tsearch(500): %s\n", *node == &keys[500] ? "found the first key" : "inserted");
+    node = tfind(&key, &root, compare);
+    printf("tfind(500): %d\n", node == NULL ? -1 : **node);
+    key = N;
+    printf("tfind(%d): %s\n", N, tfind(&key, &root, compare) == NULL ? "NULL" : "found");
+
+    // Delete the even keys, keeping what was found for an odd one
+    key = 501;
+    int **odd = tfind(&key, &root, compare);
+    for (int i = 0; i < N; i += 2) {
+        if (tdelete(&keys[i], &root, compare) == NULL) {
+            printf("tdelete(%d) failed\n", i);
+            exit(EXIT_FAILURE);
+        }
+    }
+    key = 0;
+    printf("tdelete(0) again: %s\n", tdelete(&key, &root, compare) == NULL ? "NULL" : "deleted");
+    printf("tfind(501): %s\n", tfind(&odd[0][0], &root, compare) == odd ? "same node" : "moved");
+    walk(root, N / 2);
+
+    // The parent of a deleted node is returned, with its key
+    key = 501;
+    int **parent = tdelete(&key, &root, compare);
+    printf("tdelete(501): parent %s\n",
+        parent != NULL && tfind(*parent, &root, compare) == parent ? "found" : "missing");
+
+    for (int i = 1; i < N; i += 2) {
+        tdelete(&keys[i], &root, compare);
+    }
+    printf("root after deleting everything: %s\n", root == NULL ? "NULL" : "not NULL");
+
+    // The visits of a small tree
+    for (int i = 1; i <= 4; i++) {
+        tsearch(&keys[i], &root, compare);
+    }
+    twalk(root, print);
+    twalk(NULL, print);
+    while (root != NULL) {
+        tdelete(*(int **) root, &root, compare);
+    }
+}
@@ -0,0 +1,84 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <limits.h>
+#include <semaphore.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define NAME "/relibc-test-named"
+
+int main(void) {
+    sem_unlink(NAME);
+
+    sem_t *sem = sem_open(NAME, O_CREAT | O_EXCL, 0600, 0);
+    ERROR_IF(sem_open, sem, == SEM_FAILED);
+
+    // It exists now, so creating it exclusively fails
+    sem_t *again = sem_open(NAME, O_CREAT | O_EXCL, 0600, 0);
+    printf("exclusive create: %s\n", again == SEM_FAILED && errno == EEXIST ? "EEXIST" : "succeeded");
+
+    // Opening it again in this process gives the same semaphore, which stays
+    // open until closed as many times
+    again = sem_open(NAME, 0);
+    ERROR_IF(sem_open, again, == SEM_FAILED);
+    printf("same address: %d\n", again == sem);
+    int status = sem_close(again);
+    ERROR_IF(sem_close, status, == -1);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        // A separate open of the same name in the child
+        sem_t *child = sem_open(NAME, 0);
+        ERROR_IF(sem_open, child, == SEM_FAILED);
+        usleep(100000);
+        for (int i = 0; i < 3; i++) {
+            int status = sem_post(child);
+            ERROR_IF(sem_post, status, == -1);
+        }
+        sem_close(child);
+        _exit(EXIT_SUCCESS);
+    }
+
+    status = sem_wait(sem);
+    ERROR_IF(sem_wait, status, == -1);
+    puts("woken by the child");
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+
+    int value;
+    status = sem_getvalue(sem, &value);
+    ERROR_IF(sem_getvalue, status, == -1);
+    printf("value after the child posted 3 times: %d\n", value);
+
+    status = sem_trywait(sem);
+    ERROR_IF(sem_trywait, status, == -1);
+    status = sem_trywait(sem);
+    ERROR_IF(sem_trywait, status, == -1);
+    status = sem_trywait(sem);
+    printf("trywait on 0: %s\n", status == -1 && errno == EAGAIN ? "EAGAIN" : "succeeded");
+
+    status = sem_close(sem);
+    ERROR_IF(sem_close, status, == -1);
+    status = sem_unlink(NAME);
+    ERROR_IF(sem_unlink, status, == -1);
+
+    sem = sem_open(NAME, 0);
+    printf("open after unlink: %s\n", sem == SEM_FAILED && errno == ENOENT ? "ENOENT" : "succeeded");
+
+    // Posting past SEM_VALUE_MAX leaves the value as it was
+    sem_t full;
+    status = sem_init(&full, 0, SEM_VALUE_MAX);
+    ERROR_IF(sem_init, status, == -1);
+    status = sem_post(&full);
+    printf("post at SEM_VALUE_MAX: %s\n", status == -1 && errno == EOVERFLOW ? "EOVERFLOW" : "succeeded");
+    status = sem_getvalue(&full, &value);
+    ERROR_IF(sem_getvalue, status, == -1);
+    printf("value still SEM_VALUE_MAX: %d\n", value == SEM_VALUE_MAX);
+}
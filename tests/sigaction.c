@@ -42,4 +42,10 @@ int main(void) {
     ERROR_IF(raise, raise_status, < 0);
 
     puts("Raised.");
+
+    // The two signals after the standard ones are reserved for the C library
+    rcode = sigaction(32, &sa1, NULL);
+    printf("signal 32: %s\n", rcode == -1 && errno == EINVAL ? "EINVAL" : "installed");
+    rcode = sigaction(33, &sa1, NULL);
+    printf("signal 33: %s\n", rcode == -1 && errno == EINVAL ? "EINVAL" : "installed");
 }
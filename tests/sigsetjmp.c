@@ -0,0 +1,45 @@
+#include <setjmp.h>
+#include <signal.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+static void set_mask(int blocked) {
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, blocked);
+    int status = sigprocmask(SIG_SETMASK, &set, NULL);
+    ERROR_IF(sigprocmask, status, == -1);
+}
+
+static void print_mask(const char *when) {
+    sigset_t set;
+    int status = sigprocmask(SIG_BLOCK, NULL, &set);
+    ERROR_IF(sigprocmask, status, == -1);
+    printf("%s: SIGUSR1 blocked %d, SIGUSR2 blocked %d\n",
+        when, sigismember(&set, SIGUSR1), sigismember(&set, SIGUSR2));
+}
+
+int main(void) {
+    sigjmp_buf env;
+
+    // The mask saved by sigsetjmp comes back with siglongjmp
+    set_mask(SIGUSR1);
+    int ret = sigsetjmp(env, 1);
+    print_mask("savemask 1");
+    if (ret == 0) {
+        set_mask(SIGUSR2);
+        siglongjmp(env, 5);
+    }
+    printf("sigsetjmp returned %d\n", ret);
+
+    // Without savemask the mask is left alone
+    set_mask(SIGUSR1);
+    ret = sigsetjmp(env, 0);
+    print_mask("savemask 0");
+    if (ret == 0) {
+        set_mask(SIGUSR2);
+        siglongjmp(env, 0);
+    }
+    printf("sigsetjmp returned %d\n", ret);
+}
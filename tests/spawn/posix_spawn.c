@@ -0,0 +1,104 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <signal.h>
+#include <spawn.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+extern char **environ;
+
+int main(void) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    // Send the child's stdout into the pipe, and close both ends of it in
+    // the child so that only the redirected stdout is left open
+    posix_spawn_file_actions_t file_actions;
+    status = posix_spawn_file_actions_init(&file_actions);
+    ERROR_IF(posix_spawn_file_actions_init, status, != 0);
+    status = posix_spawn_file_actions_adddup2(&file_actions, fds[1], STDOUT_FILENO);
+    ERROR_IF(posix_spawn_file_actions_adddup2, status, != 0);
+    status = posix_spawn_file_actions_addclose(&file_actions, fds[0]);
+    ERROR_IF(posix_spawn_file_actions_addclose, status, != 0);
+    status = posix_spawn_file_actions_addclose(&file_actions, fds[1]);
+    ERROR_IF(posix_spawn_file_actions_addclose, status, != 0);
+    status = posix_spawn_file_actions_addopen(&file_actions, STDIN_FILENO, "/dev/null", O_RDONLY, 0);
+    ERROR_IF(posix_spawn_file_actions_addopen, status, != 0);
+
+    posix_spawnattr_t attr;
+    status = posix_spawnattr_init(&attr);
+    ERROR_IF(posix_spawnattr_init, status, != 0);
+    sigset_t mask;
+    sigemptyset(&mask);
+    status = posix_spawnattr_setsigmask(&attr, &mask);
+    ERROR_IF(posix_spawnattr_setsigmask, status, != 0);
+    status = posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETSIGMASK | POSIX_SPAWN_SETPGROUP);
+    ERROR_IF(posix_spawnattr_setflags, status, != 0);
+
+    char *args[] = { "sh", "-c", "echo spawned; cat", NULL };
+    pid_t pid;
+    status = posix_spawnp(&pid, "sh", &file_actions, &attr, args, environ);
+    ERROR_IF(posix_spawnp, status, != 0);
+    close(fds[1]);
+
+    char buf[64] = { 0 };
+    size_t len = 0;
+    ssize_t read_len;
+    while ((read_len = read(fds[0], buf + len, sizeof(buf) - 1 - len)) > 0) {
+        len += read_len;
+    }
+    ERROR_IF(read, read_len, == -1);
+    close(fds[0]);
+    printf("child wrote: %s", buf);
+
+    int wstatus;
+    pid_t waited = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, waited, == -1);
+    printf("exited: %d, status %d\n", WIFEXITED(wstatus), WEXITSTATUS(wstatus));
+
+    // Errors in the child are reported to the parent
+    char *missing_args[] = { "posix_spawn.missing", NULL };
+    errno = 0;
+    status = posix_spawn(&pid, "posix_spawn.missing", NULL, NULL, missing_args, environ);
+    printf("missing program: ENOENT %d, errno unchanged %d\n", status == ENOENT, errno == 0);
+
+    posix_spawn_file_actions_t bad_actions;
+    posix_spawn_file_actions_init(&bad_actions);
+    posix_spawn_file_actions_addopen(&bad_actions, 3, "posix_spawn.missing", O_RDONLY, 0);
+    status = posix_spawnp(&pid, "sh", &bad_actions, NULL, args, environ);
+    printf("failing file action: ENOENT %d\n", status == ENOENT);
+    posix_spawn_file_actions_destroy(&bad_actions);
+
+    // Errors are still reported when the file actions close or replace every
+    // descriptor the child may have for reporting them
+    posix_spawn_file_actions_t closing_actions;
+    posix_spawn_file_actions_init(&closing_actions);
+    for (int fd = 3; fd < 32; fd++) {
+        posix_spawn_file_actions_addclose(&closing_actions, fd);
+    }
+    posix_spawn_file_actions_adddup2(&closing_actions, 1, 40);
+    status = posix_spawn(&pid, "posix_spawn.missing", &closing_actions, NULL, missing_args, environ);
+    printf("missing program after closing: ENOENT %d\n", status == ENOENT);
+    posix_spawn_file_actions_destroy(&closing_actions);
+
+    // PATH is searched for a program without a slash
+    char *true_args[] = { "true", NULL };
+    status = posix_spawnp(&pid, "true", NULL, NULL, true_args, environ);
+    ERROR_IF(posix_spawnp, status, != 0);
+    waited = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, waited, == -1);
+    printf("true from PATH: exited %d, status %d\n", WIFEXITED(wstatus), WEXITSTATUS(wstatus));
+    status = posix_spawnp(&pid, "posix_spawn.missing", NULL, NULL, missing_args, environ);
+    printf("missing program in PATH: ENOENT %d\n", status == ENOENT);
+
+    status = posix_spawnattr_setflags(&attr, 0x4000);
+    printf("unknown flags: EINVAL %d\n", status == EINVAL);
+
+    posix_spawnattr_destroy(&attr);
+    posix_spawn_file_actions_destroy(&file_actions);
+}
@@ -0,0 +1,43 @@
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Built with -fstack-protector-all, so every function here checks its canary
+
+__attribute__((noinline))
+static void copy(const char *src, size_t len) {
+    char buffer[8];
+    memcpy(buffer, src, len);
+    printf("copied %zu bytes\n", len);
+}
+
+static void copy_child(void *len) {
+    static char src[64];
+    memset(src, 'A', sizeof(src));
+    copy(src, *(size_t *) len);
+    fflush(stdout);
+}
+
+// Run copy in a child, printing what it wrote to stderr and how it exited
+static void run(size_t len) {
+    char message[256];
+    int wstatus = run_child_capture(STDERR_FILENO, copy_child, &len, message, sizeof(message));
+
+    printf("stderr: %s", message[0] != '\0' ? message : "(empty)\n");
+    if (WIFSIGNALED(wstatus)) {
+        printf("aborted: %d\n", WTERMSIG(wstatus) == SIGABRT);
+    } else {
+        printf("exit status %d\n", WEXITSTATUS(wstatus));
+    }
+}
+
+int main(void) {
+    // Within bounds, so the canary is left intact
+    run(8);
+
+    // Overwrites the canary and the saved registers after it
+    run(64);
+}
@@ -0,0 +1,47 @@
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+static int wrapper(char **strp, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    int ret = vasprintf(strp, fmt, ap);
+    va_end(ap);
+    return ret;
+}
+
+int main(void) {
+    char *s = NULL;
+    int len = asprintf(&s, "%s", "");
+    ERROR_IF(asprintf, len, == -1);
+    printf("empty: %d '%s'\n", len, s);
+    free(s);
+
+    len = asprintf(&s, "%d %s %c", 42, "hello", '!');
+    ERROR_IF(asprintf, len, == -1);
+    printf("short: %d '%s', strlen %zu\n", len, s, strlen(s));
+    free(s);
+
+    // Longer than any initial buffer guess
+    char big[5000];
+    memset(big, 'x', sizeof(big) - 1);
+    big[sizeof(big) - 1] = 0;
+    len = asprintf(&s, "[%s][%s]", big, big);
+    ERROR_IF(asprintf, len, == -1);
+    printf("long: %d, strlen %zu, ends with ']': %d\n", len, strlen(s), s[len - 1] == ']');
+    free(s);
+
+    len = asprintf(&s, "%1000d|", 7);
+    ERROR_IF(asprintf, len, == -1);
+    printf("padded: %d, strlen %zu\n", len, strlen(s));
+    free(s);
+
+    // The arguments are used for both passes over the format
+    len = wrapper(&s, "%s=%d, %s=%d", "a", 1, "bb", 22);
+    ERROR_IF(vasprintf, len, == -1);
+    printf("vasprintf: %d '%s'\n", len, s);
+    free(s);
+}
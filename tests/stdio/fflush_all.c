@@ -0,0 +1,118 @@
+#include <fcntl.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <stdio_ext.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static const char *paths[] = { "fflush_all_0.out", "fflush_all_1.out", "fflush_all_2.out" };
+static const char *data[] = { "first", "second", "third" };
+
+// Read what has reached the file itself, bypassing stdio
+static void show(const char *path) {
+    char buf[32] = { 0 };
+    int fd = open(path, O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+    ssize_t len = read(fd, buf, sizeof(buf) - 1);
+    ERROR_IF(read, len, == -1);
+    close(fd);
+    printf("%s: '%s'\n", path, buf);
+}
+
+// Opens and closes streams while the main thread flushes them all
+static void *churn(void *arg) {
+    const char *path = arg;
+    for (int i = 0; i < 1000; i++) {
+        FILE *f = fopen(path, "w");
+        ERROR_IF(fopen, f, == NULL);
+        fputs("churn", f);
+        int status = fclose(f);
+        ERROR_IF(fclose, status, == EOF);
+    }
+    return NULL;
+}
+
+// Reopens a stream while the main thread flushes them all
+static void *reopen(void *arg) {
+    const char *path = arg;
+    FILE *f = fopen(path, "w");
+    ERROR_IF(fopen, f, == NULL);
+    for (int i = 0; i < 1000; i++) {
+        fputs("reopen", f);
+        f = freopen(path, "w", f);
+        ERROR_IF(freopen, f, == NULL);
+    }
+    int status = fclose(f);
+    ERROR_IF(fclose, status, == EOF);
+    return NULL;
+}
+
+int main(void) {
+    FILE *files[3];
+    for (int i = 0; i < 3; i++) {
+        files[i] = fopen(paths[i], "w");
+        ERROR_IF(fopen, files[i], == NULL);
+        int status = fputs(data[i], files[i]);
+        ERROR_IF(fputs, status, == EOF);
+    }
+
+    puts("before fflush(NULL):");
+    for (int i = 0; i < 3; i++) {
+        show(paths[i]);
+    }
+
+    int status = fflush(NULL);
+    ERROR_IF(fflush, status, == EOF);
+    puts("after fflush(NULL):");
+    for (int i = 0; i < 3; i++) {
+        show(paths[i]);
+    }
+
+    // A purged stream drops its buffered output without writing it
+    fputs(" dropped", files[0]);
+    __fpurge(files[0]);
+    fputs(" kept", files[0]);
+    status = fflush(files[0]);
+    ERROR_IF(fflush, status, == EOF);
+    puts("after __fpurge:");
+    show(paths[0]);
+
+    // Closed streams are left out of later flushes
+    for (int i = 0; i < 3; i++) {
+        fclose(files[i]);
+    }
+    status = fflush(NULL);
+    ERROR_IF(fflush, status, == EOF);
+
+    // Flushing every stream and closing one at the same time neither
+    // deadlocks nor flushes a freed stream
+    pthread_t thread;
+    status = pthread_create(&thread, NULL, churn, (void *) paths[1]);
+    ERROR_IF(pthread_create, status, != 0);
+    for (int i = 0; i < 1000; i++) {
+        status = fflush(NULL);
+        ERROR_IF(fflush, status, == EOF);
+    }
+    status = pthread_join(thread, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+    puts("flushed while streams were closed");
+
+    // Nor while a stream is reopened, which opens and closes a stream of its
+    // own
+    status = pthread_create(&thread, NULL, reopen, (void *) paths[2]);
+    ERROR_IF(pthread_create, status, != 0);
+    for (int i = 0; i < 1000; i++) {
+        status = fflush(NULL);
+        ERROR_IF(fflush, status, == EOF);
+    }
+    status = pthread_join(thread, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+    puts("flushed while a stream was reopened");
+
+    for (int i = 0; i < 3; i++) {
+        status = unlink(paths[i]);
+        ERROR_IF(unlink, status, == -1);
+    }
+}
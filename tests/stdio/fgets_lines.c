@@ -0,0 +1,50 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    FILE *f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    const char data[] = "a line that is longer than the buffer\nnul\0inside\nlast";
+    size_t written = fwrite(data, 1, sizeof(data) - 1, f);
+    ERROR_IF(fwrite, written, != sizeof(data) - 1);
+    rewind(f);
+
+    // A long line is split over several calls, each filling the buffer
+    char buf[16];
+    char line[64] = { 0 };
+    do {
+        char *res = fgets(buf, sizeof(buf), f);
+        ERROR_IF(fgets, res, == NULL);
+        printf("part: '%s' (%zu bytes)\n", buf, strlen(buf));
+        strcat(line, buf);
+    } while (buf[strlen(buf) - 1] != '\n');
+    printf("reconstructed: %d\n", strcmp(line, "a line that is longer than the buffer\n") == 0);
+
+    // An embedded NUL doesn't end the line, the newline after it does
+    memset(buf, 'x', sizeof(buf));
+    char *res = fgets(buf, sizeof(buf), f);
+    ERROR_IF(fgets, res, == NULL);
+    printf("with NUL: '%s', then '%s', ends after the newline: %d\n",
+        buf, buf + 4, buf[11] == '\0');
+
+    // A last line without newline is returned at EOF, after that NULL
+    res = fgets(buf, sizeof(buf), f);
+    printf("last: '%s', returned buf: %d\n", buf, res == buf);
+    res = fgets(buf, sizeof(buf), f);
+    printf("at EOF: NULL %d, feof %d\n", res == NULL, feof(f) != 0);
+
+    // Pushed back bytes are read first, including a newline
+    ungetc('\n', f);
+    ungetc('b', f);
+    res = fgets(buf, sizeof(buf), f);
+    printf("pushed back: '%s', returned buf: %d\n", buf == res ? buf : "(null)", res == buf);
+
+    // A buffer of one byte only gets the NUL
+    buf[0] = 'x';
+    res = fgets(buf, 1, f);
+    printf("size 1: returned buf %d, empty %d\n", res == buf, buf[0] == '\0');
+
+    fclose(f);
+}
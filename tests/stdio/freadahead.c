@@ -0,0 +1,44 @@
+#include <stdio.h>
+#include <stdio_ext.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "freadahead.out"
+
+int main(void) {
+    FILE *f = fopen(PATH, "w");
+    ERROR_IF(fopen, f, == NULL);
+    for (int i = 0; i < 100; i++) {
+        fputc('a' + i % 26, f);
+    }
+    printf("writing: pending %zu, readable %d, writable %d, reading %d, writing %d\n",
+        __fpending(f), __freadable(f), __fwritable(f), __freading(f) != 0, __fwriting(f) != 0);
+    fclose(f);
+
+    f = fopen(PATH, "r");
+    ERROR_IF(fopen, f, == NULL);
+    printf("before reading: %zu ahead\n", __freadahead(f));
+
+    // The first read fills the buffer with the whole file
+    for (int i = 0; i < 10; i++) {
+        fgetc(f);
+    }
+    printf("after 10 bytes: %zu ahead, buffer size is set: %d\n", __freadahead(f), __fbufsize(f) > 0);
+
+    char buf[40];
+    size_t len = fread(buf, 1, sizeof(buf), f);
+    ERROR_IF(fread, len, != sizeof(buf));
+    printf("after 50 bytes: %zu ahead\n", __freadahead(f));
+
+    int status = fseek(f, 0, SEEK_SET);
+    ERROR_IF(fseek, status, == -1);
+    printf("after fseek: %zu ahead\n", __freadahead(f));
+
+    printf("reading: pending %zu, readable %d, writable %d, reading %d, writing %d, line buffered %d\n",
+        __fpending(f), __freadable(f), __fwritable(f), __freading(f) != 0, __fwriting(f) != 0, __flbf(f) != 0);
+    fclose(f);
+
+    status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
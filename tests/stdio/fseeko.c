@@ -0,0 +1,53 @@
+#include <stdio.h>
+#include <sys/types.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "fseeko.out"
+
+int main(void) {
+    FILE *f = fopen(PATH, "w+");
+    ERROR_IF(fopen, f, == NULL);
+
+    // Write a single byte past 2 GiB, leaving a hole before it
+    off_t far = (off_t) 3 << 30;
+    int status = fseeko(f, far, SEEK_SET);
+    ERROR_IF(fseeko, status, == -1);
+    status = fputs("xyz", f);
+    ERROR_IF(fputs, status, == EOF);
+
+    // The pending write is counted before it is flushed by the next seek
+    off_t pos = ftello(f);
+    ERROR_IF(ftello, pos, == -1);
+    printf("ftello after write: %lld\n", (long long) pos);
+
+    status = fseeko(f, 0, SEEK_SET);
+    ERROR_IF(fseeko, status, == -1);
+    int c = fgetc(f);
+    printf("byte at 0: %d\n", c);
+
+    // Buffered read data is discarded by the seek
+    status = fseeko(f, far + 1, SEEK_SET);
+    ERROR_IF(fseeko, status, == -1);
+    c = fgetc(f);
+    printf("byte at %lld: %c\n", (long long) (far + 1), c);
+
+    // Pushed back bytes count against the position, and are dropped by a
+    // relative seek
+    status = ungetc('Y', f);
+    ERROR_IF(ungetc, status, == EOF);
+    printf("ftello after ungetc: %lld\n", (long long) ftello(f));
+    status = fseeko(f, -1, SEEK_CUR);
+    ERROR_IF(fseeko, status, == -1);
+    c = fgetc(f);
+    printf("byte after seeking back: %c, ftello %lld\n", c, (long long) ftello(f));
+
+    status = fseeko(f, 0, SEEK_END);
+    ERROR_IF(fseeko, status, == -1);
+    printf("ftello at end: %lld\n", (long long) ftello(f));
+
+    fclose(f);
+    status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
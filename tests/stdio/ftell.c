@@ -0,0 +1,65 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    FILE *f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+
+    // Buffered writes must be reflected in the position before a flush
+    const char *chunks[] = { "Hello", ", ", "world", "!\n" };
+    for (int i = 0; i < sizeof(chunks) / sizeof(chunks[0]); i++) {
+        size_t written = fwrite(chunks[i], 1, strlen(chunks[i]), f);
+        ERROR_IF(fwrite, written, != strlen(chunks[i]));
+
+        long pos = ftell(f);
+        ERROR_IF(ftell, pos, == -1);
+        printf("ftell after fwrite: %ld\n", pos);
+    }
+
+    int status = fputs("second line\n", f);
+    ERROR_IF(fputs, status, == EOF);
+    printf("ftell after fputs: %ld\n", ftell(f));
+
+    // Seeking relative to the current position must take pending writes
+    // into account
+    status = fseek(f, -5, SEEK_CUR);
+    ERROR_IF(fseek, status, == -1);
+    printf("ftell after fseek(-5, SEEK_CUR): %ld\n", ftell(f));
+
+    char buffer[16] = { 0 };
+    size_t read = fread(buffer, 1, 4, f);
+    ERROR_IF(fread, read, != 4);
+    printf("read: %s, ftell: %ld\n", buffer, ftell(f));
+
+    // The read buffer holds the rest of the file now
+    rewind(f);
+    int c = fgetc(f);
+    ERROR_IF(fgetc, c, == EOF);
+    printf("fgetc: %c, ftell: %ld\n", c, ftell(f));
+
+    status = fseek(f, 6, SEEK_CUR);
+    ERROR_IF(fseek, status, == -1);
+    c = fgetc(f);
+    ERROR_IF(fgetc, c, == EOF);
+    printf("fgetc after fseek(6, SEEK_CUR): %c, ftell: %ld\n", c, ftell(f));
+
+    // Pushed back bytes move the position backwards
+    c = ungetc('W', f);
+    ERROR_IF(ungetc, c, == EOF);
+    printf("ftell after ungetc: %ld\n", ftell(f));
+
+    status = fseek(f, 1, SEEK_CUR);
+    ERROR_IF(fseek, status, == -1);
+    c = fgetc(f);
+    ERROR_IF(fgetc, c, == EOF);
+    printf("fgetc after ungetc and fseek(1, SEEK_CUR): %c, ftell: %ld\n", c, ftell(f));
+
+    status = fseek(f, 0, SEEK_END);
+    ERROR_IF(fseek, status, == -1);
+    printf("ftell at end: %ld\n", ftell(f));
+
+    fclose(f);
+}
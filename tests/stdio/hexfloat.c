@@ -0,0 +1,81 @@
+#include <float.h>
+#include <math.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+static uint64_t bits(double d) {
+    uint64_t b;
+    memcpy(&b, &d, sizeof(b));
+    return b;
+}
+
+static void round_trip(double d) {
+    char buf[64];
+    double back = 0.0;
+    snprintf(buf, sizeof(buf), "%a", d);
+    int matched = sscanf(buf, "%la", &back);
+    printf("%s: matched %d, same bits %d\n", buf, matched, bits(back) == bits(d));
+}
+
+int main(void) {
+    printf("%a %A\n", 3.14, 3.14);
+    printf("%a %a %a\n", 1.0, 0.5, -2.0);
+    printf("%a %a\n", 0.0, -0.0);
+    printf("%a %A %a %A\n", INFINITY, -INFINITY, NAN, NAN);
+
+    // Rounding to the precision is to nearest even
+    printf("%.1a %.1a %.1a\n", 1.03125, 1.09375, 1.0625);
+    printf("%.3a %.0a %#.0a\n", 3.14, 1.5, 1.0);
+    // A carry into the leading digit moves to the exponent
+    printf("%.0a %.2a\n", 1.75, 1.999);
+    printf("%.15a\n", 1.0);
+
+    printf("[%24a] [%-24a] [%024a]\n", 3.14, 3.14, -3.14);
+
+    // Subnormals are normalized too
+    printf("%a %a\n", DBL_TRUE_MIN, DBL_MIN / 3);
+
+    round_trip(3.14);
+    round_trip(-0.1);
+    round_trip(0.0);
+    round_trip(-0.0);
+    round_trip(DBL_MAX);
+    round_trip(DBL_MIN);
+    round_trip(DBL_TRUE_MIN);
+    round_trip(DBL_MIN / 3);
+    round_trip(INFINITY);
+    round_trip(-INFINITY);
+
+    double d = 0.0;
+    int matched = sscanf("nan", "%la", &d);
+    printf("nan: matched %d, isnan %d\n", matched, isnan(d) != 0);
+
+    // Long inputs round correctly, and %a without l stores a float
+    matched = sscanf("0x1.000000000000080000001p0", "%la", &d);
+    printf("%d %a\n", matched, d);
+    matched = sscanf("0X1.0000000000000800P0", "%lA", &d);
+    printf("%d %a\n", matched, d);
+    float f = 0.0f;
+    matched = sscanf("0x1.8p-3", "%a", &f);
+    printf("%d %a\n", matched, (double) f);
+    matched = sscanf("1.5e2", "%la", &d);
+    printf("%d %a\n", matched, d);
+    d = 42.0;
+    matched = sscanf("0xg", "%la", &d);
+    printf("%d %a\n", matched, d);
+    matched = sscanf("x", "%la", &d);
+    printf("%d %a\n", matched, d);
+
+    // %e, %f and %g take the same forms as %a
+    matched = sscanf("-1.5e3", "%le", &d);
+    printf("%d %a\n", matched, d);
+    matched = sscanf("0x1.8p1", "%lf", &d);
+    printf("%d %a\n", matched, d);
+    matched = sscanf("-INF", "%lg", &d);
+    printf("%d %a\n", matched, d);
+    matched = sscanf("0.1", "%lG", &d);
+    printf("%d %a\n", matched, d);
+    matched = sscanf("+2.5E-1", "%E", &f);
+    printf("%d %a\n", matched, (double) f);
+}
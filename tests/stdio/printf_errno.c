@@ -0,0 +1,27 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char buf[256];
+    char expected[256];
+
+    errno = EINVAL;
+    int len = snprintf(buf, sizeof(buf), "%m");
+    snprintf(expected, sizeof(expected), "%s", strerror(EINVAL));
+    printf("'%s' (%d), same as strerror: %d\n", buf, len, strcmp(buf, expected) == 0);
+
+    // No argument is consumed
+    errno = ENOENT;
+    snprintf(buf, sizeof(buf), "%d: %m: %s", 1, "next");
+    printf("%s\n", buf);
+
+    // Width, precision and flags apply like for %s
+    errno = EPERM;
+    snprintf(buf, sizeof(buf), "[%30m] [%-30m] [%.9m] [%*m]", 26);
+    printf("%s\n", buf);
+
+    errno = EINVAL;
+    printf("printf: %m\n");
+    fprintf(stderr, "fprintf: %m\n");
+}
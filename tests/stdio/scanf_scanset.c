@@ -0,0 +1,49 @@
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char line[64] = { 0 };
+    char rest[64] = { 0 };
+    int n = -1, m = -1;
+
+    // Read a whole line, then what follows it
+    int ret = sscanf("first line here\nsecond", "%[^\n]%n\n%s%n", line, &n, rest, &m);
+    printf("line: %d '%s' '%s', offsets %d %d\n", ret, line, rest, n, m);
+
+    // Ranges, several of them in one set
+    char word[16] = { 0 };
+    ret = sscanf("abcXYZ123_rest", "%[a-zA-Z0-9]%n", word, &n);
+    printf("ranges: %d '%s', offset %d\n", ret, word, n);
+
+    // A ']' first in the set is literal, also after '^'
+    char brackets[16] = { 0 };
+    ret = sscanf("]]x[y", "%[]x]%n", brackets, &n);
+    printf("literal ]: %d '%s', offset %d\n", ret, brackets, n);
+    ret = sscanf("ab]c", "%[^]]%n", brackets, &n);
+    printf("negated ]: %d '%s', offset %d\n", ret, brackets, n);
+
+    // A '-' at the end of the set is literal
+    ret = sscanf("a-b-c+", "%[a-c-]%n", word, &n);
+    printf("literal -: %d '%s', offset %d\n", ret, word, n);
+
+    // Width limits the match, suppression matches without storing
+    ret = sscanf("aaaaab", "%3[a]%n", word, &n);
+    printf("width: %d '%s', offset %d\n", ret, word, n);
+    ret = sscanf("   skip this:value", "%*[ ]%*[^:]:%s%n", word, &n);
+    printf("suppressed: %d '%s', offset %d\n", ret, word, n);
+
+    // An empty match fails the conversion and stops the scan
+    strcpy(word, "unchanged");
+    n = -1;
+    ret = sscanf("123", "%[a-z]%n", word, &n);
+    printf("empty match: %d '%s', offset %d\n", ret, word, n);
+
+    // %n counts what was consumed, and is not a conversion
+    int a = 0, b = 0;
+    ret = sscanf("12 345 6", "%d%n %d%n", &a, &n, &b, &m);
+    printf("numbers: %d, %d %d, offsets %d %d\n", ret, a, b, n, m);
+    ret = sscanf("0x5z", "%2i%n", &a, &n);
+    printf("width-limited number: %d, %d, offset %d\n", ret, a, n);
+    ret = sscanf("abc", "%n", &n);
+    printf("only %%n: %d, offset %d\n", ret, n);
+}
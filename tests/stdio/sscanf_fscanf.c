@@ -0,0 +1,68 @@
+#include <stdarg.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+struct result {
+    int ret;
+    int i;
+    unsigned int x;
+    double d;
+    char c;
+    char s[16];
+    char set[16];
+};
+
+static int scan_string(const char *input, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    int ret = vsscanf(input, fmt, ap);
+    va_end(ap);
+    return ret;
+}
+
+static int scan_file(FILE *f, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    int ret = vfscanf(f, fmt, ap);
+    va_end(ap);
+    return ret;
+}
+
+static void print(const char *source, struct result *r) {
+    printf("  %s: %d, i %d, x %#x, d %g, c %d, s '%s', set '%s'\n",
+        source, r->ret, r->i, r->x, r->d, r->c, r->s, r->set);
+}
+
+// Run the same format over a string and over a file with the same contents
+static void check(const char *fmt, const char *input) {
+    struct result str, file;
+    memset(&str, 0, sizeof(str));
+    memset(&file, 0, sizeof(file));
+
+    str.ret = scan_string(input, fmt, &str.i, &str.x, &str.d, &str.c, str.s, str.set);
+
+    FILE *f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    fputs(input, f);
+    rewind(f);
+    file.ret = scan_file(f, fmt, &file.i, &file.x, &file.d, &file.c, file.s, file.set);
+    fclose(f);
+
+    printf("\"%s\" on \"%s\": same %d\n", fmt, input, memcmp(&str, &file, sizeof(str)) == 0);
+    print("sscanf", &str);
+    print("fscanf", &file);
+}
+
+int main(void) {
+    const char *fmt = "%d %x %lf %c%5s %*d %[a-z]";
+    check(fmt, "42 ff 2.5 zword 7 abc!");
+    check(fmt, "12 1F 0.125 ?toolongword 7 xyz");
+    check(fmt, "12 1F 0.125 ?short 7 xyz");
+    check(fmt, "1 2 3");
+    check(fmt, "");
+    check(fmt, "nope");
+    check("%d", "123");
+    check("%d %x", "5 ");
+}
@@ -0,0 +1,55 @@
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    FILE *f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    fputs("abcdef", f);
+    rewind(f);
+
+    char buf[5] = { 0 };
+    for (int i = 0; i < 4; i++) {
+        buf[i] = fgetc(f);
+    }
+    printf("read: %s, ftell %ld\n", buf, ftell(f));
+
+    // Three characters pushed back come out in reverse order of pushing
+    int pushed = 0;
+    pushed += ungetc('3', f) == '3';
+    pushed += ungetc('2', f) == '2';
+    pushed += ungetc('1', f) == '1';
+    printf("pushed back: %d, ftell %ld\n", pushed, ftell(f));
+
+    int c = fgetc(f);
+    printf("got %c, ftell %ld\n", c, ftell(f));
+    c = fgetc(f);
+    printf("got %c, ftell %ld\n", c, ftell(f));
+    c = fgetc(f);
+    printf("got %c, ftell %ld\n", c, ftell(f));
+    c = fgetc(f);
+    printf("then the stream: %c\n", c);
+
+    // Pushing back EOF fails and leaves the stream alone
+    int status = ungetc(EOF, f);
+    c = fgetc(f);
+    printf("ungetc(EOF): %d, next: %c\n", status, c);
+
+    // A seek discards pushed back characters
+    ungetc('x', f);
+    ungetc('y', f);
+    status = fseek(f, 1, SEEK_SET);
+    ERROR_IF(fseek, status, == -1);
+    c = fgetc(f);
+    printf("after fseek: %c\n", c);
+
+    // Pushing back at the end of the file clears the end-of-file indicator
+    while (fgetc(f) != EOF) {}
+    printf("feof: %d\n", feof(f) != 0);
+    ungetc('z', f);
+    printf("feof after ungetc: %d\n", feof(f) != 0);
+    c = fgetc(f);
+    printf("got %c, then EOF: %d\n", c, fgetc(f) == EOF);
+
+    fclose(f);
+}
@@ -0,0 +1,58 @@
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void returning_handler(int sig) {
+    (void) sig;
+    static const char message[] = "handler returned\n";
+    write(STDOUT_FILENO, message, sizeof(message) - 1);
+}
+
+static void with_handler(void) {
+    struct sigaction act;
+    memset(&act, 0, sizeof(act));
+    act.sa_handler = returning_handler;
+    sigaction(SIGABRT, &act, NULL);
+}
+
+static void with_blocked(void) {
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, SIGABRT);
+    sigprocmask(SIG_BLOCK, &set, NULL);
+}
+
+static void with_ignored(void) {
+    signal(SIGABRT, SIG_IGN);
+}
+
+static void abort_child(void *arg) {
+    void (*setup)(void) = *(void (**)(void)) arg;
+    if (setup != NULL) {
+        setup();
+    }
+    // Left in the buffer, as abort does not flush streams
+    fputs("buffered\n", stdout);
+    abort();
+}
+
+// Call abort in a child after setup, printing what reached its stdout and how
+// the child exited
+static void run(const char *name, void (*setup)(void)) {
+    char output[256];
+    int wstatus = run_child_capture(STDOUT_FILENO, abort_child, &setup, output, sizeof(output));
+
+    printf("%s: %s", name, output[0] != '\0' ? output : "no output\n");
+    printf("%s: aborted %d\n", name, WIFSIGNALED(wstatus) && WTERMSIG(wstatus) == SIGABRT);
+}
+
+int main(void) {
+    run("default", NULL);
+    run("handler", with_handler);
+    run("blocked", with_blocked);
+    run("ignored", with_ignored);
+}
@@ -0,0 +1,60 @@
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define SAMPLES 30000
+
+int main(void) {
+    // Successive values differ without any seeding
+    uint32_t first = arc4random();
+    int differ = 0;
+    for (int i = 0; i < 4; i++) {
+        if (arc4random() != first) {
+            differ = 1;
+        }
+    }
+    printf("arc4random values differ: %d\n", differ);
+
+    unsigned char buf[256];
+    memset(buf, 0, sizeof(buf));
+    arc4random_buf(buf, sizeof(buf));
+    int zeros = 0;
+    for (size_t i = 0; i < sizeof(buf); i++) {
+        if (buf[i] == 0) {
+            zeros++;
+        }
+    }
+    printf("arc4random_buf filled the buffer: %d\n", zeros < 16);
+
+    printf("arc4random_uniform(0): %u\n", arc4random_uniform(0));
+    printf("arc4random_uniform(1): %u\n", arc4random_uniform(1));
+
+    // Each of the three outputs should get close to a third of the samples.
+    // The allowed deviation is more than ten standard deviations.
+    int counts[3] = { 0 };
+    int out_of_range = 0;
+    for (int i = 0; i < SAMPLES; i++) {
+        uint32_t value = arc4random_uniform(3);
+        if (value < 3) {
+            counts[value]++;
+        } else {
+            out_of_range++;
+        }
+    }
+    printf("arc4random_uniform(3) out of range: %d\n", out_of_range);
+    for (int i = 0; i < 3; i++) {
+        printf("%d is close to a third: %d\n", i, counts[i] > 9200 && counts[i] < 10800);
+    }
+
+    // With a bound of 3 * 2^30, plain modulo would return the lowest third
+    // of the range half of the time
+    uint32_t bound = 0xC0000000;
+    int low = 0;
+    for (int i = 0; i < SAMPLES; i++) {
+        if (arc4random_uniform(bound) < bound / 3) {
+            low++;
+        }
+    }
+    printf("large bound unbiased: %d\n", low > 9200 && low < 10800);
+}
@@ -0,0 +1,69 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+#define HANDLERS 100
+
+// Each handler checks that it runs right after the one registered after it
+static int expected = HANDLERS - 1;
+static int in_order = 1;
+
+#define HANDLER(n) \
+    static void handler##n(void) { \
+        if (n != expected) { \
+            in_order = 0; \
+        } \
+        expected--; \
+    }
+#define HANDLERS10(n) \
+    HANDLER(n##0) HANDLER(n##1) HANDLER(n##2) HANDLER(n##3) HANDLER(n##4) \
+    HANDLER(n##5) HANDLER(n##6) HANDLER(n##7) HANDLER(n##8) HANDLER(n##9)
+
+HANDLER(0) HANDLER(1) HANDLER(2) HANDLER(3) HANDLER(4)
+HANDLER(5) HANDLER(6) HANDLER(7) HANDLER(8) HANDLER(9)
+HANDLERS10(1) HANDLERS10(2) HANDLERS10(3) HANDLERS10(4)
+HANDLERS10(5) HANDLERS10(6) HANDLERS10(7) HANDLERS10(8) HANDLERS10(9)
+
+#define REF10(n) \
+    handler##n##0, handler##n##1, handler##n##2, handler##n##3, handler##n##4, \
+    handler##n##5, handler##n##6, handler##n##7, handler##n##8, handler##n##9
+
+static void (*handlers[HANDLERS])(void) = {
+    handler0, handler1, handler2, handler3, handler4,
+    handler5, handler6, handler7, handler8, handler9,
+    REF10(1), REF10(2), REF10(3), REF10(4), REF10(5), REF10(6), REF10(7), REF10(8), REF10(9),
+};
+
+static void late_handler(void) {
+    printf("late handler, all %d handlers in reverse order: %d\n", HANDLERS,
+        expected == -1 && in_order);
+}
+
+// Registered first, so it runs last and registers one more handler, which
+// runs right after it
+static void registering_handler(void) {
+    printf("registering handler\n");
+    int status = atexit(late_handler);
+    ERROR_IF(atexit, status, != 0);
+}
+
+static void quick_handler(void) {
+    puts("quick exit handler");
+}
+
+int main(void) {
+    int status = atexit(registering_handler);
+    ERROR_IF(atexit, status, != 0);
+    for (int i = 0; i < HANDLERS; i++) {
+        status = atexit(handlers[i]);
+        ERROR_IF(atexit, status, != 0);
+    }
+
+    // Only run by quick_exit
+    status = at_quick_exit(quick_handler);
+    ERROR_IF(at_quick_exit, status, != 0);
+
+    puts("exit");
+    return 0;
+}
@@ -1,3 +1,4 @@
+#include <stdint.h>
 #include <stdlib.h>
 #include <stdio.h>
 
@@ -7,6 +8,21 @@ int int_cmp(const void* a, const void* b) {
     return *(const int*) a - *(const int*) b;
 }
 
+static int calls = 0;
+
+int counting_cmp(const void* a, const void* b) {
+    calls++;
+    return int_cmp(a, b);
+}
+
+// Compares the addresses of the elements of an array that is never read
+int address_cmp(const void* a, const void* b) {
+    calls++;
+    uintptr_t key = *(const uintptr_t*) a;
+    uintptr_t elem = (uintptr_t) b;
+    return (key > elem) - (key < elem);
+}
+
 #define BSEARCH_TEST_INT(key, arr, len, expect) \
     do { \
         void* res = bsearch((const void*) &key, (void*) arr, len, sizeof(int), int_cmp); \
@@ -23,12 +39,12 @@ int main(void) {
     int x = 0;
     int y = 1024;
 
-    // TODO: Zero sized arrays are a non-standard GNU extension
-    //int empty[] = {};
-    //BSEARCH_TEST_INT(x, empty, 0, NULL);
+    // An empty array has nothing to compare
+    int empty[] = {0};
+    void* res = bsearch(&x, empty, 0, sizeof(int), counting_cmp);
+    printf("empty: %d, %d calls\n", res == NULL, calls);
 
     int singleton[] = {42};
-    printf("%p\n%p\n", singleton, &singleton[1]);
     BSEARCH_TEST_INT(x, singleton, 1, NULL);
     BSEARCH_TEST_INT(singleton[0], singleton, 1, &singleton[0]);
     BSEARCH_TEST_INT(y, singleton, 1, NULL);
@@ -52,5 +68,20 @@ int main(void) {
     BSEARCH_TEST_INT(big[3], big, 7, &big[3]);
     BSEARCH_TEST_INT(x, big, 7, NULL);
 
+    // Any of the equal elements may be found
+    int same[] = {1, 7, 7, 7, 7, 7, 9};
+    int seven = 7;
+    res = bsearch(&seven, same, 7, sizeof(int), int_cmp);
+    printf("duplicates: %d\n", res != NULL && *(int*) res == 7);
+
+    // Halfway through an array covering most of the address space, the start
+    // and end indices added together would overflow
+    calls = 0;
+    char* base = (char*) 16;
+    size_t len = SIZE_MAX - 16;
+    uintptr_t target = SIZE_MAX - 2;
+    res = bsearch(&target, base, len, 1, address_cmp);
+    printf("large array: %d, few calls: %d\n", res == (void*) target, calls <= 64);
+
     printf("PASS bsearch\n");
 }
@@ -0,0 +1,37 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+extern char **environ;
+
+static void print_var(const char *name) {
+    const char *value = getenv(name);
+    printf("%s: %s\n", name, value ? value : "(unset)");
+}
+
+int main(void) {
+    setenv("CLEARENV_SET", "set", 1);
+    char string[] = "CLEARENV_PUT=put";
+    putenv(string);
+
+    int status = clearenv();
+    ERROR_IF(clearenv, status, != 0);
+
+    // Variables from the start of the program are gone too
+    print_var("CLEARENV_SET");
+    print_var("CLEARENV_PUT");
+    print_var("PATH");
+    printf("environ: %s\n", environ == NULL || environ[0] == NULL ? "empty" : "not empty");
+    printf("putenv string: %s\n", string);
+
+    setenv("CLEARENV_NEW", "new", 1);
+    print_var("CLEARENV_NEW");
+    for (char **item = environ; *item; item++) {
+        printf("environ: %s\n", *item);
+    }
+
+    status = clearenv();
+    ERROR_IF(clearenv, status, != 0);
+    print_var("CLEARENV_NEW");
+}
@@ -0,0 +1,53 @@
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+static void test_ecvt(double value, int ndigit) {
+    int decpt, sign;
+    char *digits = ecvt(value, ndigit, &decpt, &sign);
+    printf("ecvt(%g, %d): \"%s\", decpt %d, sign %d\n", value, ndigit, digits, decpt, sign);
+}
+
+static void test_fcvt(double value, int ndigit) {
+    int decpt, sign;
+    char *digits = fcvt(value, ndigit, &decpt, &sign);
+    printf("fcvt(%g, %d): \"%s\", decpt %d, sign %d\n", value, ndigit, digits, decpt, sign);
+}
+
+static void test_gcvt(double value, int ndigit) {
+    char buf[64];
+    printf("gcvt(%g, %d): \"%s\"\n", value, ndigit, gcvt(value, ndigit, buf));
+}
+
+int main(void) {
+    test_ecvt(123.456, 5);
+    test_ecvt(-123.456, 2);
+    test_ecvt(0.0012345, 3);
+    test_ecvt(1e300, 2);
+    // Rounding carries into a new leading digit
+    test_ecvt(9.999, 3);
+    test_ecvt(99.99, 3);
+    test_ecvt(0, 3);
+    test_ecvt(-0.0, 3);
+    test_ecvt(INFINITY, 3);
+
+    test_fcvt(123.456, 2);
+    test_fcvt(-1.26, 1);
+    test_fcvt(0.0012, 3);
+    test_fcvt(1e20, 2);
+    test_fcvt(9.999, 2);
+    test_fcvt(99.99, 1);
+    test_fcvt(0.6, 0);
+    test_fcvt(0, 2);
+    test_fcvt(0.0012, 2);
+
+    test_gcvt(123.456, 5);
+    test_gcvt(9.999, 3);
+    test_gcvt(0.0001234, 3);
+    test_gcvt(1234567, 3);
+    test_gcvt(100, 3);
+    test_gcvt(-1.5, 5);
+    test_gcvt(1e-5, 2);
+    test_gcvt(0, 3);
+    test_gcvt(1.0 / 3, 17);
+}
@@ -0,0 +1,47 @@
+#include <stdio.h>
+#include <stdlib.h>
+
+// Normally called by C++ compilers and the startup files, not declared in
+// any header
+int __cxa_atexit(void (*func)(void *), void *arg, void *dso);
+void __cxa_finalize(void *dso);
+
+// Two objects to tell the handlers apart
+static char dso_a;
+static char dso_b;
+
+static void handler(void *arg) {
+    printf("handler %s\n", (const char *) arg);
+}
+
+static void atexit_handler(void) {
+    puts("atexit handler");
+}
+
+static void registering_handler(void *arg) {
+    printf("handler %s, registering a3\n", (const char *) arg);
+    __cxa_atexit(handler, "a3", &dso_a);
+}
+
+int main(void) {
+    __cxa_atexit(handler, "a1", &dso_a);
+    __cxa_atexit(handler, "b1", &dso_b);
+    __cxa_atexit(registering_handler, "a2", &dso_a);
+    __cxa_atexit(handler, "b2", &dso_b);
+
+    // Only the handlers of one object, newest first, including those
+    // registered meanwhile
+    puts("finalize a");
+    __cxa_finalize(&dso_a);
+
+    // They are removed once called
+    puts("finalize a again");
+    __cxa_finalize(&dso_a);
+
+    // atexit handlers run in the same reverse order of registration
+    __cxa_atexit(handler, "b3", &dso_b);
+    atexit(atexit_handler);
+    __cxa_atexit(handler, "b4", &dso_b);
+    puts("exit");
+    return 0;
+}
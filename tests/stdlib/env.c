@@ -17,12 +17,11 @@ int main(void) {
     strcpy(owned, "TEST=Updates accordingly.");
     puts(getenv("TEST"));
 
-    // Allocation is reused
+    // The string given to putenv is replaced, not written to
     setenv("TEST", "in place", 1);
     puts(getenv("TEST"));
     puts(owned);
 
-    // Allocation is not reused
     setenv("TEST", "Value overwritten and not in place because it's really long", 1);
     puts(getenv("TEST"));
     puts(owned);
@@ -0,0 +1,24 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main(void) {
+    char *const tokens[] = { "ro", "rw", "size", "noexec", NULL };
+
+    char buffer[] = "rw,size=10,noexec,bogus=1,ro=,,size";
+    char *options = buffer;
+    char *value;
+    while (*options != '\0') {
+        int index = getsubopt(&options, tokens, &value);
+        if (index == -1) {
+            printf("unknown '%s'\n", value);
+        } else if (value) {
+            printf("%d: %s = '%s'\n", index, tokens[index], value);
+        } else {
+            printf("%d: %s\n", index, tokens[index]);
+        }
+    }
+
+    // Nothing left to parse
+    printf("at end: %d\n", getsubopt(&options, tokens, &value));
+}
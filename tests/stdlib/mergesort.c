@@ -0,0 +1,74 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+#define LEN 1000
+
+struct record {
+    int key;
+    int seq;
+};
+
+static int key_cmp(const void *a, const void *b) {
+    const struct record *ra = a;
+    const struct record *rb = b;
+    return (ra->key > rb->key) - (ra->key < rb->key);
+}
+
+// Few distinct keys in a scrambled order, numbered in input order
+static void fill(struct record *records) {
+    unsigned state = 1;
+    for (int i = 0; i < LEN; i++) {
+        state = state * 1103515245 + 12345;
+        records[i].key = (state >> 16) % 10;
+        records[i].seq = i;
+    }
+}
+
+static int sorted(const struct record *records) {
+    for (int i = 1; i < LEN; i++) {
+        if (records[i - 1].key > records[i].key) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+// Equal keys are still in input order
+static int stable(const struct record *records) {
+    for (int i = 1; i < LEN; i++) {
+        if (records[i - 1].key == records[i].key && records[i - 1].seq > records[i].seq) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(void) {
+    static struct record records[LEN];
+
+    fill(records);
+    int status = mergesort(records, LEN, sizeof(struct record), key_cmp);
+    ERROR_IF(mergesort, status, == -1);
+    printf("mergesort: sorted %d, stable %d\n", sorted(records), stable(records));
+
+    // Order among equal keys isn't guaranteed by the others
+    fill(records);
+    status = heapsort(records, LEN, sizeof(struct record), key_cmp);
+    ERROR_IF(heapsort, status, == -1);
+    printf("heapsort: sorted %d\n", sorted(records));
+
+    fill(records);
+    qsort(records, LEN, sizeof(struct record), key_cmp);
+    printf("qsort: sorted %d\n", sorted(records));
+
+    // Nothing to sort
+    status = mergesort(records, 1, sizeof(struct record), key_cmp);
+    printf("one element: %d %d\n", status, heapsort(records, 0, sizeof(struct record), key_cmp));
+
+    errno = 0;
+    status = mergesort(records, LEN, 0, key_cmp);
+    printf("zero size: %d, EINVAL %d\n", status, errno == EINVAL);
+}
@@ -0,0 +1,158 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define N 100000
+
+static size_t comparisons;
+
+static int compare_int(const void *a, const void *b) {
+    int x = *(const int *) a;
+    int y = *(const int *) b;
+    comparisons++;
+    return (x > y) - (x < y);
+}
+
+// McIlroy's adversary, "A Killer Adversary for Quicksort": values are only
+// decided ("solid") when they have to be, always making the pivot as bad as
+// possible. The array being sorted holds indices into `adversary_values`.
+static int adversary_values[N];
+static int gas, solid, candidate;
+
+static int compare_adversary(const void *a, const void *b) {
+    int x = *(const int *) a;
+    int y = *(const int *) b;
+    comparisons++;
+    if (adversary_values[x] == gas && adversary_values[y] == gas) {
+        if (x == candidate) {
+            adversary_values[x] = solid++;
+        } else {
+            adversary_values[y] = solid++;
+        }
+    }
+    if (adversary_values[x] == gas) {
+        candidate = x;
+    } else if (adversary_values[y] == gas) {
+        candidate = y;
+    }
+    return adversary_values[x] - adversary_values[y];
+}
+
+static size_t log2_floor(size_t n) {
+    size_t log = 0;
+    while (n >>= 1) {
+        log++;
+    }
+    return log;
+}
+
+// A few n*log2(n) comparisons, far from the n*n/2 of a quadratic sort
+static const char *check_comparisons(size_t n) {
+    return comparisons <= 5 * n * log2_floor(n) ? "within bound" : "too many comparisons";
+}
+
+static void test(const char *name, int *values, size_t n) {
+    comparisons = 0;
+    qsort(values, n, sizeof(int), compare_int);
+    for (size_t i = 1; i < n; i++) {
+        if (values[i - 1] > values[i]) {
+            printf("%s: not sorted at %zu\n", name, i);
+            exit(EXIT_FAILURE);
+        }
+    }
+    printf("%s: sorted, %s\n", name, check_comparisons(n));
+}
+
+struct wide {
+    int key;
+    char payload[300];
+};
+
+static int compare_wide(const void *a, const void *b) {
+    return compare_int(&((const struct wide *) a)->key, &((const struct wide *) b)->key);
+}
+
+static int compare_triple(const void *a, const void *b) {
+    return memcmp(a, b, 3);
+}
+
+int main(void) {
+    static int values[N];
+
+    for (int i = 0; i < N; i++) {
+        values[i] = i;
+    }
+    test("sorted", values, N);
+
+    for (int i = 0; i < N; i++) {
+        values[i] = N - i;
+    }
+    test("reversed", values, N);
+
+    for (int i = 0; i < N; i++) {
+        values[i] = 7;
+    }
+    test("equal", values, N);
+
+    for (int i = 0; i < N; i++) {
+        values[i] = i < N / 2 ? i : N - i;
+    }
+    test("organ pipe", values, N);
+
+    for (int i = 0; i < N; i++) {
+        values[i] = i % 2;
+    }
+    test("zeros and ones", values, N);
+
+    // Each median of three is the second smallest element
+    for (int i = 0; i < N / 2; i++) {
+        values[i] = i % 2 == 0 ? i + 1 : N / 2 + i + (N / 2 % 2 == 0 ? 0 : 1);
+        values[N / 2 + i] = 2 * (i + 1);
+    }
+    test("median of three killer", values, N);
+
+    srand(1);
+    for (int i = 0; i < N; i++) {
+        values[i] = rand();
+    }
+    test("random", values, N);
+
+    gas = N - 1;
+    solid = 0;
+    for (int i = 0; i < N; i++) {
+        values[i] = i;
+        adversary_values[i] = gas;
+    }
+    comparisons = 0;
+    qsort(values, N, sizeof(int), compare_adversary);
+    for (int i = 1; i < N; i++) {
+        if (adversary_values[values[i - 1]] > adversary_values[values[i]]) {
+            printf("adversary: not sorted at %d\n", i);
+            exit(EXIT_FAILURE);
+        }
+    }
+    printf("adversary: sorted, %s\n", check_comparisons(N));
+
+    // Elements wider than the buffer used for swapping
+    static struct wide wide[1000];
+    for (int i = 0; i < 1000; i++) {
+        wide[i].key = (i * 7919) % 1000;
+        memset(wide[i].payload, wide[i].key % 256, sizeof(wide[i].payload));
+    }
+    qsort(wide, 1000, sizeof(struct wide), compare_wide);
+    for (int i = 0; i < 1000; i++) {
+        for (size_t j = 0; j < sizeof(wide[i].payload); j++) {
+            if (wide[i].key != i || wide[i].payload[j] != (char) (i % 256)) {
+                printf("wide: wrong element at %d\n", i);
+                exit(EXIT_FAILURE);
+            }
+        }
+    }
+    puts("wide: sorted");
+
+    char triples[] = "fox dog cat ant bee owl yak emu ";
+    qsort(triples, 8, 4, compare_triple);
+    printf("triples: %s\n", triples);
+}
@@ -0,0 +1,33 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void handler(void) {
+    puts("atexit handler");
+}
+
+static void quick_handler1(void) {
+    puts("quick exit handler 1");
+    fflush(stdout);
+}
+
+static void quick_handler2(void) {
+    puts("quick exit handler 2");
+}
+
+int main(void) {
+    int status = atexit(handler);
+    ERROR_IF(atexit, status, != 0);
+    status = at_quick_exit(quick_handler1);
+    ERROR_IF(at_quick_exit, status, != 0);
+    status = at_quick_exit(quick_handler2);
+    ERROR_IF(at_quick_exit, status, != 0);
+
+    // The atexit handler isn't called, and streams are only flushed by the
+    // handlers themselves
+    puts("quick exit");
+    fflush(stdout);
+    quick_exit(EXIT_SUCCESS);
+}
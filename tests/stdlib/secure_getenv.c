@@ -0,0 +1,21 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/auxv.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    // Without setuid, the kernel says this isn't a secure execution
+    printf("AT_SECURE: %lu\n", getauxval(AT_SECURE));
+    printf("same ids: %s\n", getuid() == geteuid() && getgid() == getegid() ? "yes" : "no");
+
+    setenv("SECURE_GETENV_TEST", "value", 1);
+    const char *value = secure_getenv("SECURE_GETENV_TEST");
+    printf("secure_getenv: %s\n", value ? value : "NULL");
+    printf("same as getenv: %s\n", value == getenv("SECURE_GETENV_TEST") ? "yes" : "no");
+
+    unsetenv("SECURE_GETENV_TEST");
+    value = secure_getenv("SECURE_GETENV_TEST");
+    printf("secure_getenv after unsetenv: %s\n", value ? value : "NULL");
+}
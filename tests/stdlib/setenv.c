@@ -0,0 +1,96 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+extern char **environ;
+
+static void print_var(const char *name) {
+    const char *value = getenv(name);
+    printf("%s: %s\n", name, value ? value : "(unset)");
+}
+
+static void print_result(const char *call, int ret) {
+    printf("%s: %d%s\n", call, ret, ret == -1 && errno == EINVAL ? ", EINVAL" : "");
+}
+
+int main(void) {
+    // Overwriting, or not
+    setenv("SETENV_TEST", "first", 0);
+    print_var("SETENV_TEST");
+    setenv("SETENV_TEST", "second", 0);
+    print_var("SETENV_TEST");
+    setenv("SETENV_TEST", "third", 1);
+    print_var("SETENV_TEST");
+
+    // The value is copied
+    char value[16] = "copied";
+    setenv("SETENV_TEST", value, 1);
+    strcpy(value, "changed");
+    print_var("SETENV_TEST");
+
+    // putenv keeps the string itself, and setenv replaces it without freeing it
+    char string[] = "SETENV_TEST=put";
+    putenv(string);
+    print_var("SETENV_TEST");
+    string[12] = 'P';
+    print_var("SETENV_TEST");
+    setenv("SETENV_TEST", "set after putenv", 1);
+    print_var("SETENV_TEST");
+    printf("putenv string: %s\n", string);
+    putenv(string);
+    unsetenv("SETENV_TEST");
+    print_var("SETENV_TEST");
+    printf("putenv string: %s\n", string);
+
+    // Unset, then get and set again
+    setenv("SETENV_TEST", "back", 1);
+    unsetenv("SETENV_TEST");
+    unsetenv("SETENV_TEST");
+    print_var("SETENV_TEST");
+    setenv("SETENV_TEST", "", 1);
+    print_var("SETENV_TEST");
+
+    // Invalid names
+    errno = 0;
+    print_result("setenv(\"\")", setenv("", "value", 1));
+    errno = 0;
+    print_result("setenv(\"A=B\")", setenv("A=B", "value", 1));
+    errno = 0;
+    print_result("unsetenv(\"\")", unsetenv(""));
+    errno = 0;
+    print_result("unsetenv(\"A=B\")", unsetenv("A=B"));
+    print_var("A");
+
+    // Many variables, moving environ around
+    for (int i = 0; i < 200; i++) {
+        char name[32];
+        snprintf(name, sizeof(name), "SETENV_MANY_%d", i);
+        setenv(name, name, 1);
+    }
+    int found = 0;
+    for (int i = 0; i < 200; i++) {
+        char name[32];
+        snprintf(name, sizeof(name), "SETENV_MANY_%d", i);
+        const char *value = getenv(name);
+        if (value && strcmp(value, name) == 0) {
+            found++;
+        }
+        unsetenv(name);
+    }
+    printf("found %d of 200 variables\n", found);
+
+    // An environment replaced by the program, with a duplicate
+    char *replacement[] = { "DUP=1", "OTHER=2", "DUP=3", NULL };
+    environ = replacement;
+    print_var("DUP");
+    print_var("SETENV_TEST");
+    unsetenv("DUP");
+    print_var("DUP");
+    setenv("NEW", "4", 1);
+    for (char **item = environ; *item; item++) {
+        printf("environ: %s\n", *item);
+    }
+}
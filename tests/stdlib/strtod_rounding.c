@@ -0,0 +1,78 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+static void test_double(const char *s) {
+    char *endptr;
+    errno = 0;
+    double d = strtod(s, &endptr);
+    int erange = errno == ERANGE;
+    uint64_t bits;
+    memcpy(&bits, &d, sizeof(bits));
+    printf("strtod(\"%.40s\"): %016llx, %g, parsed %d%s\n", s,
+        (unsigned long long) bits, d, (int) (endptr - s),
+        erange ? ", ERANGE" : "");
+}
+
+static void test_float(const char *s) {
+    char *endptr;
+    errno = 0;
+    float f = strtof(s, &endptr);
+    int erange = errno == ERANGE;
+    uint32_t bits;
+    memcpy(&bits, &f, sizeof(bits));
+    printf("strtof(\"%.40s\"): %08x, %g, parsed %d%s\n", s,
+        (unsigned) bits, f, (int) (endptr - s),
+        erange ? ", ERANGE" : "");
+}
+
+int main(void) {
+    // Exactly representable, and close to halfway between two doubles
+    const char *doubles[] = {
+        "0", "-0", "1", "0.1", "1e23", "8.589973e9", "123456789012345678901234567890",
+        "9007199254740993", "9007199254740993.0000000000000000000000001",
+        "9007199254740995", "2.2250738585072014e-308", "2.2250738585072009e-308",
+        "4.9406564584124654e-324", "2.4703282292062327e-324",
+        "2.4703282292062328e-324", "1.7976931348623157e308",
+        "1.7976931348623158e308", "1.7976931348623159e308", "1e309", "1e-400",
+        "-1e400", "0.000000000000000000000000000000000000001e39",
+        "1.00000000000000011102230246251565404236316680908203125",
+        "1.00000000000000011102230246251565404236316680908203124",
+        "1.00000000000000011102230246251565404236316680908203126",
+        "7.2057594037927933e16", "3.0540316996548617e-5",
+        // Hexadecimal
+        "0x1p-1074", "0x1p-1075", "0x1.8p-1075", "0x1.fffffffffffffp1023",
+        "0x1.fffffffffffff8p1023", "0x.8p1", "0X1P+3", "0x1.00000000000008p0",
+        "0x1.000000000000080000001p0", "0x1.00000000000018p0", "-0x10.123",
+        // Infinity and NaN
+        "inf", "-INFINITY", "infinit", "nan", "-nan(n_char)", "nan(",
+        // Where parsing stops
+        "  +.5e+", "1e", "1e+x", "0x", "0xg", "0x.p1", ".", "-", "+.e1", "x",
+        "1.5.5", " \t12abc",
+    };
+    for (size_t i = 0; i < sizeof(doubles) / sizeof(*doubles); i++) {
+        test_double(doubles[i]);
+    }
+
+    const char *floats[] = {
+        "0.1", "16777217", "16777217.000000001", "3.4028235e38", "3.4028236e38",
+        "3.40282357e38", "1.17549435e-38", "1.4e-45", "7.006492321624085e-46",
+        "7.006492321624086e-46", "1e-46", "0x1p-149", "0x1.8p-150", "0x1p-150",
+        "0x1.fffffe8p127", "1.00000005960464477539062500000000001",
+    };
+    for (size_t i = 0; i < sizeof(floats) / sizeof(*floats); i++) {
+        test_float(floats[i]);
+    }
+
+    // Far more digits than are kept, with the halfway point decided at the end
+    static char digits[2048];
+    strcpy(digits, "1.00000000000000011102230246251565404236316680908203125");
+    memset(digits + strlen(digits), '0', 1500);
+    test_double(digits);
+    strcat(digits, "1");
+    test_double(digits);
+}
@@ -0,0 +1,58 @@
+#include <errno.h>
+#include <inttypes.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+#define TEST(func, type, format, s, base) do { \
+        char *endptr; \
+        errno = 0; \
+        type result = func(s, &endptr, base); \
+        int erange = errno == ERANGE; \
+        printf(#func "(\"%s\", %d): " format ", parsed %d%s\n", s, base, \
+            result, (int) (endptr - s), erange ? ", ERANGE" : ""); \
+    } while (0)
+
+int main(void) {
+    // Signed boundaries
+    const char *signed_inputs[] = {
+        "9223372036854775807", "9223372036854775808", "-9223372036854775808",
+        "-9223372036854775809", "99999999999999999999999 rest",
+        "-99999999999999999999999 rest", "0x7fffffffffffffff",
+        "0x8000000000000000", "-0x8000000000000000", "-0x8000000000000001",
+    };
+    for (size_t i = 0; i < sizeof(signed_inputs) / sizeof(*signed_inputs); i++) {
+        TEST(strtoimax, intmax_t, "%" PRIdMAX, signed_inputs[i], 0);
+        TEST(strtol, long, "%ld", signed_inputs[i], 0);
+        TEST(strtoll, long long, "%lld", signed_inputs[i], 0);
+    }
+
+    // Unsigned boundaries, where a minus sign negates modulo the width
+    const char *unsigned_inputs[] = {
+        "18446744073709551615", "18446744073709551616",
+        "-18446744073709551615", "-18446744073709551616", "-1", "-0",
+        "0xffffffffffffffff", "0x10000000000000000",
+        "1777777777777777777777", "2000000000000000000000",
+    };
+    for (size_t i = 0; i < sizeof(unsigned_inputs) / sizeof(*unsigned_inputs); i++) {
+        TEST(strtoumax, uintmax_t, "%" PRIuMAX, unsigned_inputs[i], 0);
+        TEST(strtoul, unsigned long, "%lu", unsigned_inputs[i], 0);
+        TEST(strtoull, unsigned long long, "%llu", unsigned_inputs[i], 0);
+    }
+
+    // Base detection, and where parsing stops
+    const char *bases[] = {
+        "0x1A", "0X1a", "0x", "0xg", "017", "019", "08", "0", "  \t-0x10",
+        "+0777", "1e3", "- 1", "", "z",
+    };
+    for (size_t i = 0; i < sizeof(bases) / sizeof(*bases); i++) {
+        TEST(strtoimax, intmax_t, "%" PRIdMAX, bases[i], 0);
+        TEST(strtoimax, intmax_t, "%" PRIdMAX, bases[i], 16);
+        TEST(strtoumax, uintmax_t, "%" PRIuMAX, bases[i], 8);
+    }
+    TEST(strtoimax, intmax_t, "%" PRIdMAX, "zz", 36);
+    TEST(strtoimax, intmax_t, "%" PRIdMAX, "1y2p0ij32e8e7", 36);
+    TEST(strtoimax, intmax_t, "%" PRIdMAX, "1y2p0ij32e8e8", 36);
+    TEST(strtoimax, intmax_t, "%" PRIdMAX, "101", 2);
+}
@@ -0,0 +1,41 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+#define COUNT 1000000
+#define WIDTH 24
+
+// Time parsing COUNT integer strings, as written by fmt, in the given base
+void bench(const char *name, const char *fmt, int base) {
+    char *strings = malloc(COUNT * WIDTH);
+    ERROR_IF(malloc, strings, == NULL);
+
+    unsigned long value = 12345;
+    for (int i = 0; i < COUNT; i++) {
+        // Vary the length, from a single digit to the full 64 bits
+        value = value * 6364136223846793005UL + 1442695040888963407UL;
+        snprintf(strings + i * WIDTH, WIDTH, fmt, value >> (i % 64));
+    }
+
+    struct timespec start, end;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    unsigned long sum = 0;
+    for (int i = 0; i < COUNT; i++) {
+        char *end_ptr;
+        sum += strtoul(strings + i * WIDTH, &end_ptr, base);
+        sum += end_ptr - (strings + i * WIDTH);
+    }
+    clock_gettime(CLOCK_MONOTONIC, &end);
+
+    long ns = (end.tv_sec - start.tv_sec) * 1000000000L + (end.tv_nsec - start.tv_nsec);
+    printf("%s: checksum %lx, %ld ns per call\n", name, sum, ns / COUNT);
+    free(strings);
+}
+
+int main(void) {
+    bench("base 10", "%lu", 10);
+    bench("base 16", "%lx", 16);
+    bench("base 8", "%lo", 8);
+}
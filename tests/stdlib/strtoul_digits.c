@@ -0,0 +1,156 @@
+#include <ctype.h>
+#include <errno.h>
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+
+#include "test_helpers.h"
+
+// strtoul converts decimal and hexadecimal digits eight at a time, reading
+// past the end of the string as long as that stays within its page. Compare
+// it with a digit by digit conversion for strings placed across a page
+// boundary and right before a page that is not mapped.
+
+#define PAGE_SIZE 4096
+
+static int digit_value(char c) {
+    if (c >= '0' && c <= '9') {
+        return c - '0';
+    }
+    if (c >= 'a' && c <= 'z') {
+        return c - 'a' + 10;
+    }
+    if (c >= 'A' && c <= 'Z') {
+        return c - 'A' + 10;
+    }
+    return 36;
+}
+
+static unsigned long reference(const char *s, char **end, int base, int *err) {
+    const char *p = s;
+    while (isspace((unsigned char)*p)) {
+        p++;
+    }
+    int negative = 0;
+    if (*p == '+' || *p == '-') {
+        negative = *p == '-';
+        p++;
+    }
+    if (base == 16 && p[0] == '0' && (p[1] == 'x' || p[1] == 'X') && digit_value(p[2]) < 16) {
+        p += 2;
+    }
+
+    const char *digits = p;
+    unsigned long num = 0;
+    int overflow = 0;
+    while (digit_value(*p) < base) {
+        unsigned long next = num * base + digit_value(*p);
+        if (num > (ULONG_MAX - digit_value(*p)) / base) {
+            overflow = 1;
+        }
+        num = next;
+        p++;
+    }
+
+    *err = 0;
+    if (p == digits) {
+        *end = (char *)s;
+        return 0;
+    }
+    *end = (char *)p;
+    if (overflow) {
+        *err = ERANGE;
+        return ULONG_MAX;
+    }
+    return negative ? -num : num;
+}
+
+static int mismatches = 0;
+
+static void check_at(char *s, const char *text, int base) {
+    strcpy(s, text);
+
+    errno = 0;
+    char *end;
+    unsigned long value = strtoul(s, &end, base);
+    int err = errno;
+
+    char *expected_end;
+    int expected_err;
+    unsigned long expected = reference(s, &expected_end, base, &expected_err);
+
+    if (value != expected || end != expected_end || err != expected_err) {
+        printf("\"%s\" in base %d at page offset %ld: got %lu, %ld, %d, expected %lu, %ld, %d\n",
+            text, base, (long)((size_t)s % PAGE_SIZE), value, (long)(end - s), err,
+            expected, (long)(expected_end - s), expected_err);
+        mismatches++;
+    }
+}
+
+// Check `text` at every offset in the 16 bytes before the boundary between
+// the first two pages of `map`, and ending right before the third
+static void check(char *map, const char *text, int base) {
+    for (int offset = 0; offset < 16; offset++) {
+        check_at(map + PAGE_SIZE - offset, text, base);
+    }
+    check_at(map + 2 * PAGE_SIZE - strlen(text) - 1, text, base);
+}
+
+int main(void) {
+    char *map = mmap(NULL, 3 * PAGE_SIZE, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    int status = mprotect(map + 2 * PAGE_SIZE, PAGE_SIZE, PROT_NONE);
+    ERROR_IF(mprotect, status, == -1);
+
+    static const struct {
+        const char *text;
+        int base;
+    } cases[] = {
+        { "0", 10 },
+        { "12345678", 10 },
+        { "123456789", 10 },
+        { "1234567890123456", 10 },
+        { "18446744073709551615", 10 },
+        { "18446744073709551616", 10 },
+        { "184467440737095516150", 10 },
+        { "00000000000000000000000000001", 10 },
+        { "1234567x", 10 },
+        { "12345678 9", 10 },
+        { "deadBEEF", 16 },
+        { "deadBEEFcafe", 16 },
+        { "ffffffffffffffff", 16 },
+        { "10000000000000000", 16 },
+        { "0000000000000000000000001f", 16 },
+        { "abcdefgh", 16 },
+        { "abcdef:@", 16 },
+    };
+    for (size_t i = 0; i < sizeof(cases) / sizeof(cases[0]); i++) {
+        check(map, cases[i].text, cases[i].base);
+    }
+    printf("boundaries: %d mismatches\n", mismatches);
+
+    // Mostly digits, so that long runs are common
+    static const char chars[] = "0123456789abcdefABCDEF/:@`gG \xff";
+    unsigned int seed = 1;
+    mismatches = 0;
+    for (int i = 0; i < 2000; i++) {
+        char text[40];
+        seed = seed * 1103515245 + 12345;
+        size_t len = (seed >> 16) % sizeof(text);
+        for (size_t j = 0; j < len; j++) {
+            seed = seed * 1103515245 + 12345;
+            int any = (seed >> 16) % 8 == 0;
+            seed = seed * 1103515245 + 12345;
+            text[j] = chars[(seed >> 16) % (any ? sizeof(chars) - 1 : 10)];
+        }
+        text[len] = '\0';
+        check(map, text, 10);
+        check(map, text, 16);
+    }
+    printf("random: %d mismatches\n", mismatches);
+
+    status = munmap(map, 3 * PAGE_SIZE);
+    ERROR_IF(munmap, status, == -1);
+}
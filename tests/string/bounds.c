@@ -0,0 +1,83 @@
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Heap overruns are only caught when relibc is built with the check_bounds
+// feature. This is built both against the default relibc, where nothing
+// aborts, and as string/bounds_checked against one with the feature, where
+// every overrun does.
+
+static char src[256];
+
+static void overrun_memcpy(void) {
+    char *buf = malloc(16);
+    memcpy(buf, src, sizeof(src));
+}
+
+static void overrun_memset(void) {
+    char *buf = malloc(16);
+    memset(buf, 0, 256);
+}
+
+static void overrun_strcpy(void) {
+    char *buf = malloc(16);
+    strcpy(buf, src);
+}
+
+static void overrun_strcat(void) {
+    char *buf = malloc(16);
+    strcpy(buf, "abc");
+    strcat(buf, src + 156);
+}
+
+static void overrun_child(void *arg) {
+    (*(void (**)(void)) arg)();
+}
+
+// Run `f` in a child and report whether it aborted, along with the diagnostic
+// it printed, without the addresses
+static void expect_abort(const char *name, void (*f)(void)) {
+    char diag[256];
+    int wstatus = run_child_capture(STDERR_FILENO, overrun_child, &f, diag, sizeof(diag));
+
+    char *at = strstr(diag, " at 0x");
+    char *overruns = strstr(diag, " overruns heap allocation 0x");
+    if (at != NULL && overruns != NULL && at < overruns) {
+        *at = '\0';
+    } else {
+        strcpy(diag, "(no diagnostic)");
+    }
+
+    printf("%s: aborted = %d, diagnostic = %s\n", name,
+        WIFSIGNALED(wstatus) && WTERMSIG(wstatus) == SIGABRT, diag);
+}
+
+int main(void) {
+    memset(src, 'a', sizeof(src) - 1);
+
+    // In range accesses must never abort, including ones filling the
+    // allocation exactly
+    char *buf = malloc(16);
+    ERROR_IF(malloc, buf, == NULL);
+    memcpy(buf, src, 16);
+    memset(buf, 0, 16);
+    memmove(buf + 1, buf, 15);
+    strcpy(buf, "in bounds");
+    strcat(buf, "!");
+    puts(buf);
+    free(buf);
+
+    // Pointers outside the heap are not checked
+    char stack[16];
+    memcpy(stack, src, sizeof(stack));
+    puts("stack buffers are not checked");
+
+    expect_abort("memcpy", overrun_memcpy);
+    expect_abort("memset", overrun_memset);
+    expect_abort("strcpy", overrun_strcpy);
+    expect_abort("strcat", overrun_strcat);
+}
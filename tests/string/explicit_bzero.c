@@ -0,0 +1,42 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+
+// The wipes are done with volatile stores and a compiler barrier, so they are
+// kept even when the buffer is dead afterwards. That can't be observed from
+// here, since reading the buffer makes the stores necessary anyway.
+
+static int all(const char *buf, size_t len, char c) {
+    for (size_t i = 0; i < len; i++) {
+        if (buf[i] != c) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(void) {
+    char secret[32];
+    memset(secret, 'x', sizeof(secret));
+    explicit_bzero(secret, 16);
+    printf("explicit_bzero: first half zero %d, rest kept %d\n",
+        all(secret, 16, 0), all(secret + 16, 16, 'x'));
+    explicit_bzero(secret, sizeof(secret));
+    printf("explicit_bzero: all zero %d\n", all(secret, sizeof(secret), 0));
+
+    memset(secret, 'x', sizeof(secret));
+    int status = memset_s(secret, sizeof(secret), 0, sizeof(secret));
+    printf("memset_s: %d, all zero %d\n", status, all(secret, sizeof(secret), 0));
+
+    // n larger than smax is an error, but the first smax bytes are set anyway
+    memset(secret, 'x', sizeof(secret));
+    status = memset_s(secret, 8, 'y', 16);
+    printf("memset_s with n > smax: EINVAL %d, set %d, rest kept %d\n",
+        status == EINVAL, all(secret, 8, 'y'), all(secret + 8, 24, 'x'));
+
+    status = memset_s(NULL, 8, 0, 8);
+    printf("memset_s with NULL: EINVAL %d\n", status == EINVAL);
+
+    status = memset_s(secret, 0, 0, 0);
+    printf("memset_s with zero size: %d\n", status);
+}
@@ -0,0 +1,42 @@
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char buf[32];
+
+    // Each copy starts where the previous one ended
+    char *end = stpcpy(buf, "one");
+    printf("stpcpy returned offset %td, points at NUL %d\n", end - buf, *end == 0);
+    end = stpcpy(end, ", two");
+    end = stpcpy(end, ", three");
+    printf("chained stpcpy: '%s', offset %td\n", buf, end - buf);
+
+    // A short source is padded with NULs up to n, and the returned pointer is
+    // the first of them
+    memset(buf, 'x', sizeof(buf));
+    end = stpncpy(buf, "abc", 8);
+    int padded = 1;
+    for (int i = 3; i < 8; i++) {
+        if (buf[i] != 0) {
+            padded = 0;
+        }
+    }
+    printf("stpncpy short: offset %td, padded %d, past n untouched %d\n", end - buf, padded, buf[8] == 'x');
+
+    // A long source fills all n bytes without a NUL, and the returned pointer
+    // is dst + n
+    memset(buf, 'x', sizeof(buf));
+    end = stpncpy(buf, "abcdefgh", 4);
+    printf("stpncpy long: offset %td, '%.4s', no NUL %d\n", end - buf, buf, buf[4] == 'x');
+
+    memset(buf, 'x', sizeof(buf));
+    end = stpncpy(buf, "abcd", 4);
+    printf("stpncpy exact: offset %td, no NUL %d\n", end - buf, buf[4] == 'x');
+
+    void *p = mempcpy(buf, "12345", 5);
+    p = mempcpy(p, "678", 4);
+    printf("mempcpy: '%s', offset %td\n", buf, (char *) p - buf);
+
+    p = mempcpy(buf, "", 0);
+    printf("mempcpy of nothing: offset %td\n", (char *) p - buf);
+}
@@ -0,0 +1,51 @@
+#include <locale.h>
+#include <stdio.h>
+#include <string.h>
+#include <strings.h>
+
+static void find(const char *haystack, const char *needle) {
+    char *found = strcasestr(haystack, needle);
+    if (found) {
+        printf("strcasestr(\"%s\", \"%s\"): offset %td\n", haystack, needle, found - haystack);
+    } else {
+        printf("strcasestr(\"%s\", \"%s\"): not found\n", haystack, needle);
+    }
+}
+
+static int sign(int x) {
+    return (x > 0) - (x < 0);
+}
+
+static void tests(void) {
+    find("Hello World", "WORLD");
+    find("Hello World", "o w");
+    find("Hello World", "");
+    find("", "");
+    find("Hello", "hello!");
+    // Only letters fold, '[' and '{' differ by the same bit as 'A' and 'a'
+    find("a[b", "A{B");
+    find("x@y", "X`Y");
+
+    printf("strcasecmp mixed case: %d\n", sign(strcasecmp("HeLLo", "hEllO")));
+    printf("strcasecmp prefix: %d\n", sign(strcasecmp("abc", "ABCD")));
+    printf("strncasecmp within n: %d\n", sign(strncasecmp("abcX", "ABCy", 3)));
+    printf("strcasecmp brackets: %d\n", sign(strcasecmp("[", "{")));
+
+    // Non-ASCII bytes compare as unsigned chars and are not folded, so the
+    // UTF-8 sequences for U+00C9 and U+00E9 differ, but ASCII around them folds
+    printf("strcasecmp non-ASCII greater: %d\n", sign(strcasecmp("\xc3\xa9", "a")));
+    printf("strcasecmp non-ASCII case: %d\n", sign(strcasecmp("\xc3\x89t\xc3\xa9", "\xc3\xa9T\xc3\xa9")));
+    printf("strcasecmp ASCII around non-ASCII: %d\n", sign(strcasecmp("CAF\xc3\xa9", "caf\xc3\xa9")));
+    find("Un CAF\xc3\xa9 noir", "caf\xc3\xa9 N");
+    find("Un CAF\xc3\x89", "caf\xc3\xa9");
+}
+
+int main(void) {
+    puts("C locale:");
+    tests();
+
+    // The result of setlocale is not printed, UTF-8 locales may not exist
+    setlocale(LC_CTYPE, "C.UTF-8");
+    puts("UTF-8 locale:");
+    tests();
+}
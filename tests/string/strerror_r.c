@@ -0,0 +1,35 @@
+#include <errno.h>
+#include <locale.h>
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char buf[64];
+
+    int ret = strerror_r(ENOENT, buf, sizeof(buf));
+    printf("ENOENT: '%s', return %d\n", buf, ret);
+
+    // A buffer that is too small gets a truncated message and ERANGE
+    char small[8];
+    ret = strerror_r(ENOENT, small, sizeof(small));
+    printf("small buffer: '%s', ERANGE %d\n", small, ret == ERANGE);
+
+    // Exactly the length of the message leaves no room for the NUL
+    size_t len = strlen("Operation not permitted");
+    ret = strerror_r(EPERM, buf, len);
+    printf("no room for NUL: '%s', ERANGE %d\n", buf, ret == ERANGE);
+    ret = strerror_r(EPERM, buf, len + 1);
+    printf("just enough: '%s', return %d\n", buf, ret);
+
+    // Unknown numbers still get a message
+    ret = strerror_r(100000, buf, sizeof(buf));
+    printf("100000: '%s', EINVAL %d\n", buf, ret == EINVAL);
+    ret = strerror_r(-1, buf, sizeof(buf));
+    printf("-1: '%s', EINVAL %d\n", buf, ret == EINVAL);
+    printf("strerror(100000): '%s'\n", strerror(100000));
+
+    // strerror_l leaves errno alone
+    locale_t locale = (locale_t) 0;
+    errno = 1234;
+    printf("strerror_l(EACCES): '%s', errno %d\n", strerror_l(EACCES, locale), errno);
+}
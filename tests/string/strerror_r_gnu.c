@@ -0,0 +1,14 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char buf[64];
+
+    // The GNU version returns the message instead of an error number
+    char *msg = strerror_r(ENOENT, buf, sizeof(buf));
+    printf("ENOENT: '%s'\n", msg);
+    msg = strerror_r(100000, buf, sizeof(buf));
+    printf("100000: '%s'\n", msg);
+}
@@ -0,0 +1,39 @@
+#include <stdio.h>
+#include <string.h>
+
+int main(void) {
+    char dst[8];
+
+    // Exact fit: the string and its NUL use the whole buffer
+    size_t len = strlcpy(dst, "1234567", sizeof(dst));
+    printf("strlcpy exact fit: %zu '%s', truncated %d\n", len, dst, len >= sizeof(dst));
+
+    len = strlcpy(dst, "123456789", sizeof(dst));
+    printf("strlcpy truncated: %zu '%s', truncated %d\n", len, dst, len >= sizeof(dst));
+
+    // A zero size writes nothing but still reports the source length
+    memset(dst, 'x', sizeof(dst));
+    len = strlcpy(dst, "abc", 0);
+    printf("strlcpy zero size: %zu, untouched %d\n", len, dst[0] == 'x');
+
+    strlcpy(dst, "abc", sizeof(dst));
+    len = strlcat(dst, "defg", sizeof(dst));
+    printf("strlcat exact fit: %zu '%s', truncated %d\n", len, dst, len >= sizeof(dst));
+
+    strlcpy(dst, "abc", sizeof(dst));
+    len = strlcat(dst, "defghij", sizeof(dst));
+    printf("strlcat truncated: %zu '%s', truncated %d\n", len, dst, len >= sizeof(dst));
+
+    strlcpy(dst, "abc", sizeof(dst));
+    len = strlcat(dst, "def", 0);
+    printf("strlcat zero size: %zu '%s'\n", len, dst);
+
+    // The NUL of dst is only looked for within size. If it isn't there, dst is
+    // left alone and the result is size plus the length of src.
+    len = strlcat(dst, "def", 2);
+    printf("strlcat size within dst: %zu '%s'\n", len, dst);
+
+    char unterminated[4] = { 'w', 'x', 'y', 'z' };
+    len = strlcat(unterminated, "abc", sizeof(unterminated));
+    printf("strlcat unterminated: %zu '%.4s'\n", len, unterminated);
+}
@@ -0,0 +1,47 @@
+#include <elf.h>
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/auxv.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    unsigned long pagesz = getauxval(AT_PAGESZ);
+    printf("AT_PAGESZ is the page size: %s\n",
+        pagesz == (unsigned long) sysconf(_SC_PAGESIZE) ? "yes" : "no");
+    printf("AT_CLKTCK is the clock tick: %s\n",
+        getauxval(AT_CLKTCK) == (unsigned long) sysconf(_SC_CLK_TCK) ? "yes" : "no");
+
+    // 16 random bytes, which are very unlikely to all be zero
+    const unsigned char *random = (const unsigned char *) getauxval(AT_RANDOM);
+    unsigned char bytes[16];
+    memcpy(bytes, random, sizeof(bytes));
+    int nonzero = 0;
+    for (size_t i = 0; i < sizeof(bytes); i++) {
+        nonzero |= bytes[i];
+    }
+    printf("AT_RANDOM: %s\n", random != NULL && nonzero ? "16 bytes" : "missing");
+
+    // The program headers of the program itself
+    const Elf64_Phdr *phdr = (const Elf64_Phdr *) getauxval(AT_PHDR);
+    unsigned long phnum = getauxval(AT_PHNUM);
+    int has_load = 0;
+    for (unsigned long i = 0; phdr != NULL && i < phnum; i++) {
+        has_load |= phdr[i].p_type == PT_LOAD;
+    }
+    printf("AT_PHDR: %s\n", has_load ? "has PT_LOAD" : "missing");
+    printf("AT_PHENT: %lu\n", getauxval(AT_PHENT));
+
+    const char *execfn = (const char *) getauxval(AT_EXECFN);
+    printf("AT_EXECFN: %s\n", execfn != NULL && strlen(execfn) > 0 ? "present" : "missing");
+    printf("AT_SECURE: %lu\n", getauxval(AT_SECURE));
+    getauxval(AT_HWCAP);
+    getauxval(AT_HWCAP2);
+
+    // A type that isn't in the vector
+    errno = 0;
+    unsigned long missing = getauxval(1000);
+    printf("1000: %lu, %s\n", missing, errno == ENOENT ? "ENOENT" : "no error");
+}
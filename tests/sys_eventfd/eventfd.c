@@ -0,0 +1,40 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/eventfd.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    int fd = eventfd(3, EFD_NONBLOCK);
+    ERROR_IF(eventfd, fd, == -1);
+
+    // Writes add to the counter
+    uint64_t value = 4;
+    ssize_t len = write(fd, &value, sizeof(value));
+    ERROR_IF(write, len, == -1);
+    int status = eventfd_write(fd, 5);
+    ERROR_IF(eventfd_write, status, == -1);
+
+    // A read returns the counter and resets it
+    value = 0;
+    len = read(fd, &value, sizeof(value));
+    ERROR_IF(read, len, == -1);
+    printf("read %zd bytes: %llu\n", len, (unsigned long long) value);
+
+    errno = 0;
+    len = read(fd, &value, sizeof(value));
+    printf("read of zero counter: %zd, EAGAIN %d\n", len, errno == EAGAIN);
+    close(fd);
+
+    // In semaphore mode, each read decrements the counter by one
+    fd = eventfd(2, EFD_SEMAPHORE | EFD_NONBLOCK | EFD_CLOEXEC);
+    ERROR_IF(eventfd, fd, == -1);
+    for (int i = 0; i < 3; i++) {
+        eventfd_t sem = 0;
+        status = eventfd_read(fd, &sem);
+        printf("semaphore read %d: %d, %llu\n", i, status, (unsigned long long) sem);
+    }
+    close(fd);
+}
@@ -0,0 +1,97 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/file.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "flock.out"
+
+// The parent holds an exclusive lock while the child contends for it. The
+// child's non-blocking attempt must fail, and its blocking attempt must only
+// succeed after the parent has written its message and released the lock.
+void contend(int use_lockf) {
+    int order[2];
+    int status = pipe(order);
+    ERROR_IF(pipe, status, == -1);
+
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+
+    if (use_lockf) {
+        status = lockf(fd, F_LOCK, 0);
+        ERROR_IF(lockf, status, == -1);
+    } else {
+        status = flock(fd, LOCK_EX);
+        ERROR_IF(flock, status, == -1);
+    }
+
+    fflush(stdout);
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+
+    if (pid == 0) {
+        close(fd);
+        // A separate open file description, so flock locks conflict too
+        fd = open(PATH, O_RDWR);
+        ERROR_IF(open, fd, == -1);
+
+        if (use_lockf) {
+            status = lockf(fd, F_TEST, 0);
+            printf("lockf F_TEST: %d, EACCES %d\n", status, errno == EACCES);
+            status = lockf(fd, F_TLOCK, 0);
+            printf("lockf F_TLOCK: %d, EAGAIN %d\n", status, errno == EAGAIN);
+            fflush(stdout);
+            status = lockf(fd, F_LOCK, 0);
+            ERROR_IF(lockf, status, == -1);
+        } else {
+            status = flock(fd, LOCK_EX | LOCK_NB);
+            printf("flock LOCK_NB: %d, EWOULDBLOCK %d\n", status, errno == EWOULDBLOCK);
+            fflush(stdout);
+            status = flock(fd, LOCK_EX);
+            ERROR_IF(flock, status, == -1);
+        }
+
+        status = write(order[1], "child\n", 6);
+        ERROR_IF(write, status, == -1);
+        _exit(0);
+    }
+
+    // Give the child time to block on the lock
+    usleep(100000);
+
+    status = write(order[1], "parent\n", 7);
+    ERROR_IF(write, status, == -1);
+
+    if (use_lockf) {
+        status = lockf(fd, F_ULOCK, 0);
+        ERROR_IF(lockf, status, == -1);
+    } else {
+        status = flock(fd, LOCK_UN);
+        ERROR_IF(flock, status, == -1);
+    }
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    close(order[1]);
+
+    char buf[64] = { 0 };
+    ssize_t len = read(order[0], buf, sizeof(buf) - 1);
+    ERROR_IF(read, len, == -1);
+    printf("%s order:\n%s", use_lockf ? "lockf" : "flock", buf);
+
+    close(order[0]);
+    close(fd);
+}
+
+int main(void) {
+    contend(0);
+    contend(1);
+
+    int status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
@@ -0,0 +1,57 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/inotify.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define DIR "inotify.out"
+
+int main(void) {
+    int status = mkdir(DIR, 0755);
+    ERROR_IF(mkdir, status, == -1);
+
+    int fd = inotify_init1(IN_CLOEXEC);
+    ERROR_IF(inotify_init1, fd, == -1);
+
+    int wd = inotify_add_watch(fd, DIR, IN_CREATE | IN_DELETE);
+    ERROR_IF(inotify_add_watch, wd, == -1);
+
+    int file = open(DIR "/first", O_WRONLY | O_CREAT, 0644);
+    ERROR_IF(open, file, == -1);
+    close(file);
+    file = open(DIR "/second_with_a_longer_name", O_WRONLY | O_CREAT, 0644);
+    ERROR_IF(open, file, == -1);
+    close(file);
+    status = unlink(DIR "/first");
+    ERROR_IF(unlink, status, == -1);
+
+    // All three events fit in one read, each followed by its padded name
+    char buf[4096] __attribute__((aligned(__alignof__(struct inotify_event))));
+    ssize_t len = read(fd, buf, sizeof(buf));
+    ERROR_IF(read, len, == -1);
+
+    for (char *p = buf; p < buf + len; ) {
+        struct inotify_event *event = (struct inotify_event *) p;
+        printf("event on watch %d:", event->wd == wd);
+        if (event->mask & IN_CREATE) {
+            printf(" IN_CREATE");
+        }
+        if (event->mask & IN_DELETE) {
+            printf(" IN_DELETE");
+        }
+        printf(" '%s'\n", event->len > 0 ? event->name : "");
+        p += sizeof(struct inotify_event) + event->len;
+    }
+
+    status = inotify_rm_watch(fd, wd);
+    ERROR_IF(inotify_rm_watch, status, == -1);
+    close(fd);
+
+    status = unlink(DIR "/second_with_a_longer_name");
+    ERROR_IF(unlink, status, == -1);
+    status = rmdir(DIR);
+    ERROR_IF(rmdir, status, == -1);
+}
@@ -0,0 +1,36 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    size_t page_size = (size_t) getpagesize();
+
+    // Reserve a range to map into
+    char *base = mmap(NULL, page_size * 4, PROT_NONE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, base, == MAP_FAILED);
+
+    errno = 0;
+    char *map = mmap(base + page_size, page_size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED_NOREPLACE, -1, 0);
+    printf("MAP_FIXED_NOREPLACE on occupied range: failed %d, EEXIST %d\n", map == MAP_FAILED, errno == EEXIST);
+
+    map = mmap(base + page_size, page_size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    map[0] = 'x';
+    printf("MAP_FIXED replaced the mapping at the address: %d\n", map == base + page_size);
+
+    errno = 0;
+    map = mmap(base + 1, page_size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED, -1, 0);
+    printf("MAP_FIXED on unaligned address: failed %d, EINVAL %d\n", map == MAP_FAILED, errno == EINVAL);
+
+    int status = munmap(base + page_size * 3, page_size);
+    ERROR_IF(munmap, status, == -1);
+    map = mmap(base + page_size * 3, page_size, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED_NOREPLACE, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    printf("MAP_FIXED_NOREPLACE on free range: %d\n", map == base + page_size * 3);
+
+    status = munmap(base, page_size * 4);
+    ERROR_IF(munmap, status, == -1);
+}
@@ -0,0 +1,41 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+
+#include "test_helpers.h"
+
+// Locking may be refused by RLIMIT_MEMLOCK, which is not a failure here
+int allowed(int status) {
+    return status == 0 || errno == EPERM || errno == ENOMEM;
+}
+
+int main(void) {
+    // Lock a range that doesn't start or end on a page boundary
+    char *secret = malloc(100);
+    ERROR_IF(malloc, secret, == NULL);
+    strcpy(secret, "hunter2");
+
+    errno = 0;
+    int status = mlock(secret + 1, 50);
+    printf("mlock: %d\n", allowed(status));
+    if (status == 0) {
+        status = munlock(secret + 1, 50);
+        ERROR_IF(munlock, status, == -1);
+    }
+
+    errno = 0;
+    status = mlockall(MCL_CURRENT | MCL_FUTURE);
+    printf("mlockall: %d\n", allowed(status));
+    if (status == 0) {
+        status = munlockall();
+        ERROR_IF(munlockall, status, == -1);
+    }
+
+    errno = 0;
+    status = mlockall(0);
+    printf("mlockall with no flags: failed %d, EINVAL %d\n", status == -1, errno == EINVAL);
+
+    free(secret);
+}
@@ -0,0 +1,56 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    size_t page_size = (size_t) getpagesize();
+
+    // Two pages with the second one in use, so the first can't grow in place
+    char *map = mmap(NULL, page_size * 2, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    memset(map, 'a', page_size);
+
+    errno = 0;
+    char *moved = mremap(map, page_size, page_size * 4, 0);
+    printf("grow without MREMAP_MAYMOVE: failed %d, ENOMEM %d\n", moved == MAP_FAILED, errno == ENOMEM);
+
+    moved = mremap(map, page_size, page_size * 4, MREMAP_MAYMOVE);
+    ERROR_IF(mremap, moved, == MAP_FAILED);
+    printf("grow with MREMAP_MAYMOVE: moved %d\n", moved != map);
+
+    memset(moved + page_size, 'b', page_size * 3);
+    int kept = 1;
+    for (size_t i = 0; i < page_size; i++) {
+        if (moved[i] != 'a') {
+            kept = 0;
+        }
+    }
+    printf("data kept: %d, written past old end: %c\n", kept, moved[page_size * 4 - 1]);
+
+    // With the following pages free, it grows where it is
+    int status = munmap(map + page_size, page_size);
+    ERROR_IF(munmap, status, == -1);
+    map = mmap(NULL, page_size * 2, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    status = munmap(map + page_size, page_size);
+    ERROR_IF(munmap, status, == -1);
+    char *grown = mremap(map, page_size, page_size * 2, MREMAP_MAYMOVE);
+    ERROR_IF(mremap, grown, == MAP_FAILED);
+    printf("grow in place: same address %d\n", grown == map);
+
+    // MREMAP_FIXED moves to the given address, replacing what is there
+    char *target = mmap(NULL, page_size * 4, PROT_NONE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, target, == MAP_FAILED);
+    char *fixed = mremap(moved, page_size * 4, page_size * 4, MREMAP_MAYMOVE | MREMAP_FIXED, target);
+    ERROR_IF(mremap, fixed, == MAP_FAILED);
+    printf("MREMAP_FIXED: at target %d, data %c%c\n", fixed == target, fixed[0], fixed[page_size * 4 - 1]);
+
+    status = munmap(grown, page_size * 2);
+    ERROR_IF(munmap, status, == -1);
+    status = munmap(fixed, page_size * 4);
+    ERROR_IF(munmap, status, == -1);
+}
@@ -0,0 +1,52 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "sys_mman_msync.out"
+
+int main(void) {
+    size_t page_size = (size_t) getpagesize();
+
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+    int status = ftruncate(fd, page_size);
+    ERROR_IF(ftruncate, status, == -1);
+
+    char *map = mmap(NULL, page_size, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+
+    strcpy(map, "written through the mapping");
+    status = msync(map, page_size, MS_SYNC);
+    ERROR_IF(msync, status, == -1);
+
+    // Read it back through a separate descriptor
+    int fd2 = open(PATH, O_RDONLY);
+    ERROR_IF(open, fd2, == -1);
+    char buf[64] = { 0 };
+    ssize_t len = read(fd2, buf, sizeof(buf) - 1);
+    ERROR_IF(read, len, == -1);
+    printf("read after MS_SYNC: '%s'\n", buf);
+    close(fd2);
+
+    status = msync(map, page_size, MS_ASYNC | MS_INVALIDATE);
+    ERROR_IF(msync, status, == -1);
+
+    errno = 0;
+    status = msync(map + 1, page_size - 1, MS_SYNC);
+    printf("unaligned address: failed %d, EINVAL %d\n", status == -1, errno == EINVAL);
+
+    errno = 0;
+    status = msync(map, page_size, MS_SYNC | MS_ASYNC);
+    printf("MS_SYNC with MS_ASYNC: failed %d, EINVAL %d\n", status == -1, errno == EINVAL);
+
+    status = munmap(map, page_size);
+    ERROR_IF(munmap, status, == -1);
+    close(fd);
+    status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
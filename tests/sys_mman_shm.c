@@ -0,0 +1,63 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Leading slashes are allowed, so both refer to the same object
+#define NAME "/relibc-test-shm"
+#define SAME_NAME "//relibc-test-shm"
+#define SIZE 4096
+
+int main(void) {
+    shm_unlink(NAME);
+
+    int fd = shm_open(NAME, O_RDWR | O_CREAT | O_EXCL, 0600);
+    ERROR_IF(shm_open, fd, == -1);
+    int status = ftruncate(fd, SIZE);
+    ERROR_IF(ftruncate, status, == -1);
+
+    int flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("close on exec: %d\n", (flags & FD_CLOEXEC) != 0);
+
+    int again = shm_open(SAME_NAME, O_RDWR | O_CREAT | O_EXCL, 0600);
+    printf("exclusive create: %s\n", again == -1 && errno == EEXIST ? "EEXIST" : "succeeded");
+
+    int bad = shm_open("/relibc/test", O_RDWR | O_CREAT, 0600);
+    printf("name with a slash: %s\n", bad == -1 && errno == EINVAL ? "EINVAL" : "succeeded");
+
+    // The child writes through its own descriptor and mapping
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        int child_fd = shm_open(SAME_NAME, O_RDWR, 0);
+        ERROR_IF(shm_open, child_fd, == -1);
+        char *map = mmap(NULL, SIZE, PROT_READ | PROT_WRITE, MAP_SHARED, child_fd, 0);
+        ERROR_IF(mmap, map, == MAP_FAILED);
+        strcpy(map, "written by the child");
+        _exit(EXIT_SUCCESS);
+    }
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+
+    char *map = mmap(NULL, SIZE, PROT_READ, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    printf("read by the parent: %s\n", map);
+
+    status = munmap(map, SIZE);
+    ERROR_IF(munmap, status, == -1);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+    status = shm_unlink(NAME);
+    ERROR_IF(shm_unlink, status, == -1);
+
+    fd = shm_open(NAME, O_RDWR, 0);
+    printf("open after unlink: %s\n", fd == -1 && errno == ENOENT ? "ENOENT" : "succeeded");
+}
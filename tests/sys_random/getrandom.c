@@ -0,0 +1,47 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/random.h>
+
+#include "test_helpers.h"
+
+#define LEN 4096
+
+// Count bytes still equal to the fill pattern. A few random bytes are
+// expected to match by chance, but nowhere near all of them.
+static size_t untouched(const unsigned char *buf, size_t len, unsigned char pattern) {
+    size_t count = 0;
+    for (size_t i = 0; i < len; i++) {
+        if (buf[i] == pattern) {
+            count++;
+        }
+    }
+    return count;
+}
+
+int main(void) {
+    static unsigned char a[LEN];
+    static unsigned char b[LEN];
+    memset(a, 0xAA, LEN);
+    memset(b, 0xAA, LEN);
+
+    ssize_t len = getrandom(a, LEN, 0);
+    ERROR_IF(getrandom, len, == -1);
+    printf("first call filled %zd bytes, untouched bytes few: %d\n", len, untouched(a, LEN, 0xAA) < 64);
+
+    len = getrandom(b, LEN, 0);
+    ERROR_IF(getrandom, len, == -1);
+    printf("second call filled %zd bytes, untouched bytes few: %d\n", len, untouched(b, LEN, 0xAA) < 64);
+
+    printf("calls differ: %d\n", memcmp(a, b, LEN) != 0);
+
+    len = getrandom(a, 16, GRND_NONBLOCK);
+    printf("nonblocking: %zd\n", len);
+
+    errno = 0;
+    len = getrandom(a, 16, 0x80);
+    printf("unknown flag: %zd, EINVAL %d\n", len, errno == EINVAL);
+
+    len = getrandom(a, 0, 0);
+    printf("empty buffer: %zd\n", len);
+}
@@ -0,0 +1,64 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+long usec(struct timeval *tv) {
+    return tv->tv_sec * 1000000L + tv->tv_usec;
+}
+
+// Burn CPU time until who has used at least 20ms more user time
+void spin(int who) {
+    struct rusage start, now;
+    int status = getrusage(who, &start);
+    ERROR_IF(getrusage, status, == -1);
+
+    volatile unsigned long counter = 0;
+    do {
+        for (int i = 0; i < 1000000; i++) {
+            counter++;
+        }
+        status = getrusage(who, &now);
+        ERROR_IF(getrusage, status, == -1);
+    } while (usec(&now.ru_utime) - usec(&start.ru_utime) < 20000);
+}
+
+int main(void) {
+    // Returns only once the user time advanced
+    spin(RUSAGE_SELF);
+    puts("RUSAGE_SELF user time advanced");
+    spin(RUSAGE_THREAD);
+    puts("RUSAGE_THREAD user time advanced");
+
+    struct rusage usage;
+    int status = getrusage(RUSAGE_SELF, &usage);
+    ERROR_IF(getrusage, status, == -1);
+    printf("max RSS set: %d\n", usage.ru_maxrss > 0);
+
+    status = getrusage(RUSAGE_CHILDREN, &usage);
+    ERROR_IF(getrusage, status, == -1);
+    long before = usec(&usage.ru_utime);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        spin(RUSAGE_SELF);
+        exit(0);
+    }
+
+    // Children only count once they have been waited for
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    status = getrusage(RUSAGE_CHILDREN, &usage);
+    ERROR_IF(getrusage, status, == -1);
+    printf("RUSAGE_CHILDREN includes the child: %d\n", usec(&usage.ru_utime) - before >= 20000);
+
+    errno = 0;
+    status = getrusage(42, &usage);
+    printf("invalid who: failed %d, EINVAL %d\n", status == -1, errno == EINVAL);
+}
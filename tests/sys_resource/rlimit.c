@@ -0,0 +1,84 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    struct rlimit old;
+    int status = getrlimit(RLIMIT_NOFILE, &old);
+    ERROR_IF(getrlimit, status, == -1);
+
+    // Only stdin, stdout, stderr and one more descriptor are allowed
+    struct rlimit low = { .rlim_cur = 4, .rlim_max = old.rlim_max };
+    status = setrlimit(RLIMIT_NOFILE, &low);
+    ERROR_IF(setrlimit, status, == -1);
+
+    struct rlimit now;
+    status = getrlimit(RLIMIT_NOFILE, &now);
+    ERROR_IF(getrlimit, status, == -1);
+    printf("soft limit read back: %d\n", (int) now.rlim_cur);
+
+    int fd = open("/dev/null", O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+    errno = 0;
+    int fd2 = open("/dev/null", O_RDONLY);
+    printf("open past the limit: failed %d, EMFILE %d\n", fd2 == -1, errno == EMFILE);
+    close(fd);
+
+    // prlimit gets the old limit while setting the new one
+    struct rlimit prev;
+    status = prlimit(0, RLIMIT_NOFILE, &old, &prev);
+    ERROR_IF(prlimit, status, == -1);
+    printf("prlimit old soft limit: %d\n", (int) prev.rlim_cur);
+    status = getrlimit(RLIMIT_NOFILE, &now);
+    ERROR_IF(getrlimit, status, == -1);
+    printf("limit restored: %d\n", now.rlim_cur == old.rlim_cur);
+
+    struct rlimit stack;
+    status = getrlimit(RLIMIT_STACK, &stack);
+    ERROR_IF(getrlimit, status, == -1);
+    printf("stack hard limit infinite or at least soft limit: %d\n",
+        stack.rlim_max == RLIM_INFINITY || stack.rlim_max >= stack.rlim_cur);
+
+    // Set the core limit of a child, which reports it back
+    int fds[2];
+    status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        char c;
+        close(fds[1]);
+        read(fds[0], &c, 1);
+        struct rlimit core;
+        getrlimit(RLIMIT_CORE, &core);
+        exit(core.rlim_cur == 0 ? 0 : 1);
+    }
+    close(fds[0]);
+    struct rlimit core = { .rlim_cur = 0, .rlim_max = RLIM_INFINITY };
+    status = prlimit(pid, RLIMIT_CORE, NULL, &core);
+    ERROR_IF(prlimit, status, == -1);
+    core.rlim_cur = 0;
+    status = prlimit(pid, RLIMIT_CORE, &core, NULL);
+    ERROR_IF(prlimit, status, == -1);
+    write(fds[1], "x", 1);
+    close(fds[1]);
+    int wstatus;
+    waitpid(pid, &wstatus, 0);
+    printf("child sees its new core limit: %d\n", WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+
+    // Raising the hard limit needs privileges
+    if (geteuid() != 0 && old.rlim_max != RLIM_INFINITY) {
+        struct rlimit high = { .rlim_cur = old.rlim_cur, .rlim_max = old.rlim_max + 1 };
+        errno = 0;
+        status = setrlimit(RLIMIT_NOFILE, &high);
+        ERROR_IF(setrlimit, status, != -1);
+        ERROR_IF(setrlimit, errno, != EPERM);
+    }
+    puts("raising the hard limit checked");
+}
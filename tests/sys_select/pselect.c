@@ -0,0 +1,67 @@
+#include <errno.h>
+#include <poll.h>
+#include <signal.h>
+#include <stdio.h>
+#include <sys/select.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+volatile sig_atomic_t caught = 0;
+
+void handler(int sig) {
+    caught = 1;
+}
+
+int main(void) {
+    int pipefd[2];
+    int status = pipe(pipefd);
+    ERROR_IF(pipe, status, == -1);
+
+    struct sigaction act = { 0 };
+    act.sa_handler = handler;
+    status = sigaction(SIGUSR1, &act, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    sigset_t blocked, unblocked;
+    sigemptyset(&blocked);
+    sigaddset(&blocked, SIGUSR1);
+    sigemptyset(&unblocked);
+
+    status = sigprocmask(SIG_BLOCK, &blocked, NULL);
+    ERROR_IF(sigprocmask, status, == -1);
+
+    struct timespec timeout = { 0, 100000000 };
+    fd_set readfds;
+
+    // A pending signal that stays blocked during the wait must not interrupt it
+    raise(SIGUSR1);
+    FD_ZERO(&readfds);
+    FD_SET(pipefd[0], &readfds);
+    status = pselect(pipefd[0] + 1, &readfds, NULL, NULL, &timeout, &blocked);
+    printf("pselect with signal blocked: %d, caught %d\n", status, caught);
+
+    // Unblocking it for the wait delivers it and interrupts the wait
+    FD_ZERO(&readfds);
+    FD_SET(pipefd[0], &readfds);
+    status = pselect(pipefd[0] + 1, &readfds, NULL, NULL, &timeout, &unblocked);
+    printf("pselect with signal unblocked: %d, EINTR %d, caught %d\n", status, errno == EINTR, caught);
+    printf("pselect timeout unchanged: %d\n", timeout.tv_sec == 0 && timeout.tv_nsec == 100000000);
+
+    caught = 0;
+    raise(SIGUSR1);
+    struct pollfd pfd = { .fd = pipefd[0], .events = POLLIN };
+    status = ppoll(&pfd, 1, &timeout, &blocked);
+    printf("ppoll with signal blocked: %d, caught %d\n", status, caught);
+
+    status = ppoll(&pfd, 1, &timeout, &unblocked);
+    printf("ppoll with signal unblocked: %d, EINTR %d, caught %d\n", status, errno == EINTR, caught);
+    printf("ppoll timeout unchanged: %d\n", timeout.tv_sec == 0 && timeout.tv_nsec == 100000000);
+
+    // The original mask is restored after each wait
+    sigset_t current;
+    status = sigprocmask(SIG_BLOCK, NULL, &current);
+    ERROR_IF(sigprocmask, status, == -1);
+    printf("signal still blocked: %d\n", sigismember(&current, SIGUSR1));
+}
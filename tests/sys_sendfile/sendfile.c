@@ -0,0 +1,50 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/sendfile.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "sendfile.out"
+
+int main(void) {
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+    ssize_t len = write(fd, "0123456789abcdef", 16);
+    ERROR_IF(write, len, == -1);
+    off_t pos = lseek(fd, 0, SEEK_SET);
+    ERROR_IF(lseek, pos, == -1);
+
+    int sv[2];
+    int status = socketpair(AF_UNIX, SOCK_STREAM, 0, sv);
+    ERROR_IF(socketpair, status, == -1);
+
+    char buf[32];
+
+    // With an offset, it is advanced and the file position is left alone
+    off_t offset = 4;
+    len = sendfile(sv[0], fd, &offset, 8);
+    ERROR_IF(sendfile, len, == -1);
+    memset(buf, 0, sizeof(buf));
+    ssize_t received = read(sv[1], buf, sizeof(buf) - 1);
+    ERROR_IF(read, received, == -1);
+    pos = lseek(fd, 0, SEEK_CUR);
+    printf("sent %zd bytes: '%s', offset %ld, position %ld\n", len, buf, (long) offset, (long) pos);
+
+    // Without one, it reads from the file position, stopping at the end
+    len = sendfile(sv[0], fd, NULL, 100);
+    ERROR_IF(sendfile, len, == -1);
+    memset(buf, 0, sizeof(buf));
+    received = read(sv[1], buf, sizeof(buf) - 1);
+    ERROR_IF(read, received, == -1);
+    pos = lseek(fd, 0, SEEK_CUR);
+    printf("sent %zd bytes: '%s', position %ld\n", len, buf, (long) pos);
+
+    close(sv[0]);
+    close(sv[1]);
+    close(fd);
+    status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
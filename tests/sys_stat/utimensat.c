@@ -0,0 +1,67 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/stat.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "utimensat.out"
+
+int main(void) {
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+
+    struct timespec times[2] = {
+        { .tv_sec = 1000000000, .tv_nsec = 123456789 },
+        { .tv_sec = 1500000000, .tv_nsec = 987654321 },
+    };
+    int status = futimens(fd, times);
+    ERROR_IF(futimens, status, == -1);
+
+    struct stat st;
+    status = stat(PATH, &st);
+    ERROR_IF(stat, status, == -1);
+    printf("futimens: atime %ld.%09ld, mtime %ld.%09ld\n",
+        (long) st.st_atim.tv_sec, st.st_atim.tv_nsec,
+        (long) st.st_mtim.tv_sec, st.st_mtim.tv_nsec);
+
+    // Set only atime, leaving mtime alone
+    struct timespec atime_only[2] = {
+        { .tv_sec = 1200000000, .tv_nsec = 5 },
+        { .tv_sec = 0, .tv_nsec = UTIME_OMIT },
+    };
+    status = utimensat(AT_FDCWD, PATH, atime_only, 0);
+    ERROR_IF(utimensat, status, == -1);
+
+    status = stat(PATH, &st);
+    ERROR_IF(stat, status, == -1);
+    printf("utimensat UTIME_OMIT: atime %ld.%09ld, mtime %ld.%09ld\n",
+        (long) st.st_atim.tv_sec, st.st_atim.tv_nsec,
+        (long) st.st_mtim.tv_sec, st.st_mtim.tv_nsec);
+
+    // Null times set both to now
+    struct timespec before;
+    clock_gettime(CLOCK_REALTIME, &before);
+    status = utimensat(AT_FDCWD, PATH, NULL, AT_SYMLINK_NOFOLLOW);
+    ERROR_IF(utimensat, status, == -1);
+
+    status = fstat(fd, &st);
+    ERROR_IF(fstat, status, == -1);
+    printf("utimensat NULL: atime recent %d, mtime recent %d\n",
+        st.st_atim.tv_sec >= before.tv_sec - 1, st.st_mtim.tv_sec >= before.tv_sec - 1);
+
+    // Nanoseconds out of range are rejected
+    struct timespec invalid[2] = {
+        { .tv_sec = 0, .tv_nsec = 1000000000 },
+        { .tv_sec = 0, .tv_nsec = UTIME_OMIT },
+    };
+    errno = 0;
+    status = futimens(fd, invalid);
+    printf("futimens invalid: %d, EINVAL %d\n", status, errno == EINVAL);
+
+    close(fd);
+    status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
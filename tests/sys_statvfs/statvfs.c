@@ -0,0 +1,36 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <sys/statvfs.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void check(const char *name, struct statvfs *buf) {
+    printf("%s: total blocks nonzero %d\n", name, buf->f_blocks > 0);
+    // The fragment size is the unit of the block counts, and sane block
+    // sizes are powers of two
+    printf("%s: f_frsize sane %d\n", name,
+        buf->f_frsize >= 512 && (buf->f_frsize & (buf->f_frsize - 1)) == 0);
+    printf("%s: f_bsize sane %d\n", name, buf->f_bsize >= 512);
+    printf("%s: free at most total %d, available at most free %d\n", name,
+        buf->f_bfree <= buf->f_blocks, buf->f_bavail <= buf->f_bfree);
+}
+
+int main(void) {
+    struct statvfs root;
+    int status = statvfs("/", &root);
+    ERROR_IF(statvfs, status, == -1);
+    check("statvfs", &root);
+
+    int fd = open("/", O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+
+    struct statvfs root_fd;
+    status = fstatvfs(fd, &root_fd);
+    ERROR_IF(fstatvfs, status, == -1);
+    check("fstatvfs", &root_fd);
+    printf("same file system: %d\n",
+        root.f_fsid == root_fd.f_fsid && root.f_blocks == root_fd.f_blocks);
+
+    close(fd);
+}
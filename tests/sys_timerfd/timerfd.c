@@ -0,0 +1,53 @@
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/timerfd.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    int fd = timerfd_create(CLOCK_MONOTONIC, TFD_CLOEXEC);
+    ERROR_IF(timerfd_create, fd, == -1);
+
+    struct itimerspec curr;
+    int status = timerfd_gettime(fd, &curr);
+    ERROR_IF(timerfd_gettime, status, == -1);
+    printf("disarmed: %d\n", curr.it_value.tv_sec == 0 && curr.it_value.tv_nsec == 0);
+
+    // First expiration after 50ms, then every 10ms
+    struct itimerspec new = {
+        .it_interval = { .tv_sec = 0, .tv_nsec = 10000000 },
+        .it_value = { .tv_sec = 0, .tv_nsec = 50000000 },
+    };
+    status = timerfd_settime(fd, 0, &new, NULL);
+    ERROR_IF(timerfd_settime, status, == -1);
+
+    status = timerfd_gettime(fd, &curr);
+    ERROR_IF(timerfd_gettime, status, == -1);
+    printf("armed: %d, interval %ld ns\n",
+        curr.it_value.tv_sec != 0 || curr.it_value.tv_nsec != 0,
+        curr.it_interval.tv_nsec);
+
+    // The read blocks until the first expiration
+    uint64_t expirations = 0;
+    ssize_t len = read(fd, &expirations, sizeof(expirations));
+    ERROR_IF(read, len, == -1);
+    printf("read %zd bytes, expired: %d\n", len, expirations >= 1);
+
+    // Sleeping past several intervals makes them accumulate
+    struct timespec nap = { .tv_sec = 0, .tv_nsec = 55000000 };
+    nanosleep(&nap, NULL);
+    len = read(fd, &expirations, sizeof(expirations));
+    ERROR_IF(read, len, == -1);
+    printf("read %zd bytes, expired several times: %d\n", len, expirations >= 2);
+
+    // Disarm it again, getting back the old setting
+    struct itimerspec zero = { 0 };
+    struct itimerspec old;
+    status = timerfd_settime(fd, 0, &zero, &old);
+    ERROR_IF(timerfd_settime, status, == -1);
+    printf("old interval %ld ns\n", old.it_interval.tv_nsec);
+
+    close(fd);
+}
@@ -0,0 +1,52 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/uio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "pwritev2.out"
+
+int main(void) {
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+
+    ssize_t len = write(fd, "hello", 5);
+    ERROR_IF(write, len, == -1);
+
+    // RWF_APPEND writes at the end of the file regardless of the offset
+    char space[] = " ";
+    char world[] = "world";
+    struct iovec out[2] = {
+        { .iov_base = space, .iov_len = 1 },
+        { .iov_base = world, .iov_len = 5 },
+    };
+    len = pwritev2(fd, out, 2, 0, RWF_APPEND);
+    ERROR_IF(pwritev2, len, == -1);
+    printf("pwritev2 RWF_APPEND wrote %zd bytes\n", len);
+
+    off_t pos = lseek(fd, 0, SEEK_CUR);
+    printf("file position after pwritev2: %ld\n", (long) pos);
+
+    char first[6] = { 0 };
+    char second[7] = { 0 };
+    struct iovec in[2] = {
+        { .iov_base = first, .iov_len = 5 },
+        { .iov_base = second, .iov_len = 6 },
+    };
+    len = preadv2(fd, in, 2, 0, 0);
+    ERROR_IF(preadv2, len, == -1);
+    printf("preadv2 at 0 read %zd bytes: '%s' '%s'\n", len, first, second);
+
+    // An offset of -1 reads from, and advances, the file position
+    memset(second, 0, sizeof(second));
+    len = preadv2(fd, &in[1], 1, -1, 0);
+    ERROR_IF(preadv2, len, == -1);
+    pos = lseek(fd, 0, SEEK_CUR);
+    printf("preadv2 at -1 read %zd bytes: '%s', position %ld\n", len, second, (long) pos);
+
+    close(fd);
+    int status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
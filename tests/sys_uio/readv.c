@@ -0,0 +1,71 @@
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/uio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "readv.out"
+
+int main(void) {
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+
+    char one[] = "one ";
+    char two[] = "two ";
+    char three[] = "three";
+    struct iovec out[3] = {
+        { .iov_base = one, .iov_len = 4 },
+        { .iov_base = two, .iov_len = 4 },
+        { .iov_base = three, .iov_len = 5 },
+    };
+    ssize_t len = writev(fd, out, 3);
+    ERROR_IF(writev, len, == -1);
+    printf("writev wrote %zd bytes\n", len);
+
+    off_t pos = lseek(fd, 0, SEEK_SET);
+    ERROR_IF(lseek, pos, == -1);
+
+    char a[5] = { 0 };
+    char b[5] = { 0 };
+    char c[6] = { 0 };
+    struct iovec in[3] = {
+        { .iov_base = a, .iov_len = 4 },
+        { .iov_base = b, .iov_len = 4 },
+        { .iov_base = c, .iov_len = 5 },
+    };
+    len = readv(fd, in, 3);
+    ERROR_IF(readv, len, == -1);
+    printf("readv read %zd bytes: '%s' '%s' '%s'\n", len, a, b, c);
+
+    // A short read fills the buffers in order and returns the total
+    char big[16] = { 0 };
+    memset(a, 0, sizeof(a));
+    struct iovec partial[2] = {
+        { .iov_base = a, .iov_len = 4 },
+        { .iov_base = big, .iov_len = 15 },
+    };
+    len = preadv(fd, partial, 2, 4);
+    ERROR_IF(preadv, len, == -1);
+    printf("preadv at 4 read %zd bytes: '%s' '%s'\n", len, a, big);
+
+    // The positioned variants leave the file offset alone
+    char upper[] = "TWO";
+    struct iovec patch = { .iov_base = upper, .iov_len = 3 };
+    len = pwritev(fd, &patch, 1, 4);
+    ERROR_IF(pwritev, len, == -1);
+    pos = lseek(fd, 0, SEEK_CUR);
+    printf("pwritev wrote %zd bytes, position %ld\n", len, (long) pos);
+
+    memset(big, 0, sizeof(big));
+    struct iovec all = { .iov_base = big, .iov_len = 15 };
+    len = preadv(fd, &all, 1, 0);
+    ERROR_IF(preadv, len, == -1);
+    pos = lseek(fd, 0, SEEK_CUR);
+    printf("preadv read '%s', position %ld\n", big, (long) pos);
+
+    close(fd);
+    int status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+}
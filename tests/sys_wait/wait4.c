@@ -0,0 +1,61 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/resource.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static pid_t spawn_busy_child(int code) {
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        // Use some CPU time, so that there is usage to report
+        clock_t start = clock();
+        volatile unsigned long counter = 0;
+        while (clock() - start < CLOCKS_PER_SEC / 20) {
+            counter++;
+        }
+        _exit(code);
+    }
+    return pid;
+}
+
+static void check_usage(const char *name, struct rusage *usage) {
+    long usec = usage->ru_utime.tv_sec * 1000000L + usage->ru_utime.tv_usec
+        + usage->ru_stime.tv_sec * 1000000L + usage->ru_stime.tv_usec;
+    printf("%s: CPU time reported %d, max RSS reported %d\n", name, usec > 0, usage->ru_maxrss > 0);
+}
+
+int main(void) {
+    pid_t pid = spawn_busy_child(3);
+
+    int status;
+    struct rusage usage = { 0 };
+    pid_t waited = wait4(pid, &status, 0, &usage);
+    ERROR_IF(wait4, waited, == -1);
+    printf("wait4: reaped child %d, exit status %d\n", waited == pid, WEXITSTATUS(status));
+    check_usage("wait4", &usage);
+
+    pid = spawn_busy_child(4);
+
+    struct rusage usage3 = { 0 };
+    waited = wait3(&status, 0, &usage3);
+    ERROR_IF(wait3, waited, == -1);
+    printf("wait3: reaped child %d, exit status %d\n", waited == pid, WEXITSTATUS(status));
+    check_usage("wait3", &usage3);
+
+    // A null rusage is allowed, and WNOHANG returns 0 while the child runs
+    pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        sleep(1);
+        _exit(0);
+    }
+    waited = wait4(pid, &status, WNOHANG, NULL);
+    printf("wait4 WNOHANG on a running child: %d\n", waited);
+    waited = wait4(pid, &status, 0, NULL);
+    ERROR_IF(wait4, waited, == -1);
+    printf("wait4 without rusage: reaped child %d\n", waited == pid);
+}
@@ -0,0 +1,74 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void print_info(const char *name, siginfo_t *info, pid_t pid) {
+    printf("%s: si_signo SIGCHLD %d, si_pid matches %d, si_code %d, si_status %d\n",
+        name, info->si_signo == SIGCHLD, info->si_pid == pid, info->si_code, info->si_status);
+}
+
+int main(void) {
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        _exit(42);
+    }
+
+    // WNOWAIT reports the child but leaves it to be reaped again
+    siginfo_t info;
+    int status = waitid(P_PID, pid, &info, WEXITED | WNOWAIT);
+    ERROR_IF(waitid, status, == -1);
+    print_info("WNOWAIT", &info, pid);
+    printf("CLD_EXITED %d\n", info.si_code == CLD_EXITED);
+
+    status = waitid(P_PID, pid, &info, WEXITED);
+    ERROR_IF(waitid, status, == -1);
+    print_info("WEXITED", &info, pid);
+
+    errno = 0;
+    status = waitid(P_PID, pid, &info, WEXITED);
+    printf("reaped already: %d, ECHILD %d\n", status, errno == ECHILD);
+
+    pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        for (;;) {
+            sleep(1);
+        }
+    }
+
+    // WNOHANG with nothing to report leaves si_pid zero
+    info.si_pid = -1;
+    status = waitid(P_ALL, 0, &info, WEXITED | WNOHANG);
+    ERROR_IF(waitid, status, == -1);
+    printf("WNOHANG: %d, si_pid %d\n", status, info.si_pid);
+
+    status = kill(pid, SIGSTOP);
+    ERROR_IF(kill, status, == -1);
+    status = waitid(P_PID, pid, &info, WSTOPPED);
+    ERROR_IF(waitid, status, == -1);
+    print_info("WSTOPPED", &info, pid);
+    printf("CLD_STOPPED %d, SIGSTOP %d\n", info.si_code == CLD_STOPPED, info.si_status == SIGSTOP);
+
+    status = kill(pid, SIGCONT);
+    ERROR_IF(kill, status, == -1);
+    status = waitid(P_PID, pid, &info, WCONTINUED);
+    ERROR_IF(waitid, status, == -1);
+    printf("WCONTINUED: CLD_CONTINUED %d, SIGCONT %d\n",
+        info.si_code == CLD_CONTINUED, info.si_status == SIGCONT);
+
+    status = kill(pid, SIGKILL);
+    ERROR_IF(kill, status, == -1);
+    status = waitid(P_PID, pid, &info, WEXITED);
+    ERROR_IF(waitid, status, == -1);
+    printf("killed: CLD_KILLED %d, SIGKILL %d\n", info.si_code == CLD_KILLED, info.si_status == SIGKILL);
+
+    errno = 0;
+    status = waitid(P_ALL, 0, &info, 0);
+    printf("no events: %d, EINVAL %d\n", status, errno == EINVAL);
+}
@@ -5,6 +5,7 @@
 #include <stdio.h>
 #include <stdlib.h>
 #include <string.h>
+#include <sys/wait.h>
 #include <unistd.h>
 
 // Throws errors on a well-defined API error values.
@@ -88,4 +89,49 @@
         _exit(code); \
     } while(0)
 
+// Runs child(arg) in a forked process with its file descriptor fd going into
+// a pipe, for tests of functions that end the process.
+//
+// What the child wrote to fd is stored in output, cut to size - 1 bytes and
+// NUL terminated, and the child's wait status is returned.
+//
+// Usage example:
+//
+// char output[256];
+// int wstatus = run_child_capture(STDERR_FILENO, child, NULL, output, sizeof(output));
+// printf("stderr: %s", output);
+// printf("aborted: %d\n", WIFSIGNALED(wstatus) && WTERMSIG(wstatus) == SIGABRT);
+//
+static inline int run_child_capture(int fd, void (*child)(void *), void *arg,
+                                    char *output, size_t size) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    fflush(stdout);
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        close(fds[0]);
+        dup2(fds[1], fd);
+        child(arg);
+        _exit(EXIT_SUCCESS);
+    }
+    close(fds[1]);
+
+    size_t total = 0;
+    ssize_t count = 0;
+    while (total < size - 1 && (count = read(fds[0], output + total, size - 1 - total)) > 0) {
+        total += count;
+    }
+    ERROR_IF(read, count, == -1);
+    output[total] = '\0';
+    close(fds[0]);
+
+    int wstatus;
+    pid_t waited = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, waited, == -1);
+    return wstatus;
+}
+
 #endif /* _TEST_HELPERS */
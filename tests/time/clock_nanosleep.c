@@ -0,0 +1,91 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/time.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+void handler(int sig) {
+    (void)sig;
+}
+
+long long diff_ns(struct timespec *end, struct timespec *start) {
+    return (end->tv_sec - start->tv_sec) * 1000000000LL + (end->tv_nsec - start->tv_nsec);
+}
+
+void start_timer(long usec) {
+    struct itimerval timer = { 0 };
+    timer.it_value.tv_usec = usec;
+    int status = setitimer(ITIMER_REAL, &timer, NULL);
+    ERROR_IF(setitimer, status, == -1);
+}
+
+int main(void) {
+    // Install a handler without SA_RESTART, so that sleeps get interrupted
+    struct sigaction sa = { .sa_handler = handler };
+    sigemptyset(&sa.sa_mask);
+    int status = sigaction(SIGALRM, &sa, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    struct timespec start, end;
+
+    // Absolute deadline on the monotonic clock
+    status = clock_gettime(CLOCK_MONOTONIC, &start);
+    ERROR_IF(clock_gettime, status, == -1);
+    struct timespec deadline = start;
+    deadline.tv_nsec += 50000000;
+    if (deadline.tv_nsec >= 1000000000) {
+        deadline.tv_sec += 1;
+        deadline.tv_nsec -= 1000000000;
+    }
+    int err = clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &deadline, NULL);
+    printf("absolute sleep: %s\n", strerror(err));
+    status = clock_gettime(CLOCK_MONOTONIC, &end);
+    ERROR_IF(clock_gettime, status, == -1);
+    printf("deadline reached: %d\n", diff_ns(&end, &deadline) >= 0);
+
+    // A deadline in the past returns immediately
+    err = clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &start, NULL);
+    printf("past deadline: %s\n", strerror(err));
+
+    // Relative sleep interrupted by a signal reports the remaining time
+    start_timer(100000);
+    struct timespec request = { .tv_sec = 2, .tv_nsec = 0 };
+    struct timespec remain = { 0 };
+    err = clock_nanosleep(CLOCK_MONOTONIC, 0, &request, &remain);
+    printf("interrupted relative sleep: %s\n", strerror(err));
+    long long remain_ns = remain.tv_sec * 1000000000LL + remain.tv_nsec;
+    printf("remaining time in range: %d\n", remain_ns > 1000000000LL && remain_ns < 2000000000LL);
+
+    // Interrupted absolute sleeps leave the remaining time untouched
+    status = clock_gettime(CLOCK_MONOTONIC, &deadline);
+    ERROR_IF(clock_gettime, status, == -1);
+    deadline.tv_sec += 2;
+    start_timer(100000);
+    remain.tv_sec = 42;
+    remain.tv_nsec = 42;
+    err = clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &deadline, &remain);
+    printf("interrupted absolute sleep: %s\n", strerror(err));
+    printf("remaining time untouched: %d\n", remain.tv_sec == 42 && remain.tv_nsec == 42);
+
+    // Errors are returned rather than stored in errno
+    errno = 0;
+    request.tv_sec = 0;
+    request.tv_nsec = 1000000000;
+    err = clock_nanosleep(CLOCK_MONOTONIC, 0, &request, NULL);
+    printf("invalid request: %s, errno = %d\n", strerror(err), errno);
+
+    // nanosleep without a remainder sleeps through signals
+    status = clock_gettime(CLOCK_MONOTONIC, &start);
+    ERROR_IF(clock_gettime, status, == -1);
+    start_timer(50000);
+    request.tv_sec = 0;
+    request.tv_nsec = 200000000;
+    status = nanosleep(&request, NULL);
+    ERROR_IF(nanosleep, status, == -1);
+    status = clock_gettime(CLOCK_MONOTONIC, &end);
+    ERROR_IF(clock_gettime, status, == -1);
+    printf("nanosleep slept through signal: %d\n", diff_ns(&end, &start) >= 200000000LL);
+}
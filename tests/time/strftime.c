@@ -11,6 +11,12 @@ void print(time_t timestamp, char* fmt) {
     free(out);
 }
 
+void print_tm(struct tm *tm, char* fmt) {
+    char out[50];
+    size_t n = strftime(out, sizeof(out), fmt, tm);
+    printf("%zu: %s\n", n, out);
+}
+
 int main(void) {
     print(1531808742, "%a %A %b %B");
     print(1531808742, "The %Cst century");
@@ -21,4 +27,17 @@ int main(void) {
     print(1531839600, "%j %U");
     print(1531839600, "%+");
     print(1533669431, "%+%+%+%+%+"); // will overflow 50 characters
+
+    struct tm tm = { 0 };
+    tm.tm_year = 118;
+    tm.tm_mon = 6;
+    tm.tm_mday = 17;
+    tm.tm_hour = 15;
+    tm.tm_wday = 2;
+    tm.tm_yday = 197;
+    print_tm(&tm, "%F %T");
+    print_tm(&tm, "%D %R %s");
+    // %s converts from the time zone the fields are in, here UTC+02:00
+    tm.tm_gmtoff = 2 * 3600;
+    print_tm(&tm, "%T %z %s");
 }
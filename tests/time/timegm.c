@@ -0,0 +1,70 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+void print_tm(const struct tm *t) {
+    printf(
+        "Year %d, Day of year: %d, Month %d, Day of month: %d, Day of week: %d, %d:%d:%d\n",
+        t->tm_year, t->tm_yday, t->tm_mon, t->tm_mday, t->tm_wday, t->tm_hour, t->tm_min, t->tm_sec
+    );
+}
+
+time_t check_timegm(int year, int mon, int mday, int hour, int min, int sec) {
+    struct tm t = { 0 };
+    t.tm_year = year;
+    t.tm_mon = mon;
+    t.tm_mday = mday;
+    t.tm_hour = hour;
+    t.tm_min = min;
+    t.tm_sec = sec;
+
+    time_t result = timegm(&t);
+    printf("%ld\n", result);
+    print_tm(&t);
+    return result;
+}
+
+int main(void) {
+    // Epoch
+    check_timegm(70, 0, 1, 0, 0, 0);
+
+    // Leap day in a leap year, and one second before 2038 overflow
+    check_timegm(100, 1, 29, 12, 0, 0);
+    check_timegm(138, 0, 19, 3, 14, 7);
+
+    // Pre-1970 dates produce negative times
+    check_timegm(69, 11, 31, 23, 59, 59);
+    check_timegm(0, 0, 1, 0, 0, 0);
+    check_timegm(-100, 1, 28, 6, 30, 0);
+
+    // Normalization of out-of-range fields: month 13 of 2019 is February
+    // 2020, day 0 is the last day of the previous month, negative and
+    // overflowing times carry into neighbouring days.
+    check_timegm(119, 13, 1, 0, 0, 0);
+    check_timegm(120, 2, 0, 0, 0, 0);
+    check_timegm(120, -1, 1, 0, 0, 0);
+    check_timegm(120, 0, 1, -1, 0, 0);
+    check_timegm(120, 0, 31, 23, 59, 60);
+    check_timegm(120, 0, 1, 0, 0, 86400 * 366);
+
+    // timegm must round-trip gmtime
+    time_t inputs[] = { -2851200, -86400, -500, 0, 1531454950 };
+    for (int i = 0; i < sizeof(inputs) / sizeof(time_t); i += 1) {
+        struct tm *t = gmtime(&inputs[i]);
+        ERROR_IF(gmtime, t, == NULL);
+        time_t output = timegm(t);
+        printf("%ld = %ld\n", inputs[i], output);
+    }
+
+    // timelocal is the timezone-aware twin of timegm
+    setenv("TZ", "UTC0", 1);
+    struct tm t = { 0 };
+    t.tm_year = 119;
+    t.tm_mon = 13;
+    t.tm_mday = 1;
+    t.tm_isdst = -1;
+    printf("%ld\n", timelocal(&t));
+    print_tm(&t);
+}
@@ -0,0 +1,134 @@
+#include <errno.h>
+#include <pthread.h>
+#include <semaphore.h>
+#include <signal.h>
+#include <stdio.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+static timer_t timer;
+static volatile int expirations = 0;
+static volatile int overrun = 0;
+
+static pthread_t main_thread;
+static sem_t notified;
+static int notified_value = 0;
+static int notified_elsewhere = 0;
+
+// Counts the delivered expiration along with those missed before it
+static void handler(int sig) {
+    (void) sig;
+    overrun = timer_getoverrun(timer);
+    expirations += 1 + overrun;
+}
+
+static void notify(union sigval value) {
+    // Unblocking every signal must not let those relibc uses itself through,
+    // or the next expiration would kill the process
+    sigset_t none;
+    sigemptyset(&none);
+    int status = pthread_sigmask(SIG_SETMASK, &none, NULL);
+    ERROR_IF(pthread_sigmask, status, != 0);
+
+    notified_value = value.sival_int;
+    notified_elsewhere = !pthread_equal(pthread_self(), main_thread);
+    sem_post(&notified);
+}
+
+// Sleeps through signals until the given number of milliseconds have passed
+static void sleep_ms(long ms) {
+    struct timespec deadline;
+    int status = clock_gettime(CLOCK_MONOTONIC, &deadline);
+    ERROR_IF(clock_gettime, status, == -1);
+    deadline.tv_sec += ms / 1000;
+    deadline.tv_nsec += (ms % 1000) * 1000000;
+    if (deadline.tv_nsec >= 1000000000) {
+        deadline.tv_sec += 1;
+        deadline.tv_nsec -= 1000000000;
+    }
+    int err;
+    while ((err = clock_nanosleep(CLOCK_MONOTONIC, TIMER_ABSTIME, &deadline, NULL)) == EINTR) {}
+    ERROR_IF(clock_nanosleep, err, != 0);
+}
+
+static void set_interval(long ns) {
+    struct itimerspec value = {
+        .it_interval = { .tv_sec = 0, .tv_nsec = ns },
+        .it_value = { .tv_sec = 0, .tv_nsec = ns },
+    };
+    int status = timer_settime(timer, 0, &value, NULL);
+    ERROR_IF(timer_settime, status, == -1);
+}
+
+static void block_alarm(int how) {
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, SIGALRM);
+    int status = sigprocmask(how, &set, NULL);
+    ERROR_IF(sigprocmask, status, == -1);
+}
+
+int main(void) {
+    struct sigaction sa = { .sa_handler = handler };
+    sigemptyset(&sa.sa_mask);
+    int status = sigaction(SIGALRM, &sa, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    // Without a sigevent, SIGALRM is sent
+    status = timer_create(CLOCK_MONOTONIC, NULL, &timer);
+    ERROR_IF(timer_create, status, == -1);
+
+    // A periodic timer expires once per interval. A loaded machine may
+    // oversleep, so only a lower bound is checked.
+    set_interval(10000000);
+    sleep_ms(105);
+    set_interval(0);
+    printf("at least 9 expirations after 10 intervals: %d\n", expirations >= 9);
+
+    // Expirations while the signal is blocked are counted as overruns
+    block_alarm(SIG_BLOCK);
+    set_interval(5000000);
+    sleep_ms(52);
+    block_alarm(SIG_UNBLOCK);
+    set_interval(0);
+    printf("overrun of at least 8 after 10 intervals: %d\n", overrun >= 8);
+
+    // The time left can be read back, and is zero once disarmed
+    struct itimerspec value = { .it_value = { .tv_sec = 1, .tv_nsec = 0 } };
+    status = timer_settime(timer, 0, &value, NULL);
+    ERROR_IF(timer_settime, status, == -1);
+    status = timer_gettime(timer, &value);
+    ERROR_IF(timer_gettime, status, == -1);
+    printf("armed: %d\n", value.it_value.tv_sec == 0 && value.it_value.tv_nsec > 0);
+    struct itimerspec disarm = { 0 };
+    status = timer_settime(timer, 0, &disarm, &value);
+    ERROR_IF(timer_settime, status, == -1);
+    printf("old value returned: %d\n", value.it_value.tv_nsec > 0);
+    status = timer_gettime(timer, &value);
+    ERROR_IF(timer_gettime, status, == -1);
+    printf("disarmed: %d\n", value.it_value.tv_sec == 0 && value.it_value.tv_nsec == 0);
+
+    status = timer_delete(timer);
+    ERROR_IF(timer_delete, status, == -1);
+
+    // SIGEV_THREAD runs the notify function on another thread
+    main_thread = pthread_self();
+    status = sem_init(&notified, 0, 0);
+    ERROR_IF(sem_init, status, == -1);
+    struct sigevent event = {
+        .sigev_notify = SIGEV_THREAD,
+        .sigev_notify_function = notify,
+        .sigev_value = { .sival_int = 42 },
+    };
+    status = timer_create(CLOCK_MONOTONIC, &event, &timer);
+    ERROR_IF(timer_create, status, == -1);
+    set_interval(10000000);
+    for (int i = 0; i < 3; i++) {
+        status = sem_wait(&notified);
+        ERROR_IF(sem_wait, status, == -1);
+    }
+    status = timer_delete(timer);
+    ERROR_IF(timer_delete, status, == -1);
+    printf("notified with %d on another thread: %d\n", notified_value, notified_elsewhere);
+}
@@ -0,0 +1,69 @@
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+// The fixtures in time/tzif have three transitions: to BBB (UTC+2, DST) at
+// 1970-01-11, to AAA (UTC+1) at 1970-01-21 and back to BBB at 1970-01-31.
+// The version 2 file continues with the TZ string "CCC-3" after the last
+// transition instead, and has a dummy version 1 block that must be skipped.
+
+void set_tzif(const char *fixture) {
+    char path[PATH_MAX];
+    char *resolved = realpath(fixture, path);
+    ERROR_IF(realpath, resolved, == NULL);
+
+    char tz[PATH_MAX + 1];
+    snprintf(tz, sizeof(tz), ":%s", path);
+    int status = setenv("TZ", tz, 1);
+    ERROR_IF(setenv, status, == -1);
+    tzset();
+    printf("%s: tzname = { %s, %s }, timezone = %ld, daylight = %d\n",
+        fixture, tzname[0], tzname[1], timezone, daylight);
+}
+
+void print_localtime(time_t input) {
+    struct tm *t = localtime(&input);
+    ERROR_IF(localtime, t, == NULL);
+
+    char buf[64];
+    strftime(buf, sizeof(buf), "%Y-%m-%d %H:%M:%S %z %Z", t);
+    printf("  %ld: %s, isdst = %d, gmtoff = %ld\n", input, buf, t->tm_isdst, t->tm_gmtoff);
+}
+
+void print_mktime(int year, int mon, int mday, int hour, int min) {
+    struct tm t = { 0 };
+    t.tm_year = year - 1900;
+    t.tm_mon = mon - 1;
+    t.tm_mday = mday;
+    t.tm_hour = hour;
+    t.tm_min = min;
+    t.tm_isdst = -1;
+
+    time_t result = mktime(&t);
+    ERROR_IF(mktime, result, == (time_t)-1);
+    printf("  mktime(%04d-%02d-%02d %02d:%02d) = %ld, isdst = %d\n",
+        year, mon, mday, hour, min, result, t.tm_isdst);
+}
+
+void print_times(void) {
+    print_localtime(0);
+    print_localtime(863999);
+    print_localtime(864000);
+    print_localtime(1728000);
+    print_localtime(2592000);
+    print_localtime(1531454950);
+    print_mktime(1970, 1, 1, 1, 0);
+    print_mktime(1970, 1, 15, 12, 0);
+    print_mktime(2018, 7, 13, 7, 9);
+}
+
+int main(void) {
+    set_tzif("time/tzif/v1");
+    print_times();
+
+    set_tzif("time/tzif/v2");
+    print_times();
+}
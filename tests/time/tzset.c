@@ -0,0 +1,92 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+void set_tz(const char *tz) {
+    int status = setenv("TZ", tz, 1);
+    ERROR_IF(setenv, status, == -1);
+    tzset();
+    printf("TZ=%s: tzname = { %s, %s }, timezone = %ld, daylight = %d\n",
+        tz, tzname[0], tzname[1], timezone, daylight);
+}
+
+void print_localtime(time_t input) {
+    struct tm *t = localtime(&input);
+    ERROR_IF(localtime, t, == NULL);
+
+    char buf[64];
+    strftime(buf, sizeof(buf), "%Y-%m-%d %H:%M:%S %z %Z", t);
+    printf("  %ld: %s, isdst = %d, gmtoff = %ld\n", input, buf, t->tm_isdst, t->tm_gmtoff);
+}
+
+void print_mktime(int year, int mon, int mday, int hour, int min, int isdst) {
+    struct tm t = { 0 };
+    t.tm_year = year - 1900;
+    t.tm_mon = mon - 1;
+    t.tm_mday = mday;
+    t.tm_hour = hour;
+    t.tm_min = min;
+    t.tm_isdst = isdst;
+
+    time_t result = mktime(&t);
+    ERROR_IF(mktime, result, == (time_t)-1);
+    printf("  mktime(%04d-%02d-%02d %02d:%02d, isdst = %d) = %ld, normalized to %02d:%02d isdst = %d\n",
+        year, mon, mday, hour, min, isdst, result, t.tm_hour, t.tm_min, t.tm_isdst);
+}
+
+int main(void) {
+    // Fixed offsets
+    set_tz("JST-9");
+    print_localtime(0);
+    print_localtime(1531454950);
+    print_mktime(1970, 1, 1, 9, 0, -1);
+
+    set_tz("<+0530>-5:30");
+    print_localtime(1531454950);
+    print_mktime(2018, 7, 13, 9, 39, -1);
+
+    set_tz("<-03>3");
+    print_localtime(1531454950);
+    print_mktime(2018, 7, 13, 1, 9, -1);
+
+    // Northern hemisphere DST, around the 2020 transitions
+    set_tz("EST5EDT,M3.2.0,M11.1.0");
+    print_localtime(1583650799); // 2020-03-08 06:59:59 UTC
+    print_localtime(1583650800); // 2020-03-08 07:00:00 UTC
+    print_localtime(1604210399); // 2020-11-01 05:59:59 UTC
+    print_localtime(1604210400); // 2020-11-01 06:00:00 UTC
+    print_mktime(2020, 1, 15, 12, 0, -1);
+    print_mktime(2020, 7, 15, 12, 0, -1);
+    // Skipped by the spring transition
+    print_mktime(2020, 3, 8, 2, 30, -1);
+    // Repeated by the autumn transition
+    print_mktime(2020, 11, 1, 1, 30, 1);
+    print_mktime(2020, 11, 1, 1, 30, 0);
+
+    // Southern hemisphere DST spans the new year
+    set_tz("AEST-10AEDT,M10.1.0,M4.1.0/3");
+    print_localtime(1578830400); // 2020-01-12 12:00:00 UTC
+    print_localtime(1594555200); // 2020-07-12 12:00:00 UTC
+    print_mktime(2020, 1, 12, 23, 0, -1);
+    print_mktime(2020, 7, 12, 22, 0, -1);
+
+    // Julian day rules, with and without leap days
+    set_tz("AAA3BBB,J60/0,300/0");
+    print_localtime(1583020800); // 2020-03-01 00:00:00 UTC
+    print_localtime(1583031600); // 2020-03-01 03:00:00 UTC
+    print_localtime(1603929600); // 2020-10-29 00:00:00 UTC
+
+    // Default DST offset and rules
+    set_tz("PST8PDT");
+    print_localtime(1594555200);
+    print_localtime(1578830400);
+
+    // Abbreviations stay valid when the time zone is changed
+    char *std = tzname[0];
+    time_t summer = 1594555200;
+    struct tm saved = *localtime(&summer);
+    set_tz("UTC0");
+    printf("  kept: %s, %s\n", std, saved.tm_zone);
+}
@@ -0,0 +1,83 @@
+#include <signal.h>
+#include <stdio.h>
+#include <ucontext.h>
+
+#include "test_helpers.h"
+
+#define ROUNDS 4
+
+static ucontext_t main_ctx, ping_ctx, pong_ctx;
+static char ping_stack[64 * 1024];
+static char pong_stack[64 * 1024];
+
+static void ping(int rounds) {
+    for (int i = 0; i < rounds; i++) {
+        printf("ping %d\n", i);
+        int status = swapcontext(&ping_ctx, &pong_ctx);
+        ERROR_IF(swapcontext, status, == -1);
+    }
+    puts("ping done");
+    // Returning continues with uc_link, which is main_ctx
+}
+
+// More arguments than fit in registers, to check those passed on the stack
+static void pong(int a, int b, int c, int d, int e, int f, int g, int h) {
+    printf("pong arguments %d %d %d %d %d %d %d %d\n", a, b, c, d, e, f, g, h);
+    for (int i = 0;; i++) {
+        printf("pong %d\n", i);
+        int status = swapcontext(&pong_ctx, &ping_ctx);
+        ERROR_IF(swapcontext, status, == -1);
+    }
+}
+
+static void set_blocked(int sig, int how) {
+    sigset_t set;
+    sigemptyset(&set);
+    sigaddset(&set, sig);
+    int status = sigprocmask(how, &set, NULL);
+    ERROR_IF(sigprocmask, status, == -1);
+}
+
+static int is_blocked(int sig) {
+    sigset_t set;
+    int status = sigprocmask(SIG_BLOCK, NULL, &set);
+    ERROR_IF(sigprocmask, status, == -1);
+    return sigismember(&set, sig);
+}
+
+int main(void) {
+    // getcontext returns again when the context is resumed, with the signal
+    // mask restored
+    volatile int resumed = 0;
+    ucontext_t saved;
+    set_blocked(SIGUSR1, SIG_BLOCK);
+    int status = getcontext(&saved);
+    ERROR_IF(getcontext, status, == -1);
+    printf("getcontext returned, resumed %d, SIGUSR1 blocked %d\n", resumed, is_blocked(SIGUSR1));
+    if (!resumed) {
+        resumed = 1;
+        set_blocked(SIGUSR1, SIG_UNBLOCK);
+        setcontext(&saved);
+        puts("setcontext returned");
+    }
+    set_blocked(SIGUSR1, SIG_UNBLOCK);
+
+    // Two contexts on their own stacks, switching back and forth
+    status = getcontext(&ping_ctx);
+    ERROR_IF(getcontext, status, == -1);
+    ping_ctx.uc_stack.ss_sp = ping_stack;
+    ping_ctx.uc_stack.ss_size = sizeof(ping_stack);
+    ping_ctx.uc_link = &main_ctx;
+    makecontext(&ping_ctx, (void (*)(void)) ping, 1, ROUNDS);
+
+    status = getcontext(&pong_ctx);
+    ERROR_IF(getcontext, status, == -1);
+    pong_ctx.uc_stack.ss_sp = pong_stack;
+    pong_ctx.uc_stack.ss_size = sizeof(pong_stack);
+    pong_ctx.uc_link = NULL;
+    makecontext(&pong_ctx, (void (*)(void)) pong, 8, 1, 2, 3, 4, 5, 6, 7, 8);
+
+    status = swapcontext(&main_ctx, &ping_ctx);
+    ERROR_IF(swapcontext, status, == -1);
+    puts("back in main");
+}
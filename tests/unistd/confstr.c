@@ -0,0 +1,28 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+int main(void) {
+    char buf[256];
+    size_t len = confstr(_CS_PATH, NULL, 0);
+    printf("_CS_PATH size: %d\n", len > 1);
+    size_t ret = confstr(_CS_PATH, buf, sizeof(buf));
+    printf("same size: %d, strlen + 1: %d, contains /bin: %d\n",
+        ret == len, strlen(buf) + 1 == len, strstr(buf, "/bin") != NULL);
+
+    // A short buffer gets a truncated copy, and the full size is returned
+    char small[4];
+    memset(small, 'x', sizeof(small));
+    ret = confstr(_CS_PATH, small, sizeof(small));
+    printf("truncated: '%s', full size returned: %d\n", small, ret == len);
+
+    ret = confstr(_CS_GNU_LIBC_VERSION, buf, sizeof(buf));
+    printf("_CS_GNU_LIBC_VERSION: %d\n", ret > 1 && strlen(buf) + 1 == ret);
+    ret = confstr(_CS_GNU_LIBPTHREAD_VERSION, buf, sizeof(buf));
+    printf("_CS_GNU_LIBPTHREAD_VERSION: %d\n", ret > 1 && strlen(buf) + 1 == ret);
+
+    errno = 0;
+    ret = confstr(-1, buf, sizeof(buf));
+    printf("unknown: %zu, EINVAL %d\n", ret, errno == EINVAL);
+}
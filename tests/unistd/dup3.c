@@ -0,0 +1,50 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void print_cloexec(const char *name, int fd) {
+    int flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("%s: FD_CLOEXEC %d\n", name, (flags & FD_CLOEXEC) != 0);
+}
+
+int main(void) {
+    int fd = open("unistd/dup3.c", O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+
+    int newfd = dup3(fd, 20, O_CLOEXEC);
+    ERROR_IF(dup3, newfd, == -1);
+    printf("dup3 returned the requested descriptor: %d\n", newfd == 20);
+    print_cloexec("dup3 O_CLOEXEC", newfd);
+
+    // Without O_CLOEXEC the flag is cleared, even if it was set on newfd
+    newfd = dup3(fd, 20, 0);
+    ERROR_IF(dup3, newfd, == -1);
+    print_cloexec("dup3 over it without flags", newfd);
+
+    newfd = dup2(fd, 21);
+    ERROR_IF(dup2, newfd, == -1);
+    print_cloexec("dup2", newfd);
+
+    // Unlike dup2, dup3 refuses to duplicate a descriptor onto itself
+    errno = 0;
+    int status = dup3(fd, fd, 0);
+    printf("dup3 onto itself: %d, EINVAL %d\n", status, errno == EINVAL);
+    status = dup2(fd, fd);
+    printf("dup2 onto itself returns it: %d\n", status == fd);
+
+    errno = 0;
+    status = dup3(fd, 22, O_NONBLOCK);
+    printf("dup3 unknown flag: %d, EINVAL %d\n", status, errno == EINVAL);
+
+    errno = 0;
+    status = dup2(100, 100);
+    printf("dup2 of a closed descriptor onto itself: %d, EBADF %d\n", status, errno == EBADF);
+
+    close(20);
+    close(21);
+    close(fd);
+}
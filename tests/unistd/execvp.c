@@ -0,0 +1,102 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static const char *dir = "execvp_dir";
+static const char *other_dir = "execvp_other_dir";
+
+static void write_file(const char *dir, const char *name, const char *contents, mode_t mode) {
+    char path[128];
+    snprintf(path, sizeof(path), "%s/%s", dir, name);
+    FILE *f = fopen(path, "w");
+    ERROR_IF(fopen, f, == NULL);
+    fputs(contents, f);
+    fclose(f);
+    int status = chmod(path, mode);
+    ERROR_IF(chmod, status, == -1);
+}
+
+static void remove_file(const char *dir, const char *name) {
+    char path[128];
+    snprintf(path, sizeof(path), "%s/%s", dir, name);
+    unlink(path);
+}
+
+// Run `file` in a child, printing why the exec failed or how the child exited
+static void run(const char *file, char *const argv[], char *const envp[]) {
+    fflush(stdout);
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        if (envp == NULL) {
+            execvp(file, argv);
+        } else {
+            execvpe(file, argv, envp);
+        }
+        printf("%s: %s\n", file, strerror(errno));
+        fflush(stdout);
+        _exit(127);
+    }
+    int status;
+    pid_t waited = waitpid(pid, &status, 0);
+    ERROR_IF(waitpid, waited, == -1);
+    printf("%s: exit status %d\n", file, WEXITSTATUS(status));
+}
+
+int main(void) {
+    int status = mkdir(dir, 0755);
+    ERROR_IF(mkdir, status, == -1);
+    status = mkdir(other_dir, 0755);
+    ERROR_IF(mkdir, status, == -1);
+
+    // A script without a "#!" line, which only runs through the shell fallback
+    write_file(dir, "script", "echo \"script ran with $1 and greeting $GREETING\"\nexit 3\n", 0755);
+    write_file(dir, "locked", "echo locked ran\n", 0644);
+    write_file(other_dir, "locked", "echo \"found after a directory without permission\"\n", 0755);
+    write_file(dir, "only_locked", "echo only_locked ran\n", 0644);
+
+    char path[256];
+    snprintf(path, sizeof(path), "/nonexistent:%s:%s:/bin:/usr/bin", dir, other_dir);
+    setenv("PATH", path, 1);
+    setenv("GREETING", "hello", 1);
+
+    // Found in PATH, and only run by the shell
+    char *script_argv[] = { "script", "argument", NULL };
+    run("script", script_argv, NULL);
+
+    // A file with a slash isn't searched for
+    char script_path[128];
+    snprintf(script_path, sizeof(script_path), "./%s/script", dir);
+    run(script_path, script_argv, NULL);
+    run("script/", script_argv, NULL);
+
+    // A binary from PATH
+    char *sh_argv[] = { "sh", "-c", "echo \"sh found in PATH\"", NULL };
+    run("sh", sh_argv, NULL);
+
+    // Permission errors only stop the search when nothing else is found
+    char *locked_argv[] = { "locked", NULL };
+    run("locked", locked_argv, NULL);
+    char *only_locked_argv[] = { "only_locked", NULL };
+    run("only_locked", only_locked_argv, NULL);
+    char *missing_argv[] = { "missing", NULL };
+    run("missing", missing_argv, NULL);
+    run("", missing_argv, NULL);
+
+    // execvpe gives the program another environment
+    char *envp[] = { "GREETING=bonjour", NULL };
+    run("script", script_argv, envp);
+
+    remove_file(dir, "script");
+    remove_file(dir, "locked");
+    remove_file(other_dir, "locked");
+    remove_file(dir, "only_locked");
+    rmdir(dir);
+    rmdir(other_dir);
+}
@@ -0,0 +1,38 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    // Fill with a pattern first, so that unfilled bytes can be spotted
+    unsigned char buf[257];
+    memset(buf, 0xAA, sizeof(buf));
+
+    int status = getentropy(buf, 256);
+    ERROR_IF(getentropy, status, == -1);
+    printf("256 bytes: %d\n", status);
+
+    // A run of 32 bytes still holding the pattern means it wasn't filled
+    int filled = 1;
+    for (int run = 0; run < 256; run += 32) {
+        int same = 1;
+        for (int i = run; i < run + 32; i++) {
+            if (buf[i] != 0xAA) {
+                same = 0;
+            }
+        }
+        if (same) {
+            filled = 0;
+        }
+    }
+    printf("whole buffer filled: %d, byte after untouched: %d\n", filled, buf[256] == 0xAA);
+
+    errno = 0;
+    status = getentropy(buf, 257);
+    printf("257 bytes: %d, EIO %d\n", status, errno == EIO);
+
+    status = getentropy(buf, 0);
+    printf("0 bytes: %d\n", status);
+}
@@ -0,0 +1,45 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Parse the same arguments with the given optstring, then print what's left
+static void run(const char *optstring) {
+    char *args[] = { "test", "-a", "file1", "-b", "arg", "file2", "-c", "--", "-a", NULL };
+    int argc = sizeof(args) / sizeof(args[0]) - 1;
+
+    printf("%s:", optstring);
+    // Reset the parser fully, including the mode
+    optind = 0;
+    int c;
+    while ((c = getopt(argc, args, optstring)) != -1) {
+        if (c == 1) {
+            printf(" [%s]", optarg);
+        } else if (optarg) {
+            printf(" -%c %s", c, optarg);
+        } else {
+            printf(" -%c", c);
+        }
+    }
+    printf(", remaining:");
+    for (int i = optind; i < argc; i++) {
+        printf(" %s", args[i]);
+    }
+    printf("\n");
+}
+
+int main(void) {
+    // Permuted, so the operands are left at the end
+    run("ab:c");
+    // Stopping at the first operand
+    run("+ab:c");
+    // Operands are returned in order, as arguments of option 1
+    run("-ab:c");
+
+    // Stopping at the first operand unless asked otherwise
+    int status = setenv("POSIXLY_CORRECT", "1", 1);
+    ERROR_IF(setenv, status, == -1);
+    run("ab:c");
+    run("-ab:c");
+}
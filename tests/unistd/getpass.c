@@ -0,0 +1,124 @@
+#include <fcntl.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <sys/wait.h>
+#include <termios.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Read from the terminal until `until` shows up, or the other side is gone
+static size_t read_until(int master, char *buf, size_t len, size_t size, const char *until) {
+    while (len < size - 1) {
+        buf[len] = 0;
+        if (until && strstr(buf, until)) {
+            break;
+        }
+        ssize_t n = read(master, buf + len, size - 1 - len);
+        if (n <= 0) {
+            break;
+        }
+        len += n;
+    }
+    buf[len] = 0;
+    return len;
+}
+
+// Open a new pty, returning its master side and the path of the other
+static int open_pty(char *path, size_t size) {
+    int master = open("/dev/ptmx", O_RDWR | O_NOCTTY);
+    ERROR_IF(open, master, == -1);
+    int unlock = 0;
+    int status = ioctl(master, TIOCSPTLCK, &unlock);
+    ERROR_IF(ioctl, status, == -1);
+    unsigned int pty = 0;
+    status = ioctl(master, TIOCGPTN, &pty);
+    ERROR_IF(ioctl, status, == -1);
+    snprintf(path, size, "/dev/pts/%u", pty);
+    return master;
+}
+
+// Run `child` in a new session with the pty at `path` as its controlling
+// terminal, which getpass opens as /dev/tty
+static pid_t start_child(int master, const char *path, void (*child)(int slave)) {
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        close(master);
+        int status = setsid();
+        ERROR_IF(setsid, status, == -1);
+        int slave = open(path, O_RDWR);
+        ERROR_IF(open, slave, == -1);
+        child(slave);
+        close(slave);
+        exit(EXIT_SUCCESS);
+    }
+    return pid;
+}
+
+static void read_password(int slave) {
+    char *password = getpass("Password: ");
+    ERROR_IF(getpass, password, == NULL);
+    printf("getpass returned '%s'\n", password);
+    fflush(stdout);
+
+    // Echo is back on afterwards, which the parent sees when it types the
+    // line read here
+    int status = write(slave, "ready\n", 6);
+    ERROR_IF(write, status, == -1);
+    char line[64];
+    ssize_t count = read(slave, line, sizeof(line));
+    ERROR_IF(read, count, == -1);
+}
+
+static void interrupt_password(int slave) {
+    // As it may have been inherited ignored
+    signal(SIGINT, SIG_DFL);
+    getpass("Password: ");
+    puts("getpass returned after ^C");
+}
+
+int main(void) {
+    char path[64];
+    int master = open_pty(path, sizeof(path));
+    pid_t pid = start_child(master, path, read_password);
+
+    char output[256];
+    size_t len = read_until(master, output, 0, sizeof(output), "Password: ");
+    int status = write(master, "secret\n", 7);
+    ERROR_IF(write, status, == -1);
+    len = read_until(master, output, len, sizeof(output), "ready");
+    size_t typed = len;
+    status = write(master, "typed\n", 6);
+    ERROR_IF(write, status, == -1);
+    len = read_until(master, output, len, sizeof(output), "typed");
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("child exited: %d, status %d\n", WIFEXITED(wstatus), WEXITSTATUS(wstatus));
+
+    printf("prompt shown: %d\n", strstr(output, "Password: ") != NULL);
+    printf("password echoed: %d\n", strstr(output, "secret") != NULL);
+    printf("echo restored: %d\n", strstr(output + typed, "typed") != NULL);
+    close(master);
+
+    // ^C still interrupts getpass, and the terminal is left with echo on
+    master = open_pty(path, sizeof(path));
+    pid = start_child(master, path, interrupt_password);
+    read_until(master, output, 0, sizeof(output), "Password: ");
+    status = write(master, "\x03", 1);
+    ERROR_IF(write, status, == -1);
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("killed by SIGINT: %d\n", WIFSIGNALED(wstatus) && WTERMSIG(wstatus) == SIGINT);
+
+    struct termios settings;
+    status = tcgetattr(master, &settings);
+    ERROR_IF(tcgetattr, status, == -1);
+    printf("echo restored after ^C: %d\n", (settings.c_lflag & ECHO) != 0);
+    close(master);
+}
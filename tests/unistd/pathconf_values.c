@@ -0,0 +1,41 @@
+#include <errno.h>
+#include <limits.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    errno = 0;
+    long path_max = pathconf(".", _PC_PATH_MAX);
+    ERROR_IF(pathconf, path_max, == -1);
+    printf("_PC_PATH_MAX matches PATH_MAX: %d\n", path_max == PATH_MAX);
+
+    long name_max = pathconf(".", _PC_NAME_MAX);
+    ERROR_IF(pathconf, name_max, == -1);
+    printf("_PC_NAME_MAX is at least 14: %d\n", name_max >= 14);
+
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    long pipe_buf = fpathconf(fds[0], _PC_PIPE_BUF);
+    ERROR_IF(fpathconf, pipe_buf, == -1);
+    printf("_PC_PIPE_BUF of a pipe: %ld\n", pipe_buf);
+
+    long no_trunc = fpathconf(fds[1], _PC_NO_TRUNC);
+    printf("_PC_NO_TRUNC: %ld\n", no_trunc);
+
+    close(fds[0]);
+    close(fds[1]);
+
+    // Unknown names are rejected
+    errno = 0;
+    long unknown = pathconf(".", -1);
+    printf("unknown name: %ld, EINVAL %d\n", unknown, errno == EINVAL);
+
+    // So is a path that does not exist, for values that depend on it
+    errno = 0;
+    long missing = pathconf("pathconf_values.missing", _PC_NAME_MAX);
+    printf("missing path: %ld, ENOENT %d\n", missing, errno == ENOENT);
+}
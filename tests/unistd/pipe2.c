@@ -0,0 +1,49 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void print_flags(const char *name, int fd) {
+    int fd_flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, fd_flags, == -1);
+    int fl_flags = fcntl(fd, F_GETFL);
+    ERROR_IF(fcntl, fl_flags, == -1);
+    printf("%s: FD_CLOEXEC %d, O_NONBLOCK %d\n", name,
+        (fd_flags & FD_CLOEXEC) != 0, (fl_flags & O_NONBLOCK) != 0);
+}
+
+int main(void) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    print_flags("pipe read end", fds[0]);
+    print_flags("pipe write end", fds[1]);
+    close(fds[0]);
+    close(fds[1]);
+
+    status = pipe2(fds, O_CLOEXEC);
+    ERROR_IF(pipe2, status, == -1);
+    print_flags("O_CLOEXEC read end", fds[0]);
+    print_flags("O_CLOEXEC write end", fds[1]);
+    close(fds[0]);
+    close(fds[1]);
+
+    status = pipe2(fds, O_CLOEXEC | O_NONBLOCK);
+    ERROR_IF(pipe2, status, == -1);
+    print_flags("O_CLOEXEC | O_NONBLOCK read end", fds[0]);
+    print_flags("O_CLOEXEC | O_NONBLOCK write end", fds[1]);
+
+    // Reading from the empty pipe does not block
+    char c;
+    errno = 0;
+    ssize_t len = read(fds[0], &c, 1);
+    printf("read from empty pipe: %zd, EAGAIN %d\n", len, errno == EAGAIN);
+    close(fds[0]);
+    close(fds[1]);
+
+    errno = 0;
+    status = pipe2(fds, O_TRUNC);
+    printf("unknown flag: %d, EINVAL %d\n", status, errno == EINVAL);
+}
@@ -0,0 +1,29 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/resource.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    printf("page size is 4096: %d\n", sysconf(_SC_PAGESIZE) == 4096);
+    printf("processors configured: %d\n", sysconf(_SC_NPROCESSORS_CONF) >= 1);
+    printf("processors online: %d\n", sysconf(_SC_NPROCESSORS_ONLN) >= 1);
+    printf("clock ticks: %ld\n", sysconf(_SC_CLK_TCK));
+    printf("physical pages: %d\n", sysconf(_SC_PHYS_PAGES) > 0);
+
+    // The open file limit follows RLIMIT_NOFILE
+    struct rlimit old;
+    int status = getrlimit(RLIMIT_NOFILE, &old);
+    ERROR_IF(getrlimit, status, == -1);
+    struct rlimit low = { .rlim_cur = 42, .rlim_max = old.rlim_max };
+    status = setrlimit(RLIMIT_NOFILE, &low);
+    ERROR_IF(setrlimit, status, == -1);
+    printf("open max: %ld\n", sysconf(_SC_OPEN_MAX));
+    status = setrlimit(RLIMIT_NOFILE, &old);
+    ERROR_IF(setrlimit, status, == -1);
+
+    errno = 0;
+    long value = sysconf(-1);
+    printf("unknown name: %ld, EINVAL %d\n", value, errno == EINVAL);
+}
@@ -0,0 +1,72 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define PATH "ttyname.out"
+
+static void not_a_tty(const char *kind, int fd) {
+    errno = 0;
+    int tty = isatty(fd);
+    int isatty_errno = errno;
+    char buf[64];
+    int ret = ttyname_r(fd, buf, sizeof(buf));
+    errno = 0;
+    char *name = ttyname(fd);
+    printf("%s: isatty %d, ENOTTY %d, ttyname_r ENOTTY %d, ttyname NULL %d, ENOTTY %d\n",
+        kind, tty, isatty_errno == ENOTTY, ret == ENOTTY, name == NULL, errno == ENOTTY);
+}
+
+int main(void) {
+    int fd = open(PATH, O_RDWR | O_CREAT | O_TRUNC, 0644);
+    ERROR_IF(open, fd, == -1);
+    not_a_tty("regular file", fd);
+    close(fd);
+    int status = unlink(PATH);
+    ERROR_IF(unlink, status, == -1);
+
+    int fds[2];
+    status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    not_a_tty("pipe", fds[0]);
+    close(fds[0]);
+    close(fds[1]);
+
+    // Open a pseudoterminal by hand and make it stdin
+    int master = open("/dev/ptmx", O_RDWR | O_NOCTTY);
+    ERROR_IF(open, master, == -1);
+    int unlock = 0;
+    status = ioctl(master, TIOCSPTLCK, &unlock);
+    ERROR_IF(ioctl, status, == -1);
+    unsigned int pty = 0;
+    status = ioctl(master, TIOCGPTN, &pty);
+    ERROR_IF(ioctl, status, == -1);
+
+    char expected[64];
+    snprintf(expected, sizeof(expected), "/dev/pts/%u", pty);
+    int slave = open(expected, O_RDWR | O_NOCTTY);
+    ERROR_IF(open, slave, == -1);
+    status = dup2(slave, STDIN_FILENO);
+    ERROR_IF(dup2, status, == -1);
+
+    printf("pty: isatty %d\n", isatty(STDIN_FILENO));
+    char *name = ttyname(STDIN_FILENO);
+    ERROR_IF(ttyname, name, == NULL);
+    printf("ttyname is under /dev/pts: %d, matches: %d\n",
+        strncmp(name, "/dev/pts/", 9) == 0, strcmp(name, expected) == 0);
+
+    // The path must fit with its NUL
+    char buf[64];
+    size_t len = strlen(expected);
+    status = ttyname_r(STDIN_FILENO, buf, len);
+    printf("ttyname_r without room for NUL: ERANGE %d\n", status == ERANGE);
+    status = ttyname_r(STDIN_FILENO, buf, len + 1);
+    printf("ttyname_r with room for NUL: %d, matches: %d\n", status, strcmp(buf, expected) == 0);
+
+    close(slave);
+    close(master);
+}
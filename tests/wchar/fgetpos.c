@@ -0,0 +1,87 @@
+#include <locale.h>
+#include <stdio.h>
+#include <unistd.h>
+#include <wchar.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    setlocale(LC_ALL, "C.UTF-8");
+
+    FILE *f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+
+    // A mix of one, two, three and four byte UTF-8 sequences
+    int status = fputws(L"aé€\U0001F600z", f);
+    ERROR_IF(fputws, status, == -1);
+    rewind(f);
+
+    wint_t wc = fgetwc(f);
+    printf("first: %#x\n", (unsigned) wc);
+
+    fpos_t pos;
+    status = fgetpos(f, &pos);
+    ERROR_IF(fgetpos, status, == -1);
+
+    wint_t before[3];
+    for (int i = 0; i < 3; i++) {
+        before[i] = fgetwc(f);
+    }
+    printf("read on: %#x %#x %#x\n", (unsigned) before[0], (unsigned) before[1], (unsigned) before[2]);
+
+    status = fsetpos(f, &pos);
+    ERROR_IF(fsetpos, status, == -1);
+
+    // The restored position is at a character boundary, so the same
+    // characters are read again
+    int same = 1;
+    for (int i = 0; i < 3; i++) {
+        if (fgetwc(f) != before[i]) {
+            same = 0;
+        }
+    }
+    printf("re-read matches: %d\n", same);
+    wc = fgetwc(f);
+    printf("last: %#x, then WEOF: %d\n", (unsigned) wc, fgetwc(f) == WEOF);
+    printf("still wide: %d\n", fwide(f, 0) > 0);
+    fclose(f);
+
+    // A character cut off by the end of the file is kept in the conversion
+    // state, which fgetpos saves along with the offset after it
+    f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    status = write(fileno(f), "a\xe2\x82", 3);
+    ERROR_IF(write, status, == -1);
+    rewind(f);
+    wc = fgetwc(f);
+    printf("before partial: %#x, then WEOF: %d\n", (unsigned) wc, fgetwc(f) == WEOF);
+    clearerr(f);
+    status = fgetpos(f, &pos);
+    ERROR_IF(fgetpos, status, == -1);
+
+    // The rest of the character shows up later
+    status = pwrite(fileno(f), "\xac", 1, 3);
+    ERROR_IF(pwrite, status, == -1);
+    wc = fgetwc(f);
+    printf("completed: %#x, then WEOF: %d\n", (unsigned) wc, fgetwc(f) == WEOF);
+
+    status = fsetpos(f, &pos);
+    ERROR_IF(fsetpos, status, == -1);
+    wc = fgetwc(f);
+    printf("re-read after fsetpos: %#x\n", (unsigned) wc);
+    fclose(f);
+
+    // Byte streams only restore the offset
+    f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    fputs("hello", f);
+    status = fgetpos(f, &pos);
+    ERROR_IF(fgetpos, status, == -1);
+    fputs(" world", f);
+    status = fsetpos(f, &pos);
+    ERROR_IF(fsetpos, status, == -1);
+    printf("byte stream rest: %c\n", fgetc(f));
+    rewind(f);
+    printf("byte stream start: %c\n", fgetc(f));
+    fclose(f);
+}
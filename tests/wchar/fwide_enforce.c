@@ -0,0 +1,48 @@
+#include <stdio.h>
+#include <wchar.h>
+
+#include "test_helpers.h"
+
+static void check(const char *name, FILE *f) {
+    int byte_res = fputc('x', f);
+    wint_t wide_res = fputwc(L'y', f);
+    printf("%s: fwide %d, fputc %s, fputwc %s\n",
+        name,
+        fwide(f, 0),
+        byte_res == EOF ? "fails" : "works",
+        wide_res == WEOF ? "fails" : "works");
+}
+
+int main(void) {
+    FILE *f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    printf("fwide(f, 1): %d\n", fwide(f, 1));
+    check("oriented by fwide", f);
+    fclose(f);
+
+    f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    wint_t wc = fputwc(L'a', f);
+    printf("first fputwc: %s\n", wc == L'a' ? "works" : "fails");
+    check("oriented by fputwc", f);
+
+    // Reading back keeps the wide orientation and decodes characters
+    rewind(f);
+    wint_t first = fgetwc(f);
+    wint_t second = fgetwc(f);
+    printf("fgetwc: %c %c, fgetc %s\n",
+        (char) first, (char) second, fgetc(f) == EOF ? "fails" : "works");
+    fclose(f);
+
+    f = tmpfile();
+    ERROR_IF(tmpfile, f, == NULL);
+    int c = fputc('a', f);
+    printf("first fputc: %s\n", c == 'a' ? "works" : "fails");
+    check("oriented by fputc", f);
+
+    // Wide reads are refused on a byte stream as well
+    rewind(f);
+    printf("fgetwc %s, fgetc %c\n", fgetwc(f) == WEOF ? "fails" : "works", fgetc(f));
+    printf("fwide(f, 1): %d\n", fwide(f, 1));
+    fclose(f);
+}
@@ -0,0 +1,62 @@
+#include <errno.h>
+#include <locale.h>
+#include <stdio.h>
+#include <wchar.h>
+
+#include "test_helpers.h"
+
+#define PATH "swprintf.out"
+
+int main(void) {
+    // Only needed to convert non-ASCII characters, the result is not printed
+    // since UTF-8 locales may not exist
+    setlocale(LC_ALL, "C.UTF-8");
+
+    wchar_t buf[64];
+
+    // Narrow arguments are converted to wide, wide ones are copied
+    int len = swprintf(buf, 64, L"%s and %ls, %c and %lc", "narrow", L"wide", 'n', L'w');
+    printf("mixed: %d \"%ls\"\n", len, buf);
+
+    len = swprintf(buf, 64, L"%s|%ls|%lc", "caf\xc3\xa9", L"été", (wint_t) 0x4E2D);
+    printf("non-ASCII: %d \"%ls\", wide chars %d %d\n", len, buf, buf[3] == 0xE9, buf[len - 1] == 0x4E2D);
+
+    // Widths and precisions count characters, not bytes
+    len = swprintf(buf, 64, L"[%5s|%-5ls|%.2s|%.2ls|%3lc]", "caf\xc3\xa9", L"été", "\xc3\xa9t\xc3\xa9", L"été", (wint_t) 0x4E2D);
+    printf("non-ASCII padded: %d \"%ls\"\n", len, buf);
+
+    len = swprintf(buf, 64, L"%d %.2f %x %-4s| %%", -42, 3.14159, 255, "ab");
+    printf("numbers: %d \"%ls\"\n", len, buf);
+
+    // The output including its NUL must fit, anything else is an error
+    len = swprintf(buf, 6, L"%ls", L"12345");
+    printf("exact fit: %d \"%ls\"\n", len, buf);
+
+    len = swprintf(buf, 5, L"%ls", L"12345");
+    printf("one too long: %d\n", len);
+
+    buf[0] = L'x';
+    len = swprintf(buf, 0, L"abc");
+    printf("zero size: %d, untouched %d\n", len, buf[0] == L'x');
+
+    // Invalid multibyte sequences in a narrow argument can't be converted
+    errno = 0;
+    len = swprintf(buf, 64, L"%s", "\xff\xfe");
+    printf("invalid narrow argument: %d, EILSEQ %d\n", len, errno == EILSEQ);
+
+    FILE *f = fopen(PATH, "w+");
+    ERROR_IF(fopen, f, == NULL);
+    len = fwprintf(f, L"%ls %s %d\n", L"été", "h\xc3\xa9", 2);
+    printf("fwprintf: %d, wide orientation %d\n", len, fwide(f, 0) > 0);
+    fclose(f);
+
+    f = fopen(PATH, "r");
+    ERROR_IF(fopen, f, == NULL);
+    char line[64];
+    ERROR_IF(fgets, fgets(line, sizeof(line), f), == NULL);
+    printf("written: %s", line);
+    fclose(f);
+
+    int status = remove(PATH);
+    ERROR_IF(remove, status, == -1);
+}
@@ -0,0 +1,36 @@
+#include <locale.h>
+#include <stdio.h>
+#include <wchar.h>
+
+static int sign(int x) {
+    return (x > 0) - (x < 0);
+}
+
+int main(void) {
+    // Only needed to print non-ASCII characters, the result is not printed
+    // since UTF-8 locales may not exist
+    setlocale(LC_ALL, "C.UTF-8");
+
+    // Without locale specific collation, wide strings compare by code point
+    // like with wcscmp
+    const wchar_t *words[] = { L"apple", L"Apple", L"apples", L"banana", L"", L"éclair", L"zebra" };
+    size_t count = sizeof(words) / sizeof(words[0]);
+    for (size_t i = 0; i + 1 < count; i++) {
+        int coll = wcscoll(words[i], words[i + 1]);
+        printf("wcscoll(\"%ls\", \"%ls\"): %d, same as wcscmp %d\n",
+            words[i], words[i + 1], sign(coll), sign(coll) == sign(wcscmp(words[i], words[i + 1])));
+    }
+    printf("wcscoll equal: %d\n", wcscoll(L"same", L"same"));
+
+    // Transformed strings compare with wcscmp like the originals with wcscoll
+    wchar_t a[16];
+    wchar_t b[16];
+    size_t len = wcsxfrm(a, L"apple", 16);
+    printf("wcsxfrm length: %zu\n", len);
+    wcsxfrm(b, L"banana", 16);
+    printf("transformed order kept: %d\n", sign(wcscmp(a, b)) == sign(wcscoll(L"apple", L"banana")));
+
+    // With too little space, only the needed length is returned
+    len = wcsxfrm(NULL, L"a longer string", 0);
+    printf("wcsxfrm needed length: %zu\n", len);
+}
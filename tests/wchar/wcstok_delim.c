@@ -0,0 +1,47 @@
+#include <locale.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <wchar.h>
+
+static void tokenize(const wchar_t *input, const wchar_t *delim) {
+    wchar_t *copy = wcsdup(input);
+    if (copy == NULL) {
+        perror("wcsdup");
+        exit(EXIT_FAILURE);
+    }
+
+    printf("\"%ls\" split on \"%ls\":", input, delim);
+    wchar_t *state = NULL;
+    for (wchar_t *token = wcstok(copy, delim, &state); token != NULL; token = wcstok(NULL, delim, &state)) {
+        printf(" [%ls]", token);
+    }
+    // Once done, further calls keep returning NULL
+    printf(", then %s\n", wcstok(NULL, delim, &state) == NULL ? "NULL" : "a token");
+
+    free(copy);
+}
+
+int main(void) {
+    // Only needed to print non-ASCII characters, the result is not printed
+    // since UTF-8 locales may not exist
+    setlocale(LC_ALL, "C.UTF-8");
+
+    // Leading, trailing and repeated delimiters never produce empty tokens
+    tokenize(L";;a,b;;c,", L",;");
+    tokenize(L"key = value ; other=thing", L" =;");
+    tokenize(L"no delimiters here", L",");
+    tokenize(L",,,", L",");
+    tokenize(L"", L",");
+    tokenize(L"été—hiver—printemps", L"—");
+
+    // The copy from wcsdup is independent of the original
+    const wchar_t *original = L"otter slide";
+    wchar_t *copy = wcsdup(original);
+    copy[0] = L'O';
+    printf("wcsdup: \"%ls\" from \"%ls\"\n", copy, original);
+
+    wchar_t *found = wcsstr(copy, L"slide");
+    printf("wcsstr: offset %td\n", found - copy);
+    printf("wcsstr missing: %d\n", wcsstr(copy, L"otters") == NULL);
+    free(copy);
+}
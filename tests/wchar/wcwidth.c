@@ -0,0 +1,46 @@
+#include <locale.h>
+#include <stdio.h>
+#include <wchar.h>
+
+static void width(const char *name, wchar_t wc) {
+    printf("wcwidth(%s U+%04X): %d\n", name, (unsigned) wc, wcwidth(wc));
+}
+
+int main(void) {
+    // The result of setlocale is not printed, widths are the same in any
+    // UTF-8 locale
+    setlocale(LC_ALL, "C.UTF-8");
+
+    width("NUL", 0);
+    width("Latin letter", L'a');
+    width("Latin letter with acute", 0xE9);
+    width("control", 0x07);
+    width("delete", 0x7F);
+    width("C1 control", 0x85);
+    width("combining acute", 0x301);
+    width("combining enclosing circle", 0x20DD);
+    width("zero width space", 0x200B);
+    width("soft hyphen", 0xAD);
+    width("Hangul jungseong", 0x1161);
+    width("CJK ideograph", 0x4E2D);
+    width("CJK extension B", 0x20000);
+    width("Hangul syllable", 0xAC00);
+    width("fullwidth A", 0xFF21);
+    width("halfwidth katakana", 0xFF76);
+    width("emoji", 0x1F600);
+    width("Greek letter", 0x3B1);
+    width("unassigned Greek", 0x378);
+    width("unassigned Bengali", 0x991);
+    width("unassigned symbol", 0x1FBFA);
+    width("noncharacter", 0xFFFFE);
+
+    // "e" with a combining acute, then two ideographs
+    wchar_t s[] = { L'e', 0x301, 0x4E2D, 0x6587, 0 };
+    printf("wcswidth: %d\n", wcswidth(s, 4));
+    printf("wcswidth limited to 2: %d\n", wcswidth(s, 2));
+    printf("wcswidth past NUL: %d\n", wcswidth(s, 10));
+
+    wchar_t bad[] = { L'a', 0x07, L'b', 0 };
+    printf("wcswidth with control: %d\n", wcswidth(bad, 3));
+    printf("wcswidth before control: %d\n", wcswidth(bad, 1));
+}
@@ -0,0 +1,64 @@
+#include <locale.h>
+#include <stdio.h>
+#include <wchar.h>
+#include <wctype.h>
+
+static const char *names[] = {
+    "alnum", "alpha", "blank", "cntrl", "digit", "graph",
+    "lower", "print", "punct", "space", "upper", "xdigit",
+};
+
+static void classify(const char *what, wint_t wc) {
+    printf("%-22s U+%04X:", what, (unsigned) wc);
+    for (size_t i = 0; i < sizeof(names) / sizeof(names[0]); i++) {
+        if (iswctype(wc, wctype(names[i]))) {
+            printf(" %s", names[i]);
+        }
+    }
+    printf("\n");
+}
+
+int main(void) {
+    // The result of setlocale is not printed, classes are the same in any
+    // UTF-8 locale
+    setlocale(LC_ALL, "C.UTF-8");
+
+    classify("Latin capital A", L'A');
+    classify("Latin small f", L'f');
+    classify("Latin small g", L'g');
+    classify("digit", L'7');
+    classify("exclamation mark", L'!');
+    classify("space", L' ');
+    classify("tab", L'\t');
+    classify("newline", L'\n');
+    classify("delete", 0x7F);
+    classify("no-break space", 0xA0);
+    classify("e with acute", 0xE9);
+    classify("E with acute", 0xC9);
+    classify("sharp s", 0xDF);
+    classify("Greek small alpha", 0x3B1);
+    classify("Cyrillic capital De", 0x414);
+    classify("Arabic letter beh", 0x628);
+    classify("CJK ideograph", 0x4E2D);
+    classify("ideographic space", 0x3000);
+    classify("ideographic comma", 0x3001);
+    classify("fullwidth digit 1", 0xFF11);
+    classify("line separator", 0x2028);
+    classify("em dash", 0x2014);
+    classify("unassigned", 0x378);
+    classify("WEOF", WEOF);
+
+    // The predicates agree with iswctype
+    printf("iswalpha: %d %d %d %d\n", !!iswalpha(L'a'), !!iswalpha(0xE9), !!iswalpha(0x4E2D), !!iswalpha(L'1'));
+    printf("iswupper/iswlower: %d %d %d %d\n", !!iswupper(0xC9), !!iswlower(0xC9), !!iswupper(0xE9), !!iswlower(0xE9));
+    printf("iswpunct: %d %d %d\n", !!iswpunct(L','), !!iswpunct(0x3001), !!iswpunct(0x4E2D));
+    printf("iswspace: %d %d %d\n", !!iswspace(L' '), !!iswspace(0x3000), !!iswspace(0xA0));
+    printf("unknown class: %ld\n", (long) wctype("vowel"));
+
+    wctrans_t upper = wctrans("toupper");
+    wctrans_t lower = wctrans("tolower");
+    printf("towctrans: U+%04X U+%04X U+%04X U+%04X\n",
+        (unsigned) towctrans(L'a', upper), (unsigned) towctrans(0xE9, upper),
+        (unsigned) towctrans(0xC9, lower), (unsigned) towctrans(0x4E2D, upper));
+    printf("unknown mapping: %d\n", wctrans("reverse") == 0);
+}